@@ -0,0 +1,84 @@
+/*
+ * C interface to puzzlething, the peg solitaire solver.
+ *
+ * Build the library with
+ * `cargo rustc --release --lib --crate-type cdylib --features ffi` and link
+ * against target/release/libpuzzlething. Boards are opaque and owned by the
+ * caller: free every board returned here with pt_board_free. Functions
+ * given a null board fail instead of crashing.
+ */
+#ifndef PUZZLETHING_H
+#define PUZZLETHING_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PtBoard PtBoard;
+
+enum {
+    PT_UP = 0,
+    PT_DOWN = 1,
+    PT_LEFT = 2,
+    PT_RIGHT = 3
+};
+
+/* The jump of the peg at (x, y), counted from the top left from 0. */
+typedef struct PtMove {
+    uint8_t x;
+    uint8_t y;
+    uint8_t direction;
+} PtMove;
+
+enum {
+    PT_STRATEGY_DFS = 0,
+    PT_STRATEGY_BIDIRECTIONAL = 1,
+    PT_STRATEGY_BACKWARD = 2,
+    PT_STRATEGY_PNS = 3
+};
+
+/* Results of pt_solve. */
+#define PT_SOLVED 1
+#define PT_UNSOLVABLE 0
+#define PT_TIMEOUT (-1)
+#define PT_ERROR (-2)
+
+/* The English board with every peg but the centre one. */
+PtBoard *pt_board_new(void);
+
+/* A board in notation such as "..OOO../..OOO../OOOOOOO/OOO-OOO/OOOOOOO/
+ * ..OOO../..OOO..", or NULL when it does not parse. */
+PtBoard *pt_board_from_notation(const char *notation);
+
+PtBoard *pt_board_clone(const PtBoard *board);
+void pt_board_free(PtBoard *board);
+
+uint32_t pt_board_pegs(const PtBoard *board);
+
+/* Writes the notation to out, NUL-terminated and cut to capacity bytes,
+ * and returns its full length, like snprintf. */
+size_t pt_board_notation(const PtBoard *board, char *out, size_t capacity);
+
+/* Writes up to capacity legal moves to out and returns how many there
+ * are. Pass NULL for out to only count them. */
+size_t pt_board_legal_moves(const PtBoard *board, PtMove *out, size_t capacity);
+
+/* Plays a move in place. Returns 0, or -1 if it is not legal, leaving the
+ * board unchanged. */
+int32_t pt_board_apply(PtBoard *board, PtMove move);
+
+/* Searches for a line from start to goal, or to the centre peg when goal
+ * is NULL, giving up after timeout_ms milliseconds unless it is 0. Writes
+ * up to capacity moves to out and the solution's length to *len. Returns
+ * PT_SOLVED, PT_UNSOLVABLE, PT_TIMEOUT or PT_ERROR. */
+int32_t pt_solve(const PtBoard *start, const PtBoard *goal, uint32_t strategy,
+                 uint32_t timeout_ms, PtMove *out, size_t capacity, size_t *len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
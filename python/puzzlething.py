@@ -0,0 +1,204 @@
+"""Python bindings for puzzlething, loaded with ctypes.
+
+Build the shared library first:
+
+    cargo rustc --release --lib --crate-type cdylib --features python
+
+The library is looked up in ../target/release next to this file, or at the
+path in the PUZZLETHING_LIB environment variable.
+
+    >>> board = Grid.english()
+    >>> len(board.legal_moves())
+    4
+    >>> line = board.solve()
+    >>> board.play(line) == Grid.english().single_peg(3, 3)
+    True
+"""
+
+import ctypes
+import os
+import sys
+import time
+
+DIRECTIONS = ("up", "down", "left", "right")
+_ARROWS = ("↑", "↓", "←", "→")
+_NO_BOARD = 0xFFFFFFFFFFFFFFFF
+
+
+def _load():
+    path = os.environ.get("PUZZLETHING_LIB")
+    if path is None:
+        name = {"darwin": "libpuzzlething.dylib", "win32": "puzzlething.dll"}.get(
+            sys.platform, "libpuzzlething.so"
+        )
+        here = os.path.dirname(os.path.abspath(__file__))
+        path = os.path.join(here, os.pardir, "target", "release", name)
+    lib = ctypes.CDLL(path)
+    u32, u64, i32 = ctypes.c_uint32, ctypes.c_uint64, ctypes.c_int32
+    signatures = {
+        "pt_english_layout": ([], u64),
+        "pt_english_start": ([], u64),
+        "pt_legal_count": ([u64, u64], u32),
+        "pt_legal_move": ([u64, u64, u32], u32),
+        "pt_apply": ([u64, u64, u32], u64),
+        "pt_solver_new": ([u64, u64, u64], u32),
+        "pt_solver_step": ([u32, u32], i32),
+        "pt_solver_nodes": ([u32], u64),
+        "pt_solver_len": ([u32], u32),
+        "pt_solver_move": ([u32, u32], u32),
+        "pt_solver_free": ([u32], None),
+        "pt_generate": ([u64, u64, u32, u64], u64),
+        "pt_state_space": ([u64, u64, ctypes.c_bool, ctypes.POINTER(u64), u32], u32),
+    }
+    for name, (args, result) in signatures.items():
+        function = getattr(lib, name)
+        function.argtypes = args
+        function.restype = result
+    return lib
+
+
+_lib = _load()
+
+
+class IllegalMove(ValueError):
+    pass
+
+
+class Action:
+    """A jump of the peg at (x, y), counted from the top left, in a
+    direction."""
+
+    __slots__ = ("x", "y", "direction")
+
+    def __init__(self, x, y, direction):
+        if direction not in DIRECTIONS:
+            raise ValueError("direction must be one of %s" % ", ".join(DIRECTIONS))
+        self.x, self.y, self.direction = x, y, direction
+
+    @classmethod
+    def _unpack(cls, packed):
+        return cls(packed & 7, packed >> 3 & 7, DIRECTIONS[packed >> 6])
+
+    def _pack(self):
+        return self.x | self.y << 3 | DIRECTIONS.index(self.direction) << 6
+
+    def __eq__(self, other):
+        return isinstance(other, Action) and self._pack() == other._pack()
+
+    def __hash__(self):
+        return self._pack()
+
+    def __repr__(self):
+        return "Action(%d, %d, %r)" % (self.x, self.y, self.direction)
+
+    def __str__(self):
+        return "(%d, %d) %s" % (self.x, self.y, _ARROWS[DIRECTIONS.index(self.direction)])
+
+
+class Grid:
+    """A position: the playable cells of the layout and the pegs on them,
+    both as bitmasks with bit x * 7 + y for the cell (x, y)."""
+
+    __slots__ = ("layout", "pegs")
+
+    def __init__(self, layout, pegs):
+        self.layout, self.pegs = layout, pegs & layout
+
+    @classmethod
+    def english(cls):
+        return cls(_lib.pt_english_layout(), _lib.pt_english_start())
+
+    @classmethod
+    def from_notation(cls, notation):
+        """Reads rows such as "..OOO../..OOO../OOOOOOO/OOO-OOO/...", where
+        O is a peg, - a hole and . a cell off the board."""
+        rows = notation.strip().split("/")
+        if len(rows) != 7 or any(len(row) != 7 for row in rows):
+            raise ValueError("a board has 7 rows of 7 cells")
+        layout = pegs = 0
+        for y, row in enumerate(rows):
+            for x, cell in enumerate(row):
+                bit = 1 << (x * 7 + y)
+                if cell in "Oo":
+                    layout |= bit
+                    pegs |= bit
+                elif cell == "-":
+                    layout |= bit
+                elif cell != ".":
+                    raise ValueError("unknown cell %r" % cell)
+        return cls(layout, pegs)
+
+    def notation(self):
+        rows = []
+        for y in range(7):
+            row = ""
+            for x in range(7):
+                bit = 1 << (x * 7 + y)
+                row += "." if not self.layout & bit else "O" if self.pegs & bit else "-"
+            rows.append(row)
+        return "/".join(rows)
+
+    def pegs_left(self):
+        return bin(self.pegs).count("1")
+
+    def single_peg(self, x, y):
+        return Grid(self.layout, 1 << (x * 7 + y))
+
+    def legal_moves(self):
+        count = _lib.pt_legal_count(self.layout, self.pegs)
+        return [Action._unpack(_lib.pt_legal_move(self.layout, self.pegs, i)) for i in range(count)]
+
+    def apply(self, action):
+        pegs = _lib.pt_apply(self.layout, self.pegs, action._pack())
+        if pegs == _NO_BOARD:
+            raise IllegalMove("%s is not legal" % action)
+        return Grid(self.layout, pegs)
+
+    def play(self, actions):
+        board = self
+        for action in actions:
+            board = board.apply(action)
+        return board
+
+    def solve(self, goal=None, timeout=None, chunk=10000):
+        """A list of moves down to `goal` (default: one peg in the centre),
+        or None when there is none. Raises TimeoutError after `timeout`
+        seconds."""
+        goal = goal or self.single_peg(3, 3)
+        handle = _lib.pt_solver_new(self.layout, self.pegs, goal.pegs)
+        try:
+            started = time.monotonic()
+            while True:
+                status = _lib.pt_solver_step(handle, chunk)
+                if status == 1:
+                    count = _lib.pt_solver_len(handle)
+                    return [Action._unpack(_lib.pt_solver_move(handle, i)) for i in range(count)]
+                if status == -1:
+                    return None
+                if timeout is not None and time.monotonic() - started > timeout:
+                    raise TimeoutError("no solution within %s seconds" % timeout)
+        finally:
+            _lib.pt_solver_free(handle)
+
+    def state_space(self, up_to_symmetry=True):
+        """Positions reachable in exactly 0, 1, 2, ... moves."""
+        counts = (ctypes.c_uint64 * 64)()
+        depths = _lib.pt_state_space(self.layout, self.pegs, up_to_symmetry, counts, 64)
+        return list(counts[:depths])
+
+    def __eq__(self, other):
+        return isinstance(other, Grid) and (self.layout, self.pegs) == (other.layout, other.pegs)
+
+    def __hash__(self):
+        return hash((self.layout, self.pegs))
+
+    def __repr__(self):
+        return "Grid.from_notation(%r)" % self.notation()
+
+
+def generate(pegs, seed, goal=None):
+    """A random position with `pegs` pegs on the English board that can be
+    played down to `goal`, or None."""
+    goal = goal or Grid.english().single_peg(3, 3)
+    bits = _lib.pt_generate(goal.layout, goal.pegs, pegs, seed)
+    return None if bits == _NO_BOARD else Grid(goal.layout, bits)
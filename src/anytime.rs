@@ -0,0 +1,107 @@
+//! A search that always has an answer: it reports the fewest pegs it has
+//! left so far, with the line that leaves them, each time that improves,
+//! and carries on until one peg is left, it has looked everywhere or its
+//! time is up. On a board too hard to solve, knowing it comes down to
+//! three pegs is still worth something.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::bitboard::JumpTable;
+use crate::symmetry::{self, Symmetry};
+use crate::trace::PROGRESS_INTERVAL;
+use crate::{Action, Grid};
+
+/// The best line found so far.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Best {
+    pub moves: Vec<Action>,
+    /// Pegs left at the end of `moves`.
+    pub pegs: u32,
+    /// Whether no line leaves fewer, because one peg is left or the search
+    /// has looked everywhere.
+    pub proven: bool,
+    /// Since the search started.
+    pub elapsed: Duration,
+    pub nodes_expanded: u64,
+}
+
+/// Plays down from `start` for as few pegs as possible, passing every
+/// improvement to `improved` as it is found, the first of them almost at
+/// once. Gives up after `timeout`, returning the best line so far.
+pub fn search(start: &Grid, timeout: Option<Duration>, improved: &mut dyn FnMut(&Best)) -> Best {
+    let started = Instant::now();
+    let mut search = Search {
+        table: JumpTable::new(start),
+        group: symmetry::layout_symmetries(start),
+        seen: HashSet::new(),
+        line: Vec::new(),
+        best: Best {
+            moves: Vec::new(),
+            pegs: start.filled_count(),
+            proven: start.filled_count() <= 1,
+            elapsed: Duration::ZERO,
+            nodes_expanded: 0,
+        },
+        started,
+        deadline: timeout.map(|timeout| started + timeout),
+        timed_out: false,
+        improved,
+    };
+    search.visit(start.encode());
+    let mut best = search.best;
+    best.proven |= !search.timed_out;
+    best.elapsed = started.elapsed();
+    best
+}
+
+struct Search<'a> {
+    table: JumpTable,
+    group: Vec<Symmetry>,
+    /// Canonical forms of the positions already explored, everything
+    /// after which has been accounted for.
+    seen: HashSet<u64>,
+    line: Vec<Action>,
+    best: Best,
+    started: Instant,
+    deadline: Option<Instant>,
+    timed_out: bool,
+    improved: &'a mut dyn FnMut(&Best),
+}
+
+impl Search<'_> {
+    /// Whether to stop looking, as one peg is left or the time is up.
+    fn done(&mut self) -> bool {
+        if !self.timed_out && self.best.nodes_expanded.is_multiple_of(PROGRESS_INTERVAL) {
+            self.timed_out = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.best.proven || self.timed_out
+    }
+
+    fn visit(&mut self, bits: u64) {
+        if !self.seen.insert(symmetry::canonical(bits, &self.group)) {
+            return;
+        }
+        self.best.nodes_expanded += 1;
+        let moves: Vec<(Action, u64)> = self.table.forward(bits).collect();
+        // Every jump takes a peg, so the fewest are left where none can be
+        // made.
+        if moves.is_empty() && bits.count_ones() < self.best.pegs {
+            self.best.moves = self.line.clone();
+            self.best.pegs = bits.count_ones();
+            self.best.proven = self.best.pegs == 1;
+            self.best.elapsed = self.started.elapsed();
+            (self.improved)(&self.best);
+        }
+        for (action, next) in moves {
+            if self.done() {
+                return;
+            }
+            self.line.push(action);
+            self.visit(next);
+            self.line.pop();
+        }
+    }
+}
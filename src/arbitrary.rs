@@ -0,0 +1,104 @@
+//! Random values for property tests, in the shape of quickcheck's
+//! `Arbitrary` (which this crate cannot depend on): boards are always
+//! reachable from the English start and moves always legal, so properties
+//! such as "performing then unperforming a jump changes nothing" can be
+//! checked with [`for_all`] here and downstream.
+
+use crate::rng::Rng;
+use crate::{Action, Grid};
+
+pub trait Arbitrary: Sized {
+    fn arbitrary(rng: &mut Rng) -> Self;
+}
+
+/// A position reached by up to 31 random jumps from the English start.
+impl Arbitrary for Grid {
+    fn arbitrary(rng: &mut Rng) -> Grid {
+        let mut grid = Grid::new();
+        for _ in 0..rng.below(32) {
+            let actions = grid.valid_actions();
+            if actions.is_empty() {
+                break;
+            }
+            grid = grid.perform_action(actions[rng.below(actions.len())]);
+        }
+        grid
+    }
+}
+
+/// A reachable position with a jump left, and one of its legal jumps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LegalMove {
+    pub grid: Grid,
+    pub action: Action,
+}
+
+impl Arbitrary for LegalMove {
+    fn arbitrary(rng: &mut Rng) -> LegalMove {
+        loop {
+            let grid = Grid::arbitrary(rng);
+            let actions = grid.valid_actions();
+            if !actions.is_empty() {
+                let action = actions[rng.below(actions.len())];
+                return LegalMove { grid, action };
+            }
+        }
+    }
+}
+
+/// A jump that is legal in some reachable position.
+impl Arbitrary for Action {
+    fn arbitrary(rng: &mut Rng) -> Action {
+        LegalMove::arbitrary(rng).action
+    }
+}
+
+/// Checks `property` against `cases` values drawn from a generator seeded
+/// with `seed`, returning the first value it fails for.
+pub fn for_all<T: Arbitrary>(
+    seed: u64,
+    cases: usize,
+    mut property: impl FnMut(&T) -> bool,
+) -> Result<(), T> {
+    let mut rng = Rng::new(seed);
+    for _ in 0..cases {
+        let value = T::arbitrary(&mut rng);
+        if !property(&value) {
+            return Err(value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::symmetry::{self, Symmetry};
+
+    #[test]
+    fn unperforming_a_jump_restores_the_grid() {
+        let undone = for_all(1, 500, |m: &LegalMove| {
+            m.grid.perform_action(m.action).unperform_action(m.action) == m.grid
+        });
+        assert_eq!(undone, Ok(()));
+    }
+
+    #[test]
+    fn canonical_is_the_same_for_every_symmetry() {
+        let invariant = for_all(2, 500, |grid: &Grid| {
+            let canonical = symmetry::canonical(grid.encode(), &Symmetry::ALL);
+            Symmetry::ALL.iter().all(|s| {
+                symmetry::canonical(s.apply_bits(grid.encode()), &Symmetry::ALL) == canonical
+            })
+        });
+        assert_eq!(invariant, Ok(()));
+    }
+
+    #[test]
+    fn display_parses_back() {
+        let round_trip = for_all(3, 500, |grid: &Grid| grid.to_string().parse() == Ok(*grid));
+        assert_eq!(round_trip, Ok(()));
+    }
+}
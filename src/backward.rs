@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::bitboard::JumpTable;
+use crate::stats::SearchStats;
+use crate::symmetry::{self, Symmetry};
+use crate::trace::{self, Level};
+use crate::{Action, Grid};
+
+/// Builds up from `goal` with inverse jumps until `start` is produced and
+/// returns the forward move sequence from `start` to `goal`.
+///
+/// Inverse jumps only ever add pegs, so positions with more pegs than
+/// `start` are never expanded and the search always terminates. Every
+/// position discovered this way can be played back down to the goal, which
+/// is what the forward line is rebuilt from once `start` turns up.
+pub fn search(start: &Grid, goal: &Grid) -> Option<Vec<Action>> {
+    search_counted(
+        start,
+        goal,
+        &mut SearchStats::default(),
+        &AtomicBool::new(false),
+        None,
+    )
+}
+
+/// The search behind [`search`], counting its work in `stats` and giving up
+/// once `stop` is set. A `seed` shuffles the order moves are tried in.
+pub(crate) fn search_counted(
+    start: &Grid,
+    goal: &Grid,
+    stats: &mut SearchStats,
+    stop: &AtomicBool,
+    seed: Option<u64>,
+) -> Option<Vec<Action>> {
+    let table = JumpTable::ordered(start, seed);
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let canon = |bits| symmetry::canonical(bits, &group);
+
+    let target = canon(start.encode());
+    let limit = start.filled_count;
+    let root = goal.encode();
+    let mut seen = HashSet::from([canon(root)]);
+    let mut stack = vec![root];
+    let mut found = false;
+    while let Some(cur) = stack.pop() {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        if canon(cur) == target {
+            found = true;
+            break;
+        }
+        if cur.count_ones() >= limit {
+            stats.prune("peg count");
+            continue;
+        }
+        stats.nodes_expanded += 1;
+        if stats
+            .nodes_expanded
+            .is_multiple_of(trace::PROGRESS_INTERVAL)
+        {
+            trace::event(
+                Level::Debug,
+                "backward",
+                "progress",
+                &[
+                    ("expanded", &stats.nodes_expanded),
+                    ("frontier", &stack.len()),
+                    ("memo", &seen.len()),
+                ],
+            );
+        }
+        for (_, prev) in table.backward(cur) {
+            if seen.insert(canon(prev)) {
+                stack.push(prev);
+            } else {
+                stats.duplicates += 1;
+            }
+        }
+        stats.observe_memo(&seen);
+        stats.observe_frontier(stack.len());
+    }
+    if !found {
+        return None;
+    }
+
+    let mut moves = Vec::new();
+    let mut cur = start.encode();
+    while cur != root {
+        let (action, next) = table
+            .forward(cur)
+            .find(|&(_, n)| seen.contains(&canon(n)))
+            .unwrap();
+        moves.push(action);
+        cur = next;
+    }
+    Some(moves)
+}
+
+/// Whether `position` can occur in some game that ends in `goal`, i.e.
+/// whether `goal` can be built back up into `position`.
+pub fn reachable_from_goal(goal: &Grid, position: &Grid) -> bool {
+    search(position, goal).is_some()
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::bitboard::JumpTable;
+use crate::stats::SearchStats;
+use crate::symmetry::{self, Symmetry};
+use crate::trace::{self, Level};
+use crate::{Action, Grid};
+
+/// Meet-in-the-middle search from `start` to `goal` on the same layout.
+///
+/// Every jump removes exactly one peg, so the positions `d` moves after the
+/// start and `d` inverse moves before the goal form disjoint layers. Both
+/// sides are expanded breadth-first, always growing the smaller frontier,
+/// until their depths add up to the required number of moves; any position
+/// shared by the two final layers links a full solution. Layers store one
+/// representative per symmetry class of the board that also fixes the goal.
+pub fn search(start: &Grid, goal: &Grid) -> Option<Vec<Action>> {
+    search_counted(
+        start,
+        goal,
+        &mut SearchStats::default(),
+        &AtomicBool::new(false),
+        None,
+    )
+}
+
+/// The search behind [`search`], counting its work in `stats` and giving up
+/// once `stop` is set. A `seed` shuffles the order moves are tried in.
+pub(crate) fn search_counted(
+    start: &Grid,
+    goal: &Grid,
+    stats: &mut SearchStats,
+    stop: &AtomicBool,
+    seed: Option<u64>,
+) -> Option<Vec<Action>> {
+    let total = start.filled_count.checked_sub(goal.filled_count)? as usize;
+    let table = JumpTable::ordered(start, seed);
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let canon = |bits| symmetry::canonical(bits, &group);
+
+    let start_bits = start.encode();
+    let mut forward = vec![HashSet::from([canon(start_bits)])];
+    let mut backward = vec![HashSet::from([canon(goal.encode())])];
+    while forward.len() + backward.len() - 2 < total {
+        let grow_forward = forward.last().unwrap().len() <= backward.last().unwrap().len();
+        let mut next = HashSet::new();
+        let layer = if grow_forward { &forward } else { &backward };
+        let depth = layer.len();
+        stats.nodes_expanded += layer.last().unwrap().len() as u64;
+        let mut generated = 0u64;
+        if grow_forward {
+            for &bits in forward.last().unwrap() {
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                for (_, n) in table.forward(bits) {
+                    next.insert(canon(n));
+                    generated += 1;
+                }
+            }
+        } else {
+            for &bits in backward.last().unwrap() {
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                for (_, p) in table.backward(bits) {
+                    next.insert(canon(p));
+                    generated += 1;
+                }
+            }
+        }
+        stats.duplicates += generated - next.len() as u64;
+        stats.observe_frontier(next.len());
+        if next.is_empty() {
+            return None;
+        }
+        trace::event(
+            Level::Debug,
+            "bidirectional",
+            "layer",
+            &[
+                ("side", &if grow_forward { "forward" } else { "backward" }),
+                ("depth", &depth),
+                ("size", &next.len()),
+                ("expanded", &stats.nodes_expanded),
+            ],
+        );
+        if grow_forward {
+            forward.push(next);
+        } else {
+            backward.push(next);
+        }
+        let (entries, bytes) =
+            forward
+                .iter()
+                .chain(&backward)
+                .fold((0, 0), |(entries, bytes), layer| {
+                    (
+                        entries + layer.len(),
+                        bytes + layer.capacity() * (mem::size_of::<u64>() + 1),
+                    )
+                });
+        stats.observe_memo_size(entries, bytes);
+    }
+
+    let meet = *forward
+        .last()
+        .unwrap()
+        .intersection(backward.last().unwrap())
+        .next()?;
+
+    // Walk back to some symmetric image of the start, then map the line
+    // back onto the real start position.
+    let mut moves = Vec::with_capacity(total);
+    let mut cur = meet;
+    for layer in forward.iter().rev().skip(1) {
+        let (action, prev) = table
+            .backward(cur)
+            .find(|&(_, p)| layer.contains(&canon(p)))
+            .unwrap();
+        moves.push(action);
+        cur = prev;
+    }
+    moves.reverse();
+    let image = *group
+        .iter()
+        .find(|s| s.apply_bits(start_bits) == cur)
+        .unwrap();
+    let undo = image.inverse();
+    for action in &mut moves {
+        *action = undo.apply_action(*action);
+    }
+
+    let mut cur = undo.apply_bits(meet);
+    for layer in backward.iter().rev().skip(1) {
+        let (action, next) = table
+            .forward(cur)
+            .find(|&(_, n)| layer.contains(&canon(n)))
+            .unwrap();
+        moves.push(action);
+        cur = next;
+    }
+    Some(moves)
+}
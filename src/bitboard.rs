@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use crate::rng::Rng;
+#[cfg(feature = "std")]
+use crate::symmetry::{self, Symmetry};
+use crate::topology::Topology;
+use crate::{Action, Direction, Grid, Tile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Jump {
+    action: Action,
+    from: u64,
+    over: u64,
+    to: u64,
+}
+
+/// Every jump that fits on a layout, precomputed so that positions encoded
+/// with [`Grid::encode`] can be expanded with a handful of bit operations.
+#[derive(Clone, Debug)]
+pub struct JumpTable {
+    jumps: Vec<Jump>,
+}
+
+impl JumpTable {
+    pub fn new(layout: &Grid) -> JumpTable {
+        JumpTable::with_topology(layout, Topology::Flat)
+    }
+
+    /// The jumps that fit on `layout` when its edges are joined as
+    /// `topology` says.
+    pub fn with_topology(layout: &Grid, topology: Topology) -> JumpTable {
+        let mut jumps = Vec::new();
+        for x in 0..7 {
+            for y in 0..7 {
+                for dir in Direction::ALL {
+                    let action = Action { x, y, dir };
+                    let Some(((mx, my), (tx, ty))) = topology.span(action) else {
+                        continue;
+                    };
+                    let cells = [(x, y), (mx, my), (tx, ty)];
+                    if cells
+                        .iter()
+                        .any(|&(cx, cy)| layout.grid[cx][cy] == Tile::Blocked)
+                    {
+                        continue;
+                    }
+                    jumps.push(Jump {
+                        action,
+                        from: 1 << (x * 7 + y),
+                        over: 1 << (mx * 7 + my),
+                        to: 1 << (tx * 7 + ty),
+                    });
+                }
+            }
+        }
+        JumpTable { jumps }
+    }
+
+    /// The table with its jumps, and so the order positions are expanded
+    /// in, permuted by a generator seeded with `seed`.
+    pub fn shuffled(mut self, seed: u64) -> JumpTable {
+        Rng::new(seed).shuffle(&mut self.jumps);
+        self
+    }
+
+    /// [`JumpTable::new`], shuffled when a seed is given.
+    #[cfg(feature = "std")]
+    pub(crate) fn ordered(layout: &Grid, seed: Option<u64>) -> JumpTable {
+        let table = JumpTable::new(layout);
+        match seed {
+            Some(seed) => table.shuffled(seed),
+            None => table,
+        }
+    }
+
+    /// Every jump that fits on the layout, legal or not.
+    pub fn jumps(&self) -> impl Iterator<Item = Action> + '_ {
+        self.jumps.iter().map(|j| j.action)
+    }
+
+    /// Legal jumps from `pegs` and the positions they lead to.
+    pub fn forward(&self, pegs: u64) -> impl Iterator<Item = (Action, u64)> + '_ {
+        self.jumps.iter().filter_map(move |j| {
+            let legal = pegs & j.from != 0 && pegs & j.over != 0 && pegs & j.to == 0;
+            legal.then_some((j.action, pegs ^ (j.from | j.over | j.to)))
+        })
+    }
+
+    /// Jumps that could have produced `pegs` and the positions they came from.
+    pub fn backward(&self, pegs: u64) -> impl Iterator<Item = (Action, u64)> + '_ {
+        self.jumps.iter().filter_map(move |j| {
+            let legal = pegs & j.from == 0 && pegs & j.over == 0 && pegs & j.to != 0;
+            legal.then_some((j.action, pegs ^ (j.from | j.over | j.to)))
+        })
+    }
+
+    /// Rebuilds a line from `start` to `end`, where `known` accepts the
+    /// canonical form (under `group`) of every position reached from
+    /// `start` and `end` is one of them. The search walks back from `end`
+    /// to an image of `start` and maps the moves onto `start` itself.
+    #[cfg(feature = "std")]
+    pub(crate) fn line_between(
+        &self,
+        start: u64,
+        end: u64,
+        group: &[Symmetry],
+        known: impl Fn(u64) -> bool,
+    ) -> Vec<Action> {
+        let mut moves = Vec::new();
+        let mut cur = end;
+        while cur.count_ones() < start.count_ones() {
+            let (action, prev) = self
+                .backward(cur)
+                .find(|&(_, p)| known(symmetry::canonical(p, group)))
+                .unwrap();
+            moves.push(action);
+            cur = prev;
+        }
+        moves.reverse();
+        let image = group.iter().find(|s| s.apply_bits(start) == cur).unwrap();
+        let undo = image.inverse();
+        moves.into_iter().map(|a| undo.apply_action(a)).collect()
+    }
+}
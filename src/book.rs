@@ -0,0 +1,169 @@
+//! An opening book of solution lines for the standard problems on the
+//! English board. A position anywhere along a line, in any rotation or
+//! reflection, is answered with the rest of the line without searching.
+
+use alloc::vec::Vec;
+
+use crate::notation;
+use crate::symmetry::Symmetry;
+use crate::{Action, Grid};
+
+/// A named problem and a line that solves it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: &'static str,
+    /// Where the problem comes from and who found the line.
+    pub source: &'static str,
+    start: &'static str,
+    /// The cell the last peg ends on.
+    finish: (usize, usize),
+    line: &'static str,
+}
+
+const FOUND: &str = "traditional problem; line found by the depth-first search";
+
+pub const BOOK: &[Entry] = &[
+    Entry {
+        name: "Central game",
+        source: "English board, centre vacancy to centre peg; line found by the depth-first search",
+        start: "..OOO../..OOO../OOOOOOO/OOO-OOO/OOOOOOO/..OOO../..OOO..",
+        finish: (3, 3),
+        line: "f4-d4 e6-e4 g5-e5 g3-g5 e4-e6 e7-e5 e3-g3 e1-e3 d5-f5 g5-e5 d7-d5 d5-f5 \
+               d3-d5 d1-d3 d3-f3 g3-e3 c5-e5 f5-d5 c7-c5 c4-c6 b3-d3 c1-c3 a5-c5 d5-b5 \
+               a3-a5 a5-c5 c6-c4 c4-c2 e3-c3 c2-c4 b4-d4",
+    },
+    Entry {
+        name: "Corner game",
+        source: "English board, c1 vacancy to c1 peg; line found by the backward search",
+        start: "..-OO../..OOO../OOOOOOO/OOOOOOO/OOOOOOO/..OOO../..OOO..",
+        finish: (2, 0),
+        line: "c3-c1 a3-c3 c4-c2 a4-c4 c1-c3 c4-c2 c6-c4 a5-c5 c4-c6 e3-c3 c2-c4 c7-c5 \
+               c4-c6 d5-d3 f5-d5 e7-e5 c6-e6 d2-d4 d4-d6 d7-d5 d5-f5 e1-e3 e3-e5 e6-e4 \
+               g3-e3 e4-e2 f5-f3 g5-g3 g3-e3 e3-e1 e1-c1",
+    },
+    Entry {
+        name: "Corner to far corner",
+        source: "English board, c1 vacancy to c7 peg; line found by the backward search",
+        start: "..-OO../..OOO../OOOOOOO/OOOOOOO/OOOOOOO/..OOO../..OOO..",
+        finish: (2, 6),
+        line: "c3-c1 a3-c3 c4-c2 c6-c4 a5-c5 c4-c6 a4-c4 c1-c3 c7-c5 c4-c6 e5-c5 d3-d5 \
+               f3-d3 c3-e3 c5-e5 f5-d5 e7-e5 c6-e6 d1-d3 d3-f3 d5-f5 e1-e3 e3-e5 e6-e4 \
+               g5-e5 e4-e6 f3-f5 g3-g5 g5-e5 e5-e7 e7-c7",
+    },
+    Entry {
+        name: "Cross",
+        source: FOUND,
+        start: "..---../..-O-../--OOO--/---O---/---O---/..---../..---..",
+        finish: (3, 3),
+        line: "d3-f3 d5-d3 c3-e3 f3-d3 d2-d4",
+    },
+    Entry {
+        name: "Plus",
+        source: FOUND,
+        start: "..---../..-O-../---O---/-OOOOO-/---O---/..-O-../..---..",
+        finish: (3, 3),
+        line: "e4-g4 c4-e4 d6-d4 d4-f4 g4-e4 d2-d4 e4-c4 b4-d4",
+    },
+    Entry {
+        name: "Fireplace",
+        source: FOUND,
+        start: "..OOO../..OOO../--OOO--/--O-O--/-------/..---../..---..",
+        finish: (3, 3),
+        line: "d3-f3 e1-e3 e4-e2 c1-e1 e1-e3 f3-d3 d2-d4 d4-b4 c2-c4 b4-d4",
+    },
+    Entry {
+        name: "Lamp",
+        source: FOUND,
+        start: "..-O-../..OOO../--OOO--/--OOO--/--OOO--/..---../..---..",
+        finish: (3, 3),
+        line: "e4-e6 e3-e1 e1-c1 d4-d6 e6-c6 d3-b3 c1-c3 b3-d3 d2-d4 d4-b4 c6-c4 b4-d4",
+    },
+    Entry {
+        name: "Pyramid",
+        source: FOUND,
+        start: "..---../..-O-../--OOO--/-OOOOO-/OOOOOOO/..---../..---..",
+        finish: (3, 3),
+        line: "f5-f3 e4-e6 d3-b3 f3-d3 c5-c3 a5-c5 c5-e5 d3-d5 d5-f5 g5-e5 e6-e4 b3-d3 \
+               d2-d4 e4-c4 b4-d4",
+    },
+    Entry {
+        name: "Arrow",
+        source: FOUND,
+        start: "..-O-../..OOO../-OOOOO-/---O---/---O---/..OOO../..OOO..",
+        finish: (3, 3),
+        line: "e7-e5 e5-c5 e3-e1 e1-c1 d7-d5 d5-b5 d3-d5 c7-c5 b3-d3 c1-c3 c3-e3 f3-d3 \
+               d2-d4 d4-d6 b5-d5 d6-d4",
+    },
+    Entry {
+        name: "Diamond",
+        source: FOUND,
+        start: "..-O-../..OOO../-OOOOO-/OOO-OOO/-OOOOO-/..OOO../..-O-..",
+        finish: (3, 3),
+        line: "f4-d4 e6-e4 e3-e5 e5-g5 g5-g3 g3-e3 e3-e1 e1-c1 d3-d1 c5-e5 d7-d5 e5-c5 \
+               c5-a5 b3-d3 d4-d2 d1-d3 c1-c3 d3-b3 a5-a3 a3-c3 c3-c5 c6-c4 b4-d4",
+    },
+];
+
+impl Entry {
+    pub fn start(&self) -> Grid {
+        self.start.parse().unwrap()
+    }
+
+    pub fn goal(&self) -> Grid {
+        let (x, y) = self.finish;
+        self.start().single_peg(x, y)
+    }
+
+    pub fn moves(&self) -> Vec<Action> {
+        self.line
+            .split_whitespace()
+            .flat_map(|jump| notation::parse(jump).unwrap())
+            .collect()
+    }
+}
+
+/// The line `solve` prints for [`Grid::new`] and the centre goal with its
+/// default settings, without searching: the one the depth-first search
+/// finds, kept in [`BOOK`] as the central game.
+pub fn instant(position: &Grid, goal: &Grid) -> Option<Vec<Action>> {
+    let central = &BOOK[0];
+    (*position == central.start() && *goal == central.goal()).then(|| central.moves())
+}
+
+/// A book answer: the entry, and the rest of its line from the position
+/// asked about, already turned to match it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub entry: &'static Entry,
+    /// How many moves of the line had already been played.
+    pub played: usize,
+    pub moves: Vec<Action>,
+}
+
+/// Looks `position` up in every entry that finishes on `goal`, in any of
+/// the board's eight orientations.
+pub fn lookup(position: &Grid, goal: &Grid) -> Option<Hit> {
+    for entry in BOOK {
+        let moves = entry.moves();
+        for symmetry in Symmetry::ALL {
+            if symmetry.apply_grid(&entry.goal()) != *goal {
+                continue;
+            }
+            let turned: Vec<Action> = moves.iter().map(|&a| symmetry.apply_action(a)).collect();
+            let mut state = symmetry.apply_grid(&entry.start());
+            for played in 0..=turned.len() {
+                if state == *position {
+                    return Some(Hit {
+                        entry,
+                        played,
+                        moves: turned[played..].to_vec(),
+                    });
+                }
+                if let Some(&action) = turned.get(played) {
+                    state = state.perform_action(action);
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::multimove;
+use crate::notation::{self, Standard};
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, Grid};
+
+/// What is known about a board and goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cached {
+    /// With the line that takes the fewest chained moves found so far.
+    Solvable(Vec<Action>),
+    Unsolvable,
+}
+
+/// The layout, the goal and the canonical board.
+type Key = (u64, u64, u64);
+
+/// Results of earlier searches, optionally kept in a file so that later
+/// runs can skip positions already settled. Boards are stored up to the
+/// symmetries they share with their goal, so a rotated or mirrored copy of
+/// a known board is a hit too.
+///
+/// The file is plain text and only ever appended to, one line per result:
+///
+/// ```text
+/// LAYOUT GOAL BOARD solvable d2-d4 f3-d3 ...
+/// LAYOUT GOAL BOARD unsolvable
+/// ```
+///
+/// with the three bitmasks of [`Grid::encode`] in hex. When a key appears
+/// more than once the best line wins.
+#[derive(Debug, Default)]
+pub struct SolutionCache {
+    entries: HashMap<Key, Cached>,
+    file: Option<File>,
+}
+
+fn invalid(path: &Path, line: usize, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: line {}: {}", path.display(), line, message),
+    )
+}
+
+impl SolutionCache {
+    /// Loads the cache at `path`, creating the file if it does not exist,
+    /// and appends new results to it from then on.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<SolutionCache> {
+        let path = path.as_ref();
+        let mut cache = SolutionCache::default();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let mut hex = || {
+                words
+                    .next()
+                    .and_then(|w| u64::from_str_radix(w, 16).ok())
+                    .ok_or_else(|| invalid(path, i + 1, "expected three hex bitmasks"))
+            };
+            let key = (hex()?, hex()?, hex()?);
+            let entry = match words.next() {
+                Some("unsolvable") => Cached::Unsolvable,
+                Some("solvable") => {
+                    let mut moves = Vec::new();
+                    for word in words {
+                        moves.extend(notation::parse(word).map_err(|e| invalid(path, i + 1, e))?);
+                    }
+                    Cached::Solvable(moves)
+                }
+                _ => return Err(invalid(path, i + 1, "expected solvable or unsolvable")),
+            };
+            cache.merge(key, entry);
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if text.is_empty() {
+            writeln!(file, "# puzzlething solution cache")?;
+        }
+        cache.file = Some(file);
+        Ok(cache)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// What is known about playing `board` down to `goal`, with any line
+    /// turned to fit `board`.
+    pub fn get(&self, board: &Grid, goal: &Grid) -> Option<Cached> {
+        let (key, symmetry) = key(board, goal);
+        Some(match self.entries.get(&key)? {
+            Cached::Solvable(moves) => {
+                let back = symmetry.inverse();
+                Cached::Solvable(moves.iter().map(|&a| back.apply_action(a)).collect())
+            }
+            Cached::Unsolvable => Cached::Unsolvable,
+        })
+    }
+
+    /// Remembers a result, writing it to the file unless it is no better
+    /// than what was already known.
+    pub fn record(&mut self, board: &Grid, goal: &Grid, result: &Cached) -> io::Result<()> {
+        let (key, symmetry) = key(board, goal);
+        let entry = match result {
+            Cached::Solvable(moves) => {
+                Cached::Solvable(moves.iter().map(|&a| symmetry.apply_action(a)).collect())
+            }
+            Cached::Unsolvable => Cached::Unsolvable,
+        };
+        if !self.merge(key, entry.clone()) {
+            return Ok(());
+        }
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        let (layout, goal, board) = key;
+        match entry {
+            Cached::Solvable(moves) => {
+                let moves: Vec<String> = moves.iter().map(|&a| Standard(a).to_string()).collect();
+                writeln!(
+                    file,
+                    "{:x} {:x} {:x} solvable {}",
+                    layout,
+                    goal,
+                    board,
+                    moves.join(" ")
+                )
+            }
+            Cached::Unsolvable => writeln!(file, "{:x} {:x} {:x} unsolvable", layout, goal, board),
+        }
+    }
+
+    /// Keeps `entry` if it is new or a better line, and says whether it
+    /// did.
+    fn merge(&mut self, key: Key, entry: Cached) -> bool {
+        let better = match (self.entries.get(&key), &entry) {
+            (None, _) => true,
+            (Some(Cached::Solvable(old)), Cached::Solvable(new)) => {
+                multimove::chain(new).len() < multimove::chain(old).len()
+            }
+            _ => false,
+        };
+        if better {
+            self.entries.insert(key, entry);
+        }
+        better
+    }
+}
+
+/// The key for `board` and `goal`, and the symmetry that turns `board`
+/// into the canonical board of the key.
+fn key(board: &Grid, goal: &Grid) -> (Key, Symmetry) {
+    let layout = symmetry::layout_symmetries(board);
+    let bits = board.encode();
+    let (canonical, symmetry) = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .map(|s| (s.apply_bits(bits), s))
+        .min_by_key(|&(b, _)| b)
+        .unwrap_or((bits, Symmetry::Identity));
+    let key = (board.with_pegs(u64::MAX).encode(), goal.encode(), canonical);
+    (key, symmetry)
+}
@@ -0,0 +1,72 @@
+//! The clock and the score of a timed challenge: clear as much of a board
+//! as possible before the time runs out.
+
+use std::time::{Duration, Instant};
+
+/// Points for every peg removed.
+pub const PEG_POINTS: u64 = 100;
+/// Points for every whole second left on the clock when the goal is
+/// reached.
+pub const SECOND_POINTS: u64 = 10;
+
+/// A countdown that can be paused, so that time spent away from the board
+/// does not count.
+#[derive(Clone, Copy, Debug)]
+pub struct Countdown {
+    limit: Duration,
+    /// Time used up to the last pause.
+    used: Duration,
+    /// When the clock last started running, unless it is paused.
+    running_since: Option<Instant>,
+}
+
+impl Countdown {
+    /// A running countdown from `limit`.
+    pub fn start(limit: Duration) -> Countdown {
+        Countdown {
+            limit,
+            used: Duration::ZERO,
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.used += since.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        let running = self
+            .running_since
+            .map_or(Duration::ZERO, |since| since.elapsed());
+        self.limit.saturating_sub(self.used + running)
+    }
+
+    pub fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+/// [`PEG_POINTS`] for each of the pegs removed from the `start_pegs`, and
+/// when the goal was reached [`SECOND_POINTS`] for each second of
+/// `remaining` time, so that a fast solve beats a slow one.
+pub fn score(start_pegs: u32, pegs_left: u32, solved: bool, remaining: Duration) -> u64 {
+    let removed = u64::from(start_pegs.saturating_sub(pegs_left));
+    let bonus = if solved {
+        remaining.as_secs() * SECOND_POINTS
+    } else {
+        0
+    };
+    removed * PEG_POINTS + bonus
+}
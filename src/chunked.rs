@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::bitboard::JumpTable;
+use crate::feasibility;
+use crate::stats::SearchStats;
+use crate::{Action, GameTree, Grid};
+
+/// Where a [`ChunkedSearch`] has got to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    Searching,
+    Solved(Vec<Action>),
+    Unsolvable,
+}
+
+/// A depth-first search that expands a bounded number of positions per
+/// call to [`ChunkedSearch::step`], so that a caller which must not block,
+/// such as a browser's event loop, can spread the work out and show
+/// progress in between.
+#[derive(Clone, Debug)]
+pub struct ChunkedSearch {
+    table: JumpTable,
+    goal: u64,
+    start: u64,
+    stack: Vec<u64>,
+    /// Every position seen, with the position and jump it was first
+    /// reached by.
+    parents: HashMap<u64, Option<(u64, Action)>>,
+    progress: Progress,
+    stats: SearchStats,
+}
+
+impl ChunkedSearch {
+    pub fn new(start: &Grid, goal: &Grid) -> ChunkedSearch {
+        let bits = start.encode();
+        let progress = match feasibility::check(start, goal) {
+            Ok(()) => Progress::Searching,
+            Err(_) => Progress::Unsolvable,
+        };
+        ChunkedSearch {
+            table: JumpTable::new(start),
+            goal: goal.encode(),
+            start: bits,
+            stack: vec![bits],
+            parents: HashMap::from([(bits, None)]),
+            progress,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Expands up to `budget` positions and reports where the search
+    /// stands. Once it has finished, further calls do nothing.
+    pub fn step(&mut self, budget: u64) -> &Progress {
+        for _ in 0..budget {
+            if self.progress != Progress::Searching {
+                break;
+            }
+            let Some(bits) = self.stack.pop() else {
+                self.progress = Progress::Unsolvable;
+                break;
+            };
+            if bits == self.goal {
+                self.progress = Progress::Solved(self.line_to(bits));
+                break;
+            }
+            self.stats.nodes_expanded += 1;
+            if bits.count_ones() <= self.goal.count_ones() {
+                continue;
+            }
+            for (action, next) in self.table.forward(bits) {
+                if self.parents.contains_key(&next) {
+                    self.stats.duplicates += 1;
+                    continue;
+                }
+                self.parents.insert(next, Some((bits, action)));
+                self.stack.push(next);
+            }
+            self.stats.observe_frontier(self.stack.len());
+        }
+        let entry = mem::size_of::<(u64, Option<(u64, Action)>)>() + 1;
+        self.stats
+            .observe_memo_size(self.parents.len(), self.parents.capacity() * entry);
+        &self.progress
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// Work done so far; `elapsed` is left at zero because the time between
+    /// steps belongs to the caller.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    fn line_to(&self, mut bits: u64) -> Vec<Action> {
+        let mut moves = Vec::new();
+        while bits != self.start {
+            let (prev, action) = self.parents[&bits].unwrap();
+            moves.push(action);
+            bits = prev;
+        }
+        moves.reverse();
+        moves
+    }
+}
+
+/// The future returned by [`GameTree::solve_async`]. Each poll expands up
+/// to `yield_every` positions and, unless that finished the search, wakes
+/// its task and returns `Pending`, so that the executor's other tasks can
+/// run in between. It needs no particular runtime.
+#[derive(Clone, Debug)]
+pub struct SolveFuture {
+    search: ChunkedSearch,
+    yield_every: u64,
+    from: GameTree,
+}
+
+impl SolveFuture {
+    pub(crate) fn new(from: &GameTree, goal: &Grid, yield_every: u64) -> SolveFuture {
+        SolveFuture {
+            search: ChunkedSearch::new(from.state(), goal),
+            yield_every: yield_every.max(1),
+            from: from.clone(),
+        }
+    }
+
+    /// Work done so far.
+    pub fn stats(&self) -> &SearchStats {
+        self.search.stats()
+    }
+}
+
+impl Future for SolveFuture {
+    type Output = Option<GameTree>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<GameTree>> {
+        let this = self.get_mut();
+        match this.search.step(this.yield_every) {
+            Progress::Searching => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Progress::Solved(moves) => Poll::Ready(Some(this.from.extend(moves))),
+            Progress::Unsolvable => Poll::Ready(None),
+        }
+    }
+}
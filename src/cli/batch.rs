@@ -0,0 +1,204 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::time::Duration;
+
+use crate::cache::{Cached, SolutionCache};
+use crate::json::{self, Json, ToJson};
+use crate::multimove;
+use crate::parallel::{self, BatchOptions, Outcome};
+use crate::stats::SearchStats;
+use crate::{Action, Grid};
+
+use super::{fail, Format, Result};
+
+/// Parses the multi-line board collected in `block`, if any, and empties it.
+fn flush(block: &mut String, boards: &mut Vec<Grid>) -> Result<()> {
+    if !block.trim().is_empty() {
+        boards.push(block.parse()?);
+    }
+    block.clear();
+    Ok(())
+}
+
+/// Splits a batch file into boards. A line with a `/` is a board in
+/// notation and a line of hex digits is the bit encoding of the pegs on the
+/// English board; anything else is read as part of a multi-line board that
+/// ends at the next blank line.
+fn parse_boards(text: &str) -> Result<Vec<Grid>> {
+    let mut boards = Vec::new();
+    let mut block = String::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let hex = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+        if trimmed.is_empty() {
+            flush(&mut block, &mut boards)?;
+        } else if trimmed.starts_with('#') {
+            continue;
+        } else if trimmed.contains('/') {
+            flush(&mut block, &mut boards)?;
+            boards.push(trimmed.parse()?);
+        } else if hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            flush(&mut block, &mut boards)?;
+            let pegs = u64::from_str_radix(hex, 16)?;
+            boards.push(Grid::new().with_pegs(pegs));
+        } else {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    flush(&mut block, &mut boards)?;
+    Ok(boards)
+}
+
+const CSV_HEADER: &str = "board,solvable,moves,chained_moves,nodes_expanded,seconds";
+
+/// One CSV row; the fields a timed-out search never learned are left empty.
+fn csv_row(index: usize, outcome: &Outcome, timeout: Option<Duration>) -> String {
+    match outcome {
+        Outcome::Solved(moves, stats) => format!(
+            "{},true,{},{},{},{:.6}",
+            index,
+            moves.len(),
+            multimove::chain(moves).len(),
+            stats.nodes_expanded,
+            stats.elapsed.as_secs_f64()
+        ),
+        Outcome::Unsolvable(stats) => format!(
+            "{},false,,,{},{:.6}",
+            index,
+            stats.nodes_expanded,
+            stats.elapsed.as_secs_f64()
+        ),
+        Outcome::Timeout => format!(
+            "{},,,,,{:.6}",
+            index,
+            timeout.unwrap_or_default().as_secs_f64()
+        ),
+    }
+}
+
+fn cached_outcome(cached: Cached) -> Outcome {
+    match cached {
+        Cached::Solvable(moves) => Outcome::Solved(moves, SearchStats::default()),
+        Cached::Unsolvable => Outcome::Unsolvable(SearchStats::default()),
+    }
+}
+
+/// Solves every board in `input` and writes one tab-separated line per
+/// board: its number, `solved`, `unsolvable` or `timeout`, the board in
+/// notation and the moves separated by `; `. The JSON format writes an
+/// object holding the same fields per line instead, and the CSV format a
+/// row of statistics. The boards are solved one at a time unless
+/// `threaded`, in which case they share a pool of `options.threads`
+/// workers. Boards found in `cache` are not searched, and the
+/// results of those that are go into it.
+pub(super) fn run(
+    input: &str,
+    output: Option<&str>,
+    options: BatchOptions,
+    threaded: bool,
+    mut cache: Option<SolutionCache>,
+    format: Format,
+) -> Result<()> {
+    let boards =
+        parse_boards(&fs::read_to_string(input)?).map_err(|e| format!("{}: {}", input, e))?;
+    if boards.is_empty() {
+        return fail(format!("{} holds no boards", input));
+    }
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    if format == Format::Csv {
+        writeln!(out, "{}", CSV_HEADER)?;
+    }
+    let (mut solved, mut unsolvable, mut timed_out) = (0, 0, 0);
+    let BatchOptions {
+        goal,
+        strategy,
+        timeout,
+        ..
+    } = options;
+    let goal_of = |board: &Grid| goal.unwrap_or_else(|| board.single_peg(3, 3));
+    let hits: Vec<Option<Cached>> = boards
+        .iter()
+        .map(|board| cache.as_ref().and_then(|c| c.get(board, &goal_of(board))))
+        .collect();
+    // Solved one at a time, each line is written as soon as it is known.
+    let outcomes: Box<dyn Iterator<Item = Outcome>> = if threaded {
+        let misses: Vec<Grid> = boards
+            .iter()
+            .zip(&hits)
+            .filter(|(_, hit)| hit.is_none())
+            .map(|(board, _)| *board)
+            .collect();
+        let mut searched = parallel::solve_many(&misses, &options).into_iter();
+        Box::new(hits.into_iter().map(move |hit| match hit {
+            Some(cached) => cached_outcome(cached),
+            None => searched.next().unwrap(),
+        }))
+    } else {
+        Box::new(boards.iter().zip(hits).map(|(board, hit)| match hit {
+            Some(cached) => cached_outcome(cached),
+            None => parallel::solve_one(*board, goal_of(board), strategy, timeout),
+        }))
+    };
+    for (i, (board, outcome)) in boards.iter().zip(outcomes).enumerate() {
+        if let Some(cache) = &mut cache {
+            let result = match &outcome {
+                Outcome::Solved(moves, _) => Some(Cached::Solvable(moves.clone())),
+                Outcome::Unsolvable(_) => Some(Cached::Unsolvable),
+                Outcome::Timeout => None,
+            };
+            if let Some(result) = result {
+                cache.record(board, &goal_of(board), &result)?;
+            }
+        }
+        let (status, moves) = match &outcome {
+            Outcome::Solved(moves, _) => {
+                solved += 1;
+                ("solved", &moves[..])
+            }
+            Outcome::Unsolvable(_) => {
+                unsolvable += 1;
+                ("unsolvable", &[][..])
+            }
+            Outcome::Timeout => {
+                timed_out += 1;
+                ("timeout", &[][..])
+            }
+        };
+        match format {
+            Format::Json => {
+                let line = Json::object([
+                    ("index", (i + 1).into()),
+                    ("status", status.into()),
+                    ("board", board.to_json()),
+                    ("moves", json::line(board, moves)),
+                ]);
+                writeln!(out, "{}", line)?;
+            }
+            Format::Csv => writeln!(out, "{}", csv_row(i + 1, &outcome, timeout))?,
+            Format::Text | Format::Moves => {
+                let moves: Vec<String> = moves.iter().map(Action::to_string).collect();
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}",
+                    i + 1,
+                    status,
+                    board.notation(),
+                    moves.join("; ")
+                )?;
+            }
+        }
+    }
+    out.flush()?;
+    eprintln!(
+        "{} boards: {} solved, {} unsolvable, {} timed out",
+        boards.len(),
+        solved,
+        unsolvable,
+        timed_out
+    );
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::json::{Json, ToJson};
+use crate::parallel::{solve_one, Outcome};
+use crate::presets::Preset;
+use crate::{Grid, SearchStrategy};
+
+use super::{load_board, Args, Format, Result};
+
+/// The boards `bench` runs without a BOARD: the two classic full boards and
+/// generated mid-game positions that the depth-first search finds hard,
+/// as `(name, board, goal)`. A missing goal means the centre peg.
+const SUITE: [(&str, &str, Option<&str>); 6] = [
+    (
+        "english",
+        "..OOO../..OOO../OOOOOOO/OOO-OOO/OOOOOOO/..OOO../..OOO..",
+        None,
+    ),
+    (
+        "european",
+        "..OOO../.OOOOO./OOO-OOO/OOOOOOO/OOOOOOO/.OOOOO./..OOO..",
+        Some("..---../.-----./-------/-------/---O---/.-----./..---.."),
+    ),
+    (
+        "midgame-1",
+        "..OOO../..OOO../-OOOOO-/-O-OOOO/-OOOOO-/..OOO../..-OO..",
+        None,
+    ),
+    (
+        "midgame-2",
+        "..OO-../..OOO../-OO--OO/OOOOOOO/OOOOOO-/..-OO../..OO-..",
+        None,
+    ),
+    (
+        "midgame-3",
+        "..OOO../..OOO../O-OOOO-/OOOOOOO/OO--OO-/..OOO../..--O..",
+        None,
+    ),
+    (
+        "midgame-4",
+        "..---../..OOO../O-OOOO-/OOOO-O-/-OOOOO-/..-OO../..OO-..",
+        None,
+    ),
+];
+
+/// Seconds each search may run before it is reported as timed out.
+const DEFAULT_TIMEOUT: f64 = 30.0;
+
+struct Case {
+    name: String,
+    board: Grid,
+    goal: Grid,
+}
+
+fn suite() -> Vec<Case> {
+    SUITE
+        .iter()
+        .map(|&(name, board, goal)| {
+            let board: Grid = board.parse().unwrap();
+            Case {
+                name: name.to_string(),
+                board,
+                goal: goal.map_or(board.single_peg(3, 3), |g| g.parse().unwrap()),
+            }
+        })
+        .collect()
+}
+
+pub(super) fn bench(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["goal", "strategy", "format", "timeout"], &[])?;
+    args.at_most(1)?;
+    let cases = match args.positional.first() {
+        Some(spec) => {
+            let board = load_board(spec)?;
+            vec![Case {
+                name: spec.clone(),
+                goal: args.goal(&board)?,
+                board,
+            }]
+        }
+        None if args.option("goal").is_some() => {
+            let board = Grid::new();
+            vec![Case {
+                name: Preset::Full.name().to_string(),
+                goal: args.goal(&board)?,
+                board,
+            }]
+        }
+        None => suite(),
+    };
+    let strategies = match args.parsed("strategy")? {
+        Some(strategy) => vec![strategy],
+        None => SearchStrategy::ALL.to_vec(),
+    };
+    let timeout = args
+        .timeout()?
+        .unwrap_or(Duration::from_secs_f64(DEFAULT_TIMEOUT));
+    let json = args.document_format()? == Format::Json;
+    if !json {
+        writeln!(
+            out,
+            "{:<12} {:<14} {:>10} {:>12} {:>6}",
+            "board", "strategy", "seconds", "nodes", "moves"
+        )?;
+    }
+    let mut reports = Vec::new();
+    for case in &cases {
+        let mut results = Vec::new();
+        for &strategy in &strategies {
+            let started = Instant::now();
+            let outcome = solve_one(case.board, case.goal, strategy, Some(timeout));
+            let seconds = started.elapsed().as_secs_f64();
+            let (status, nodes, moves) = match &outcome {
+                Outcome::Solved(moves, stats) => {
+                    ("solved", Some(stats.nodes_expanded), Some(moves.len()))
+                }
+                Outcome::Unsolvable(stats) => ("unsolvable", Some(stats.nodes_expanded), None),
+                Outcome::Timeout => ("timeout", None, None),
+            };
+            if json {
+                results.push(Json::object([
+                    ("strategy", strategy.name().into()),
+                    ("status", status.into()),
+                    ("seconds", seconds.into()),
+                    ("nodes_expanded", nodes.into()),
+                    ("moves", moves.into()),
+                ]));
+                continue;
+            }
+            let dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            writeln!(
+                out,
+                "{:<12} {:<14} {:>10.3} {:>12} {:>6}",
+                case.name,
+                strategy.name(),
+                seconds,
+                dash(nodes.map(|n| n.to_string())),
+                match outcome {
+                    Outcome::Timeout => "timeout".to_string(),
+                    _ => dash(moves.map(|m| m.to_string())),
+                }
+            )?;
+        }
+        reports.push(Json::object([
+            ("name", case.name.as_str().into()),
+            ("board", case.board.to_json()),
+            ("goal", case.goal.to_json()),
+            ("results", Json::Array(results)),
+        ]));
+    }
+    if json {
+        writeln!(out, "{}", Json::object([("boards", Json::Array(reports))]))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! What `solve`, `verify`, `hint` and `play` need of a board and its
+//! moves, so that they run the same on the English [`Grid`] and on a
+//! [`DiamondBoard`](crate::diamond::DiamondBoard).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::feasibility::{self, Infeasible};
+use crate::hint::HintMove;
+use crate::json::{self, Json, ToJson};
+use crate::notation;
+use crate::render::{self, RenderOptions};
+use crate::topology::Topology;
+use crate::{Action, Grid, IllegalMove, ReplayError};
+
+use super::{Args, MoveNotation, Result};
+
+/// A move on a [`Board`].
+pub(super) trait BoardMove:
+    HintMove + PartialEq + fmt::Debug + fmt::Display + ToJson + 'static
+{
+    /// The move written as `notation` says.
+    fn write(self, notation: MoveNotation) -> String;
+}
+
+impl BoardMove for Action {
+    fn write(self, notation: MoveNotation) -> String {
+        notation.write(self)
+    }
+}
+
+/// A board that the commands can play on.
+pub(super) trait Board: Copy + PartialEq + ToJson {
+    /// A move, read at the prompt with its [`FromStr`].
+    type Move: BoardMove + FromStr<Err: fmt::Display>;
+    /// How [`Board::draw`] draws the board.
+    type Style;
+
+    fn pegs(&self) -> u32;
+
+    fn check(&self, mv: Self::Move) -> std::result::Result<(), IllegalMove>;
+
+    fn play(&self, mv: Self::Move) -> Self;
+
+    fn unplay(&self, mv: Self::Move) -> Self;
+
+    fn legal_moves(&self) -> Vec<Self::Move>;
+
+    /// Plays `moves` in order, stopping at the first illegal one. Only the
+    /// English board joins its edges as `topology` says; the others take
+    /// no `--topology` and are always played flat.
+    fn play_line(
+        &self,
+        moves: &[Self::Move],
+        topology: Topology,
+    ) -> std::result::Result<Self, ReplayError<Self::Move>>;
+
+    /// `moves` played from the board, each with the position it leads to.
+    fn line_json(&self, moves: &[Self::Move], topology: Topology) -> Json;
+
+    /// Why `goal` is out of reach, when a cheap check can tell.
+    fn infeasible(&self, _goal: &Self) -> Option<Infeasible> {
+        None
+    }
+
+    /// The jumps of a chained move in a move list, such as `d2-d4-f4`.
+    fn parse_chain(_text: &str) -> Option<Vec<Self::Move>> {
+        None
+    }
+
+    /// The style the display flags ask for, with row and column numbers
+    /// when `coords` is set.
+    fn style(args: &Args, goal: &Self, coords: bool) -> Result<Self::Style>;
+
+    /// The board as `style` draws it, marking where `last` landed.
+    fn draw(&self, style: &Self::Style, last: Option<Self::Move>) -> String;
+}
+
+impl Board for Grid {
+    type Move = Action;
+    type Style = RenderOptions;
+
+    fn pegs(&self) -> u32 {
+        self.filled_count()
+    }
+
+    fn check(&self, mv: Action) -> std::result::Result<(), IllegalMove> {
+        self.check_action(mv)
+    }
+
+    fn play(&self, mv: Action) -> Grid {
+        self.perform_action(mv)
+    }
+
+    fn unplay(&self, mv: Action) -> Grid {
+        self.unperform_action(mv)
+    }
+
+    fn legal_moves(&self) -> Vec<Action> {
+        self.valid_actions()
+    }
+
+    fn play_line(
+        &self,
+        moves: &[Action],
+        topology: Topology,
+    ) -> std::result::Result<Grid, ReplayError> {
+        self.play_moves_on(moves, topology)
+    }
+
+    fn line_json(&self, moves: &[Action], topology: Topology) -> Json {
+        json::line_on(self, moves, topology)
+    }
+
+    fn infeasible(&self, goal: &Grid) -> Option<Infeasible> {
+        feasibility::check(self, goal).err()
+    }
+
+    fn parse_chain(text: &str) -> Option<Vec<Action>> {
+        notation::parse(text).ok()
+    }
+
+    fn style(args: &Args, goal: &Grid, coords: bool) -> Result<RenderOptions> {
+        Ok(RenderOptions {
+            coords,
+            ..args.render_options(goal)?
+        })
+    }
+
+    fn draw(&self, style: &RenderOptions, last: Option<Action>) -> String {
+        let options = RenderOptions {
+            last_move: last,
+            ..style.clone()
+        };
+        render::render(self, &options)
+    }
+}
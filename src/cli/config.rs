@@ -0,0 +1,235 @@
+//! Defaults for the flags people pass every time, read from a TOML file:
+//! `--config FILE`, `$PUZZLETHING_CONFIG`, or `puzzlething/config.toml`
+//! under `$XDG_CONFIG_HOME` or `~/.config`. Flags on the command line win.
+//!
+//! ```text
+//! strategy = "pns"          # --strategy
+//! notation = "standard"     # --notation
+//!
+//! [display]
+//! color = "always"          # --color
+//! theme = "rings"           # --theme, a built-in name or a file
+//!
+//! [hint]
+//! level = 2                 # hint --level
+//!
+//! [limits]
+//! timeout = 30              # --timeout, in seconds
+//! threads = 4               # --threads
+//! ```
+//!
+//! Only this much of TOML is read: tables, `key = value` lines, strings,
+//! numbers and comments.
+
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::hint::HintLevel;
+use crate::SearchStrategy;
+
+use super::{ColorChoice, MoveNotation};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Config {
+    pub strategy: Option<SearchStrategy>,
+    pub notation: Option<MoveNotation>,
+    pub color: Option<ColorChoice>,
+    /// A built-in theme's name or a file, read when a board is drawn.
+    pub theme: Option<String>,
+    pub hint_level: Option<HintLevel>,
+    pub timeout: Option<Duration>,
+    pub threads: Option<usize>,
+}
+
+static CONFIG: RwLock<Config> = RwLock::new(Config {
+    strategy: None,
+    notation: None,
+    color: None,
+    theme: None,
+    hint_level: None,
+    timeout: None,
+    threads: None,
+});
+
+/// The settings in force, empty until [`set`] is called.
+pub(super) fn current() -> Config {
+    CONFIG.read().unwrap().clone()
+}
+
+pub(super) fn set(config: Config) {
+    *CONFIG.write().unwrap() = config;
+}
+
+/// Where the settings live when `--config` is not given.
+pub(super) fn default_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("PUZZLETHING_CONFIG") {
+        return Some(path.into());
+    }
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(&env::var_os("HOME")?).join(".config"),
+    };
+    Some(config.join("puzzlething").join("config.toml"))
+}
+
+/// Reads the settings at `path`, or none if there is no file.
+pub(super) fn load(path: impl AsRef<Path>) -> io::Result<Config> {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(text) => text.parse().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct ParseConfigError {
+    /// One-based line of the problem.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseConfigError {}
+
+/// A value as written: a quoted string, or a bare number.
+enum Value {
+    String(String),
+    Bare(String),
+}
+
+impl Value {
+    fn text(&self) -> &str {
+        match self {
+            Value::String(text) | Value::Bare(text) => text,
+        }
+    }
+}
+
+/// Splits `value = # comment` into the value, failing on anything else
+/// after it.
+fn parse_value(text: &str) -> Result<Value, String> {
+    let (value, rest) = match text.chars().next() {
+        Some('"') => {
+            let mut value = String::new();
+            let mut chars = text.char_indices().skip(1);
+            loop {
+                match chars.next() {
+                    Some((i, '"')) => break (Value::String(value), &text[i + 1..]),
+                    Some((_, '\\')) => value.push(match chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, c @ ('"' | '\\'))) => c,
+                        _ => return Err("unknown escape in string".to_string()),
+                    }),
+                    Some((_, c)) => value.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        }
+        Some('\'') => match text[1..].split_once('\'') {
+            Some((value, rest)) => (Value::String(value.to_string()), rest),
+            None => return Err("unterminated string".to_string()),
+        },
+        Some(_) => {
+            let end = text.find([' ', '\t', '#']).unwrap_or(text.len());
+            (Value::Bare(text[..end].to_string()), &text[end..])
+        }
+        None => return Err("expected a value".to_string()),
+    };
+    let rest = rest.trim_start();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected {:?} after the value", rest));
+    }
+    Ok(value)
+}
+
+impl FromStr for Config {
+    type Err = ParseConfigError;
+
+    fn from_str(text: &str) -> Result<Config, ParseConfigError> {
+        let mut config = Config::default();
+        let mut table = String::new();
+        let mut seen = HashSet::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |message: String| ParseConfigError {
+                line: i + 1,
+                message,
+            };
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header
+                    .split_once(']')
+                    .filter(|(_, rest)| rest.trim().is_empty() || rest.trim().starts_with('#'))
+                    .map(|(name, _)| name.trim())
+                    .ok_or_else(|| err("expected `[table]`".to_string()))?;
+                if !["display", "hint", "limits"].contains(&name) {
+                    return Err(err(format!(
+                        "unknown table [{}], expected [display], [hint] or [limits]",
+                        name
+                    )));
+                }
+                table = format!("{}.", name);
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`".to_string()))?;
+            let key = format!("{}{}", table, key.trim().trim_matches('"'));
+            let value = parse_value(value.trim()).map_err(err)?;
+            if !seen.insert(key.clone()) {
+                return Err(err(format!("{} is set twice", key)));
+            }
+            let text = value.text();
+            let number = || {
+                text.parse::<f64>()
+                    .ok()
+                    .filter(|n| matches!(value, Value::Bare(_)) && n.is_finite() && *n >= 0.0)
+                    .ok_or_else(|| err(format!("{}: expected a number, not {:?}", key, text)))
+            };
+            let parsed = |e: &dyn fmt::Display| err(format!("{}: {}", key, e));
+            match key.as_str() {
+                "strategy" => config.strategy = Some(text.parse().map_err(|e| parsed(&e))?),
+                "notation" => config.notation = Some(text.parse().map_err(|e| parsed(&e))?),
+                "display.color" => config.color = Some(text.parse().map_err(|e| parsed(&e))?),
+                "display.theme" => config.theme = Some(text.to_string()),
+                "hint.level" => config.hint_level = Some(text.parse().map_err(|e| parsed(&e))?),
+                "limits.timeout" => {
+                    let timeout = Duration::try_from_secs_f64(number()?)
+                        .map_err(|_| err(format!("{}: {} seconds is too long", key, text)))?;
+                    config.timeout = Some(timeout);
+                }
+                "limits.threads" => {
+                    let threads = number()?;
+                    if threads.fract() != 0.0 {
+                        return Err(err(format!("{}: expected a whole number", key)));
+                    }
+                    config.threads = Some(threads as usize);
+                }
+                _ => return Err(err(format!("unknown setting {}", key))),
+            }
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,251 @@
+//! `solve`, `verify`, `hint` and `play` with a diamond board, `diamond-32`
+//! or `diamond-41`, as BOARD. They are too big for the 7x7 grid, so these
+//! play them on a [`DiamondBoard`] of their own, without the book, the
+//! cache, the themes or the strategies of the English board: each plays
+//! its built-in line towards its usual goal, and otherwise searches depth
+//! first.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::diamond::{Diamond, DiamondBoard, Jump, Outcome};
+use crate::hint::HintLevel;
+use crate::json::{self, Json};
+use crate::speech;
+use crate::stats::SearchStats;
+use crate::topology::Topology;
+use crate::{Action, IllegalMove, ReplayError, SearchStrategy};
+
+use super::board::{Board, BoardMove};
+use super::play::{self, Game, Playable};
+use super::{
+    config, fail, load_moves, no_solution, print_hint, print_line, report_search, verify_line,
+    Args, MoveNotation, Result,
+};
+
+pub(super) const HELP: &str = "\
+Enter a move as `d1-d3`, with columns lettered from a and rows numbered
+from 1, or as `x y direction` with both counted from 0.
+Other commands: moves (list the legal moves), undo (u), help, quit.
+";
+
+/// Fails on the first of `flags` that was given, none of which work on a
+/// diamond board.
+fn refuse(args: &Args, flags: &[&str]) -> Result<()> {
+    match flags
+        .iter()
+        .find(|&&flag| args.option(flag).is_some() || args.switch(flag))
+    {
+        Some(flag) => fail(format!("--{} does not work on the diamond boards", flag)),
+        None => Ok(()),
+    }
+}
+
+/// `--goal` read as a board of the same shape, or the usual goal.
+fn goal(args: &Args, diamond: Diamond) -> Result<DiamondBoard> {
+    let Some(spec) = args.option("goal") else {
+        return Ok(diamond.goal());
+    };
+    let goal: DiamondBoard = if Path::new(spec).is_file() {
+        fs::read_to_string(spec)?
+            .parse()
+            .map_err(|e| format!("{}: {}", spec, e))?
+    } else {
+        spec.parse()?
+    };
+    if !goal.same_layout(&diamond.start()) {
+        return fail(format!("--goal is not drawn on the {} board", diamond));
+    }
+    Ok(goal)
+}
+
+/// Only depth-first search is written for the diamond boards.
+fn check_strategy(args: &Args) -> Result<()> {
+    match args.parsed::<SearchStrategy>("strategy")? {
+        Some(SearchStrategy::DepthFirst) | None => Ok(()),
+        Some(other) => fail(format!(
+            "the diamond boards are only searched depth first, not with {}",
+            other
+        )),
+    }
+}
+
+/// A line from `start` to `goal`: the built-in one when it leads there and
+/// `search` is not asked for, otherwise a depth-first search within
+/// `timeout`. Also names where the line came from.
+fn find_line(
+    diamond: Diamond,
+    start: &DiamondBoard,
+    goal: &DiamondBoard,
+    search: bool,
+    timeout: Option<Duration>,
+) -> Result<(&'static str, Option<Vec<Jump>>, SearchStats)> {
+    let built_in = (*goal == diamond.goal())
+        .then(|| diamond.line_from(start))
+        .flatten();
+    if let (Some(line), false) = (built_in, search) {
+        return Ok(("built-in", Some(line), SearchStats::default()));
+    }
+    let name = SearchStrategy::DepthFirst.name();
+    match start.search(goal, timeout) {
+        Outcome::Solved(line, stats) => Ok((name, Some(line), stats)),
+        Outcome::Unsolvable(stats) => Ok((name, None, stats)),
+        Outcome::Timeout => fail("the search ran out of time before settling the board"),
+    }
+}
+
+impl BoardMove for Jump {
+    fn write(self, notation: MoveNotation) -> String {
+        match notation {
+            MoveNotation::Coords => Action::new(self.x, self.y, self.direction).to_string(),
+            MoveNotation::Standard => self.to_string(),
+            MoveNotation::Spoken => {
+                speech::describe_jump((self.x, self.y), self.direction, self.over())
+            }
+        }
+    }
+}
+
+impl Board for DiamondBoard {
+    type Move = Jump;
+    /// Whether to number the columns and rows from 0, as `--coords`
+    /// numbers the English board.
+    type Style = bool;
+
+    fn pegs(&self) -> u32 {
+        self.filled_count()
+    }
+
+    fn check(&self, jump: Jump) -> std::result::Result<(), IllegalMove> {
+        self.check_jump(jump)
+    }
+
+    fn play(&self, jump: Jump) -> DiamondBoard {
+        self.perform_jump(jump)
+    }
+
+    fn unplay(&self, jump: Jump) -> DiamondBoard {
+        self.unperform_jump(jump)
+    }
+
+    fn legal_moves(&self) -> Vec<Jump> {
+        self.valid_jumps()
+    }
+
+    fn play_line(
+        &self,
+        jumps: &[Jump],
+        _topology: Topology,
+    ) -> std::result::Result<DiamondBoard, ReplayError<Jump>> {
+        self.play_jumps(jumps)
+    }
+
+    fn line_json(&self, jumps: &[Jump], _topology: Topology) -> Json {
+        json::diamond_line(self, jumps)
+    }
+
+    fn style(_args: &Args, _goal: &DiamondBoard, coords: bool) -> Result<bool> {
+        Ok(coords)
+    }
+
+    fn draw(&self, &coords: &bool, _last: Option<Jump>) -> String {
+        let rows = self.to_string();
+        if !coords {
+            return rows;
+        }
+        let header: String = (0..self.width()).map(|x| x.to_string()).collect();
+        let mut out = format!("  {}\n", header);
+        for (y, row) in rows.lines().enumerate() {
+            out.push_str(&format!("{} {}\n", y, row));
+        }
+        out
+    }
+}
+
+impl Playable for DiamondBoard {
+    const HELP: &'static str = HELP;
+}
+
+pub(super) fn solve(args: &Args, diamond: Diamond) -> Result<()> {
+    refuse(
+        args,
+        &[
+            "color",
+            "theme",
+            "delay",
+            "batch",
+            "output",
+            "seed",
+            "max-solutions",
+            "threads",
+            "cache",
+            "topology",
+            "solvers",
+            "engine",
+            "animate",
+            "all",
+            "variants",
+            "book",
+            "portfolio",
+            "anytime",
+        ],
+    )?;
+    check_strategy(args)?;
+    let timeout = args.timeout()?;
+    let start = diamond.start();
+    let goal = goal(args, diamond)?;
+    let format = args.document_format()?;
+    let search =
+        args.switch("force-search") || args.switch("stats") || args.option("strategy").is_some();
+    let (found_by, line, stats) = find_line(diamond, &start, &goal, search, timeout)?;
+    report_search(
+        args,
+        &start,
+        &goal,
+        found_by,
+        line.as_deref(),
+        &stats,
+        format,
+    )?;
+    let Some(line) = line else {
+        return no_solution(&start, &goal);
+    };
+    print_line(args, &start, &goal, &line, &[], format, args.notation()?)
+}
+
+pub(super) fn verify(args: &Args, diamond: Diamond) -> Result<()> {
+    refuse(args, &["color", "theme", "topology", "variants"])?;
+    let start = diamond.start();
+    let goal = goal(args, diamond)?;
+    let jumps = load_moves::<DiamondBoard>(&args.positional[1])?;
+    verify_line(args, &start, &goal, &jumps, Topology::Flat)
+}
+
+/// The next move from the start towards the goal, or as much of it as
+/// `--level` gives away.
+pub(super) fn hint(args: &Args, diamond: Diamond) -> Result<()> {
+    refuse(args, &["top"])?;
+    check_strategy(args)?;
+    let level = args
+        .parsed::<HintLevel>("level")?
+        .or(config::current().hint_level)
+        .unwrap_or_default();
+    let start = diamond.start();
+    let goal = goal(args, diamond)?;
+    let search = args.option("strategy").is_some();
+    let (_, line, _) = find_line(diamond, &start, &goal, search, args.timeout()?)?;
+    let Some(line) = line else {
+        return no_solution(&start, &goal);
+    };
+    print_hint(&line, level, args.document_format()?, args.notation()?)
+}
+
+/// Plays a diamond board at the prompt, from its start to `--goal` or its
+/// usual goal.
+pub(super) fn play(args: &Args, diamond: Diamond) -> Result<()> {
+    refuse(args, &["color", "theme", "load", "daily"])?;
+    args.at_most(1)?;
+    let goal = goal(args, diamond)?;
+    play::run(Game::new(diamond.start(), goal), true, Vec::new())
+}
@@ -0,0 +1,121 @@
+//! `play --duotaire`: two players at one keyboard take turns jumping, and
+//! whoever makes the last jump wins.
+
+use std::io::{self, BufRead, Write};
+
+use crate::duotaire::Duotaire;
+use crate::render::{self, RenderOptions};
+use crate::{Action, Grid};
+
+use super::i18n::{not_legal, tr, tr_text, trf};
+use super::{move_list, move_text, Args, Result};
+
+pub(super) const HELP: &str = "\
+Players take turns making one jump each; whoever makes the last jump wins.
+Enter a move as `x y direction`, e.g. `3 1 down` or `3,1,d`.
+Other commands: moves (list the legal moves), hint (a winning move, if
+the player to move has one), undo (u), help, quit.
+";
+
+enum Command {
+    Play(Action),
+    Hint,
+    Undo,
+    Quit,
+}
+
+pub(super) fn play(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    args.at_most(1)?;
+    let start = args.board(0)?;
+    let mut options = RenderOptions {
+        coords: true,
+        goal: None,
+        ..args.render_options(&start)?
+    };
+    let mut solver = Duotaire::new(&start);
+    let mut board = start;
+    let mut history: Vec<Action> = Vec::new();
+    let mut lines = io::stdin().lock().lines();
+    write!(out, "{}", tr(HELP))?;
+    loop {
+        let player = history.len() % 2 + 1;
+        writeln!(out)?;
+        write!(out, "{}", render::render(&board, &options))?;
+        if board.valid_actions().is_empty() {
+            match history.len() {
+                0 => writeln!(out, "{}", tr("No jump can be made."))?,
+                _ => writeln!(
+                    out,
+                    "{}",
+                    trf(
+                        "Player {player} cannot jump: player {other} wins!",
+                        &[("player", &player), ("other", &(3 - player))]
+                    )
+                )?,
+            }
+            return Ok(());
+        }
+        writeln!(
+            out,
+            "{}",
+            trf("Player {player} to move", &[("player", &player)])
+        )?;
+        match read_command(&mut lines, &board)? {
+            Command::Quit => return Ok(()),
+            Command::Play(action) => {
+                board = board.perform_action(action);
+                history.push(action);
+            }
+            Command::Undo => match history.pop() {
+                Some(action) => board = board.unperform_action(action),
+                None => writeln!(out, "{}", tr("Nothing to undo."))?,
+            },
+            Command::Hint => {
+                match solver.winning_moves(&board).first() {
+                    Some(&action) => {
+                        let winning = move_text(action);
+                        writeln!(out, "{}", trf("{move} wins.", &[("move", &winning)]))?
+                    }
+                    None => writeln!(out, "{}", tr("Every jump loses against best play."))?,
+                }
+                continue;
+            }
+        }
+        options.last_move = history.last().copied();
+    }
+}
+
+/// Prompts until the player enters a legal move or another command.
+/// Closing the input counts as quitting.
+fn read_command(
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+    board: &Grid,
+) -> Result<Command> {
+    let mut out = io::stdout().lock();
+    loop {
+        write!(out, "move> ")?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(out)?;
+            return Ok(Command::Quit);
+        };
+        match line?.trim() {
+            "" => {}
+            "q" | "quit" | "exit" => return Ok(Command::Quit),
+            "u" | "undo" => return Ok(Command::Undo),
+            "hint" => return Ok(Command::Hint),
+            "h" | "help" | "?" => write!(out, "{}", tr(HELP))?,
+            "m" | "moves" => {
+                writeln!(out, "{}", move_list(&board.valid_actions()))?;
+            }
+            input => match input.parse::<Action>() {
+                Ok(action) => match board.check_action(action) {
+                    Ok(()) => return Ok(Command::Play(action)),
+                    Err(reason) => writeln!(out, "{}", not_legal(action, reason))?,
+                },
+                Err(e) => writeln!(out, "{}", tr_text(&e.to_string()))?,
+            },
+        }
+    }
+}
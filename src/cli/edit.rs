@@ -0,0 +1,202 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use crate::feasibility;
+use crate::render::{self, Theme};
+use crate::{GameTree, Grid, SearchStrategy, Tile};
+
+use super::tui::{read_key, Key, RawTerminal};
+use super::{fail, load_board, Args, Result};
+
+const HELP: &str = "\
+arrows/hjkl move  space cycle  o peg  - hole  # blocked  f fill  c clear
+v validate  S save  q quit";
+
+struct Editor {
+    board: Grid,
+    theme: Theme,
+    cursor: (usize, usize),
+    path: String,
+    saved: bool,
+    message: String,
+}
+
+impl Editor {
+    fn step(&mut self, dx: isize, dy: isize) {
+        let x = (self.cursor.0 as isize + dx).clamp(0, 6) as usize;
+        let y = (self.cursor.1 as isize + dy).clamp(0, 6) as usize;
+        self.cursor = (x, y);
+    }
+
+    fn set(&mut self, tile: Tile) {
+        let (x, y) = self.cursor;
+        self.board = self.board.with_tile(x, y, tile);
+        self.saved = false;
+        self.message.clear();
+    }
+
+    /// Turns every hole into a peg, or with `tile` set to `Empty` every peg
+    /// into a hole.
+    fn fill(&mut self, tile: Tile) {
+        for x in 0..7 {
+            for y in 0..7 {
+                if self.board.tile(x, y) != Tile::Blocked {
+                    self.board = self.board.with_tile(x, y, tile);
+                }
+            }
+        }
+        self.saved = false;
+        self.message.clear();
+    }
+
+    /// Checks the board against the single centre peg goal, running the
+    /// search when `search` is set.
+    fn validate(&self, search: bool) -> String {
+        if self.board.filled_count() == 0 {
+            return "The board has no pegs".to_string();
+        }
+        if self.board.tile(3, 3) == Tile::Blocked {
+            return "The centre is blocked, so there is no default goal".to_string();
+        }
+        let goal = self.board.single_peg(3, 3);
+        if let Err(reason) = feasibility::check(&self.board, &goal) {
+            return format!("The centre cannot be reached: {}", reason);
+        }
+        if !search {
+            return "Passes the quick checks, press v to search".to_string();
+        }
+        match GameTree::new(self.board, Vec::new()).search_for(&goal, SearchStrategy::default()) {
+            Some(g) => format!("Solvable in {} moves", g.history().len()),
+            None => "No line reaches the centre".to_string(),
+        }
+    }
+
+    fn save(&mut self) -> Result<()> {
+        fs::write(&self.path, self.board.to_string())?;
+        self.saved = true;
+        self.message = format!("Saved to {}", self.path);
+        Ok(())
+    }
+
+    fn render(&self) -> String {
+        let width = self.theme.cell_width();
+        let mut frame = String::from("\x1b[H\x1b[2J");
+        let _ = write!(
+            frame,
+            "\x1b[1mpuzzlething editor\x1b[0m  {}\r\n\r\n",
+            self.path
+        );
+        frame.push_str("  ");
+        for x in 0..7 {
+            let _ = write!(frame, " {:<width$} ", x, width = width);
+        }
+        frame.push_str("\r\n");
+        for y in 0..7 {
+            let _ = write!(frame, "{} ", y);
+            for x in 0..7 {
+                let tile = self.board.tile(x, y);
+                // Blocked cells are blank in most themes, so mark them.
+                let symbol = match tile {
+                    Tile::Blocked => "#",
+                    _ => self.theme.symbol(tile),
+                };
+                let style = if self.cursor == (x, y) { "\x1b[7m" } else { "" };
+                let padding = " ".repeat(width.saturating_sub(render::display_width(symbol)));
+                let _ = write!(frame, "{} {}{} \x1b[0m", style, symbol, padding);
+            }
+            frame.push_str("\r\n");
+        }
+        let status = if self.message.is_empty() {
+            self.validate(false)
+        } else {
+            self.message.clone()
+        };
+        let _ = write!(
+            frame,
+            "\r\n\x1b[7m Pegs: {}{}  {} \x1b[0m\r\n{}",
+            self.board.filled_count(),
+            if self.saved { "" } else { " (modified)" },
+            status,
+            HELP.replace('\n', "\r\n")
+        );
+        frame
+    }
+}
+
+pub(super) fn edit(rest: &[String]) -> Result<()> {
+    let args = Args::parse(rest, &["from", "theme"], &[])?;
+    args.at_most(1)?;
+    let Some(path) = args.positional.first() else {
+        return fail("edit needs the file to write the board to");
+    };
+    let board = match args.option("from") {
+        Some(spec) => load_board(spec)?,
+        None if Path::new(path).is_file() => load_board(path)?,
+        None => Grid::new(),
+    };
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return fail("the editor needs an interactive terminal");
+    }
+    let mut editor = Editor {
+        board,
+        theme: args.theme()?,
+        cursor: (3, 3),
+        path: path.clone(),
+        saved: args.option("from").is_none() && Path::new(path).is_file(),
+        message: String::new(),
+    };
+
+    let terminal = RawTerminal::enter()?;
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut confirm_quit = false;
+    loop {
+        stdout.write_all(editor.render().as_bytes())?;
+        stdout.flush()?;
+        let key = read_key(&mut stdin)?;
+        if key != Key::Quit {
+            confirm_quit = false;
+        }
+        match key {
+            Key::Up => editor.step(0, -1),
+            Key::Down => editor.step(0, 1),
+            Key::Left => editor.step(-1, 0),
+            Key::Right => editor.step(1, 0),
+            Key::Pick => {
+                let (x, y) = editor.cursor;
+                editor.set(match editor.board.tile(x, y) {
+                    Tile::Filled => Tile::Empty,
+                    Tile::Empty => Tile::Blocked,
+                    Tile::Blocked => Tile::Filled,
+                });
+            }
+            Key::Char(b'o' | b'O' | b'p') => editor.set(Tile::Filled),
+            Key::Char(b'-' | b'e') => editor.set(Tile::Empty),
+            Key::Char(b'#' | b'b') => editor.set(Tile::Blocked),
+            Key::Char(b'f') => editor.fill(Tile::Filled),
+            Key::Char(b'c') => editor.fill(Tile::Empty),
+            Key::Char(b'v') => {
+                editor.message = "Searching...".to_string();
+                stdout.write_all(editor.render().as_bytes())?;
+                stdout.flush()?;
+                editor.message = editor.validate(true);
+            }
+            Key::Char(b'S') => {
+                if let Err(e) = editor.save() {
+                    editor.message = format!("Could not save: {}", e);
+                }
+            }
+            Key::Quit if editor.saved || confirm_quit => break,
+            Key::Quit => {
+                confirm_quit = true;
+                editor.message = "Unsaved changes, press q again to quit".to_string();
+            }
+            _ => {}
+        }
+    }
+    drop(terminal);
+    write!(stdout, "{}", editor.board)?;
+    Ok(())
+}
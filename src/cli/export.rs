@@ -0,0 +1,79 @@
+use std::fs;
+use std::io::{self, Write};
+
+use crate::raster::{self, Palette};
+use crate::{gif, graph, png, svg};
+use crate::{Action, GameTree, Grid};
+
+use super::{fail, load_moves, no_solution, Args, Result};
+
+/// Writes `data` to `path`, or to stdout when `path` is `-`.
+fn write_output(path: &str, data: &[u8]) -> Result<()> {
+    if path == "-" {
+        io::stdout().lock().write_all(data)?;
+    } else {
+        fs::write(path, data)?;
+    }
+    Ok(())
+}
+
+pub(super) fn export(rest: &[String]) -> Result<()> {
+    let args = Args::parse(
+        rest,
+        &[
+            "goal", "strategy", "svg", "gif", "png", "dot", "delay", "size", "colors",
+        ],
+        &["solve"],
+    )?;
+    args.at_most(2)?;
+    let board = args.board(0)?;
+    let moves: Option<Vec<Action>> = match args.positional.get(1) {
+        Some(spec) => Some(load_moves::<Grid>(spec)?),
+        None if args.switch("solve") => {
+            let goal = args.goal(&board)?;
+            let Some(g) = GameTree::new(board, Vec::new()).search_for(&goal, args.strategy()?)
+            else {
+                return no_solution(&board, &goal);
+            };
+            Some(g.history().to_vec())
+        }
+        None => None,
+    };
+    // The PNG shows where the moves lead rather than every step.
+    let end = match &moves {
+        Some(moves) => board.play_moves(moves)?,
+        None => board,
+    };
+    let paths = ["svg", "gif", "png", "dot"].map(|format| args.option(format));
+    let [svg_path, gif_path, png_path, dot_path] = paths;
+    if paths.iter().all(Option::is_none) {
+        return fail("export needs an output: --svg, --gif, --png or --dot FILE");
+    }
+    let cell = args.parsed("size")?.unwrap_or(40usize);
+    if !(4..=400).contains(&cell) {
+        return fail("--size must be between 4 and 400 pixels");
+    }
+    let palette: Palette = args.parsed("colors")?.unwrap_or_default();
+    if let Some(path) = svg_path {
+        let image = match &moves {
+            Some(moves) => svg::solution(&board, moves),
+            None => svg::position(&board),
+        };
+        write_output(path, image.as_bytes())?;
+    }
+    if let Some(path) = gif_path {
+        let delay: u64 = args.parsed("delay")?.unwrap_or(500);
+        let moves = moves.as_deref().unwrap_or_default();
+        let animation = gif::solution(&board, moves, cell, (delay / 10) as u16, &palette);
+        write_output(path, &animation)?;
+    }
+    if let Some(path) = png_path {
+        let image = raster::draw(&end, cell, None);
+        write_output(path, &png::encode(&image, &palette))?;
+    }
+    if let Some(path) = dot_path {
+        let moves = moves.as_deref().unwrap_or_default();
+        write_output(path, graph::solution_dot(&board, moves).as_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,793 @@
+//! `solve --game NAME`: the puzzles other than peg solitaire. Each reads
+//! its position from a file or inline text and is searched with the
+//! generic searches of [`puzzle`].
+
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::duotaire::Duotaire;
+use crate::fifteen::{self, Fifteen};
+use crate::hanoi::{self, Hanoi, Towers};
+use crate::hex::HexBoard;
+use crate::json::{Json, ToJson};
+use crate::klotski::Klotski;
+use crate::knights_tour::{self, Square};
+use crate::layers::Layers;
+use crate::lights_out::{self, Lights, LightsOut};
+use crate::nonogram::Clues;
+use crate::puzzle::{Puzzle, Search};
+use crate::queens;
+use crate::render::RenderOptions;
+use crate::river::{Banks, MissionariesAndCannibals, WolfGoatCabbage};
+use crate::rush_hour::RushHour;
+use crate::sokoban;
+use crate::stats::SearchStats;
+use crate::sudoku::{self, Grade, Sudoku};
+
+use super::i18n::trf;
+use super::{clock_seed, fail, Args, Format, Result};
+
+const GAMES: [&str; 15] = [
+    "fifteen",
+    "sokoban",
+    "lights-out",
+    "hanoi",
+    "klotski",
+    "rush-hour",
+    "knights-tour",
+    "queens",
+    "sudoku",
+    "nonogram",
+    "duotaire",
+    "wolf-goat-cabbage",
+    "missionaries",
+    "hex",
+    "3d",
+];
+
+pub(super) fn solve(rest: &[String]) -> Result<()> {
+    let args = Args::parse(
+        rest,
+        &[
+            "game",
+            "strategy",
+            "format",
+            "seed",
+            "scramble",
+            "sokoban-level",
+            "disks",
+            "towers",
+            "side",
+            "people",
+            "color",
+            "theme",
+            "max-solutions",
+            "boat",
+            "goal",
+        ],
+        &["stats", "closed", "coords", "all", "count"],
+    )?;
+    args.at_most(1)?;
+    match args.option("game").unwrap_or_default() {
+        "fifteen" => solve_fifteen(&args),
+        "sokoban" => solve_sokoban(&args),
+        "lights-out" => solve_lights_out(&args),
+        "hanoi" => solve_hanoi(&args),
+        "klotski" => solve_klotski(&args),
+        "rush-hour" => solve_rush_hour(&args),
+        "knights-tour" => solve_knights_tour(&args),
+        "queens" => solve_queens(&args),
+        "sudoku" => solve_sudoku(&args),
+        "nonogram" => solve_nonogram(&args),
+        "duotaire" => solve_duotaire(&args),
+        "wolf-goat-cabbage" => solve_wolf_goat_cabbage(&args),
+        "missionaries" => solve_missionaries(&args),
+        "hex" => solve_hex(&args),
+        "3d" => solve_layers(&args),
+        other => fail(format!(
+            "unknown game {:?}, expected {}",
+            other,
+            GAMES.join(", ")
+        )),
+    }
+}
+
+/// `gen --game sudoku`: a puzzle of `--difficulty` (default easy) from
+/// `--seed`, the only game with a generator.
+pub(super) fn generate(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["game", "format", "seed", "difficulty"], &[])?;
+    args.at_most(0)?;
+    if args.option("game") != Some("sudoku") {
+        return fail("gen --game only generates sudoku");
+    }
+    let grade: Grade = args.parsed("difficulty")?.unwrap_or_default();
+    let seed = args.parsed("seed")?.unwrap_or_else(clock_seed);
+    let puzzle = sudoku::generate(grade, seed);
+    match args.document_format()? {
+        Format::Json => writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("game", "sudoku".into()),
+                ("seed", seed.into()),
+                ("difficulty", grade.name().into()),
+                ("clues", puzzle.clues().into()),
+                ("puzzle", puzzle.line().into()),
+            ])
+        )?,
+        Format::Moves => writeln!(out, "{}", puzzle.line())?,
+        Format::Text | Format::Csv => {
+            writeln!(out, "Seed {} ({}, {} clues)", seed, grade, puzzle.clues())?;
+            writeln!(out, "{}", puzzle.line())?;
+            writeln!(out, "{}", puzzle)?;
+        }
+    }
+    Ok(())
+}
+
+/// The text of positional `index`, read from the file of that name if
+/// there is one.
+fn text(args: &Args, index: usize) -> Result<Option<String>> {
+    match args.positional.get(index) {
+        Some(spec) if Path::new(spec).is_file() => Ok(Some(fs::read_to_string(spec)?)),
+        Some(spec) => Ok(Some(spec.clone())),
+        None => Ok(None),
+    }
+}
+
+/// Without a board, a solved fifteen puzzle scrambled by `--scramble`
+/// random slides (default 40) from `--seed`.
+fn solve_fifteen(args: &Args) -> Result<()> {
+    let board: fifteen::Board = match text(args, 0)? {
+        Some(text) => text.parse()?,
+        None => fifteen::Board::scrambled(
+            4,
+            args.parsed("scramble")?.unwrap_or(40),
+            args.parsed("seed")?.unwrap_or(0),
+        ),
+    };
+    if !board.is_solvable() {
+        return fail("no solution: the tiles are an odd permutation away from order");
+    }
+    let game = Game {
+        name: "fifteen",
+        puzzle: &Fifteen,
+        start: &board,
+        show: &|b| b.to_string(),
+    };
+    report(&game, args)?;
+    Ok(())
+}
+
+/// Level `--sokoban-level` (default 1) of an XSB file or text. In text the pushes
+/// are followed by the whole solution in LURD.
+fn solve_sokoban(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(text) = text(args, 0)? else {
+        return fail("solve --game sokoban needs a level");
+    };
+    let levels = sokoban::parse_levels(&text)?;
+    let number: usize = args.parsed("sokoban-level")?.unwrap_or(1);
+    let Some(level) = number.checked_sub(1).and_then(|i| levels.get(i)) else {
+        return fail(format!("no level {}, found {}", number, levels.len()));
+    };
+    if level.is_deadlocked(&level.start()) {
+        return fail("no solution: a box can never reach a goal");
+    }
+    let game = Game {
+        name: "sokoban",
+        puzzle: level,
+        start: &level.start(),
+        show: &|p| level.render(p),
+    };
+    let pushes = report(&game, args)?;
+    if args.document_format()? == Format::Text {
+        // The pushes came from the search, so they can all be made.
+        writeln!(out, "\n{}", level.lurd(&pushes).unwrap())?;
+    }
+    Ok(())
+}
+
+/// Solved directly over GF(2) unless `--strategy` names a search.
+fn solve_lights_out(args: &Args) -> Result<()> {
+    let Some(text) = text(args, 0)? else {
+        return fail("solve --game lights-out needs a board");
+    };
+    let lights: Lights = text.parse()?;
+    let game = Game {
+        name: "lights-out",
+        puzzle: &LightsOut,
+        start: &lights,
+        show: &|l| l.to_string(),
+    };
+    match args.option("strategy") {
+        None | Some("gf2") => {
+            let started = Instant::now();
+            let line = lights_out::solve(&lights);
+            let stats = SearchStats {
+                elapsed: started.elapsed(),
+                ..SearchStats::default()
+            };
+            print_line(&game, "gf2", line, &stats, args)?;
+        }
+        Some(_) => {
+            report(&game, args)?;
+        }
+    }
+    Ok(())
+}
+
+/// `--disks` (default 4) on the first of `--towers` (default 3), moved by
+/// Frame–Stewart unless `--strategy` names a search.
+fn solve_hanoi(args: &Args) -> Result<()> {
+    args.at_most(0)?;
+    let disks: usize = args.parsed("disks")?.unwrap_or(4);
+    let towers: usize = args.parsed("towers")?.unwrap_or(3);
+    if !(3..=26).contains(&towers) {
+        return fail("--towers must be from 3 to 26");
+    }
+    if disks > 24 {
+        return fail("--disks must be at most 24");
+    }
+    let start = Towers::new(disks, towers);
+    let game = Game {
+        name: "hanoi",
+        puzzle: &Hanoi,
+        start: &start,
+        show: &|t| t.to_string(),
+    };
+    match args.option("strategy") {
+        None | Some("frame-stewart") => {
+            let started = Instant::now();
+            let line = hanoi::frame_stewart(disks, towers);
+            let stats = SearchStats {
+                elapsed: started.elapsed(),
+                ..SearchStats::default()
+            };
+            print_line(&game, "frame-stewart", Some(line), &stats, args)?;
+        }
+        Some(_) => {
+            report(&game, args)?;
+        }
+    }
+    Ok(())
+}
+
+/// The classic Klotski layout when no board is given. Solved breadth
+/// first unless `--strategy` says otherwise, for the fewest slides.
+fn solve_klotski(args: &Args) -> Result<()> {
+    let text = text(args, 0)?.unwrap_or_else(|| "ABBC/ABBC/DEEF/DGHF/I..J".to_string());
+    let klotski: Klotski = text.parse()?;
+    let game = Game {
+        name: "klotski",
+        puzzle: &klotski,
+        start: klotski.start(),
+        show: &|b| klotski.render(b),
+    };
+    if args.option("strategy").is_none() {
+        let (line, stats) = Search::BreadthFirst.run(&klotski, klotski.start());
+        print_line(&game, Search::BreadthFirst.name(), line, &stats, args)?;
+    } else {
+        report(&game, args)?;
+    }
+    Ok(())
+}
+
+/// A level in the usual 36-letter notation or a row per line, defaulting
+/// to the first beginner card. Solved breadth first unless `--strategy`
+/// says otherwise, for the fewest drives.
+fn solve_rush_hour(args: &Args) -> Result<()> {
+    let text = text(args, 0)?.unwrap_or_else(|| "ooBoooooBoCoAABoCoooooCooooooooooooo".to_string());
+    let level: RushHour = text.parse()?;
+    let game = Game {
+        name: "rush-hour",
+        puzzle: &level,
+        start: level.start(),
+        show: &|t| level.render(t),
+    };
+    if args.option("strategy").is_none() {
+        let (line, stats) = Search::BreadthFirst.run(&level, level.start());
+        print_line(&game, Search::BreadthFirst.name(), line, &stats, args)?;
+    } else {
+        report(&game, args)?;
+    }
+    Ok(())
+}
+
+/// A tour of the `--side` board (default 8) from the square given, or a1,
+/// drawn with the usual board options.
+fn solve_knights_tour(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let size: usize = args.parsed("side")?.unwrap_or(8);
+    if !(1..=26).contains(&size) {
+        return fail("--side must be from 1 to 26 for knights-tour");
+    }
+    let start: Square = match args.positional.first() {
+        Some(square) => square.parse()?,
+        None => Square { x: 0, y: 0 },
+    };
+    let closed = args.switch("closed");
+    let format = args.document_format()?;
+    let (tour, stats) = knights_tour::find(size, start, closed);
+    let options = RenderOptions {
+        color: args.color()? && format == Format::Text,
+        coords: args.switch("coords"),
+        theme: args.theme()?,
+        ..RenderOptions::default()
+    };
+    if format == Format::Json {
+        let mut fields = vec![
+            ("game", "knights-tour".into()),
+            ("size", size.into()),
+            ("start", start.to_string().into()),
+            ("closed", closed.into()),
+            ("solved", tour.is_some().into()),
+        ];
+        if let Some(tour) = &tour {
+            let squares = tour.squares().iter().map(|s| s.to_string().into());
+            fields.push(("squares", Json::Array(squares.collect())));
+            fields.push(("board", tour.render(&options).into()));
+        }
+        fields.push(("stats", stats.to_json()));
+        writeln!(out, "{}", Json::object(fields))?;
+    } else if args.switch("stats") {
+        eprintln!("{}", stats);
+    }
+    let Some(tour) = tour else {
+        return fail("no solution");
+    };
+    match format {
+        Format::Text => {
+            write!(out, "{}", tour.render(&options))?;
+            let squares = tour.squares();
+            let kind = if tour.is_closed() { "Closed" } else { "Open" };
+            writeln!(
+                out,
+                "\n{} tour from {} to {}",
+                kind,
+                squares[0],
+                squares[squares.len() - 1]
+            )?;
+        }
+        Format::Moves => {
+            for square in tour.squares() {
+                writeln!(out, "{}", square)?;
+            }
+        }
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// `--side` queens (default 8): one placement, every one with `--all` or
+/// `--max-solutions`, or just how many there are with `--count`.
+fn solve_queens(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    args.at_most(0)?;
+    let size: usize = args.parsed("side")?.unwrap_or(8);
+    if !(1..=queens::MAX_SIZE).contains(&size) {
+        return fail(format!(
+            "--side must be from 1 to {} for queens",
+            queens::MAX_SIZE
+        ));
+    }
+    let format = args.document_format()?;
+    if args.switch("count") {
+        let count = queens::count(size);
+        match format {
+            Format::Json => writeln!(
+                out,
+                "{}",
+                Json::object([
+                    ("game", "queens".into()),
+                    ("size", size.into()),
+                    ("count", count.into()),
+                ])
+            )?,
+            Format::Moves => writeln!(out, "{}", count)?,
+            Format::Text | Format::Csv => writeln!(out, "{} solutions", count)?,
+        }
+        return Ok(());
+    }
+    let max_solutions = args.parsed::<usize>("max-solutions")?;
+    if args.switch("all") || max_solutions.is_some() {
+        let solutions = queens::solutions(size, max_solutions);
+        if format == Format::Json {
+            let lines = solutions.iter().map(|p| p.to_string().into());
+            writeln!(
+                out,
+                "{}",
+                Json::object([
+                    ("game", "queens".into()),
+                    ("size", size.into()),
+                    ("count", solutions.len().into()),
+                    ("solutions", Json::Array(lines.collect())),
+                ])
+            )?;
+        } else {
+            for placement in &solutions {
+                writeln!(out, "{}", placement)?;
+            }
+            if format == Format::Text {
+                writeln!(out, "{} solutions", solutions.len())?;
+            }
+        }
+        if solutions.is_empty() {
+            return fail("no solution");
+        }
+        return Ok(());
+    }
+    let placement = queens::first(size);
+    let options = RenderOptions {
+        color: args.color()? && format == Format::Text,
+        coords: args.switch("coords"),
+        theme: args.theme()?,
+        ..RenderOptions::default()
+    };
+    if format == Format::Json {
+        let mut fields = vec![
+            ("game", "queens".into()),
+            ("size", size.into()),
+            ("solved", placement.is_some().into()),
+        ];
+        if let Some(placement) = &placement {
+            fields.push(("queens", placement.to_string().into()));
+            fields.push(("board", placement.render(&options).into()));
+        }
+        writeln!(out, "{}", Json::object(fields))?;
+    }
+    let Some(placement) = placement else {
+        return fail("no solution");
+    };
+    match format {
+        Format::Text => writeln!(out, "{}\n{}", placement.render(&options), placement)?,
+        Format::Moves => writeln!(out, "{}", placement)?,
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// A grid from a file or inline, graded and solved. Several solutions are
+/// reported as a fault of the puzzle, though the first is still printed.
+fn solve_sudoku(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(text) = text(args, 0)? else {
+        return fail("solve --game sudoku needs a grid");
+    };
+    let puzzle: Sudoku = text.parse()?;
+    let started = Instant::now();
+    let solution = puzzle.solve();
+    let unique = puzzle.count_solutions(2) == 1;
+    let stats = SearchStats {
+        elapsed: started.elapsed(),
+        ..SearchStats::default()
+    };
+    let grade = unique.then(|| puzzle.grade());
+    let format = args.document_format()?;
+    if format == Format::Json {
+        let mut fields = vec![
+            ("game", "sudoku".into()),
+            ("puzzle", puzzle.line().into()),
+            ("solved", solution.is_some().into()),
+            ("unique", unique.into()),
+        ];
+        if let Some(grade) = grade {
+            fields.push(("difficulty", grade.name().into()));
+        }
+        if let Some(solution) = &solution {
+            fields.push(("solution", solution.line().into()));
+        }
+        fields.push(("stats", stats.to_json()));
+        writeln!(out, "{}", Json::object(fields))?;
+    } else if args.switch("stats") {
+        eprintln!("{}", stats);
+    }
+    let Some(solution) = solution else {
+        return fail("no solution");
+    };
+    match format {
+        Format::Text => {
+            writeln!(out, "{}\n", puzzle)?;
+            match grade {
+                Some(grade) => writeln!(out, "Difficulty: {}\n", grade)?,
+                None => writeln!(out, "The puzzle has more than one solution\n")?,
+            }
+            writeln!(out, "{}", solution)?;
+        }
+        Format::Moves => writeln!(out, "{}", solution.line())?,
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// Clues in the `.non` format from a file or inline, drawn solved with the
+/// usual board options.
+fn solve_nonogram(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(text) = text(args, 0)? else {
+        return fail("solve --game nonogram needs clues");
+    };
+    let clues: Clues = text.parse()?;
+    let (mut pictures, stats) = clues.solutions(2);
+    let unique = pictures.len() == 1;
+    pictures.truncate(1);
+    let picture = pictures.pop();
+    let format = args.document_format()?;
+    if format == Format::Json {
+        let mut fields = vec![
+            ("game", "nonogram".into()),
+            ("width", clues.width().into()),
+            ("height", clues.height().into()),
+            ("solved", picture.is_some().into()),
+            ("unique", unique.into()),
+        ];
+        if let Some(picture) = &picture {
+            let rows = picture.rows().into_iter().map(Json::from);
+            fields.push(("rows", Json::Array(rows.collect())));
+        }
+        fields.push(("stats", stats.to_json()));
+        writeln!(out, "{}", Json::object(fields))?;
+    } else if args.switch("stats") {
+        eprintln!("{}", stats);
+    }
+    let Some(picture) = picture else {
+        return fail("no solution");
+    };
+    match format {
+        Format::Text => {
+            let options = RenderOptions {
+                color: args.color()?,
+                theme: args.theme()?,
+                ..RenderOptions::default()
+            };
+            write!(out, "{}", clues.render(&picture, &options))?;
+            if !unique {
+                writeln!(out, "\nThe clues have more than one solution")?;
+            }
+        }
+        Format::Moves => {
+            for row in picture.rows() {
+                writeln!(out, "{}", row)?;
+            }
+        }
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// A hexagonal board drawn with offset rows, or `triangle` or `hexagon`
+/// with `--side` cells to a side (default 5 and 3), solved depth first
+/// unless `--strategy` says otherwise, down to `--goal` or one peg.
+fn solve_hex(args: &Args) -> Result<()> {
+    let spec = text(args, 0)?.unwrap_or_else(|| "triangle".to_string());
+    let preset = |name: &str, side| {
+        let side = args.parsed("side")?.unwrap_or(side);
+        match HexBoard::preset(name, side) {
+            Some(board) => Ok(board),
+            None => fail(format!("--side {} gives no {} board that fits", side, name)),
+        }
+    };
+    let mut board = match spec.trim() {
+        "triangle" => preset("triangle", 5)?,
+        "hexagon" => preset("hexagon", 3)?,
+        _ => spec.parse()?,
+    };
+    if let Some(goal) = args.option("goal") {
+        let goal: HexBoard = if Path::new(goal).is_file() {
+            fs::read_to_string(goal)?.parse()?
+        } else {
+            goal.parse()?
+        };
+        board = board.with_goal(&goal)?;
+    }
+    let game = Game {
+        name: "hex",
+        puzzle: &board,
+        start: board.start(),
+        show: &|p| board.render(p),
+    };
+    if args.option("strategy").is_none() {
+        let (line, stats) = Search::DepthFirst.run(&board, board.start());
+        print_line(&game, Search::DepthFirst.name(), line, &stats, args)?;
+    } else {
+        report(&game, args)?;
+    }
+    Ok(())
+}
+
+/// Peg solitaire on a stack of layers, by default a box written as its
+/// size, `3x3x4`, solved depth first unless `--strategy` says otherwise,
+/// down to `--goal` or one peg.
+fn solve_layers(args: &Args) -> Result<()> {
+    let spec = text(args, 0)?.unwrap_or_else(|| "3x3x4".to_string());
+    let size: Option<Vec<usize>> = spec.trim().split('x').map(|n| n.parse().ok()).collect();
+    let mut board = match size.as_deref() {
+        Some(&[width, height, depth]) => Layers::cuboid(width, height, depth)?,
+        _ => spec.parse()?,
+    };
+    if let Some(goal) = args.option("goal") {
+        let goal: Layers = if Path::new(goal).is_file() {
+            fs::read_to_string(goal)?.parse()?
+        } else {
+            goal.parse()?
+        };
+        board = board.with_goal(&goal)?;
+    }
+    let game = Game {
+        name: "3d",
+        puzzle: &board,
+        start: board.start(),
+        show: &|p| board.render(p),
+    };
+    if args.option("strategy").is_none() {
+        let (line, stats) = Search::DepthFirst.run(&board, board.start());
+        print_line(&game, Search::DepthFirst.name(), line, &stats, args)?;
+    } else {
+        report(&game, args)?;
+    }
+    Ok(())
+}
+
+/// Whether the first player to jump on a peg solitaire board wins when
+/// both play their best, and the jumps that win.
+fn solve_duotaire(args: &Args) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let board = args.board(0)?;
+    let mut solver = Duotaire::new(&board);
+    let started = Instant::now();
+    let wins = solver.winning_moves(&board);
+    let stats = SearchStats {
+        elapsed: started.elapsed(),
+        ..solver.stats().clone()
+    };
+    let format = args.document_format()?;
+    if format == Format::Json {
+        let moves = wins.iter().map(|m| m.to_string().into());
+        writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("game", "duotaire".into()),
+                ("board", board.to_json()),
+                ("first_player_wins", (!wins.is_empty()).into()),
+                ("winning_moves", Json::Array(moves.collect())),
+                ("stats", stats.to_json()),
+            ])
+        )?;
+        return Ok(());
+    }
+    if args.switch("stats") {
+        eprintln!("{}", stats);
+    }
+    if format == Format::Text {
+        write!(out, "{}", board)?;
+        if wins.is_empty() {
+            writeln!(out, "\nThe second player wins with best play")?;
+        } else {
+            writeln!(out, "\nThe first player wins with best play, starting with")?;
+        }
+    }
+    for action in &wins {
+        writeln!(out, "{}", action)?;
+    }
+    Ok(())
+}
+
+fn solve_wolf_goat_cabbage(args: &Args) -> Result<()> {
+    args.at_most(0)?;
+    let game = Game {
+        name: "wolf-goat-cabbage",
+        puzzle: &WolfGoatCabbage,
+        start: &Banks::start(),
+        show: &|b| b.to_string(),
+    };
+    report(&game, args)?;
+    Ok(())
+}
+
+/// `--people` missionaries and as many cannibals (default 3) with a boat for
+/// `--boat` (default 2).
+fn solve_missionaries(args: &Args) -> Result<()> {
+    args.at_most(0)?;
+    let puzzle = MissionariesAndCannibals {
+        people: args.parsed("people")?.unwrap_or(3),
+        boat: args.parsed("boat")?.unwrap_or(2),
+    };
+    let game = Game {
+        name: "missionaries",
+        puzzle: &puzzle,
+        start: &puzzle.start(),
+        show: &|s| puzzle.render(s),
+    };
+    report(&game, args)?;
+    Ok(())
+}
+
+/// A puzzle to solve from `start`, with positions drawn by `show`.
+struct Game<'a, P: Puzzle> {
+    name: &'a str,
+    puzzle: &'a P,
+    start: &'a P::State,
+    show: &'a dyn Fn(&P::State) -> String,
+}
+
+/// Searches with `--strategy` and prints the line, which it returns.
+fn report<P>(game: &Game<P>, args: &Args) -> Result<Vec<P::Move>>
+where
+    P: Puzzle,
+    P::Move: Display + PartialEq,
+{
+    let search: Search = args.parsed("strategy")?.unwrap_or_default();
+    let (line, stats) = search.run(game.puzzle, game.start);
+    print_line(game, search.name(), line, &stats, args)
+}
+
+/// Prints the line found by `strategy` in the chosen format, failing when
+/// there is none.
+fn print_line<P>(
+    game: &Game<P>,
+    strategy: &str,
+    line: Option<Vec<P::Move>>,
+    stats: &SearchStats,
+    args: &Args,
+) -> Result<Vec<P::Move>>
+where
+    P: Puzzle,
+    P::Move: Display + PartialEq,
+{
+    let mut out = io::stdout().lock();
+    let format = args.document_format()?;
+    let show = game.show;
+    let last = line.as_ref().map(|line| {
+        line.iter().fold(game.start.clone(), |state, step| {
+            let mut next = game.puzzle.successors(&state).into_iter();
+            next.find(|(m, _)| m == step).unwrap().1
+        })
+    });
+    if format == Format::Json {
+        let mut fields = vec![
+            ("game", game.name.into()),
+            ("start", show(game.start).into()),
+            ("strategy", strategy.into()),
+            ("solved", line.is_some().into()),
+        ];
+        if let (Some(line), Some(last)) = (&line, &last) {
+            let moves: Vec<Json> = line.iter().map(|m| m.to_string().into()).collect();
+            fields.push(("moves", Json::Array(moves)));
+            fields.push(("final", show(last).into()));
+        }
+        fields.push(("stats", stats.to_json()));
+        writeln!(out, "{}", Json::object(fields))?;
+    } else if args.switch("stats") {
+        eprintln!("{}", stats);
+    }
+    let (Some(line), Some(last)) = (line, last) else {
+        return fail("no solution");
+    };
+    match format {
+        Format::Text => {
+            writeln!(out, "{}\n", show(game.start))?;
+            let moves = line.len();
+            writeln!(
+                out,
+                "{}\n",
+                trf("Solved in {moves} moves", &[("moves", &moves)])
+            )?;
+            for step in &line {
+                writeln!(out, "{}", step)?;
+            }
+            writeln!(out, "\n{}", show(&last))?;
+        }
+        Format::Moves => {
+            for step in &line {
+                writeln!(out, "{}", step)?;
+            }
+        }
+        Format::Json | Format::Csv => {}
+    }
+    Ok(line)
+}
@@ -0,0 +1,560 @@
+//! Translations of the messages the interactive commands, `solve`,
+//! `verify` and `hint` print, and of the errors, chosen with `--lang` or
+//! the system locale. Messages are looked up by their English text, which
+//! is also what is printed when a catalog has no entry for one, so a
+//! missing translation falls back to English rather than failing.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::IllegalMove;
+
+use super::board::BoardMove;
+use super::{duotaire, play, replay, train, tui, CliError};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum Lang {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Lang {
+    const ALL: [Lang; 2] = [Lang::English, Lang::Spanish];
+
+    /// The language of a locale such as `es_ES.UTF-8`, or of a bare code
+    /// such as `es`.
+    fn from_locale(locale: &str) -> Option<Lang> {
+        let code = locale.split(['_', '.', '@', '-']).next()?;
+        match code.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Lang::English),
+            "es" => Some(Lang::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set, or
+    /// English when it names a language without a catalog.
+    pub(super) fn system() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::English => &[],
+            Lang::Spanish => SPANISH,
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Lang, CliError> {
+        Lang::from_locale(s)
+            .ok_or_else(|| CliError(format!("unknown language {:?}, expected en or es", s)))
+    }
+}
+
+static LANG: AtomicU8 = AtomicU8::new(Lang::English as u8);
+
+pub(super) fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+fn lang() -> Lang {
+    let stored = LANG.load(Ordering::Relaxed);
+    Lang::ALL
+        .into_iter()
+        .find(|&lang| lang as u8 == stored)
+        .unwrap_or_default()
+}
+
+fn translate(message: &str) -> &str {
+    lang()
+        .catalog()
+        .iter()
+        .find(|(english, _)| *english == message)
+        .map_or(message, |(_, translated)| translated)
+}
+
+/// `message` in the chosen language.
+pub(super) fn tr(message: &str) -> String {
+    translate(message).to_string()
+}
+
+/// `message` in the chosen language with each `{name}` replaced by the
+/// value given for it.
+pub(super) fn trf(message: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut out = translate(message).to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
+
+/// `text`, a message already filled in, in the chosen language. The
+/// message is found by the fixed parts of its English text, and each value
+/// filled into it is translated in turn, so that a library error inside
+/// one of the CLI's reads in the same language.
+pub(super) fn tr_text(text: &str) -> String {
+    // The message with the most fixed text is the one filled in, so that
+    // `move {n}, {move}, is not legal: {reason}` wins over `move {n}:
+    // {reason}`, which matches the same text.
+    let found = lang()
+        .catalog()
+        .iter()
+        .filter_map(|&(english, translated)| {
+            let values = filled_in(english, text)?;
+            let fixed = english.len() - values.iter().map(|(n, _)| n.len() + 2).sum::<usize>();
+            Some((fixed, translated, values))
+        })
+        .max_by_key(|&(fixed, _, _)| fixed);
+    let Some((_, translated, values)) = found else {
+        return text.to_string();
+    };
+    let mut out = translated.to_string();
+    for (name, value) in values {
+        out = out.replace(&format!("{{{}}}", name), &tr_text(value));
+    }
+    out
+}
+
+/// The value each `{name}` of `message` takes in `text`, or `None` when
+/// `text` is not `message` filled in.
+fn filled_in<'a>(message: &'a str, text: &'a str) -> Option<Vec<(&'a str, &'a str)>> {
+    let Some((head, mut rest)) = message.split_once('{') else {
+        return (message == text).then(Vec::new);
+    };
+    let mut text = text.strip_prefix(head)?;
+    let mut values = Vec::new();
+    loop {
+        let (name, after) = rest.split_once('}')?;
+        let Some((fixed, next)) = after.split_once('{') else {
+            values.push((name, text.strip_suffix(after)?));
+            return Some(values);
+        };
+        // Two values back to back could be split anywhere.
+        if fixed.is_empty() {
+            return None;
+        }
+        let end = text.find(fixed)?;
+        values.push((name, &text[..end]));
+        text = &text[end + fixed.len()..];
+        rest = next;
+    }
+}
+
+/// `err` in the chosen language. I/O errors are left as they are: their
+/// text comes from the system, and the caller still needs to tell a
+/// closed pipe from them.
+pub(super) fn tr_error(err: Box<dyn Error>) -> Box<dyn Error> {
+    if err.is::<io::Error>() {
+        return err;
+    }
+    let text = err.to_string();
+    match tr_text(&text) {
+        translated if translated == text => err,
+        translated => Box::new(CliError(translated)),
+    }
+}
+
+/// `(3, 1) ↓ is not legal: there is no peg to jump over`.
+pub(super) fn not_legal<M: BoardMove>(mv: M, reason: IllegalMove) -> String {
+    trf(
+        "{move} is not legal: {reason}",
+        &[
+            ("move", &super::move_text(mv)),
+            ("reason", &tr(&reason.to_string())),
+        ],
+    )
+}
+
+const SPANISH: &[(&str, &str)] = &[
+    (
+        play::HELP,
+        "\
+Escribe una jugada como `x y dirección`, p. ej. `3 1 down` o `3,1,d`, con
+la dirección en inglés: up, down, left o right. x cuenta las columnas desde
+la izquierda e y las filas desde arriba, ambas desde 0. También vale
+`d2-d4`, con las columnas con letras desde la a y las filas numeradas
+desde 1, como en las frases de --screen-reader.
+Otros comandos: moves (lista las jugadas legales), undo (u), redo (r),
+save ARCHIVO, load ARCHIVO, help, quit.
+",
+    ),
+    (
+        train::HELP,
+        "\
+Cada posición tiene exactamente un salto con el que aún se puede acabar
+con la última ficha en el centro. Escríbelo como `x y dirección`, p. ej.
+`3 1 down` o `3,1,d`. Tres aciertos seguidos hacen las posiciones más
+grandes y difíciles; un fallo las hace más fáciles.
+Otros comandos: moves (lista las jugadas legales), skip (muestra la
+respuesta), help, quit.
+",
+    ),
+    (
+        duotaire::HELP,
+        "\
+Los jugadores saltan por turnos; gana quien haga el último salto.
+Escribe una jugada como `x y dirección`, p. ej. `3 1 down` o `3,1,d`.
+Otros comandos: moves (lista las jugadas legales), hint (una jugada
+ganadora, si el jugador al que le toca tiene una), undo (u), help, quit.
+",
+    ),
+    (
+        tui::HELP,
+        "flechas/hjkl mover  intro/espacio elegir  x soltar  u deshacer  r rehacer  q salir",
+    ),
+    (
+        replay::HELP,
+        "derecha/espacio siguiente  izquierda/retroceso atrás  q salir",
+    ),
+    // Why a move or a goal fails, from the library.
+    (
+        "there is no peg on the starting cell",
+        "no hay ficha en la casilla de salida",
+    ),
+    (
+        "the move starts or lands off the board",
+        "la jugada sale o cae fuera del tablero",
+    ),
+    (
+        "there is no peg to jump over",
+        "no hay ninguna ficha que saltar",
+    ),
+    (
+        "the landing cell already holds a peg",
+        "la casilla de llegada ya tiene una ficha",
+    ),
+    (
+        "the position and the goal use different board layouts",
+        "la posición y el objetivo usan tableros distintos",
+    ),
+    (
+        "no jump is possible and the goal is not met",
+        "no queda ningún salto y el objetivo no se ha cumplido",
+    ),
+    (
+        "{move} is not legal: {reason}",
+        "{move} no es legal: {reason}",
+    ),
+    ("move {n}: {reason}", "jugada {n}: {reason}"),
+    ("easy", "fácil"),
+    ("medium", "media"),
+    ("hard", "difícil"),
+    ("fine", "bien"),
+    ("already lost", "ya perdida"),
+    // Errors from the library.
+    (
+        "invalid move {input}, expected `x y direction` such as `3 1 down` or `d2-d4`",
+        "jugada {input} no válida, se esperaba `x y dirección` como `3 1 down` o `d2-d4`",
+    ),
+    (
+        "invalid move {input}, expected cells two apart such as `d1-d3`, or `x y direction`",
+        "jugada {input} no válida, se esperaban dos casillas separadas por otra como \
+         `d1-d3`, o `x y dirección`",
+    ),
+    (
+        "invalid move {input}, expected cells two apart such as `d2-d4`",
+        "jugada {input} no válida, se esperaban dos casillas separadas por otra como `d2-d4`",
+    ),
+    (
+        "move {n}, {move}, is not legal: {reason}",
+        "la jugada {n}, {move}, no es legal: {reason}",
+    ),
+    (
+        "the position has {pegs} pegs and the goal {goal}; jumps only remove pegs",
+        "la posición tiene {pegs} fichas y el objetivo {goal}; los saltos solo quitan fichas",
+    ),
+    (
+        "the pegs at {cells} can never move or be captured again",
+        "las fichas en {cells} ya no pueden moverse ni ser capturadas",
+    ),
+    (
+        "position class {position} differs from the goal's {goal}; jumps preserve it",
+        "la clase de la posición, {position}, no es la del objetivo, {goal}; los \
+         saltos la conservan",
+    ),
+    (
+        "a board has at most {rows} rows",
+        "un tablero tiene como mucho {rows} filas",
+    ),
+    (
+        "row {row} has more than {cells} cells",
+        "la fila {row} tiene más de {cells} casillas",
+    ),
+    (
+        "unknown cell {symbol} in row {row}",
+        "casilla desconocida {symbol} en la fila {row}",
+    ),
+    (
+        "unknown strategy {name}, expected dfs, bidirectional, backward or pns",
+        "estrategia desconocida {name}, se esperaba dfs, bidirectional, backward o pns",
+    ),
+    (
+        "unknown hint level {name}, expected 1 to 4 or peg, direction, move or line",
+        "nivel de pista desconocido {name}, se esperaba del 1 al 4 o peg, direction, \
+         move o line",
+    ),
+    // Errors from reading the command line.
+    ("--{name} needs a value", "--{name} necesita un valor"),
+    ("--{name} does not take a value", "--{name} no lleva valor"),
+    ("unknown option --{name}", "opción desconocida --{name}"),
+    (
+        "unexpected argument {argument}",
+        "argumento inesperado {argument}",
+    ),
+    ("--{name}: {error}", "--{name}: {error}"),
+    (
+        "unknown format {name}, expected text, moves, json or csv",
+        "formato desconocido {name}, se esperaba text, moves, json o csv",
+    ),
+    (
+        "unknown color choice {name}, expected auto, always or never",
+        "opción de color desconocida {name}, se esperaba auto, always o never",
+    ),
+    (
+        "unknown notation {name}, expected coords, standard or spoken",
+        "notación desconocida {name}, se esperaba coords, standard o spoken",
+    ),
+    (
+        "--format csv is only available with solve --batch and heatmap",
+        "--format csv solo está disponible con solve --batch y heatmap",
+    ),
+    (
+        "--timeout: expected a non-negative number of seconds",
+        "--timeout: se esperaba un número de segundos no negativo",
+    ),
+    (
+        "--lang only works with play, train, tui, replay, solve, verify and hint",
+        "--lang solo funciona con play, train, tui, replay, solve, verify y hint",
+    ),
+    (
+        "no config file {path}",
+        "no existe el archivo de configuración {path}",
+    ),
+    (
+        "no preset, file or board called {name}",
+        "ningún preset, archivo ni tablero se llama {name}",
+    ),
+    (
+        "{command} needs a list of moves",
+        "{command} necesita una lista de jugadas",
+    ),
+    // solve, verify and hint
+    ("no solution", "no hay solución"),
+    ("no solution: {reason}", "no hay solución: {reason}"),
+    (
+        "the search ran out of time before settling the board",
+        "la búsqueda se quedó sin tiempo antes de resolver el tablero",
+    ),
+    (
+        "no solver settled the board in time",
+        "ningún solucionador resolvió el tablero a tiempo",
+    ),
+    (
+        "--{name} only works with --batch",
+        "--{name} solo funciona con --batch",
+    ),
+    (
+        "--batch only solves flat boards",
+        "--batch solo resuelve tableros planos",
+    ),
+    (
+        "--timeout does not limit --topology or --all",
+        "--timeout no limita --topology ni --all",
+    ),
+    (
+        "--variants only works on flat boards",
+        "--variants solo funciona en tableros planos",
+    ),
+    (
+        "--{name} does not work on the diamond boards",
+        "--{name} no funciona en los tableros de diamante",
+    ),
+    (
+        "--goal is not drawn on the {board} board",
+        "--goal no está dibujado en el tablero {board}",
+    ),
+    (
+        "the moves do not reach the goal",
+        "las jugadas no llegan al objetivo",
+    ),
+    (
+        "From the book: {name} ({source})",
+        "Del libro: {name} ({source})",
+    ),
+    ("Found by {solver}", "Encontrado por {solver}"),
+    ("Solved in {moves} moves", "Resuelto en {moves} jugadas"),
+    ("(x, y) direction", "(x, y) dirección"),
+    (
+        "Moves: {moves}  Pegs left: {pegs}",
+        "Jugadas: {moves}  Fichas restantes: {pegs}",
+    ),
+    ("The goal is reached.", "Se ha alcanzado el objetivo."),
+    (
+        "The goal is already reached.",
+        "El objetivo ya está alcanzado.",
+    ),
+    ("Next move: {move}", "Siguiente jugada: {move}"),
+    ("Move the peg at {cell}.", "Mueve la ficha de {cell}."),
+    (
+        "Jump the peg at {cell} {direction}.",
+        "Salta con la ficha de {cell} hacia {direction}.",
+    ),
+    ("Next {count} moves:", "Siguientes {count} jugadas:"),
+    ("up", "arriba"),
+    ("down", "abajo"),
+    ("left", "la izquierda"),
+    ("right", "la derecha"),
+    // play
+    (
+        "Pegs: {pegs}  Moves: {moves}",
+        "Fichas: {pegs}  Jugadas: {moves}",
+    ),
+    ("Solved in {moves} moves!", "¡Resuelto en {moves} jugadas!"),
+    (
+        "Stuck: no legal moves are left. Undo or quit.",
+        "Atascado: no quedan jugadas legales. Deshaz o sal.",
+    ),
+    ("Nothing to undo.", "No hay nada que deshacer."),
+    ("Nothing to redo.", "No hay nada que rehacer."),
+    ("Saved to {path}.", "Guardado en {path}."),
+    (
+        "Could not save to {path}: {error}",
+        "No se pudo guardar en {path}: {error}",
+    ),
+    ("Loaded {path}.", "Cargado {path}."),
+    (
+        "Only games on the English board can be saved.",
+        "Solo se pueden guardar partidas en el tablero inglés.",
+    ),
+    (
+        "only games on the English board can be loaded",
+        "solo se pueden cargar partidas en el tablero inglés",
+    ),
+    (
+        "Could not load {path}: {error}",
+        "No se pudo cargar {path}: {error}",
+    ),
+    (
+        "The goal can no longer be reached: {reason}.",
+        "Ya no se puede alcanzar el objetivo: {reason}.",
+    ),
+    (
+        "Give a file name, e.g. `{command} game.txt`.",
+        "Indica un nombre de archivo, p. ej. `{command} partida.txt`.",
+    ),
+    (
+        "Not counting this game: {error}",
+        "Esta partida no cuenta: {error}",
+    ),
+    (
+        "{seconds} s, number {place} on your leaderboard.",
+        "{seconds} s, puesto {place} en tu clasificación.",
+    ),
+    (
+        "Daily puzzle streak: {days}.",
+        "Racha del reto diario: {days}.",
+    ),
+    (
+        "Could not save statistics to {path}: {error}",
+        "No se pudieron guardar las estadísticas en {path}: {error}",
+    ),
+    (
+        "Games finished: {games} ({solved} solved)",
+        "Partidas terminadas: {games} ({solved} resueltas)",
+    ),
+    ("Fewest pegs left: {pegs}", "Menos fichas al final: {pegs}"),
+    (
+        "Fastest solve: {seconds} s",
+        "Resolución más rápida: {seconds} s",
+    ),
+    (
+        "Daily puzzle streak: {days} (best {best})",
+        "Racha del reto diario: {days} (mejor {best})",
+    ),
+    ("Leaderboard:", "Clasificación:"),
+    // train
+    (
+        "Level {level}, {rating}: {choices} legal jumps, one of them wins.",
+        "Nivel {level}, {rating}: {choices} saltos legales, uno de ellos gana.",
+    ),
+    ("The winning jump: {move}.", "El salto ganador: {move}."),
+    ("Right!", "¡Correcto!"),
+    (
+        " {streak} in a row, on to level {level}.",
+        " {streak} seguidos, pasas al nivel {level}.",
+    ),
+    (
+        "That loses. The winning jump: {move}.",
+        "Ese pierde. El salto ganador: {move}.",
+    ),
+    (
+        "{right} of {asked} right, best streak {best}, reached level {level}.",
+        "{right} de {asked} correctas, mejor racha {best}, nivel alcanzado {level}.",
+    ),
+    // duotaire
+    ("No jump can be made.", "No se puede hacer ningún salto."),
+    (
+        "Player {player} cannot jump: player {other} wins!",
+        "El jugador {player} no puede saltar: ¡gana el jugador {other}!",
+    ),
+    ("Player {player} to move", "Le toca al jugador {player}"),
+    ("{move} wins.", "{move} gana."),
+    (
+        "Every jump loses against best play.",
+        "Todos los saltos pierden contra el mejor juego.",
+    ),
+    // tui
+    (
+        "the TUI needs an interactive terminal, try `play` instead",
+        "la interfaz a pantalla completa necesita un terminal interactivo, prueba `play`",
+    ),
+    (
+        "The selected peg cannot jump there",
+        "La ficha elegida no puede saltar ahí",
+    ),
+    ("Pick a peg to move first", "Elige primero una ficha"),
+    (
+        "That peg has no legal jump",
+        "Esa ficha no tiene saltos legales",
+    ),
+    ("Nothing to undo", "No hay nada que deshacer"),
+    ("Nothing to redo", "No hay nada que rehacer"),
+    (
+        "Stuck: no legal moves are left",
+        "Atascado: no quedan jugadas legales",
+    ),
+    ("Moves", "Jugadas"),
+    (tui::PAUSE_HELP, "  p pausa"),
+    ("Paused, press p to resume", "En pausa, pulsa p para seguir"),
+    ("Time is up.", "Se acabó el tiempo."),
+    ("Score: {score}", "Puntuación: {score}"),
+    (
+        "Timed challenges: {count} (best score {score})",
+        "Retos contrarreloj: {count} (mejor puntuación {score})",
+    ),
+    // replay
+    ("Move {n}/{total}: {move}", "Jugada {n}/{total}: {move}"),
+    ("Finished in {moves} moves", "Terminado en {moves} jugadas"),
+    (
+        "After move {n}/{total}: {move}",
+        "Tras la jugada {n}/{total}: {move}",
+    ),
+    (
+        "Start, {total} moves to play",
+        "Inicio, {total} jugadas por delante",
+    ),
+];
@@ -0,0 +1,241 @@
+//! `--protocol jsonrpc`: JSON-RPC 2.0 over stdin and stdout, one message
+//! per line, for frontends that drive the engine as a subprocess. The
+//! methods are
+//!
+//! ```text
+//! newgame    {"board"?, "goal"?}     start a game, returns the state
+//! applymove  {"move"}                play a move, returns the state
+//! legalmoves                         the legal moves
+//! solve      {"strategy"?}           searches in the background and
+//!                                    answers when done
+//! stop                               ends a running solve early
+//! ```
+//!
+//! Boards and moves are read as by [`FromJson`]. A request without an
+//! `id` is a notification and gets no answer. At the end of the input a
+//! running solve is waited for, so only `stop` cuts one short.
+
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::{Action, GameTree, Grid, SearchStrategy};
+
+use super::{solve_json, Result};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// An illegal move, or a solve while another is running.
+const REFUSED: i64 = -32000;
+
+struct RpcError(i64, String);
+
+fn invalid_params(message: impl ToString) -> RpcError {
+    RpcError(INVALID_PARAMS, message.to_string())
+}
+
+fn reply(id: &Json, result: std::result::Result<Json, RpcError>) -> Json {
+    let outcome = match result {
+        Ok(result) => ("result", result),
+        Err(RpcError(code, message)) => (
+            "error",
+            Json::object([
+                ("code", Json::Integer(code as i128)),
+                ("message", message.into()),
+            ]),
+        ),
+    };
+    Json::object([("jsonrpc", "2.0".into()), ("id", id.clone()), outcome])
+}
+
+/// A search running on its own thread.
+struct Search {
+    stop: Arc<AtomicBool>,
+    worker: JoinHandle<()>,
+}
+
+struct Engine {
+    start: Grid,
+    goal: Grid,
+    moves: Vec<Action>,
+    position: Grid,
+    search: Option<Search>,
+}
+
+impl Engine {
+    fn state(&self) -> Json {
+        Json::object([
+            ("board", self.position.to_json()),
+            ("start", self.start.to_json()),
+            ("goal", self.goal.to_json()),
+            ("moves", Json::array(&self.moves)),
+            ("won", (self.position == self.goal).into()),
+        ])
+    }
+
+    fn searching(&self) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|s| !s.worker.is_finished())
+    }
+
+    /// The outcome of one call, or `None` when it is answered later.
+    fn call(
+        &mut self,
+        method: &str,
+        params: &Json,
+        id: Option<&Json>,
+    ) -> Option<std::result::Result<Json, RpcError>> {
+        Some(match method {
+            "newgame" => self.new_game(params),
+            "applymove" => self.apply_move(params),
+            "legalmoves" => Ok(Json::array(&self.position.valid_actions())),
+            "solve" => match self.solve(params, id.cloned()) {
+                Ok(()) => return None,
+                Err(e) => Err(e),
+            },
+            "stop" => Ok(self.stop().into()),
+            _ => Err(RpcError(
+                METHOD_NOT_FOUND,
+                format!("no method {:?}", method),
+            )),
+        })
+    }
+
+    fn new_game(&mut self, params: &Json) -> std::result::Result<Json, RpcError> {
+        let start = match params.get("board") {
+            Some(board) => Grid::from_json(board).map_err(invalid_params)?,
+            None => Grid::new(),
+        };
+        let goal = match params.get("goal") {
+            Some(goal) => Grid::from_json(goal).map_err(invalid_params)?,
+            None => start.single_peg(3, 3),
+        };
+        self.start = start;
+        self.goal = goal;
+        self.moves.clear();
+        self.position = start;
+        Ok(self.state())
+    }
+
+    fn apply_move(&mut self, params: &Json) -> std::result::Result<Json, RpcError> {
+        let action = params
+            .get("move")
+            .ok_or_else(|| invalid_params("applymove needs a `move`"))
+            .and_then(|m| Action::from_json(m).map_err(invalid_params))?;
+        self.position
+            .check_action(action)
+            .map_err(|reason| RpcError(REFUSED, format!("{} is not legal: {}", action, reason)))?;
+        self.position = self.position.perform_action(action);
+        self.moves.push(action);
+        Ok(self.state())
+    }
+
+    fn solve(&mut self, params: &Json, id: Option<Json>) -> std::result::Result<(), RpcError> {
+        if self.searching() {
+            return Err(RpcError(REFUSED, "a search is already running".to_string()));
+        }
+        let strategy: SearchStrategy = match params.get("strategy") {
+            Some(Json::String(name)) => name.parse().map_err(invalid_params)?,
+            Some(_) => return Err(invalid_params("strategy must be a string")),
+            None => SearchStrategy::default(),
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let (board, goal) = (self.position, self.goal);
+        let worker = thread::spawn(move || {
+            let (found, stats) =
+                GameTree::new(board, Vec::new()).search_cancellable(&goal, strategy, &flag);
+            let moves = found.as_ref().map(GameTree::history);
+            let Json::Object(mut fields) =
+                solve_json(&board, &goal, strategy.name(), moves, &stats)
+            else {
+                unreachable!()
+            };
+            let stopped = found.is_none() && flag.load(Ordering::Relaxed);
+            fields.push(("stopped".to_string(), stopped.into()));
+            // A closed stdout is left for the request loop to notice, as
+            // the search has no one to report it to.
+            if let Some(id) = id {
+                let _ = writeln!(
+                    io::stdout().lock(),
+                    "{}",
+                    reply(&id, Ok(Json::Object(fields)))
+                );
+            }
+        });
+        self.search = Some(Search { stop, worker });
+        Ok(())
+    }
+
+    /// Stops the running search, if any, and waits for it to answer.
+    /// Returns whether there was one.
+    fn stop(&mut self) -> bool {
+        let running = self.searching();
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Relaxed);
+            let _ = search.worker.join();
+        }
+        running
+    }
+
+    /// Waits for the running search, if any, to finish and answer.
+    fn finish(&mut self) {
+        if let Some(search) = self.search.take() {
+            let _ = search.worker.join();
+        }
+    }
+}
+
+pub(super) fn run() -> Result<()> {
+    // Locked a line at a time rather than for the session, as the search
+    // thread writes its results to it too.
+    let mut out = io::stdout();
+    let mut engine = Engine {
+        start: Grid::new(),
+        goal: Grid::new().single_peg(3, 3),
+        moves: Vec::new(),
+        position: Grid::new(),
+        search: None,
+    };
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Json = match line.parse() {
+            Ok(request) => request,
+            Err(e) => {
+                let error = Err(RpcError(PARSE_ERROR, e.to_string()));
+                writeln!(out, "{}", reply(&Json::Null, error))?;
+                continue;
+            }
+        };
+        let id = request.get("id");
+        let params = request
+            .get("params")
+            .cloned()
+            .unwrap_or(Json::Object(Vec::new()));
+        let result = match (request.get("method").and_then(Json::as_str), &params) {
+            (Some(method), Json::Object(_)) => engine.call(method, &params, id),
+            (Some(_), _) => Some(Err(invalid_params("params must be an object"))),
+            // Not a valid request, so answered even without an id.
+            (None, _) => {
+                let error = Err(RpcError(INVALID_REQUEST, "missing method".to_string()));
+                writeln!(out, "{}", reply(id.unwrap_or(&Json::Null), error))?;
+                continue;
+            }
+        };
+        if let (Some(id), Some(result)) = (id, result) {
+            writeln!(out, "{}", reply(id, result))?;
+        }
+    }
+    // Requests are often piped in, so the answer to a last solve is still
+    // wanted once they run out.
+    engine.finish();
+    Ok(())
+}
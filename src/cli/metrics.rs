@@ -0,0 +1,177 @@
+//! Counters for `serve`, written at `GET /metrics` in the Prometheus text
+//! format. The cache hit rate is `puzzlething_cache_hits_total` over the
+//! sum of hits and misses.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::parallel::Outcome;
+
+/// Upper bounds of the solve latency buckets, in seconds.
+const BUCKETS: [f64; 8] = [0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+#[derive(Default)]
+pub(super) struct Metrics {
+    /// By endpoint and status.
+    requests: Mutex<BTreeMap<(&'static str, u16), u64>>,
+    solved: AtomicU64,
+    unsolvable: AtomicU64,
+    timeouts: AtomicU64,
+    nodes_expanded: AtomicU64,
+    /// Searches no slower than each bound of [`BUCKETS`].
+    latency_buckets: [AtomicU64; BUCKETS.len()],
+    latency_count: AtomicU64,
+    latency_nanos: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+impl Metrics {
+    /// Counts a response; paths with no endpoint share the label `other`
+    /// so that clients cannot add series without end.
+    pub(super) fn request(&self, path: &str, status: u16) {
+        let endpoint = match path {
+            "/" => "/",
+            "/solve" => "/solve",
+            "/hint" => "/hint",
+            "/daily" => "/daily",
+            "/play" => "/play",
+            "/metrics" => "/metrics",
+            _ => "other",
+        };
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((endpoint, status))
+            .or_default() += 1;
+    }
+
+    /// Counts a search that took `elapsed`.
+    pub(super) fn search(&self, outcome: &Outcome, elapsed: Duration) {
+        let (counter, stats) = match outcome {
+            Outcome::Solved(_, stats) => (&self.solved, Some(stats)),
+            Outcome::Unsolvable(stats) => (&self.unsolvable, Some(stats)),
+            Outcome::Timeout => (&self.timeouts, None),
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(stats) = stats {
+            self.nodes_expanded
+                .fetch_add(stats.nodes_expanded, Ordering::Relaxed);
+        }
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, &bound) in self.latency_buckets.iter().zip(&BUCKETS) {
+            if seconds <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.latency_count.fetch_add(1, Ordering::Relaxed);
+        self.latency_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn cache_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn render(&self) -> String {
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let mut out = String::new();
+        let header = |out: &mut String, name: &str, kind: &str, help: &str| {
+            let _ = writeln!(out, "# HELP puzzlething_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE puzzlething_{} {}", name, kind);
+        };
+        header(
+            &mut out,
+            "http_requests_total",
+            "counter",
+            "HTTP requests answered, by endpoint and status.",
+        );
+        for ((endpoint, status), count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "puzzlething_http_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                endpoint, status, count
+            );
+        }
+        header(
+            &mut out,
+            "solves_total",
+            "counter",
+            "Searches run for /solve and /hint, by outcome.",
+        );
+        for (outcome, counter) in [
+            ("solved", &self.solved),
+            ("unsolvable", &self.unsolvable),
+            ("timeout", &self.timeouts),
+        ] {
+            let _ = writeln!(
+                out,
+                "puzzlething_solves_total{{outcome=\"{}\"}} {}",
+                outcome,
+                get(counter)
+            );
+        }
+        header(
+            &mut out,
+            "nodes_expanded_total",
+            "counter",
+            "Nodes expanded by searches that finished.",
+        );
+        let _ = writeln!(
+            out,
+            "puzzlething_nodes_expanded_total {}",
+            get(&self.nodes_expanded)
+        );
+        header(
+            &mut out,
+            "solve_duration_seconds",
+            "histogram",
+            "Time spent searching per request.",
+        );
+        for (bucket, bound) in self.latency_buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(
+                out,
+                "puzzlething_solve_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound,
+                get(bucket)
+            );
+        }
+        let count = get(&self.latency_count);
+        let _ = writeln!(
+            out,
+            "puzzlething_solve_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            count
+        );
+        let _ = writeln!(
+            out,
+            "puzzlething_solve_duration_seconds_sum {}",
+            get(&self.latency_nanos) as f64 / 1e9
+        );
+        let _ = writeln!(out, "puzzlething_solve_duration_seconds_count {}", count);
+        for (name, help, counter) in [
+            (
+                "cache_hits_total",
+                "Searches answered from the solution cache.",
+                &self.cache_hits,
+            ),
+            (
+                "cache_misses_total",
+                "Cache lookups that had to search.",
+                &self.cache_misses,
+            ),
+        ] {
+            header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "puzzlething_{} {}", name, get(counter));
+        }
+        out
+    }
+}
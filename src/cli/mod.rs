@@ -0,0 +1,1952 @@
+mod batch;
+mod bench;
+mod board;
+mod config;
+mod diamond;
+mod duotaire;
+mod edit;
+mod export;
+mod games;
+mod i18n;
+mod jsonrpc;
+#[cfg(feature = "serve")]
+mod metrics;
+mod play;
+mod replay;
+#[cfg(feature = "serve")]
+mod serve;
+mod train;
+mod tui;
+mod uci;
+#[cfg(feature = "serve")]
+mod websocket;
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{self, AtomicBool};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::anytime;
+use crate::book;
+use crate::cache::{Cached, SolutionCache};
+use crate::daily::{self, Date};
+use crate::diamond::Diamond;
+use crate::diff;
+use crate::enumerate;
+use crate::generate;
+use crate::heatmap::{self, Role};
+use crate::hint::{self, Hint, HintLevel};
+use crate::json::{self, Json, ToJson};
+use crate::last_peg;
+use crate::notation::{self, Standard};
+use crate::parallel::{self, BatchOptions, Outcome};
+use crate::portfolio::{self, Finish};
+use crate::presets::Preset;
+use crate::registry::{self, Builtin};
+use crate::render::{self, RenderOptions, Theme};
+use crate::speech;
+use crate::stats::SearchStats;
+use crate::symmetry;
+use crate::topology::{self, Topology};
+use crate::trace;
+use crate::{Action, GameTree, Grid, SearchStrategy};
+
+use board::{Board, BoardMove};
+use i18n::{tr, trf, Lang};
+
+const USAGE: &str = "\
+Usage: puzzlething [COMMAND] [OPTIONS]
+       puzzlething --protocol jsonrpc|uci
+
+Commands:
+  solve [BOARD]          Solve a board and print the moves (the default)
+  play [BOARD]           Play a board interactively, or resume one with --load
+                         or, with --duotaire, take turns with another player
+                         and make the last jump to win
+  tui [BOARD]            Play a board in a full-screen terminal interface, or
+                         against the clock with --timed
+  train                  Find the one winning jump in positions that get
+                         harder as you get them right
+  verify [BOARD] MOVES   Replay a list of moves and check that the goal is met
+  replay [BOARD] MOVES   Step through a list of moves one key at a time
+  hint [BOARD]           Print the next move of a solution
+  analyze [BOARD]        Judge a proposed move given with --move, or with
+                         --last-peg find the cells the last peg can end on
+  book [BOARD]           List the opening book of known solutions, or look a
+                         board up in it
+  heatmap [BOARD]        Count how often each cell is jumped from, over and
+                         into across many solutions
+  stats [BOARD]          Explore every position reachable from a board and
+                         count them by depth, dead ends and branching
+  stats me               Show your games finished, best result, fastest
+                         solves and daily puzzle streak
+  diff [BOARD] A B       Compare two lines of moves played from the same board
+  gen                    Generate a random solvable board, or a Sudoku with
+                         --game sudoku
+  edit FILE              Draw a board with the keyboard and save it to FILE
+  export [BOARD] [MOVES] Draw a board, or every step of a line of moves, as an
+                         image or animation
+  bench [BOARD]          Time the search strategies on a board, or on a fixed
+                         suite of boards when none is given
+  serve                  Answer POST /solve, POST /hint and GET /daily over
+                         HTTP with JSON, play games over a WebSocket on
+                         /play, serve a board to play in the browser on /
+                         and Prometheus metrics on /metrics (needs the
+                         serve feature)
+  help                   Print this message
+
+Games finished with play are counted in a statistics file, by default
+~/.local/share/puzzlething/stats; set PUZZLETHING_STATS to use another.
+
+Defaults for --strategy, --notation, --color, --theme, hint --level,
+--timeout and --threads can be set in ~/.config/puzzlething/config.toml,
+or the file in PUZZLETHING_CONFIG or --config; flags given on the command
+line win:
+
+  strategy = \"pns\"
+  notation = \"standard\"
+  [display]
+  color = \"always\"
+  theme = \"rings\"
+  [hint]
+  level = 2
+  [limits]
+  timeout = 30
+  threads = 4
+
+BOARD is a preset name (cross, plus, fireplace, lamp, pyramid, arrow,
+diamond, full, diamond-32, diamond-41), a file, or a board in notation
+such as ..OOO../..OOO../OOOOOOO/OOO-OOO/OOOOOOO/..OOO../..OOO.. and
+defaults to the full English board. diamond-32 and diamond-41 are too big
+for the 7x7 grid and work with solve, verify, hint and play only, played
+from their built-in lines or searched depth first. MOVES is a file or a
+list of moves like \"3 1 down; 3 4 up\" or \"d2-d4; f3-d3\", one move per
+line or separated by `;`.
+
+Options:
+  --goal BOARD         Position to play down to (default: one peg in the centre)
+  --strategy NAME      dfs, bidirectional, backward or pns (default: dfs)
+  --format NAME        text, moves, json or csv (csv: solve --batch and
+                       heatmap only)
+  --notation NAME      Write moves as coords, (3, 1) ↓, standard, d2-d4, or
+                       spoken, peg at column 4 row 2 jumps down, capturing
+                       column 4 row 3
+  --topology NAME      flat or torus, where jumps wrap round the edges of the
+                       7x7 grid onto the far side (solve, verify; default:
+                       flat)
+  --color WHEN         auto, always or never (default: auto)
+  --coords             Number the rows and columns of printed boards
+  --animate            Play the solution back on screen (solve)
+  --delay MS           Pause between animation frames (default: 500)
+  --theme NAME|FILE    classic, dots, rings, blocks, emoji or a file
+  --top K              List the K best moves with evaluations (hint)
+  --level N            How much a hint gives away: 1 the peg to move, 2 also
+                       its direction, 3 the move (the default), 4 the next
+                       three moves (hint)
+  --move MOVE          The move to judge, such as \"3 1 down\" (analyze)
+  --last-peg           Mark where the last peg can end, * where a finish
+                       was found and ? where only the cheap checks pass
+                       (analyze)
+  --from BOARD         Board to start editing from instead of FILE (edit)
+  --load FILE          Resume a game saved with `save FILE` (play)
+  --timed SECONDS      Clear as much of the board as you can before the time
+                       runs out, scoring 100 per peg removed and, if you
+                       solve it, 10 per second left; p pauses (tui)
+  --batch FILE         Solve every board in FILE, one line of results each (solve)
+  --output FILE        Where to write batch results (default: stdout)
+  --threads N          Solve batch boards on N threads, 0 for one per core,
+                       writing the results once all are done
+  --cache FILE         Look boards up in FILE before searching and add new
+                       results to it, creating it if missing (solve, serve)
+  --timeout SECS       Give up on a solve, or a batch or bench board, after
+                       SECS seconds (bench default: 30), or on a request to
+                       serve (default: 10); --anytime stops with its best
+                       so far (default: 10)
+  --bind ADDR          Address for serve to listen on (default: 127.0.0.1)
+  --port N             Port for serve to listen on (default: 8080)
+  --annotate           Mark each move as fine, losing or already lost
+                       (replay)
+  --force-search       Run the search even for the full English board with
+                       default settings, whose line is built in; --strategy,
+                       --seed and --stats also search (solve)
+  --book               Answer from the opening book when the board is on one
+                       of its lines, before searching (solve)
+  --stats              Print search counters after solving (solve)
+  --anytime            Report the fewest pegs reached so far each time it
+                       improves, carrying on until one peg is left, nothing
+                       fewer is possible or --timeout (default: 10) has
+                       passed, then print the best line (solve)
+  --portfolio          Race several solvers on threads of their own and take
+                       the first line found, stopping the rest (solve)
+  --solvers LIST       The solvers --portfolio races, by name, such as
+                       dfs,dfs:1,ida,beam:500 for depth first in two move
+                       orders, IDA* and a beam 500 positions wide (default:
+                       dfs,dfs:1,dfs:2,bidirectional,ida,beam:1000)
+  --engine NAME        Solve with one named solver, as in --solvers, which
+                       can also be one registered by a program built on the
+                       library (solve)
+  --symmetry           Count positions related by a rotation or reflection
+                       of the board once (stats)
+  --all                List every distinct solution, one per line (solve)
+  --variants           Print the line in all eight rotations and reflections
+                       of the board (solve, verify)
+  --max-solutions N    Stop listing solutions after N; implies --all. For
+                       heatmap, how many to count (default: 1000)
+  --sample N           Count N solutions found by searches shuffled with
+                       --seed instead of the first ones (heatmap)
+  --count              Only count the solutions (queens)
+  --game NAME          Solve another puzzle instead of peg solitaire (solve).
+                       Sliding puzzles: fifteen, klotski, rush-hour and
+                       sokoban. Switch and disk puzzles: lights-out and
+                       hanoi. Chessboard puzzles: knights-tour, whose BOARD
+                       is the square to start on (default: a1), and queens.
+                       Logic grids: sudoku, and nonogram, whose clues are in
+                       the .non format. River crossings: wolf-goat-cabbage
+                       and missionaries. Peg solitaire variants: duotaire,
+                       hex and 3d. duotaire finds who wins a BOARD when two
+                       players take turns. hex has six jump directions, on a
+                       BOARD drawn with offset rows or on a triangle or
+                       hexagon with --side cells to a side (default: a
+                       triangle of 5, or a hexagon of 3). Its --goal is drawn
+                       the same way (default: one peg anywhere). 3d also
+                       jumps between layers. Its BOARD is layers written from
+                       the bottom and separated by `|`, such as
+                       -OO/OOO/OOO|OOO/OOO/OOO, or a box of WxHxD cells with
+                       one corner empty (default: 3x3x4). --strategy is bfs,
+                       dfs or astar (default: astar, but bfs for klotski and
+                       rush-hour). lights-out also takes gf2, which solves it
+                       as linear equations, and hanoi takes frame-stewart.
+                       Those are their defaults, as dfs is for hex and 3d.
+                       With gen, only sudoku is supported.
+  --difficulty NAME    easy, medium, hard or expert, for gen --game sudoku
+                       (default: easy)
+  --disks N            Disks for hanoi (default: 4)
+  --towers N           Towers for hanoi, from 3 to 26 (default: 3)
+  --side N             Squares to a side for knights-tour and queens
+                       (default: 8), or cells for a hex triangle or hexagon
+  --people N           Missionaries, and as many cannibals (default: 3)
+  --closed             Make the knight's tour end a jump from its start
+  --scramble N         Random slides from solved for a fifteen puzzle given
+                       no board (default: 40)
+  --sokoban-level N    Which level of a Sokoban collection to solve
+                       (default: 1)
+  --pegs N             Pegs on a generated board (default: 16)
+  --seed N             Generator seed (default: taken from the clock), or with
+                       solve a seed that shuffles the order moves are tried in
+  --daily              Generate the puzzle of the day, or play it and keep
+                       your streak going (play)
+  --date YYYY-MM-DD    Day for --daily (default: today)
+  --svg FILE           Write an SVG image to FILE, or - for stdout (export)
+  --gif FILE           Write an animated GIF to FILE, or - for stdout (export)
+  --png FILE           Write a PNG image of the board to FILE, or - for stdout (export)
+  --dot FILE           Write the line of moves as a Graphviz graph (export)
+  --size PX            Pixels per cell in GIF and PNG images (default: 40)
+  --boat N             Seats in the missionaries' boat (default: 2)
+  --colors SPEC        Image colours, such as \"peg=#224488,hole=#000000\"; the
+                       names are background, cell, peg, hole and jump
+  --solve              Draw the solver's line from the board (export)
+  --protocol NAME      Instead of a command, speak JSON-RPC (jsonrpc) or a
+                       UCI-style engine protocol (uci), one message per
+                       line, on stdin and stdout
+  -v, -vv              Log search progress to stderr; -vv adds periodic
+                       node, frontier and memo counts
+  --screen-reader      Describe boards and moves in sentences instead of
+                       drawing them, with any command; also turned on by
+                       setting PUZZLETHING_SCREEN_READER=1
+  --lang NAME          Language for the messages of play, train, tui, replay,
+                       solve, verify and hint, and for errors: en or es
+                       (default: from LC_ALL, LC_MESSAGES or LANG)
+  --config FILE        Read defaults from FILE instead of
+                       ~/.config/puzzlething/config.toml, with any command
+";
+
+#[derive(Debug)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CliError {}
+
+/// `--help` after a command, with the options that command takes: not an
+/// error, but it stops the command before it runs.
+#[derive(Debug)]
+struct HelpRequest(Vec<&'static str>);
+
+impl fmt::Display for HelpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "help was asked for")
+    }
+}
+
+impl Error for HelpRequest {}
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+fn fail<T>(message: impl Into<String>) -> Result<T> {
+    Err(Box::new(CliError(message.into())))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Moves,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> std::result::Result<Format, CliError> {
+        match s {
+            "text" => Ok(Format::Text),
+            "moves" => Ok(Format::Moves),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(CliError(format!(
+                "unknown format {:?}, expected text, moves, json or csv",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> std::result::Result<ColorChoice, CliError> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(CliError(format!(
+                "unknown color choice {:?}, expected auto, always or never",
+                s
+            ))),
+        }
+    }
+}
+
+/// How moves are written: `(x, y)` coordinates with an arrow, the
+/// lettered columns and numbered rows found in books, or sentences.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum MoveNotation {
+    #[default]
+    Coords,
+    Standard,
+    Spoken,
+}
+
+impl MoveNotation {
+    /// Sentences in screen reader mode, coordinates otherwise.
+    fn preferred() -> MoveNotation {
+        if screen_reader() {
+            MoveNotation::Spoken
+        } else {
+            MoveNotation::Coords
+        }
+    }
+
+    fn write(self, action: Action) -> String {
+        match self {
+            MoveNotation::Coords => action.to_string(),
+            MoveNotation::Standard => Standard(action).to_string(),
+            MoveNotation::Spoken => speech::describe_action(action),
+        }
+    }
+
+    /// [`MoveNotation::write`] for a move made under `topology`, whose
+    /// landing cell may be round the edge from where the flat board has it.
+    fn write_on(self, action: Action, topology: Topology) -> String {
+        match (self, topology.span(action)) {
+            (MoveNotation::Standard, Some((_, (tx, ty)))) => {
+                let (x, y) = action.origin();
+                format!(
+                    "{}-{}",
+                    notation::cell_name(x, y),
+                    notation::cell_name(tx, ty)
+                )
+            }
+            _ => self.write(action),
+        }
+    }
+}
+
+/// A move written the [`MoveNotation::preferred`] way.
+fn move_text<M: BoardMove>(mv: M) -> String {
+    mv.write(MoveNotation::preferred())
+}
+
+/// Moves for a prompt's `moves` command: on one line, or one sentence to
+/// a line in screen reader mode.
+fn move_list<M: BoardMove>(moves: &[M]) -> String {
+    let moves: Vec<String> = moves.iter().map(|&m| move_text(m)).collect();
+    moves.join(if screen_reader() { "\n" } else { ", " })
+}
+
+static SCREEN_READER: AtomicBool = AtomicBool::new(false);
+
+/// Whether boards are described and moves spelled out in sentences
+/// instead of drawn, for screen readers.
+fn screen_reader() -> bool {
+    SCREEN_READER.load(atomic::Ordering::Relaxed)
+}
+
+impl FromStr for MoveNotation {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> std::result::Result<MoveNotation, CliError> {
+        match s {
+            "coords" => Ok(MoveNotation::Coords),
+            "standard" => Ok(MoveNotation::Standard),
+            "spoken" => Ok(MoveNotation::Spoken),
+            _ => Err(CliError(format!(
+                "unknown notation {:?}, expected coords, standard or spoken",
+                s
+            ))),
+        }
+    }
+}
+
+/// Positional arguments and `--name value` options of one subcommand.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<&'static str, String>,
+    switches: HashSet<&'static str>,
+}
+
+impl Args {
+    /// Splits `args` into positionals, the value-taking `options` and the
+    /// boolean `switches`, rejecting flags the subcommand does not know.
+    fn parse(args: &[String], options: &[&'static str], switches: &[&'static str]) -> Result<Args> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            switches: HashSet::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--help" || arg == "-h" {
+                let names = options.iter().chain(switches).copied().collect();
+                return Err(Box::new(HelpRequest(names)));
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+            let (name, inline) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            if let Some(&name) = options.iter().find(|&&o| o == name) {
+                let Some(value) = inline.or_else(|| iter.next().cloned()) else {
+                    return fail(format!("--{} needs a value", name));
+                };
+                parsed.options.insert(name, value);
+            } else if let Some(&name) = switches.iter().find(|&&s| s == name) {
+                if inline.is_some() {
+                    return fail(format!("--{} does not take a value", name));
+                }
+                parsed.switches.insert(name);
+            } else {
+                return fail(format!("unknown option --{}", name));
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn at_most(&self, count: usize) -> Result<()> {
+        match self.positional.get(count) {
+            Some(extra) => fail(format!("unexpected argument {:?}", extra)),
+            None => Ok(()),
+        }
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.switches.contains(name)
+    }
+
+    fn parsed<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Error + 'static,
+    {
+        match self.option(name) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("--{}: {}", name, e).into()),
+            None => Ok(None),
+        }
+    }
+
+    /// The board in positional slot `index`, or the English board.
+    fn board(&self, index: usize) -> Result<Grid> {
+        match self.positional.get(index) {
+            Some(spec) => load_board(spec),
+            None => Ok(Grid::new()),
+        }
+    }
+
+    /// The diamond board named by the `index`th positional argument, which
+    /// the 7x7 grid cannot hold.
+    fn diamond(&self, index: usize) -> Option<Diamond> {
+        self.positional
+            .get(index)?
+            .parse::<Preset>()
+            .ok()?
+            .diamond()
+    }
+
+    /// `[BOARD] MOVES` from the positionals of `command`.
+    fn board_and_moves(&self, command: &str) -> Result<(Grid, Vec<Action>)> {
+        self.at_most(2)?;
+        match &self.positional[..] {
+            [moves] => Ok((Grid::new(), load_moves::<Grid>(moves)?)),
+            [board, moves] => Ok((load_board(board)?, load_moves::<Grid>(moves)?)),
+            _ => fail(format!("{} needs a list of moves", command)),
+        }
+    }
+
+    fn goal(&self, board: &Grid) -> Result<Grid> {
+        match self.option("goal") {
+            Some(spec) => load_board(spec),
+            None => Ok(board.single_peg(3, 3)),
+        }
+    }
+
+    fn strategy(&self) -> Result<SearchStrategy> {
+        Ok(self
+            .parsed("strategy")?
+            .or(config::current().strategy)
+            .unwrap_or_default())
+    }
+
+    fn format(&self) -> Result<Format> {
+        Ok(self.parsed("format")?.unwrap_or_default())
+    }
+
+    /// The format of a command whose output is not a table, which has no
+    /// CSV form.
+    fn document_format(&self) -> Result<Format> {
+        match self.format()? {
+            Format::Csv => fail("--format csv is only available with solve --batch and heatmap"),
+            format => Ok(format),
+        }
+    }
+
+    fn topology(&self) -> Result<Topology> {
+        Ok(self.parsed("topology")?.unwrap_or_default())
+    }
+
+    fn notation(&self) -> Result<MoveNotation> {
+        Ok(self
+            .parsed("notation")?
+            .or(config::current().notation)
+            .unwrap_or_else(MoveNotation::preferred))
+    }
+
+    /// Colour, labels and theme for printing boards that play down to
+    /// `goal`.
+    fn render_options(&self, goal: &Grid) -> Result<RenderOptions> {
+        Ok(RenderOptions {
+            color: self.color()?,
+            goal: Some(*goal),
+            coords: self.switch("coords"),
+            theme: self.theme()?,
+            spoken: screen_reader(),
+            ..RenderOptions::default()
+        })
+    }
+
+    fn theme(&self) -> Result<Theme> {
+        match self.option("theme") {
+            Some(spec) => load_theme(spec),
+            None => match config::current().theme {
+                Some(spec) => load_theme(&spec),
+                None => Ok(Theme::default()),
+            },
+        }
+    }
+
+    fn color(&self) -> Result<bool> {
+        Ok(use_color(
+            self.parsed("color")?
+                .or(config::current().color)
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// `--timeout` in seconds, or the configured limit.
+    fn timeout(&self) -> Result<Option<Duration>> {
+        match self.parsed::<f64>("timeout")? {
+            Some(secs) => match Duration::try_from_secs_f64(secs) {
+                Ok(timeout) => Ok(Some(timeout)),
+                Err(_) => fail("--timeout: expected a non-negative number of seconds"),
+            },
+            None => Ok(config::current().timeout),
+        }
+    }
+}
+
+/// Whether to colour boards. `auto` colours only when stdout is a terminal
+/// and `NO_COLOR` is not set.
+fn use_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            io::stdout().is_terminal()
+                && env::var_os("NO_COLOR").is_none()
+                && env::var("TERM").map_or(true, |t| t != "dumb")
+        }
+    }
+}
+
+/// Reads a theme given as a built-in name or a file.
+fn load_theme(spec: &str) -> Result<Theme> {
+    if let Some(theme) = Theme::named(spec) {
+        return Ok(theme);
+    }
+    if Path::new(spec).is_file() {
+        return Theme::parse(&fs::read_to_string(spec)?)
+            .map_err(|e| format!("{}: {}", spec, e).into());
+    }
+    fail(format!(
+        "no theme or file called {:?}, the built-in themes are {}",
+        spec,
+        Theme::NAMES.join(", ")
+    ))
+}
+
+/// Reads a board given as a preset name, a file or inline notation.
+fn load_board(spec: &str) -> Result<Grid> {
+    if let Ok(preset) = spec.parse::<Preset>() {
+        return match preset.position() {
+            Some(position) => Ok(position),
+            None => fail(format!(
+                "{} is too big for the 7x7 grid; solve, verify, hint and play take it",
+                preset
+            )),
+        };
+    }
+    if Path::new(spec).is_file() {
+        return fs::read_to_string(spec)?
+            .parse()
+            .map_err(|e| format!("{}: {}", spec, e).into());
+    }
+    if !spec.contains(['/', '\n']) {
+        return fail(format!("no preset, file or board called {:?}", spec));
+    }
+    Ok(spec.parse()?)
+}
+
+/// Reads moves from a file or from inline text, one per line or separated
+/// by `;`. A chained move in standard notation, `d2-d4-f4`, counts as its
+/// separate jumps. Blank lines and lines starting with `#` are skipped.
+fn load_moves<B: Board>(spec: &str) -> Result<Vec<B::Move>> {
+    let text = if Path::new(spec).is_file() {
+        fs::read_to_string(spec)?
+    } else {
+        spec.to_string()
+    };
+    let mut moves = Vec::new();
+    for part in text.split(['\n', ';']) {
+        let part = part.trim();
+        if part.is_empty() || part.starts_with('#') {
+            continue;
+        }
+        if let Some(jumps) = B::parse_chain(part) {
+            moves.extend(jumps);
+            continue;
+        }
+        match part.parse() {
+            Ok(mv) => moves.push(mv),
+            Err(e) => return fail(format!("move {}: {}", moves.len() + 1, e)),
+        }
+    }
+    Ok(moves)
+}
+
+fn no_solution<B: Board>(board: &B, goal: &B) -> Result<()> {
+    match board.infeasible(goal) {
+        Some(reason) => fail(format!("no solution: {}", reason)),
+        None => fail("no solution"),
+    }
+}
+
+/// Removes `-v`, `-vv` and `--verbose` from anywhere in `args`, returning
+/// how many times verbosity was raised.
+fn take_verbosity(args: &[String]) -> (u8, Vec<String>) {
+    let mut verbosity = 0u8;
+    let mut rest = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--verbose" => verbosity = verbosity.saturating_add(1),
+            flag if flag.len() > 1
+                && flag.starts_with('-')
+                && flag[1..].bytes().all(|b| b == b'v') =>
+            {
+                verbosity = verbosity.saturating_add(flag.len() as u8 - 1)
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    (verbosity, rest)
+}
+
+/// Removes `--screen-reader` from anywhere in `args`, turning screen
+/// reader mode on for it or for a `PUZZLETHING_SCREEN_READER` other than
+/// empty or `0`.
+fn take_screen_reader(args: Vec<String>) -> Vec<String> {
+    let from_env = env::var("PUZZLETHING_SCREEN_READER").is_ok_and(|v| !v.is_empty() && v != "0");
+    let (flags, rest): (Vec<String>, Vec<String>) =
+        args.into_iter().partition(|arg| arg == "--screen-reader");
+    SCREEN_READER.store(from_env || !flags.is_empty(), atomic::Ordering::Relaxed);
+    rest
+}
+
+/// Removes `--lang NAME` from anywhere in `args` and switches the
+/// messages to that language, or to the system locale's without it,
+/// returning whether it was given.
+fn take_lang(args: Vec<String>) -> Result<(bool, Vec<String>)> {
+    let mut lang = None;
+    let mut rest = Vec::new();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if let Some(name) = arg.strip_prefix("--lang=") {
+            lang = Some(name.parse()?);
+        } else if arg == "--lang" {
+            let Some(name) = iter.next() else {
+                return fail("--lang needs a value");
+            };
+            lang = Some(name.parse()?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let given = lang.is_some();
+    i18n::set_lang(lang.unwrap_or_else(Lang::system));
+    Ok((given, rest))
+}
+
+/// Removes `--config FILE` from anywhere in `args` and reads the defaults
+/// from that file, or from [`config::default_path`] if there is one.
+fn take_config(args: Vec<String>) -> Result<Vec<String>> {
+    let mut path = None;
+    let mut rest = Vec::new();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if let Some(file) = arg.strip_prefix("--config=") {
+            path = Some(file.to_string());
+        } else if arg == "--config" {
+            let Some(file) = iter.next() else {
+                return fail("--config needs a value");
+            };
+            path = Some(file);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let settings = match path {
+        Some(path) if !Path::new(&path).is_file() => {
+            return fail(format!("no config file {:?}", path))
+        }
+        Some(path) => config::load(path)?,
+        None => match config::default_path() {
+            Some(path) => config::load(path)?,
+            None => config::Config::default(),
+        },
+    };
+    config::set(settings);
+    Ok(rest)
+}
+
+/// The commands whose messages are translated, beyond their errors.
+const TRANSLATED: &[&str] = &["play", "train", "tui", "replay", "solve", "verify", "hint"];
+
+pub fn run(args: &[String]) -> Result<()> {
+    dispatch(args).map_err(i18n::tr_error)
+}
+
+fn dispatch(args: &[String]) -> Result<()> {
+    let (verbosity, args) = take_verbosity(args);
+    if verbosity > 0 {
+        trace::set_verbosity(verbosity);
+    }
+    let args = take_screen_reader(args);
+    let (lang_given, args) = take_lang(args)?;
+    let args = take_config(args)?;
+    let Some((command, rest)) = args.split_first() else {
+        return solve(&[]);
+    };
+    if lang_given && !TRANSLATED.contains(&command.as_str()) {
+        return fail("--lang only works with play, train, tui, replay, solve, verify and hint");
+    }
+    if command.starts_with("--protocol") {
+        return protocol(&args);
+    }
+    let result = match command.as_str() {
+        "solve" => solve(rest),
+        "play" => play::play(rest),
+        "tui" => tui::tui(rest),
+        "train" => train::train(rest),
+        "verify" => verify(rest),
+        "replay" => replay::replay(rest),
+        "hint" => hint(rest),
+        "analyze" => analyze(rest),
+        "book" => book(rest),
+        "heatmap" => heatmap(rest),
+        "stats" => state_space(rest),
+        "diff" => diff(rest),
+        "gen" => gen(rest),
+        "edit" => edit::edit(rest),
+        "export" => export::export(rest),
+        "bench" => bench::bench(rest),
+        #[cfg(feature = "serve")]
+        "serve" => serve::serve(rest),
+        #[cfg(not(feature = "serve"))]
+        "serve" => fail("this build has no server, rebuild with `--features serve`"),
+        "help" | "-h" | "--help" => {
+            write!(io::stdout().lock(), "{}", USAGE)?;
+            Ok(())
+        }
+        _ => fail(format!(
+            "unknown command {:?}, run `puzzlething help` for a list",
+            command
+        )),
+    };
+    match result {
+        Err(err) => match err.downcast::<HelpRequest>() {
+            Ok(request) => {
+                write!(io::stdout().lock(), "{}", command_help(command, &request.0))?;
+                Ok(())
+            }
+            Err(err) => Err(err),
+        },
+        Ok(()) => Ok(()),
+    }
+}
+
+/// What `COMMAND --help` prints: the entries of [`USAGE`] for `command`
+/// and for the options in `names`, in the order they are listed there.
+fn command_help(command: &str, names: &[&str]) -> String {
+    #[derive(Clone, Copy)]
+    enum Section {
+        About,
+        Options,
+    }
+    let mut synopses = Vec::new();
+    let mut about = String::new();
+    let mut options = String::new();
+    // Which entry is being read, for the lines that carry one on.
+    let mut section = None;
+    for line in USAGE.lines() {
+        if let Some(entry) = line
+            .strip_prefix("  ")
+            .filter(|rest| !rest.starts_with(' '))
+        {
+            let word = entry.split_whitespace().next().unwrap_or_default();
+            section = None;
+            if word == command {
+                let (synopsis, text) = entry.split_once("  ").unwrap_or((entry, ""));
+                synopses.push(synopsis);
+                about.push_str(text.trim_start());
+                about.push('\n');
+                section = Some(Section::About);
+                continue;
+            }
+            if word
+                .strip_prefix("--")
+                .is_some_and(|name| names.contains(&name))
+            {
+                section = Some(Section::Options);
+            }
+        } else if !line.starts_with("   ") {
+            section = None;
+        }
+        match section {
+            Some(Section::About) => {
+                about.push_str(line.trim_start());
+                about.push('\n');
+            }
+            Some(Section::Options) => {
+                options.push_str(line);
+                options.push('\n');
+            }
+            None => {}
+        }
+    }
+    let mut help = String::new();
+    for synopsis in synopses {
+        help.push_str(&format!("Usage: puzzlething {} [OPTIONS]\n", synopsis));
+    }
+    help.push('\n');
+    help.push_str(&about);
+    if !options.is_empty() {
+        help.push_str("\nOptions:\n");
+        help.push_str(&options);
+    }
+    help
+}
+
+/// Speaks a machine protocol on stdin and stdout instead of running a
+/// command.
+fn protocol(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, &["protocol"], &[])?;
+    args.at_most(0)?;
+    match args.option("protocol") {
+        Some("jsonrpc") => jsonrpc::run(),
+        Some("uci") => uci::run(),
+        Some(other) => fail(format!(
+            "unknown protocol {:?}, expected jsonrpc or uci",
+            other
+        )),
+        None => fail("--protocol needs a value"),
+    }
+}
+
+fn solve(rest: &[String]) -> Result<()> {
+    if rest
+        .iter()
+        .any(|a| a == "--game" || a.starts_with("--game="))
+    {
+        return games::solve(rest);
+    }
+    let args = Args::parse(
+        rest,
+        &[
+            "goal",
+            "strategy",
+            "format",
+            "notation",
+            "color",
+            "theme",
+            "delay",
+            "batch",
+            "output",
+            "timeout",
+            "seed",
+            "max-solutions",
+            "threads",
+            "cache",
+            "topology",
+            "solvers",
+            "engine",
+        ],
+        &[
+            "coords",
+            "animate",
+            "stats",
+            "all",
+            "variants",
+            "book",
+            "force-search",
+            "portfolio",
+            "anytime",
+        ],
+    )?;
+    args.at_most(1)?;
+    if let Some(board) = args.diamond(0) {
+        return diamond::solve(&args, board);
+    }
+    if args.option("batch").is_some() && args.topology()? != Topology::Flat {
+        return fail("--batch only solves flat boards");
+    }
+    let mut cache = match args.option("cache") {
+        Some(path) => Some(SolutionCache::open(path)?),
+        None => None,
+    };
+    if let Some(input) = args.option("batch") {
+        let goal = match args.option("goal") {
+            Some(spec) => Some(load_board(spec)?),
+            None => None,
+        };
+        let threads = args.parsed("threads")?.or(config::current().threads);
+        let options = BatchOptions {
+            goal,
+            strategy: args.strategy()?,
+            threads: threads.unwrap_or(0),
+            timeout: args.timeout()?,
+            share_unsolvable: true,
+        };
+        return batch::run(
+            input,
+            args.option("output"),
+            options,
+            threads.is_some(),
+            cache,
+            args.format()?,
+        );
+    }
+    // Only a batch has files to write or boards to share out.
+    if let Some(flag) = ["output", "threads"]
+        .into_iter()
+        .find(|&flag| args.option(flag).is_some())
+    {
+        return fail(format!("--{} only works with --batch", flag));
+    }
+    let board = args.board(0)?;
+    let goal = args.goal(&board)?;
+    let strategy = args.strategy()?;
+    let format = args.document_format()?;
+    let notation = args.notation()?;
+    let topology = args.topology()?;
+    let max_solutions = args.parsed::<usize>("max-solutions")?;
+    let listing = args.switch("all") || max_solutions.is_some();
+    if args.option("timeout").is_some() && (topology != Topology::Flat || listing) {
+        return fail("--timeout does not limit --topology or --all");
+    }
+    if topology != Topology::Flat {
+        return solve_on(&args, &board, &goal, topology, format, notation);
+    }
+    if args.switch("anytime") {
+        if args.option("goal").is_some() {
+            return fail("--anytime plays for the fewest pegs anywhere and takes no --goal");
+        }
+        return solve_anytime(&args, &board, &goal, format, notation);
+    }
+    if listing {
+        return solve_all(&board, &goal, max_solutions, format, notation);
+    }
+    // The solvers to race, by name; --engine is a race of one.
+    let portfolio: Option<Vec<String>> = match (args.option("engine"), args.option("solvers")) {
+        (Some(_), Some(_)) => return fail("--engine and --solvers both choose the solver"),
+        (Some(spec), None) => Some(vec![spec.to_string()]),
+        (None, Some(list)) => Some(
+            list.split(',')
+                .map(|spec| spec.trim().to_string())
+                .collect(),
+        ),
+        (None, None) => args
+            .switch("portfolio")
+            .then(|| portfolio::DEFAULT.iter().map(Builtin::to_string).collect()),
+    };
+    if portfolio.is_some() && (args.option("strategy").is_some() || args.option("seed").is_some()) {
+        return fail(
+            "--engine and --portfolio pick their own strategies and seeds; name them with \
+             --engine or --solvers",
+        );
+    }
+    let solvers = portfolio
+        .iter()
+        .flatten()
+        .map(|spec| registry::solver(spec))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut tree = GameTree::new(board, Vec::new());
+    if let Some(seed) = args.parsed("seed")? {
+        tree = tree.with_seed(seed);
+    }
+    let hit = match args.switch("book") {
+        true => book::lookup(&board, &goal),
+        false => None,
+    };
+    // The default board with default settings has its answer built in.
+    let searching = args.switch("force-search")
+        || args.switch("stats")
+        || args.option("strategy").is_some()
+        || config::current().strategy.is_some()
+        || args.option("seed").is_some()
+        || portfolio.is_some();
+    let instant = if searching {
+        None
+    } else {
+        book::instant(&board, &goal)
+    };
+    let cached = match (&hit, instant) {
+        (Some(hit), _) => Some(Cached::Solvable(hit.moves.clone())),
+        (None, Some(moves)) => Some(Cached::Solvable(moves)),
+        (None, None) => cache.as_ref().and_then(|c| c.get(&board, &goal)),
+    };
+    // The solver that found the line, when a portfolio raced for it.
+    let mut found_by = strategy.to_string();
+    let (solved, stats) = match cached {
+        Some(Cached::Solvable(moves)) => {
+            let finished = GameTree::new(board.play_moves(&moves)?, moves);
+            (Some(finished), SearchStats::default())
+        }
+        Some(Cached::Unsolvable) => (None, SearchStats::default()),
+        None => {
+            let (solved, stats) = match &portfolio {
+                Some(names) => {
+                    let timeout = args.timeout()?;
+                    match portfolio::race(&board, &goal, &solvers, timeout) {
+                        Finish::Solved {
+                            solver,
+                            moves,
+                            stats,
+                        } => {
+                            found_by = names[solver].clone();
+                            (Some(GameTree::new(board.play_moves(&moves)?, moves)), stats)
+                        }
+                        Finish::Unsolvable { solver, stats } => {
+                            found_by = names[solver].clone();
+                            (None, stats)
+                        }
+                        Finish::Timeout => return fail("no solver settled the board in time"),
+                        Finish::GaveUp if names.len() == 1 => {
+                            return fail(format!(
+                                "{} found no line, which does not rule one out",
+                                names[0]
+                            ))
+                        }
+                        Finish::GaveUp => {
+                            return fail(
+                                "every solver gave up without ruling a line out; add one that \
+                                 searches everywhere, such as dfs",
+                            )
+                        }
+                    }
+                }
+                None => match parallel::solve_tree(&tree, &goal, strategy, args.timeout()?) {
+                    Outcome::Solved(moves, stats) => {
+                        (Some(GameTree::new(board.play_moves(&moves)?, moves)), stats)
+                    }
+                    Outcome::Unsolvable(stats) => (None, stats),
+                    Outcome::Timeout => {
+                        return fail("the search ran out of time before settling the board")
+                    }
+                },
+            };
+            if let Some(cache) = &mut cache {
+                let result = match &solved {
+                    Some(g) => Cached::Solvable(g.history().to_vec()),
+                    None => Cached::Unsolvable,
+                };
+                cache.record(&board, &goal, &result)?;
+            }
+            (solved, stats)
+        }
+    };
+    if let (Some(g), true) = (&solved, args.switch("variants")) {
+        return print_variants(&board, g.history(), format, notation);
+    }
+    report_search(
+        &args,
+        &board,
+        &goal,
+        &found_by,
+        solved.as_ref().map(GameTree::history),
+        &stats,
+        format,
+    )?;
+    let Some(g) = solved else {
+        return no_solution(&board, &goal);
+    };
+    if args.switch("animate") {
+        let delay = Duration::from_millis(args.parsed("delay")?.unwrap_or(500));
+        let options = args.render_options(&goal)?;
+        return replay::animate(&board, g.history(), &options, delay);
+    }
+    let mut notes = Vec::new();
+    if let Some(hit) = &hit {
+        notes.push(trf(
+            "From the book: {name} ({source})",
+            &[("name", &hit.entry.name), ("source", &hit.entry.source)],
+        ));
+    }
+    if portfolio.as_ref().is_some_and(|names| names.len() > 1) {
+        notes.push(trf("Found by {solver}", &[("solver", &found_by)]));
+    }
+    print_line(&args, &board, &goal, g.history(), &notes, format, notation)
+}
+
+/// What a search settled, as the JSON document on stdout, or as its
+/// counters on stderr with `--stats`.
+fn report_search<B: Board>(
+    args: &Args,
+    board: &B,
+    goal: &B,
+    found_by: &str,
+    moves: Option<&[B::Move]>,
+    stats: &SearchStats,
+    format: Format,
+) -> Result<()> {
+    if format == Format::Json {
+        let mut out = io::stdout().lock();
+        writeln!(out, "{}", solve_json(board, goal, found_by, moves, stats))?;
+    } else if args.switch("stats") {
+        // On stderr, so that the moves on stdout can still be piped.
+        eprintln!("{}", stats);
+    }
+    Ok(())
+}
+
+/// The board `moves` lead to and the moves themselves, after `notes` on
+/// where they came from, or just the moves with `--format moves`.
+fn print_line<B: Board>(
+    args: &Args,
+    board: &B,
+    goal: &B,
+    moves: &[B::Move],
+    notes: &[String],
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Text => {
+            let state = board.play_line(moves, Topology::Flat)?;
+            let style = B::style(args, goal, args.switch("coords"))?;
+            writeln!(out, "{}", state.draw(&style, moves.last().copied()))?;
+            for note in notes {
+                writeln!(out, "{}", note)?;
+            }
+            let count = moves.len();
+            writeln!(
+                out,
+                "{}\n",
+                trf("Finished in {moves} moves", &[("moves", &count)])
+            )?;
+            if notation == MoveNotation::Coords {
+                writeln!(out, "{}", tr("(x, y) direction"))?;
+            }
+            for &mv in moves {
+                writeln!(out, "{}", mv.write(notation))?;
+            }
+        }
+        Format::Moves => {
+            for &mv in moves {
+                writeln!(out, "{}", mv.write(notation))?;
+            }
+        }
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// `solve` for a board whose edges are joined as `topology` says, with
+/// its own search: the book, the cache and the strategies all assume a
+/// flat board.
+fn solve_on(
+    args: &Args,
+    board: &Grid,
+    goal: &Grid,
+    topology: Topology,
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let solved = topology::search(board, goal, topology);
+    if format == Format::Json {
+        let moves = solved.as_ref().map_or(Json::Null, |moves| {
+            Json::Array(moves.iter().map(ToJson::to_json).collect())
+        });
+        writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("board", board.to_json()),
+                ("goal", goal.to_json()),
+                ("topology", topology.name().into()),
+                ("solved", solved.is_some().into()),
+                ("moves", moves),
+            ])
+        )?;
+    }
+    let Some(moves) = solved else {
+        return fail(format!(
+            "no solution reaches the goal on a {} board",
+            topology
+        ));
+    };
+    match format {
+        Format::Text => {
+            let state = board.play_moves_on(&moves, topology)?;
+            writeln!(
+                out,
+                "{}",
+                render::render(&state, &args.render_options(goal)?)
+            )?;
+            writeln!(
+                out,
+                "Finished in {} moves on a {} board\n",
+                moves.len(),
+                topology
+            )?;
+            if notation == MoveNotation::Coords {
+                writeln!(out, "(x, y) direction")?;
+            }
+            for &action in &moves {
+                writeln!(out, "{}", notation.write_on(action, topology))?;
+            }
+        }
+        Format::Moves => {
+            for &action in &moves {
+                writeln!(out, "{}", notation.write_on(action, topology))?;
+            }
+        }
+        Format::Json | Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// The JSON document `solve --format json` prints, also served by `serve`.
+fn solve_json<B: Board>(
+    board: &B,
+    goal: &B,
+    strategy: &str,
+    moves: Option<&[B::Move]>,
+    stats: &SearchStats,
+) -> Json {
+    let mut fields = vec![
+        ("board", board.to_json()),
+        ("goal", goal.to_json()),
+        ("strategy", strategy.into()),
+        ("solved", moves.is_some().into()),
+    ];
+    match moves {
+        Some(moves) => {
+            let last = moves.iter().fold(*board, |b, &m| b.play(m));
+            fields.push(("moves", board.line_json(moves, Topology::Flat)));
+            fields.push(("final", last.to_json()));
+        }
+        None => {
+            let reason = board.infeasible(goal);
+            fields.push(("reason", reason.map(|r| r.to_string()).into()));
+        }
+    }
+    let mut counters = vec![("moves".to_string(), moves.map(<[B::Move]>::len).into())];
+    if let Json::Object(rest) = stats.to_json() {
+        counters.extend(rest);
+    }
+    fields.push(("stats", Json::Object(counters)));
+    Json::object(fields)
+}
+
+/// `solve --anytime`: reports each improvement as soon as it is found,
+/// then the best line once the search is over or out of time. In JSON,
+/// one document per line, each improvement and then the best.
+fn solve_anytime(
+    args: &Args,
+    board: &Grid,
+    goal: &Grid,
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let timeout = args.timeout()?.unwrap_or(Duration::from_secs(10));
+    let count = |n: usize, what: &str| match n {
+        1 => format!("1 {}", what),
+        n => format!("{} {}s", n, what),
+    };
+    let document = |kind: &str, best: &anytime::Best| {
+        Json::object([
+            ("type", kind.into()),
+            ("board", board.to_json()),
+            ("pegs", best.pegs.into()),
+            ("proven", best.proven.into()),
+            ("seconds", best.elapsed.as_secs_f64().into()),
+            ("nodes_expanded", best.nodes_expanded.into()),
+            ("moves", json::line(board, &best.moves)),
+        ])
+    };
+    // The first write that fails, once the search is over.
+    let mut written = Ok(());
+    let best = anytime::search(board, Some(timeout), &mut |best| {
+        if written.is_err() {
+            return;
+        }
+        written = match format {
+            Format::Json => writeln!(out, "{}", document("improved", best)),
+            Format::Text => writeln!(
+                out,
+                "{} left after {} ({:.2} s)",
+                count(best.pegs as usize, "peg"),
+                count(best.moves.len(), "move"),
+                best.elapsed.as_secs_f64()
+            ),
+            Format::Moves | Format::Csv => Ok(()),
+        };
+    });
+    written?;
+    match format {
+        Format::Text => {
+            let state = board.play_moves(&best.moves)?;
+            writeln!(
+                out,
+                "\n{}",
+                render::render(&state, &args.render_options(goal)?)
+            )?;
+            let proof = if best.proven {
+                "none fewer"
+            } else {
+                "out of time, fewer not ruled out"
+            };
+            writeln!(
+                out,
+                "Best: {} left after {} ({})\n",
+                count(best.pegs as usize, "peg"),
+                count(best.moves.len(), "move"),
+                proof
+            )?;
+            if notation == MoveNotation::Coords {
+                writeln!(out, "(x, y) direction")?;
+            }
+            for &action in &best.moves {
+                writeln!(out, "{}", notation.write(action))?;
+            }
+        }
+        Format::Moves => {
+            for &action in &best.moves {
+                writeln!(out, "{}", notation.write(action))?;
+            }
+        }
+        Format::Json => writeln!(out, "{}", document("best", &best))?,
+        Format::Csv => {}
+    }
+    Ok(())
+}
+
+/// Lists distinct solutions, one per line, up to `max` of them.
+fn solve_all(
+    board: &Grid,
+    goal: &Grid,
+    max: Option<usize>,
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let solutions = enumerate::solutions(board, goal, max);
+    if format == Format::Json {
+        let lines = solutions.iter().map(|moves| json::line(board, moves));
+        writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("board", board.to_json()),
+                ("goal", goal.to_json()),
+                ("count", solutions.len().into()),
+                ("solutions", Json::Array(lines.collect())),
+            ])
+        )?;
+    } else {
+        for moves in &solutions {
+            let line: Vec<String> = moves.iter().map(|&a| notation.write(a)).collect();
+            writeln!(out, "{}", line.join("; "))?;
+        }
+        if format == Format::Text {
+            writeln!(out, "{} solutions", solutions.len())?;
+        }
+    }
+    if solutions.is_empty() {
+        return no_solution(board, goal);
+    }
+    Ok(())
+}
+
+/// Prints the line in all eight orientations of the board.
+fn print_variants(
+    board: &Grid,
+    moves: &[Action],
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let variants = symmetry::variants(board, moves);
+    if format == Format::Json {
+        let items = variants.iter().map(|(s, start, line)| {
+            Json::object([
+                ("symmetry", s.name().into()),
+                ("board", start.to_json()),
+                ("moves", json::line(start, line)),
+            ])
+        });
+        writeln!(out, "{}", Json::Array(items.collect()))?;
+        return Ok(());
+    }
+    for (s, start, line) in &variants {
+        let line: Vec<String> = line.iter().map(|&a| notation.write(a)).collect();
+        if format == Format::Text {
+            writeln!(out, "{} {}", s, start.notation())?;
+        }
+        writeln!(out, "{}", line.join("; "))?;
+    }
+    Ok(())
+}
+
+fn verify(rest: &[String]) -> Result<()> {
+    let args = Args::parse(
+        rest,
+        &["goal", "format", "notation", "color", "theme", "topology"],
+        &["coords", "variants"],
+    )?;
+    if let (Some(board), 2) = (args.diamond(0), args.positional.len()) {
+        return diamond::verify(&args, board);
+    }
+    let (board, moves) = args.board_and_moves("verify")?;
+    let goal = args.goal(&board)?;
+    let topology = args.topology()?;
+    if args.switch("variants") {
+        if topology != Topology::Flat {
+            return fail("--variants only works on flat boards");
+        }
+        if board.play_moves(&moves).is_ok() {
+            return print_variants(&board, &moves, args.document_format()?, args.notation()?);
+        }
+    }
+    verify_line(&args, &board, &goal, &moves, topology)
+}
+
+/// Plays `moves` from `board` under `topology` and reports whether they
+/// are legal and reach `goal`, failing when either is not so.
+fn verify_line<B: Board>(
+    args: &Args,
+    board: &B,
+    goal: &B,
+    moves: &[B::Move],
+    topology: Topology,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let played = board.play_line(moves, topology);
+    let json = args.document_format()? == Format::Json;
+    if json {
+        let error = played.as_ref().err().map(|e| {
+            Json::object([
+                ("index", (e.index + 1).into()),
+                ("move", e.action.to_json()),
+                ("reason", e.reason.to_string().into()),
+            ])
+        });
+        let legal = played.as_ref().err().map_or(moves.len(), |e| e.index);
+        let state = played.as_ref().ok();
+        writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("board", board.to_json()),
+                ("goal", goal.to_json()),
+                ("moves", board.line_json(&moves[..legal], topology)),
+                ("valid", played.is_ok().into()),
+                ("error", error.unwrap_or(Json::Null)),
+                ("final", state.map_or(Json::Null, B::to_json)),
+                ("goal_reached", (state == Some(goal)).into()),
+            ])
+        )?;
+    }
+    let state = played?;
+    if !json {
+        let style = B::style(args, goal, args.switch("coords"))?;
+        writeln!(out, "{}", state.draw(&style, moves.last().copied()))?;
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Moves: {moves}  Pegs left: {pegs}",
+                &[("moves", &moves.len()), ("pegs", &state.pegs())]
+            )
+        )?;
+    }
+    if state != *goal {
+        return fail("the moves do not reach the goal");
+    }
+    if !json {
+        writeln!(out, "{}", tr("The goal is reached."))?;
+    }
+    Ok(())
+}
+
+fn hint(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(
+        rest,
+        &["goal", "strategy", "format", "notation", "top", "level"],
+        &[],
+    )?;
+    let notation = args.notation()?;
+    let level = args
+        .parsed::<HintLevel>("level")?
+        .or(config::current().hint_level)
+        .unwrap_or_default();
+    args.at_most(1)?;
+    if let Some(board) = args.diamond(0) {
+        return diamond::hint(&args, board);
+    }
+    let board = args.board(0)?;
+    let goal = args.goal(&board)?;
+    if let Some(top) = args.parsed::<usize>("top")? {
+        let evaluations = hint::evaluate_moves(&board, &goal, args.strategy()?);
+        if evaluations.is_empty() {
+            return no_solution(&board, &goal);
+        }
+        let evaluations = &evaluations[..top.min(evaluations.len())];
+        match args.document_format()? {
+            Format::Text | Format::Csv if notation == MoveNotation::Coords => {
+                for e in evaluations {
+                    writeln!(out, "{}", e)?;
+                }
+            }
+            Format::Text | Format::Csv => {
+                for e in evaluations {
+                    let verdict = if e.solvable { "solvable" } else { "lost" };
+                    let rate = e.finish_rate * 100.0;
+                    writeln!(
+                        out,
+                        "{:<5}  {:<8}  random finish {:.1}%",
+                        Standard(e.action),
+                        verdict,
+                        rate
+                    )?;
+                }
+            }
+            Format::Moves => {
+                for e in evaluations {
+                    writeln!(out, "{}", notation.write(e.action))?;
+                }
+            }
+            Format::Json => writeln!(
+                out,
+                "{}",
+                Json::object([("moves", Json::array(evaluations))])
+            )?,
+        }
+        return Ok(());
+    }
+    let Some(g) = GameTree::new(board, Vec::new()).search_for(&goal, args.strategy()?) else {
+        return no_solution(&board, &goal);
+    };
+    print_hint(g.history(), level, args.document_format()?, notation)
+}
+
+/// The hint `level` gives away of `line`, which solves the position.
+fn print_hint<M: BoardMove>(
+    line: &[M],
+    level: HintLevel,
+    format: Format,
+    notation: MoveNotation,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(hint) = Hint::new(line, level) else {
+        match format {
+            Format::Json => writeln!(out, "{}", Json::object([("move", Json::Null)]))?,
+            _ => writeln!(out, "{}", tr("The goal is already reached."))?,
+        }
+        return Ok(());
+    };
+    let cell = |(x, y): (usize, usize)| match notation {
+        MoveNotation::Coords => format!("({}, {})", x, y),
+        MoveNotation::Standard => notation::cell_name(x, y),
+        MoveNotation::Spoken => speech::cell((x, y)),
+    };
+    let cell_json = |(x, y): (usize, usize)| Json::Array(vec![x.into(), y.into()]);
+    match (hint, format) {
+        (Hint::Move(action), Format::Json) => {
+            writeln!(out, "{}", Json::object([("move", action.to_json())]))?
+        }
+        (Hint::Move(action), Format::Text | Format::Csv) => writeln!(
+            out,
+            "{}",
+            trf("Next move: {move}", &[("move", &action.write(notation))])
+        )?,
+        (Hint::Move(action), Format::Moves) => writeln!(out, "{}", action.write(notation))?,
+        (Hint::Peg(from), Format::Json) => writeln!(
+            out,
+            "{}",
+            Json::object([("level", level.number().into()), ("peg", cell_json(from))])
+        )?,
+        (Hint::Peg(from), Format::Text | Format::Csv) => writeln!(
+            out,
+            "{}",
+            trf("Move the peg at {cell}.", &[("cell", &cell(from))])
+        )?,
+        (Hint::Peg(from), Format::Moves) => writeln!(out, "{}", cell(from))?,
+        (Hint::Direction(from, dir), Format::Json) => writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("level", level.number().into()),
+                ("peg", cell_json(from)),
+                ("direction", dir.to_json()),
+            ])
+        )?,
+        (Hint::Direction(from, dir), Format::Text | Format::Csv) => writeln!(
+            out,
+            "{}",
+            trf(
+                "Jump the peg at {cell} {direction}.",
+                &[("cell", &cell(from)), ("direction", &tr(dir.name()))]
+            )
+        )?,
+        (Hint::Direction(from, dir), Format::Moves) => {
+            writeln!(out, "{} {}", cell(from), dir.name())?
+        }
+        (Hint::Line(moves), Format::Json) => writeln!(
+            out,
+            "{}",
+            Json::object([
+                ("level", level.number().into()),
+                ("moves", Json::array(&moves)),
+            ])
+        )?,
+        (Hint::Line(moves), Format::Text | Format::Csv) => {
+            writeln!(
+                out,
+                "{}",
+                trf("Next {count} moves:", &[("count", &moves.len())])
+            )?;
+            for &a in &moves {
+                writeln!(out, "{}", a.write(notation))?;
+            }
+        }
+        (Hint::Line(moves), Format::Moves) => {
+            for &a in &moves {
+                writeln!(out, "{}", a.write(notation))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn analyze(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(
+        rest,
+        &["goal", "strategy", "format", "move", "color", "theme"],
+        &["coords", "last-peg"],
+    )?;
+    args.at_most(1)?;
+    let board = args.board(0)?;
+    if args.switch("last-peg") {
+        return last_peg(&args, &board);
+    }
+    let goal = args.goal(&board)?;
+    let Some(action) = args.parsed::<Action>("move")? else {
+        return fail("analyze needs a move to judge, given with --move");
+    };
+    let verdict = hint::critique(&board, &goal, action, args.strategy()?)
+        .map_err(|reason| format!("{} is not legal: {}", action, reason))?;
+    let after = board.perform_action(action);
+    if args.document_format()? == Format::Json {
+        let Json::Object(mut fields) = verdict.to_json() else {
+            unreachable!()
+        };
+        fields.insert(0, ("move".to_string(), action.to_json()));
+        fields.insert(1, ("after".to_string(), after.to_json()));
+        writeln!(out, "{}", Json::Object(fields))?;
+        return Ok(());
+    }
+    let options = RenderOptions {
+        last_move: Some(action),
+        ..args.render_options(&goal)?
+    };
+    writeln!(out, "After {}:\n", action)?;
+    writeln!(out, "{}", render::render(&after, &options))?;
+    writeln!(out, "{}", verdict)?;
+    Ok(())
+}
+
+fn last_peg(args: &Args, board: &Grid) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let endings = last_peg::endings(board, args.strategy()?);
+    if args.document_format()? == Format::Json {
+        writeln!(out, "{}", endings.to_json())?;
+        return Ok(());
+    }
+    let options = args.render_options(board)?;
+    write!(out, "{}", last_peg::render(board, &endings, &options))?;
+    let cells: Vec<String> = endings
+        .reachable
+        .iter()
+        .map(|&(x, y)| notation::cell_name(x, y))
+        .collect();
+    match cells.len() {
+        0 => writeln!(out, "The board cannot be played down to one peg.")?,
+        _ => writeln!(out, "The last peg can end on {}.", cells.join(", "))?,
+    }
+    writeln!(out, "Position class {:?}", endings.class)?;
+    Ok(())
+}
+
+fn book(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["goal", "notation"], &[])?;
+    args.at_most(1)?;
+    let notation = args.notation()?;
+    if args.positional.is_empty() {
+        for entry in book::BOOK {
+            let moves = entry.moves();
+            writeln!(
+                out,
+                "{:<22} {:>2} moves  {}",
+                entry.name,
+                moves.len(),
+                entry.source
+            )?;
+        }
+        return Ok(());
+    }
+    let board = args.board(0)?;
+    let goal = args.goal(&board)?;
+    let Some(hit) = book::lookup(&board, &goal) else {
+        return fail("the board is not on any line in the book");
+    };
+    writeln!(
+        out,
+        "{}, after {} of its {} moves ({})",
+        hit.entry.name,
+        hit.played,
+        hit.played + hit.moves.len(),
+        hit.entry.source
+    )?;
+    for &action in &hit.moves {
+        writeln!(out, "{}", notation.write(action))?;
+    }
+    Ok(())
+}
+
+fn heatmap(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(
+        rest,
+        &["goal", "format", "max-solutions", "sample", "seed", "color"],
+        &["coords"],
+    )?;
+    args.at_most(1)?;
+    let board = args.board(0)?;
+    let goal = args.goal(&board)?;
+    let heatmap = match args.parsed::<usize>("sample")? {
+        Some(samples) => {
+            let seed = args.parsed("seed")?.unwrap_or_else(clock_seed);
+            heatmap::sampled(&board, &goal, samples, seed)
+        }
+        None => {
+            let max = args.parsed("max-solutions")?.unwrap_or(1000);
+            heatmap::of_solutions(&board, &goal, Some(max))
+        }
+    };
+    if heatmap.lines() == 0 {
+        return no_solution(&board, &goal);
+    }
+    match args.format()? {
+        Format::Json => writeln!(out, "{}", heatmap.to_json())?,
+        Format::Csv => write!(out, "{}", heatmap.to_csv())?,
+        Format::Text | Format::Moves => {
+            let options = args.render_options(&goal)?;
+            writeln!(out, "Across {} solutions:", heatmap.lines())?;
+            for role in Role::ALL {
+                writeln!(out, "\nJumped {}:", role.name())?;
+                write!(out, "{}", heatmap.render(role, &options))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn state_space(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["format"], &["symmetry"])?;
+    args.at_most(1)?;
+    if args.positional.first().is_some_and(|a| a == "me") {
+        return play::show_profile(args.document_format()?);
+    }
+    let board = args.board(0)?;
+    let format = args.document_format()?;
+    let space = enumerate::enumerate(&board, args.switch("symmetry"));
+    if format == Format::Json {
+        writeln!(out, "{}", space.to_json())?;
+    } else {
+        writeln!(out, "{}", space)?;
+    }
+    Ok(())
+}
+
+fn diff(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["format"], &[])?;
+    args.at_most(3)?;
+    let (board, a, b) = match &args.positional[..] {
+        [a, b] => (Grid::new(), a, b),
+        [board, a, b] => (load_board(board)?, a, b),
+        _ => return fail("diff needs two lines of moves to compare"),
+    };
+    let (a, b) = (load_moves::<Grid>(a)?, load_moves::<Grid>(b)?);
+    for (name, moves) in [("first", &a), ("second", &b)] {
+        board
+            .play_moves(moves)
+            .map_err(|e| format!("the {} line: {}", name, e))?;
+    }
+    let report = diff::compare(&board, &a, &b)?;
+    if args.document_format()? == Format::Json {
+        writeln!(out, "{}", report.to_json())?;
+    } else {
+        writeln!(out, "{}", report)?;
+    }
+    Ok(())
+}
+
+/// A generator seed taken from the clock.
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn gen(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    if rest
+        .iter()
+        .any(|a| a == "--game" || a.starts_with("--game="))
+    {
+        return games::generate(rest);
+    }
+    let args = Args::parse(
+        rest,
+        &["goal", "format", "pegs", "seed", "date"],
+        &["daily"],
+    )?;
+    args.at_most(0)?;
+    let json = args.document_format()? == Format::Json;
+    if args.switch("daily") {
+        let date = args.parsed::<Date>("date")?.unwrap_or_else(Date::today);
+        let daily = daily::daily(date);
+        if json {
+            writeln!(out, "{}", daily.to_json())?;
+        } else {
+            writeln!(out, "{}", daily)?;
+        }
+        return Ok(());
+    }
+    let goal = args.goal(&Grid::new())?;
+    let pegs = args.parsed("pegs")?.unwrap_or(16);
+    let seed = args.parsed("seed")?.unwrap_or_else(clock_seed);
+    let Some(puzzle) = generate::random_solvable_from(&goal, pegs, seed) else {
+        return fail(format!("could not generate a board with {} pegs", pegs));
+    };
+    if json {
+        writeln!(out, "{}", puzzle.to_json())?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "Seed {} ({} pegs)",
+        puzzle.seed,
+        puzzle.position.filled_count()
+    )?;
+    writeln!(out, "{}", puzzle.position.notation())?;
+    write!(out, "{}", puzzle.position)?;
+    Ok(())
+}
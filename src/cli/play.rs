@@ -0,0 +1,522 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::daily::{self, Date};
+use crate::feasibility::Infeasible;
+use crate::json::ToJson;
+use crate::profile::Profile;
+use crate::render::RenderOptions;
+use crate::session::Session;
+use crate::Grid;
+
+use super::board::Board;
+use super::i18n::{not_legal, tr, tr_text, trf};
+use super::{diamond, duotaire, fail, load_theme, move_list, use_color, Args, Format, Result};
+
+pub(super) const HELP: &str = "\
+Enter a move as `x y direction`, e.g. `3 1 down` or `3,1,d`. x counts
+columns from the left and y rows from the top, both starting at 0.
+`d2-d4` works too, with columns lettered from a and rows numbered from 1
+as in the sentences of --screen-reader.
+Other commands: moves (list the legal moves), undo (u), redo (r),
+save FILE, load FILE, help, quit.
+";
+
+/// What the player asked for at the prompt.
+enum Command<M> {
+    Play(M),
+    Undo,
+    Redo,
+    Save(String),
+    Load(String),
+    Quit,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The game being played, with the moves that can be undone and redone.
+pub(super) struct Game<B: Board> {
+    start: B,
+    goal: B,
+    board: B,
+    history: Vec<B::Move>,
+    /// Undone moves, the most recently undone last.
+    undone: Vec<B::Move>,
+    started: u64,
+}
+
+impl<B: Board> Game<B> {
+    /// A new game from `start` to `goal`, started now.
+    pub(super) fn new(start: B, goal: B) -> Game<B> {
+        Game {
+            start,
+            goal,
+            board: start,
+            history: Vec::new(),
+            undone: Vec::new(),
+            started: now(),
+        }
+    }
+}
+
+/// What `play` does besides moving pegs, which only games on the English
+/// board do: saving and loading, and counting finished games.
+pub(super) trait Playable: Board {
+    /// What `help` prints at the prompt.
+    const HELP: &'static str;
+
+    /// The game to save, or `None` when games on this board cannot be
+    /// saved.
+    fn session(_game: &Game<Self>, _settings: Vec<(String, String)>) -> Option<Session> {
+        None
+    }
+
+    /// The game saved in `session`, with its display settings applied to
+    /// `style`, or `None` when games on this board cannot be loaded.
+    fn resume(_session: &Session, _style: &mut Self::Style) -> Option<Result<Game<Self>>> {
+        None
+    }
+
+    /// Counts a finished game in the player's statistics.
+    fn record_finish(_game: &Game<Self>, _daily: Option<Date>) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Playable for Grid {
+    const HELP: &'static str = HELP;
+
+    fn session(game: &Game<Grid>, settings: Vec<(String, String)>) -> Option<Session> {
+        Some(game.session(settings))
+    }
+
+    fn resume(session: &Session, options: &mut RenderOptions) -> Option<Result<Game<Grid>>> {
+        Some(Game::resume(session).and_then(|game| {
+            apply_settings(session, options)?;
+            options.goal = Some(game.goal);
+            Ok(game)
+        }))
+    }
+
+    fn record_finish(game: &Game<Grid>, daily: Option<Date>) -> Result<()> {
+        record_finish(game, daily)
+    }
+}
+
+impl Game<Grid> {
+    fn resume(session: &Session) -> Result<Game<Grid>> {
+        Ok(Game {
+            start: session.start,
+            goal: session.goal,
+            board: session.position()?,
+            history: session.moves.clone(),
+            undone: Vec::new(),
+            started: session.started,
+        })
+    }
+
+    fn session(&self, settings: Vec<(String, String)>) -> Session {
+        Session {
+            start: self.start,
+            goal: self.goal,
+            moves: self.history.clone(),
+            started: self.started,
+            saved: now(),
+            settings,
+        }
+    }
+}
+
+fn load_session(path: &str) -> Result<Session> {
+    fs::read_to_string(path)?
+        .parse()
+        .map_err(|e| format!("{}: {}", path, e).into())
+}
+
+/// Applies the display settings stored in a session.
+fn apply_settings(session: &Session, options: &mut RenderOptions) -> Result<()> {
+    if let Some(spec) = session.setting("theme") {
+        options.theme = load_theme(spec)?;
+    }
+    if let Some(choice) = session.setting("color") {
+        options.color = use_color(choice.parse()?);
+    }
+    Ok(())
+}
+
+/// Counts a finished game in the player's statistics and says how it
+/// compares. Statistics that cannot be read or written are only warned
+/// about, so that a broken file never stops a game.
+fn record_finish(game: &Game<Grid>, daily: Option<Date>) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(path) = Profile::default_path() else {
+        return Ok(());
+    };
+    let mut profile = match Profile::load(&path) {
+        Ok(profile) => profile,
+        Err(e) => {
+            writeln!(
+                out,
+                "{}",
+                trf("Not counting this game: {error}", &[("error", &e)])
+            )?;
+            return Ok(());
+        }
+    };
+    let solved = game.board == game.goal;
+    let seconds = now().saturating_sub(game.started);
+    let pegs = game.board.filled_count();
+    if let Some(place) = profile.record_game(&game.start, pegs, solved, seconds) {
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "{seconds} s, number {place} on your leaderboard.",
+                &[("seconds", &seconds), ("place", &place)]
+            )
+        )?;
+    }
+    if let Some(date) = daily.filter(|&date| solved && date == Date::today()) {
+        profile.record_daily(date);
+        let days = profile.daily_streak;
+        writeln!(
+            out,
+            "{}",
+            trf("Daily puzzle streak: {days}.", &[("days", &days)])
+        )?;
+    }
+    if let Err(e) = profile.save(&path) {
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Could not save statistics to {path}: {error}",
+                &[("path", &path.display()), ("error", &e)]
+            )
+        )?;
+    }
+    Ok(())
+}
+
+/// `stats me`: the statistics kept by [`record_finish`].
+pub(super) fn show_profile(format: Format) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(path) = Profile::default_path() else {
+        return super::fail("no statistics file, set HOME or PUZZLETHING_STATS");
+    };
+    let profile = Profile::load(&path)?;
+    if format == Format::Json {
+        writeln!(out, "{}", profile.to_json())?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "{}",
+        trf(
+            "Games finished: {games} ({solved} solved)",
+            &[
+                ("games", &profile.games_finished),
+                ("solved", &profile.games_solved)
+            ]
+        )
+    )?;
+    if let Some(pegs) = profile.best_pegs {
+        writeln!(
+            out,
+            "{}",
+            trf("Fewest pegs left: {pegs}", &[("pegs", &pegs)])
+        )?;
+    }
+    if let Some(seconds) = profile.fastest_solve() {
+        writeln!(
+            out,
+            "{}",
+            trf("Fastest solve: {seconds} s", &[("seconds", &seconds)])
+        )?;
+    }
+    writeln!(
+        out,
+        "{}",
+        trf(
+            "Daily puzzle streak: {days} (best {best})",
+            &[
+                ("days", &profile.current_daily_streak(Date::today())),
+                ("best", &profile.best_daily_streak)
+            ]
+        )
+    )?;
+    if let Some(best) = profile.best_challenge() {
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Timed challenges: {count} (best score {score})",
+                &[("count", &profile.challenges.len()), ("score", &best.score)]
+            )
+        )?;
+    }
+    if !profile.leaderboard.is_empty() {
+        writeln!(out, "{}", tr("Leaderboard:"))?;
+        for (i, solve) in profile.leaderboard.iter().enumerate() {
+            writeln!(
+                out,
+                "{:>3}. {:>5} s  {}",
+                i + 1,
+                solve.seconds,
+                solve.start.notation()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn play(rest: &[String]) -> Result<()> {
+    let args = Args::parse(
+        rest,
+        &["goal", "color", "theme", "load"],
+        &["duotaire", "daily"],
+    )?;
+    if args.switch("duotaire") {
+        return duotaire::play(&args);
+    }
+    if let Some(board) = args.diamond(0) {
+        return diamond::play(&args, board);
+    }
+    args.at_most(1)?;
+    let session = match args.option("load") {
+        Some(path) => Some(load_session(path)?),
+        None => None,
+    };
+    let today = args.switch("daily").then(Date::today);
+    let game = match (&session, today) {
+        (Some(session), _) => Game::resume(session)?,
+        (None, Some(date)) => {
+            let puzzle = daily::daily(date).puzzle;
+            Game::new(puzzle.position, puzzle.goal)
+        }
+        (None, None) => {
+            let board = args.board(0)?;
+            Game::new(board, args.goal(&board)?)
+        }
+    };
+    let mut options = Grid::style(&args, &game.goal, true)?;
+    // Display options given on the command line win over the ones saved
+    // in a resumed session, and are saved with the game.
+    let mut settings: Vec<(String, String)> = ["theme", "color"]
+        .into_iter()
+        .filter_map(|key| Some((key.to_string(), args.option(key)?.to_string())))
+        .collect();
+    if let Some(date) = today {
+        settings.push(("daily".to_string(), date.to_string()));
+    }
+    if let Some(session) = &session {
+        let saved = Session {
+            settings: session
+                .settings
+                .iter()
+                .filter(|(key, _)| args.option(key).is_none())
+                .cloned()
+                .collect(),
+            ..session.clone()
+        };
+        apply_settings(&saved, &mut options)?;
+        settings.extend(saved.settings);
+    }
+    run(game, options, settings)
+}
+
+/// Plays `game` at the prompt until it is solved or the player quits,
+/// drawing it in `style` and saving `settings` with it.
+pub(super) fn run<B: Playable>(
+    mut game: Game<B>,
+    mut style: B::Style,
+    mut settings: Vec<(String, String)>,
+) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let mut lines = io::stdin().lock().lines();
+    let mut hopeless = game.board.infeasible(&game.goal).is_some();
+    // Only the first time a game runs out of jumps counts, not again after
+    // undoing back into it.
+    let mut finished = false;
+    write!(out, "{}", tr(B::HELP))?;
+    loop {
+        let board = game.board;
+        writeln!(out)?;
+        write!(out, "{}", board.draw(&style, game.history.last().copied()))?;
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Pegs: {pegs}  Moves: {moves}",
+                &[("pegs", &board.pegs()), ("moves", &game.history.len())]
+            )
+        )?;
+        let stuck = board.legal_moves().is_empty();
+        if (stuck || board == game.goal) && !finished {
+            finished = true;
+            let daily = settings
+                .iter()
+                .find(|(key, _)| key == "daily")
+                .and_then(|(_, date)| date.parse().ok());
+            B::record_finish(&game, daily)?;
+        }
+        if board == game.goal {
+            let moves = game.history.len();
+            writeln!(
+                out,
+                "{}",
+                trf("Solved in {moves} moves!", &[("moves", &moves)])
+            )?;
+            return Ok(());
+        }
+        if stuck {
+            writeln!(
+                out,
+                "{}",
+                tr("Stuck: no legal moves are left. Undo or quit.")
+            )?;
+        }
+        match read_command(&mut lines, &board)? {
+            Command::Quit => return Ok(()),
+            Command::Play(mv) => {
+                game.board = board.play(mv);
+                game.history.push(mv);
+                game.undone.clear();
+            }
+            Command::Undo => {
+                let Some(mv) = game.history.pop() else {
+                    writeln!(out, "{}", tr("Nothing to undo."))?;
+                    continue;
+                };
+                game.board = board.unplay(mv);
+                game.undone.push(mv);
+                hopeless = game.board.infeasible(&game.goal).is_some();
+            }
+            Command::Redo => {
+                let Some(mv) = game.undone.pop() else {
+                    writeln!(out, "{}", tr("Nothing to redo."))?;
+                    continue;
+                };
+                game.board = board.play(mv);
+                game.history.push(mv);
+            }
+            Command::Save(path) => {
+                let Some(session) = B::session(&game, settings.clone()) else {
+                    writeln!(
+                        out,
+                        "{}",
+                        tr("Only games on the English board can be saved.")
+                    )?;
+                    continue;
+                };
+                match fs::write(&path, session.to_string()) {
+                    Ok(()) => writeln!(out, "{}", trf("Saved to {path}.", &[("path", &path)]))?,
+                    Err(e) => writeln!(
+                        out,
+                        "{}",
+                        trf(
+                            "Could not save to {path}: {error}",
+                            &[("path", &path), ("error", &e)]
+                        )
+                    )?,
+                }
+                continue;
+            }
+            Command::Load(path) => {
+                let loaded =
+                    load_session(&path).and_then(|session| match B::resume(&session, &mut style) {
+                        Some(game) => Ok((game?, session.settings)),
+                        None => fail("only games on the English board can be loaded"),
+                    });
+                match loaded {
+                    Ok((loaded, saved)) => {
+                        game = loaded;
+                        settings = saved;
+                        hopeless = game.board.infeasible(&game.goal).is_some();
+                        writeln!(out, "{}", trf("Loaded {path}.", &[("path", &path)]))?;
+                    }
+                    Err(e) => {
+                        writeln!(
+                            out,
+                            "{}",
+                            trf(
+                                "Could not load {path}: {error}",
+                                &[("path", &path), ("error", &tr_text(&e.to_string()))]
+                            )
+                        )?;
+                        continue;
+                    }
+                }
+            }
+        }
+        if !hopeless {
+            if let Some(reason) = game.board.infeasible(&game.goal) {
+                hopeless = true;
+                if reason != Infeasible::Stuck && game.board != game.goal {
+                    writeln!(
+                        out,
+                        "{}",
+                        trf(
+                            "The goal can no longer be reached: {reason}.",
+                            &[("reason", &tr(&reason.to_string()))]
+                        )
+                    )?;
+                }
+            }
+        }
+    }
+}
+
+/// Prompts until the player enters a legal move or another command.
+/// Closing the input counts as quitting.
+fn read_command<B: Playable>(
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+    board: &B,
+) -> Result<Command<B::Move>> {
+    let mut out = io::stdout().lock();
+    loop {
+        write!(out, "move> ")?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(out)?;
+            return Ok(Command::Quit);
+        };
+        let line = line?;
+        match line.trim() {
+            "" => {}
+            "q" | "quit" | "exit" => return Ok(Command::Quit),
+            "u" | "undo" => return Ok(Command::Undo),
+            "r" | "redo" => return Ok(Command::Redo),
+            "save" | "load" => writeln!(
+                out,
+                "{}",
+                trf(
+                    "Give a file name, e.g. `{command} game.txt`.",
+                    &[("command", &line.trim())]
+                )
+            )?,
+            input if input.starts_with("save ") => {
+                return Ok(Command::Save(input[5..].trim().to_string()))
+            }
+            input if input.starts_with("load ") => {
+                return Ok(Command::Load(input[5..].trim().to_string()))
+            }
+            "h" | "help" | "?" => write!(out, "{}", tr(B::HELP))?,
+            "m" | "moves" => {
+                writeln!(out, "{}", move_list(&board.legal_moves()))?;
+            }
+            input => match input.parse::<B::Move>() {
+                Ok(mv) => match board.check(mv) {
+                    Ok(()) => return Ok(Command::Play(mv)),
+                    Err(reason) => writeln!(out, "{}", not_legal(mv, reason))?,
+                },
+                Err(e) => writeln!(out, "{}", tr_text(&e.to_string()))?,
+            },
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::hint;
+use crate::render::{self, RenderOptions};
+use crate::{Action, Grid};
+
+use super::i18n::{not_legal, tr, trf};
+use super::tui::{self, Key, RawTerminal};
+use super::{fail, move_text, Args, Result};
+
+const CLEAR: &str = "\x1b[H\x1b[2J";
+pub(super) const HELP: &str = "right/space next  left/backspace back  q quit";
+
+/// Plays `moves` from `start`, redrawing the board in place every `delay`
+/// with the jumping and the captured peg of the coming move marked.
+pub(super) fn animate(
+    start: &Grid,
+    moves: &[Action],
+    options: &RenderOptions,
+    delay: Duration,
+) -> Result<()> {
+    let mut stdout = io::stdout();
+    let mut board = *start;
+    for (i, &action) in moves.iter().enumerate() {
+        let frame = RenderOptions {
+            next_move: Some(action),
+            ..options.clone()
+        };
+        let caption = trf(
+            "Move {n}/{total}: {move}",
+            &[
+                ("n", &(i + 1)),
+                ("total", &moves.len()),
+                ("move", &move_text(action)),
+            ],
+        );
+        write!(
+            stdout,
+            "{}{}\n\n{}",
+            CLEAR,
+            caption,
+            render::render(&board, &frame)
+        )?;
+        stdout.flush()?;
+        thread::sleep(delay);
+        board = board.perform_action(action);
+    }
+    let frame = RenderOptions {
+        last_move: moves.last().copied(),
+        ..options.clone()
+    };
+    write!(
+        stdout,
+        "{}{}\n\n{}",
+        CLEAR,
+        trf("Finished in {moves} moves", &[("moves", &moves.len())]),
+        render::render(&board, &frame)
+    )?;
+    Ok(())
+}
+
+pub(super) fn replay(rest: &[String]) -> Result<()> {
+    let args = Args::parse(
+        rest,
+        &["goal", "strategy", "color", "theme"],
+        &["coords", "annotate"],
+    )?;
+    let (board, moves) = args.board_and_moves("replay")?;
+    let goal = args.goal(&board)?;
+    let options = args.render_options(&goal)?;
+    let mut positions = vec![board];
+    for (i, &action) in moves.iter().enumerate() {
+        let current = positions[i];
+        if let Err(reason) = current.check_action(action) {
+            return fail(trf(
+                "move {n}: {reason}",
+                &[("n", &(i + 1)), ("reason", &not_legal(action, reason))],
+            ));
+        }
+        positions.push(current.perform_action(action));
+    }
+    let notes = if args.switch("annotate") {
+        hint::annotate(&board, &goal, &moves, args.strategy()?)?
+    } else {
+        Vec::new()
+    };
+    let frame = |i: usize| {
+        let frame = RenderOptions {
+            last_move: i.checked_sub(1).map(|prev| moves[prev]),
+            next_move: moves.get(i).copied(),
+            ..options.clone()
+        };
+        let caption = match i.checked_sub(1) {
+            Some(prev) => {
+                let after = trf(
+                    "After move {n}/{total}: {move}",
+                    &[
+                        ("n", &i),
+                        ("total", &moves.len()),
+                        ("move", &move_text(moves[prev])),
+                    ],
+                );
+                match notes.get(prev) {
+                    Some(note) => format!("{} ({})", after, tr(&note.to_string())),
+                    None => after,
+                }
+            }
+            None => trf("Start, {total} moves to play", &[("total", &moves.len())]),
+        };
+        format!("{}\n\n{}", caption, render::render(&positions[i], &frame))
+    };
+
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        let mut out = io::stdout().lock();
+        for i in 0..positions.len() {
+            writeln!(out, "{}", frame(i))?;
+        }
+        return Ok(());
+    }
+    let _terminal = RawTerminal::enter()?;
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout();
+    let mut i = 0;
+    loop {
+        let text = frame(i).replace('\n', "\r\n");
+        write!(stdout, "{}{}\r\n{}", CLEAR, text, tr(HELP))?;
+        stdout.flush()?;
+        match tui::read_key(&mut stdin)? {
+            Key::Right | Key::Down | Key::Pick | Key::Redo => i = (i + 1).min(positions.len() - 1),
+            Key::Left | Key::Up | Key::Drop | Key::Undo => i = i.saturating_sub(1),
+            Key::Quit => return Ok(()),
+            Key::Char(_) | Key::Other => {}
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! `serve`: the solver behind a small HTTP/1.1 server, built with the
+//! `serve` feature. Every request and response body is JSON; boards are
+//! read as by [`FromJson`] and written as by [`ToJson`]. `GET /play`
+//! opens a WebSocket for playing a game, see the `websocket` module,
+//! `GET /` serves a page that plays on it in the browser and
+//! `GET /metrics` reports counters for monitoring.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache::{Cached, SolutionCache};
+use crate::daily::{self, Date};
+use crate::hint;
+use crate::json::{FromJson, Json, ToJson};
+use crate::parallel::{solve_one, Outcome};
+use crate::stats::SearchStats;
+use crate::{Grid, SearchStrategy};
+
+use super::metrics::Metrics;
+use super::websocket;
+use super::{solve_json, Args, Result};
+
+/// Seconds a solve or hint may search before the request fails.
+const DEFAULT_TIMEOUT: f64 = 10.0;
+
+/// Larger request bodies are refused; a board fits in well under a
+/// kilobyte.
+const MAX_BODY: usize = 64 * 1024;
+
+/// The page at `/`: a board to play in the browser.
+const PAGE: &str = include_str!("../../web/index.html");
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) fn serve(rest: &[String]) -> Result<()> {
+    let args = Args::parse(rest, &["bind", "port", "timeout", "cache"], &[])?;
+    args.at_most(0)?;
+    let port: u16 = args.parsed("port")?.unwrap_or(8080);
+    let bind = args.option("bind").unwrap_or("127.0.0.1");
+    let server = Arc::new(Server {
+        timeout: args
+            .timeout()?
+            .unwrap_or(Duration::from_secs_f64(DEFAULT_TIMEOUT)),
+        cache: match args.option("cache") {
+            Some(path) => Some(Mutex::new(SolutionCache::open(path)?)),
+            None => None,
+        },
+        metrics: Metrics::default(),
+    });
+    let listener = TcpListener::bind((bind, port))?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            if let Err(e) = handle(stream, &server) {
+                eprintln!("serve: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// What every connection shares.
+struct Server {
+    /// How long a solve or hint may search.
+    timeout: Duration,
+    cache: Option<Mutex<SolutionCache>>,
+    metrics: Metrics,
+}
+
+impl Server {
+    /// Answers from the cache when it can, and otherwise searches and
+    /// remembers the answer.
+    fn solve(&self, board: Grid, goal: Grid, strategy: SearchStrategy) -> Outcome {
+        self.solve_within(board, goal, strategy, self.timeout)
+    }
+
+    /// [`Server::solve`], searching for at most `timeout`.
+    fn solve_within(
+        &self,
+        board: Grid,
+        goal: Grid,
+        strategy: SearchStrategy,
+        timeout: Duration,
+    ) -> Outcome {
+        if let Some(cache) = &self.cache {
+            let cached = cache.lock().unwrap().get(&board, &goal);
+            self.metrics.cache_lookup(cached.is_some());
+            match cached {
+                Some(Cached::Solvable(moves)) => {
+                    return Outcome::Solved(moves, SearchStats::default())
+                }
+                Some(Cached::Unsolvable) => return Outcome::Unsolvable(SearchStats::default()),
+                None => {}
+            }
+        }
+        let started = Instant::now();
+        let outcome = solve_one(board, goal, strategy, Some(timeout));
+        self.metrics.search(&outcome, started.elapsed());
+        if let Some(cache) = &self.cache {
+            let result = match &outcome {
+                Outcome::Solved(moves, _) => Some(Cached::Solvable(moves.clone())),
+                Outcome::Unsolvable(_) => Some(Cached::Unsolvable),
+                Outcome::Timeout => None,
+            };
+            if let Some(result) = result {
+                // Losing a line to a full disk is no reason to fail the request.
+                if let Err(e) = cache.lock().unwrap().record(&board, &goal, &result) {
+                    eprintln!("serve: cache: {}", e);
+                }
+            }
+        }
+        outcome
+    }
+
+    fn respond(&self, stream: &TcpStream, path: &str, status: u16, body: &Json) -> io::Result<()> {
+        self.metrics.request(path, status);
+        respond(stream, status, body)
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    /// Header names in lower case, with their values.
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A failed request: the status code and a message for the client.
+struct HttpError(u16, String);
+
+impl HttpError {
+    fn bad_request(message: impl ToString) -> HttpError {
+        HttpError(400, message.to_string())
+    }
+}
+
+fn handle(stream: TcpStream, server: &Server) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let request = match read_request(&mut reader) {
+        Ok(request) => request,
+        Err(HttpError(status, message)) => {
+            return server.respond(&stream, "", status, &error_json(&message))
+        }
+    };
+    let path = request.path.as_str();
+    if path == "/play" {
+        return match upgrade(&request) {
+            Ok(accept) => {
+                server.metrics.request(path, 101);
+                websocket::play(reader, &stream, &accept, server.timeout)
+            }
+            Err(HttpError(status, message)) => {
+                server.respond(&stream, path, status, &error_json(&message))
+            }
+        };
+    }
+    let page = match (request.method.as_str(), path) {
+        ("GET", "/") => Some(("text/html; charset=utf-8", PAGE.to_string())),
+        ("GET", "/metrics") => Some((
+            "text/plain; version=0.0.4; charset=utf-8",
+            server.metrics.render(),
+        )),
+        _ => None,
+    };
+    if let Some((content_type, body)) = page {
+        server.metrics.request(path, 200);
+        return respond_with(&stream, 200, content_type, &body);
+    }
+    let (status, body) = match route(&request, server) {
+        Ok(json) => (200, json),
+        Err(HttpError(status, message)) => (status, error_json(&message)),
+    };
+    server.respond(&stream, path, status, &body)
+}
+
+/// Checks that `request` asks for a WebSocket and returns the handshake's
+/// accept key.
+fn upgrade(request: &Request) -> std::result::Result<String, HttpError> {
+    if request.method != "GET" {
+        return Err(HttpError(
+            405,
+            format!("{} is not allowed on /play", request.method),
+        ));
+    }
+    let wants_websocket = request
+        .header("upgrade")
+        .is_some_and(|u| u.eq_ignore_ascii_case("websocket"));
+    match request.header("sec-websocket-key") {
+        Some(key) if wants_websocket => Ok(websocket::accept(key)),
+        _ => Err(HttpError(426, "/play is a WebSocket endpoint".to_string())),
+    }
+}
+
+fn error_json(message: &str) -> Json {
+    Json::object([("error", message.into())])
+}
+
+fn read_request(reader: &mut impl BufRead) -> std::result::Result<Request, HttpError> {
+    let mut line = String::new();
+    read_line(reader, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(HttpError::bad_request("malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers: Vec::new(),
+        body: String::new(),
+    };
+    let mut length = 0;
+    loop {
+        read_line(reader, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let (name, value) = (name.trim().to_ascii_lowercase(), value.trim());
+            if name == "content-length" {
+                length = value
+                    .parse()
+                    .map_err(|_| HttpError::bad_request("invalid Content-Length"))?;
+            }
+            request.headers.push((name, value.to_string()));
+        }
+    }
+    if length > MAX_BODY {
+        return Err(HttpError(413, "request body too large".to_string()));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(HttpError::bad_request)?;
+    request.body =
+        String::from_utf8(body).map_err(|_| HttpError::bad_request("body is not UTF-8"))?;
+    Ok(request)
+}
+
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> std::result::Result<(), HttpError> {
+    line.clear();
+    // Bounded so that a client cannot grow a header without end.
+    match reader.by_ref().take(8 * 1024).read_line(line) {
+        Ok(0) => Err(HttpError::bad_request("connection closed")),
+        Ok(_) if !line.ends_with('\n') => Err(HttpError(431, "header too long".to_string())),
+        Ok(_) => Ok(()),
+        Err(e) => Err(HttpError::bad_request(e)),
+    }
+}
+
+fn respond(stream: &TcpStream, status: u16, body: &Json) -> io::Result<()> {
+    respond_with(stream, status, "application/json", &format!("{}\n", body))
+}
+
+fn respond_with(
+    mut stream: &TcpStream,
+    status: u16,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        426 => "Upgrade Required",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn route(request: &Request, server: &Server) -> std::result::Result<Json, HttpError> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/solve") => post_solve(&request.body, server),
+        ("POST", "/hint") => post_hint(&request.body, server),
+        ("GET", "/daily") => get_daily(&request.query),
+        (_, "/" | "/metrics" | "/solve" | "/hint" | "/daily") => Err(HttpError(
+            405,
+            format!("{} is not allowed on {}", request.method, request.path),
+        )),
+        _ => Err(HttpError(404, format!("no endpoint {}", request.path))),
+    }
+}
+
+/// The board, goal and strategy of a solve or hint request, defaulting
+/// to the full English board, the centre peg and depth-first search.
+fn position(body: &str) -> std::result::Result<(Json, Grid, Grid, SearchStrategy), HttpError> {
+    let json: Json = if body.trim().is_empty() {
+        Json::Object(Vec::new())
+    } else {
+        body.parse().map_err(HttpError::bad_request)?
+    };
+    let board = match json.get("board") {
+        Some(board) => Grid::from_json(board).map_err(HttpError::bad_request)?,
+        None => Grid::new(),
+    };
+    let goal = match json.get("goal") {
+        Some(goal) => Grid::from_json(goal).map_err(HttpError::bad_request)?,
+        None => board.single_peg(3, 3),
+    };
+    let strategy = match json.get("strategy") {
+        Some(Json::String(name)) => name.parse().map_err(HttpError::bad_request)?,
+        Some(_) => return Err(HttpError::bad_request("strategy must be a string")),
+        None => SearchStrategy::default(),
+    };
+    Ok((json, board, goal, strategy))
+}
+
+fn timed_out(timeout: Duration) -> HttpError {
+    HttpError(
+        503,
+        format!("no answer within {} seconds", timeout.as_secs_f64()),
+    )
+}
+
+fn post_solve(body: &str, server: &Server) -> std::result::Result<Json, HttpError> {
+    let (_, board, goal, strategy) = position(body)?;
+    match server.solve(board, goal, strategy) {
+        Outcome::Solved(moves, stats) => Ok(solve_json(
+            &board,
+            &goal,
+            strategy.name(),
+            Some(&moves),
+            &stats,
+        )),
+        Outcome::Unsolvable(stats) => Ok(solve_json(&board, &goal, strategy.name(), None, &stats)),
+        Outcome::Timeout => Err(timed_out(server.timeout)),
+    }
+}
+
+/// `{"move": ...}`, null when the goal is already reached, or with `top`
+/// in the request the best `top` moves as `{"moves": [...]}`.
+fn post_hint(body: &str, server: &Server) -> std::result::Result<Json, HttpError> {
+    let (json, board, goal, strategy) = position(body)?;
+    if let Some(top) = json.get("top") {
+        let top = top
+            .as_u64()
+            .ok_or_else(|| HttpError::bad_request("top must be a whole number"))?;
+        // Every move is searched, all of them within the one deadline.
+        let deadline = Instant::now() + server.timeout;
+        let evaluations = hint::evaluate_moves_with(&board, &goal, |next| {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match server.solve_within(*next, goal, strategy, left) {
+                Outcome::Solved(..) => Ok(true),
+                Outcome::Unsolvable(_) => Ok(false),
+                Outcome::Timeout => Err(timed_out(server.timeout)),
+            }
+        })?;
+        if evaluations.is_empty() {
+            return Err(HttpError(422, "no solution".to_string()));
+        }
+        let evaluations = &evaluations[..(top as usize).min(evaluations.len())];
+        return Ok(Json::object([("moves", Json::array(evaluations))]));
+    }
+    match server.solve(board, goal, strategy) {
+        Outcome::Solved(moves, _) => {
+            let next = moves.first().map_or(Json::Null, ToJson::to_json);
+            Ok(Json::object([("move", next)]))
+        }
+        Outcome::Unsolvable(_) => Err(HttpError(422, "no solution".to_string())),
+        Outcome::Timeout => Err(timed_out(server.timeout)),
+    }
+}
+
+/// The puzzle of the day, or of the day in `?date=YYYY-MM-DD`.
+fn get_daily(query: &str) -> std::result::Result<Json, HttpError> {
+    let date = query.split('&').find_map(|pair| pair.strip_prefix("date="));
+    let date = match date {
+        Some(date) => date.parse().map_err(HttpError::bad_request)?,
+        None => Date::today(),
+    };
+    Ok(daily::daily(date).to_json())
+}
@@ -0,0 +1,170 @@
+//! `train`: positions where exactly one jump still wins, getting harder
+//! with every few found in a row.
+
+use std::io::{self, BufRead, Write};
+
+use crate::difficulty::Rating;
+use crate::render::{self, RenderOptions};
+use crate::train::{self, Exercise};
+use crate::{Action, Grid};
+
+use super::i18n::{not_legal, tr, tr_text, trf};
+use super::{clock_seed, fail, move_list, move_text, Args, Result};
+
+pub(super) const HELP: &str = "\
+Each position has exactly one jump that can still end on the centre peg.
+Enter it as `x y direction`, e.g. `3 1 down` or `3,1,d`. Three right in a
+row make the positions bigger and harder; a miss makes them easier.
+Other commands: moves (list the legal moves), skip (show the answer),
+help, quit.
+";
+
+/// Right answers in a row that move the trainer up a level.
+const STREAK_TO_LEVEL: u32 = 3;
+
+enum Command {
+    Answer(Action),
+    Skip,
+    Quit,
+}
+
+/// Pegs and the lowest rating wanted at `level`.
+fn level_settings(level: u32) -> (u32, Rating) {
+    let rating = match level {
+        0..=3 => Rating::Easy,
+        4..=7 => Rating::Medium,
+        _ => Rating::Hard,
+    };
+    ((5 + level).min(24), rating)
+}
+
+pub(super) fn train(rest: &[String]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let args = Args::parse(rest, &["seed", "color", "theme"], &[])?;
+    args.at_most(0)?;
+    let mut seed = args.parsed("seed")?.unwrap_or_else(clock_seed);
+    let options = RenderOptions {
+        coords: true,
+        ..args.render_options(&Grid::new().single_peg(3, 3))?
+    };
+    let mut lines = io::stdin().lock().lines();
+    let (mut level, mut streak, mut best) = (0, 0, 0);
+    let (mut right, mut asked) = (0, 0);
+    write!(out, "{}", tr(HELP))?;
+    loop {
+        let (pegs, rating) = level_settings(level);
+        let Some(exercise) = train::exercise(pegs, rating, seed) else {
+            return fail(format!("no exercise with {} pegs turned up", pegs));
+        };
+        seed = exercise.seed.wrapping_add(1);
+        writeln!(out)?;
+        write!(out, "{}", render::render(&exercise.position, &options))?;
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Level {level}, {rating}: {choices} legal jumps, one of them wins.",
+                &[
+                    ("level", &(level + 1)),
+                    ("rating", &tr(&exercise.rating.to_string())),
+                    ("choices", &exercise.choices)
+                ]
+            )
+        )?;
+        asked += 1;
+        match read_command(&mut lines, &exercise)? {
+            Command::Quit => {
+                asked -= 1;
+                break;
+            }
+            Command::Skip => {
+                let answer = move_text(exercise.answer);
+                writeln!(
+                    out,
+                    "{}",
+                    trf("The winning jump: {move}.", &[("move", &answer)])
+                )?;
+                streak = 0;
+            }
+            Command::Answer(action) if action == exercise.answer => {
+                right += 1;
+                streak += 1;
+                best = best.max(streak);
+                write!(out, "{}", tr("Right!"))?;
+                if streak % STREAK_TO_LEVEL == 0 {
+                    level += 1;
+                    write!(
+                        out,
+                        "{}",
+                        trf(
+                            " {streak} in a row, on to level {level}.",
+                            &[("streak", &streak), ("level", &(level + 1))]
+                        )
+                    )?;
+                }
+                writeln!(out)?;
+            }
+            Command::Answer(_) => {
+                let answer = move_text(exercise.answer);
+                writeln!(
+                    out,
+                    "{}",
+                    trf(
+                        "That loses. The winning jump: {move}.",
+                        &[("move", &answer)]
+                    )
+                )?;
+                streak = 0;
+                level = level.saturating_sub(1);
+            }
+        }
+    }
+    writeln!(
+        out,
+        "{}",
+        trf(
+            "{right} of {asked} right, best streak {best}, reached level {level}.",
+            &[
+                ("right", &right),
+                ("asked", &asked),
+                ("best", &best),
+                ("level", &(level + 1))
+            ]
+        )
+    )?;
+    Ok(())
+}
+
+/// Prompts until the player enters a legal jump or another command.
+/// Closing the input counts as quitting.
+fn read_command(
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+    exercise: &Exercise,
+) -> Result<Command> {
+    let mut out = io::stdout().lock();
+    let board = &exercise.position;
+    loop {
+        write!(out, "move> ")?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(out)?;
+            return Ok(Command::Quit);
+        };
+        match line?.trim() {
+            "" => {}
+            "q" | "quit" | "exit" => return Ok(Command::Quit),
+            "s" | "skip" => return Ok(Command::Skip),
+            "h" | "help" | "?" => write!(out, "{}", tr(HELP))?,
+            "m" | "moves" => {
+                writeln!(out, "{}", move_list(&board.valid_actions()))?;
+            }
+            input => match input.parse::<Action>() {
+                Ok(action) => match board.check_action(action) {
+                    Ok(()) => return Ok(Command::Answer(action)),
+                    Err(reason) => writeln!(out, "{}", not_legal(action, reason))?,
+                },
+                Err(e) => writeln!(out, "{}", tr_text(&e.to_string()))?,
+            },
+        }
+    }
+}
@@ -0,0 +1,468 @@
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::challenge::{self, Countdown};
+use crate::profile::{ChallengeResult, Profile};
+use crate::render::{self, Theme};
+use crate::{Action, Grid, Tile};
+
+use super::i18n::{tr, trf};
+use super::{fail, Args, Result};
+
+const LISTED_MOVES: usize = 14;
+pub(super) const HELP: &str = "arrows/hjkl move  enter/space pick  x drop  u undo  r redo  q quit";
+pub(super) const PAUSE_HELP: &str = "  p pause";
+/// How often a running countdown is redrawn.
+const TICK: Duration = Duration::from_millis(200);
+
+/// Puts the terminal in raw mode on an alternate screen until dropped.
+pub(super) struct RawTerminal {
+    saved: String,
+}
+
+impl RawTerminal {
+    pub(super) fn enter() -> Result<RawTerminal> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return fail(tr(
+                "the TUI needs an interactive terminal, try `play` instead",
+            ));
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        let mut out = io::stdout().lock();
+        write!(out, "\x1b[?1049h\x1b[?25l")?;
+        out.flush()?;
+        Ok(RawTerminal {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let mut out = io::stdout().lock();
+        let _ = write!(out, "\x1b[?25h\x1b[?1049l");
+        let _ = out.flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return fail("could not switch the terminal to raw mode");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Pick,
+    Drop,
+    Undo,
+    Redo,
+    Quit,
+    /// Any other single byte.
+    Char(u8),
+    Other,
+}
+
+pub(super) fn read_key(input: &mut impl Read) -> io::Result<Key> {
+    let mut byte = [0];
+    input.read_exact(&mut byte)?;
+    Ok(match byte[0] {
+        b'\x1b' => {
+            let mut seq = [0; 2];
+            input.read_exact(&mut seq)?;
+            match seq {
+                [b'[', b'A'] => Key::Up,
+                [b'[', b'B'] => Key::Down,
+                [b'[', b'C'] => Key::Right,
+                [b'[', b'D'] => Key::Left,
+                _ => Key::Other,
+            }
+        }
+        b'k' | b'w' => Key::Up,
+        b'j' | b's' => Key::Down,
+        b'h' | b'a' => Key::Left,
+        b'l' | b'd' => Key::Right,
+        b'\r' | b'\n' | b' ' => Key::Pick,
+        b'x' | b'\x7f' => Key::Drop,
+        b'u' => Key::Undo,
+        b'r' => Key::Redo,
+        b'q' | b'\x03' => Key::Quit,
+        byte => Key::Char(byte),
+    })
+}
+
+struct App {
+    board: Grid,
+    goal: Grid,
+    theme: Theme,
+    cursor: (usize, usize),
+    selected: Option<(usize, usize)>,
+    history: Vec<Action>,
+    /// Undone moves, the most recently undone last.
+    undone: Vec<Action>,
+    message: String,
+    /// The clock of a timed challenge.
+    countdown: Option<Countdown>,
+}
+
+impl App {
+    fn new(board: Grid, goal: Grid, theme: Theme) -> App {
+        let cursor = (0..7)
+            .flat_map(|y| (0..7).map(move |x| (x, y)))
+            .find(|&(x, y)| board.tile(x, y) == Tile::Filled)
+            .unwrap_or((3, 3));
+        let mut app = App {
+            board,
+            goal,
+            theme,
+            cursor,
+            selected: None,
+            history: Vec::new(),
+            undone: Vec::new(),
+            message: String::new(),
+            countdown: None,
+        };
+        app.update_status();
+        app
+    }
+
+    /// Legal jumps of the selected peg.
+    fn destinations(&self) -> Vec<Action> {
+        match self.selected {
+            Some((x, y)) => self.board.tile_actions(x, y),
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves the cursor to the next board cell in the given direction,
+    /// skipping blocked corners.
+    fn step(&mut self, dx: isize, dy: isize) {
+        let (mut x, mut y) = (self.cursor.0 as isize, self.cursor.1 as isize);
+        loop {
+            x += dx;
+            y += dy;
+            if !(0..7).contains(&x) || !(0..7).contains(&y) {
+                return;
+            }
+            if self.board.tile(x as usize, y as usize) != Tile::Blocked {
+                self.cursor = (x as usize, y as usize);
+                return;
+            }
+        }
+    }
+
+    fn pick(&mut self) {
+        let jump = self
+            .destinations()
+            .into_iter()
+            .find(|a| a.landing() == Some(self.cursor));
+        if let Some(action) = jump {
+            self.board = self.board.perform_action(action);
+            self.history.push(action);
+            self.undone.clear();
+            self.selected = None;
+            self.update_status();
+            return;
+        }
+        if self.selected == Some(self.cursor) {
+            self.selected = None;
+            self.message.clear();
+            return;
+        }
+        let (x, y) = self.cursor;
+        if self.board.tile(x, y) != Tile::Filled {
+            self.message = if self.selected.is_some() {
+                tr("The selected peg cannot jump there")
+            } else {
+                tr("Pick a peg to move first")
+            };
+        } else if self.board.tile_actions(x, y).is_empty() {
+            self.message = tr("That peg has no legal jump");
+        } else {
+            self.selected = Some(self.cursor);
+            self.message.clear();
+        }
+    }
+
+    fn undo(&mut self) {
+        let Some(action) = self.history.pop() else {
+            self.message = tr("Nothing to undo");
+            return;
+        };
+        self.board = self.board.unperform_action(action);
+        self.undone.push(action);
+        self.selected = None;
+        self.update_status();
+    }
+
+    fn redo(&mut self) {
+        let Some(action) = self.undone.pop() else {
+            self.message = tr("Nothing to redo");
+            return;
+        };
+        self.board = self.board.perform_action(action);
+        self.history.push(action);
+        self.selected = None;
+        self.update_status();
+    }
+
+    fn update_status(&mut self) {
+        self.message = if self.board == self.goal {
+            trf(
+                "Solved in {moves} moves!",
+                &[("moves", &self.history.len())],
+            )
+        } else if self.board.valid_actions().is_empty() {
+            tr("Stuck: no legal moves are left")
+        } else {
+            String::new()
+        };
+    }
+
+    fn render(&self) -> String {
+        let targets: Vec<(usize, usize)> = self
+            .destinations()
+            .iter()
+            .filter_map(Action::landing)
+            .collect();
+        let width = self.theme.cell_width();
+        // A paused challenge hides the pegs, so that pausing does not buy
+        // thinking time.
+        let paused = self.countdown.is_some_and(|c| c.is_paused());
+        let mut header = "  ".to_string();
+        for x in 0..7 {
+            let _ = write!(header, " {:<width$} ", x, width = width);
+        }
+        let mut left = vec![header];
+        for y in 0..7 {
+            let mut row = format!("{} ", y);
+            for x in 0..7 {
+                let tile = self.board.tile(x, y);
+                let mut style = String::new();
+                if self.selected == Some((x, y)) {
+                    style.push_str("\x1b[1;33m");
+                }
+                if targets.contains(&(x, y)) {
+                    style.push_str("\x1b[42m");
+                }
+                if self.cursor == (x, y) {
+                    style.push_str("\x1b[7m");
+                }
+                let symbol = if paused { " " } else { self.theme.symbol(tile) };
+                let padding = " ".repeat(width - render::display_width(symbol));
+                let _ = write!(row, "{} {}{} \x1b[0m", style, symbol, padding);
+            }
+            left.push(row);
+        }
+
+        let shown = self.history.len().saturating_sub(LISTED_MOVES);
+        let mut right = vec![tr("Moves")];
+        for (i, action) in self.history.iter().enumerate().skip(shown) {
+            right.push(format!("{:>3}. {}", i + 1, action));
+        }
+
+        let mut frame = String::from("\x1b[H\x1b[2J");
+        frame.push_str("\x1b[1mpuzzlething\x1b[0m\r\n\r\n");
+        for i in 0..left.len().max(right.len()) {
+            match left.get(i) {
+                Some(row) => frame.push_str(row),
+                None => frame.push_str(&" ".repeat(2 + 7 * (width + 2))),
+            }
+            let _ = write!(frame, "   {}\r\n", right.get(i).map_or("", String::as_str));
+        }
+        let mut counts = trf(
+            "Pegs: {pegs}  Moves: {moves}",
+            &[
+                ("pegs", &self.board.filled_count()),
+                ("moves", &self.history.len()),
+            ],
+        );
+        let mut help = tr(HELP);
+        if let Some(countdown) = self.countdown {
+            let _ = write!(counts, "  {}", clock(countdown.remaining()));
+            help.push_str(&tr(PAUSE_HELP));
+        }
+        let message = if paused {
+            tr("Paused, press p to resume")
+        } else {
+            self.message.clone()
+        };
+        let _ = write!(
+            frame,
+            "\r\n\x1b[7m {}  {} \x1b[0m\r\n{}",
+            counts, message, help
+        );
+        frame
+    }
+
+    fn toggle_pause(&mut self) {
+        match &mut self.countdown {
+            Some(countdown) if countdown.is_paused() => countdown.resume(),
+            Some(countdown) => countdown.pause(),
+            None => {}
+        }
+    }
+
+    /// Whether a timed challenge has ended, by running out of time or by
+    /// reaching the goal.
+    fn challenge_over(&self) -> bool {
+        self.countdown
+            .is_some_and(|c| c.expired() || self.board == self.goal)
+    }
+}
+
+/// `1:05` for a minute and five seconds, counting a started second as
+/// whole so that `0:00` means the time is up.
+fn clock(remaining: Duration) -> String {
+    let seconds = remaining.as_millis().div_ceil(1000);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Reads keys on another thread, so that a running countdown can be
+/// redrawn while waiting for one.
+fn key_reader() -> Receiver<io::Result<Key>> {
+    let (send, receive) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let key = read_key(&mut stdin);
+            let failed = key.is_err();
+            if send.send(key).is_err() || failed {
+                return;
+            }
+        }
+    });
+    receive
+}
+
+/// Scores a finished challenge and adds it to the statistics file.
+fn finish_challenge(app: &App, start: &Grid, countdown: Countdown) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let solved = app.board == app.goal;
+    let remaining = countdown.remaining();
+    if countdown.expired() {
+        writeln!(out, "{}", tr("Time is up."))?;
+    }
+    let result = ChallengeResult {
+        score: challenge::score(
+            start.filled_count(),
+            app.board.filled_count(),
+            solved,
+            remaining,
+        ),
+        pegs: app.board.filled_count(),
+        seconds_left: remaining.as_secs(),
+        start: *start,
+    };
+    writeln!(
+        out,
+        "{}",
+        trf("Score: {score}", &[("score", &result.score)])
+    )?;
+    let Some(path) = Profile::default_path() else {
+        return Ok(());
+    };
+    let saved = Profile::load(&path).and_then(|mut profile| {
+        profile.challenges.push(result);
+        profile.save(&path)
+    });
+    if let Err(e) = saved {
+        writeln!(
+            out,
+            "{}",
+            trf(
+                "Could not save statistics to {path}: {error}",
+                &[("path", &path.display()), ("error", &e)]
+            )
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn tui(rest: &[String]) -> Result<()> {
+    let args = Args::parse(rest, &["goal", "theme", "timed"], &[])?;
+    args.at_most(1)?;
+    let board = args.board(0)?;
+    let goal = args.goal(&board)?;
+    let limit = match args.parsed::<u64>("timed")? {
+        Some(0) => return fail("--timed needs a positive number of seconds"),
+        limit => limit.map(Duration::from_secs),
+    };
+    let mut app = App::new(board, goal, args.theme()?);
+
+    let terminal = RawTerminal::enter()?;
+    app.countdown = limit.map(Countdown::start);
+    let keys = key_reader();
+    let mut stdout = io::stdout().lock();
+    loop {
+        stdout.write_all(app.render().as_bytes())?;
+        stdout.flush()?;
+        if app.challenge_over() {
+            break;
+        }
+        let paused = app.countdown.is_some_and(|c| c.is_paused());
+        let key = if app.countdown.is_some() && !paused {
+            match keys.recv_timeout(TICK) {
+                Ok(key) => key?,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match keys.recv() {
+                Ok(key) => key?,
+                Err(_) => break,
+            }
+        };
+        if paused && !matches!(key, Key::Char(b'p') | Key::Quit) {
+            continue;
+        }
+        match key {
+            Key::Up => app.step(0, -1),
+            Key::Down => app.step(0, 1),
+            Key::Left => app.step(-1, 0),
+            Key::Right => app.step(1, 0),
+            Key::Pick => app.pick(),
+            Key::Drop => {
+                app.selected = None;
+                app.update_status();
+            }
+            Key::Undo => app.undo(),
+            Key::Redo => app.redo(),
+            Key::Quit => break,
+            Key::Char(b'p') => app.toggle_pause(),
+            Key::Char(_) | Key::Other => {}
+        }
+    }
+    drop(terminal);
+    writeln!(stdout, "{}", app.board)?;
+    writeln!(
+        stdout,
+        "{}",
+        trf(
+            "Pegs: {pegs}  Moves: {moves}",
+            &[
+                ("pegs", &app.board.filled_count()),
+                ("moves", &app.history.len())
+            ]
+        )
+    )?;
+    if let Some(countdown) = app.countdown {
+        finish_challenge(&app, &board, countdown)?;
+    }
+    Ok(())
+}
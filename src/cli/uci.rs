@@ -0,0 +1,321 @@
+//! `--protocol uci`: a line protocol modelled on the Universal Chess
+//! Interface, so that arbiters and GUIs written for board-game engines can
+//! drive the solver. Moves are in standard notation, `d2-d4`.
+//!
+//! ```text
+//! uci                                  identify; answered by `uciok`
+//! isready                              answered by `readyok`
+//! setoption name Strategy value pns    choose the search strategy
+//! ucinewgame
+//! position startpos [moves d2-d4 ...]
+//! position board NOTATION [goal NOTATION] [moves ...]
+//! go [depth N] [movetime MS]           search; answered by `bestmove`
+//! stop                                 end the search early
+//! quit
+//! ```
+//!
+//! A search reports `info` lines with its counters and the principal
+//! variation before `bestmove`, which is `none` when it found no move
+//! towards the goal, as when it was stopped first. At the end of the input
+//! a running search is waited for; after `quit` it is dropped unanswered.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::feasibility;
+use crate::notation::{self, Standard};
+use crate::presets::Preset;
+use crate::stats::SearchStats;
+use crate::{Action, GameTree, Grid, SearchStrategy};
+
+use super::Result;
+
+struct Search {
+    stop: Arc<AtomicBool>,
+    /// Set with `stop` when the answer is no longer wanted.
+    abandon: Arc<AtomicBool>,
+    worker: JoinHandle<()>,
+}
+
+impl Search {
+    /// Stops the search and waits for its `bestmove`.
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.worker.join();
+    }
+
+    /// Stops the search without letting it answer.
+    fn abandon(self) {
+        self.abandon.store(true, Ordering::Relaxed);
+        self.stop();
+    }
+
+    /// Waits for the search to finish by itself.
+    fn finish(self) {
+        let _ = self.worker.join();
+    }
+}
+
+/// How far `go` may look.
+#[derive(Clone, Copy, Default)]
+struct Limits {
+    depth: Option<usize>,
+    movetime: Option<Duration>,
+}
+
+pub(super) fn run() -> Result<()> {
+    // Locked a line at a time rather than for the session, as the search
+    // thread writes its results to it too.
+    let mut out = io::stdout();
+    let mut strategy = SearchStrategy::default();
+    let mut position = Grid::new();
+    let mut goal = position.single_peg(3, 3);
+    let mut search: Option<Search> = None;
+    let stop_search = |search: &mut Option<Search>| {
+        if let Some(search) = search.take() {
+            search.stop();
+        }
+    };
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            continue;
+        };
+        match command {
+            "uci" => {
+                writeln!(out, "id name puzzlething {}", env!("CARGO_PKG_VERSION"))?;
+                let names: Vec<String> = SearchStrategy::ALL
+                    .iter()
+                    .map(|s| format!("var {}", s.name()))
+                    .collect();
+                writeln!(
+                    out,
+                    "option name Strategy type combo default {} {}",
+                    strategy.name(),
+                    names.join(" ")
+                )?;
+                writeln!(out, "uciok")?;
+            }
+            "isready" => writeln!(out, "readyok")?,
+            "setoption" => match args {
+                ["name", name, "value", value] if name.eq_ignore_ascii_case("strategy") => {
+                    match value.parse() {
+                        Ok(s) => strategy = s,
+                        Err(e) => writeln!(out, "info string {}", e)?,
+                    }
+                }
+                _ => writeln!(out, "info string unknown option")?,
+            },
+            "ucinewgame" => {
+                stop_search(&mut search);
+                position = Grid::new();
+                goal = position.single_peg(3, 3);
+            }
+            "position" => match parse_position(args) {
+                Ok((p, g)) => (position, goal) = (p, g),
+                Err(e) => writeln!(out, "info string {}", e)?,
+            },
+            "go" => {
+                stop_search(&mut search);
+                match parse_limits(args) {
+                    Ok(limits) => search = Some(go(position, goal, strategy, limits)),
+                    Err(e) => writeln!(out, "info string {}", e)?,
+                }
+            }
+            "stop" => stop_search(&mut search),
+            "quit" => {
+                if let Some(search) = search.take() {
+                    search.abandon();
+                }
+                return Ok(());
+            }
+            _ => writeln!(out, "info string unknown command {}", command)?,
+        }
+    }
+    // As with a UCI engine, input that simply runs out still gets its
+    // answer.
+    if let Some(search) = search {
+        search.finish();
+    }
+    Ok(())
+}
+
+/// The position and goal of a `position` command, with its moves played.
+fn parse_position(args: &[&str]) -> std::result::Result<(Grid, Grid), String> {
+    let (board, rest) = match args {
+        ["startpos", rest @ ..] => (Grid::new(), rest),
+        ["board", spec, rest @ ..] => (read_board(spec)?, rest),
+        _ => return Err("expected `position startpos` or `position board NOTATION`".into()),
+    };
+    let (goal, rest) = match rest {
+        ["goal", spec, rest @ ..] => (read_board(spec)?, rest),
+        rest => (board.single_peg(3, 3), rest),
+    };
+    let moves = match rest {
+        [] => &[][..],
+        ["moves", moves @ ..] => moves,
+        _ => return Err(format!("unexpected {:?}", rest.join(" "))),
+    };
+    let mut position = board;
+    for word in moves {
+        for action in notation::parse(word).map_err(|e| e.to_string())? {
+            position
+                .check_action(action)
+                .map_err(|reason| format!("{} is not legal: {}", Standard(action), reason))?;
+            position = position.perform_action(action);
+        }
+    }
+    Ok((position, goal))
+}
+
+fn read_board(spec: &str) -> std::result::Result<Grid, String> {
+    match spec.parse::<Preset>() {
+        Ok(preset) => preset
+            .position()
+            .ok_or_else(|| format!("{} is too big for the 7x7 grid", preset)),
+        Err(_) => spec.parse().map_err(|e| format!("{}", e)),
+    }
+}
+
+fn parse_limits(args: &[&str]) -> std::result::Result<Limits, String> {
+    let mut limits = Limits::default();
+    let mut words = args.iter();
+    while let Some(&word) = words.next() {
+        let mut value = || -> std::result::Result<u64, String> {
+            words
+                .next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("{} needs a number", word))
+        };
+        match word {
+            "depth" => limits.depth = Some(value()? as usize),
+            "movetime" => limits.movetime = Some(Duration::from_millis(value()?)),
+            "infinite" => {}
+            _ => return Err(format!("unknown go option {}", word)),
+        }
+    }
+    Ok(limits)
+}
+
+/// Starts a search on its own thread, stopped by the flag or once
+/// `movetime` runs out.
+fn go(position: Grid, goal: Grid, strategy: SearchStrategy, limits: Limits) -> Search {
+    let stop = Arc::new(AtomicBool::new(false));
+    let abandon = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stop);
+    let abandoned = Arc::clone(&abandon);
+    let worker = thread::spawn(move || {
+        let started = Instant::now();
+        let (tx, rx) = mpsc::channel::<()>();
+        let watchdog = limits.movetime.map(|movetime| {
+            let flag = Arc::clone(&flag);
+            thread::spawn(move || {
+                if rx.recv_timeout(movetime) == Err(RecvTimeoutError::Timeout) {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            })
+        });
+        let remaining = position.filled_count().saturating_sub(goal.filled_count()) as usize;
+        let (line, stats, depth) = match limits.depth {
+            Some(depth) if depth < remaining => {
+                let mut stats = SearchStats::default();
+                let line = lookahead(
+                    position,
+                    &goal,
+                    depth,
+                    &flag,
+                    &mut stats,
+                    &mut HashSet::new(),
+                );
+                (line, stats, depth)
+            }
+            _ => {
+                let (found, stats) =
+                    GameTree::new(position, Vec::new()).search_cancellable(&goal, strategy, &flag);
+                (found.map(|g| g.history().to_vec()), stats, remaining)
+            }
+        };
+        drop(tx);
+        if let Some(watchdog) = watchdog {
+            let _ = watchdog.join();
+        }
+        if abandoned.load(Ordering::Relaxed) {
+            return;
+        }
+        let elapsed = started.elapsed();
+        let millis = elapsed.as_millis();
+        let nps = (stats.nodes_expanded as f64 / elapsed.as_secs_f64().max(1e-3)) as u64;
+        let pv: Vec<String> = line
+            .iter()
+            .flatten()
+            .map(|&a| Standard(a).to_string())
+            .collect();
+        // A closed stdout is left for the command loop to notice, as the
+        // search has no one to report it to.
+        let mut out = io::stdout().lock();
+        let _ = writeln!(
+            out,
+            "info depth {} nodes {} time {} nps {}{}{}",
+            depth,
+            stats.nodes_expanded,
+            millis,
+            nps,
+            if pv.is_empty() { "" } else { " pv " },
+            pv.join(" ")
+        );
+        let best = match line.as_deref() {
+            Some([first, ..]) => Standard(*first).to_string(),
+            // A search cut short proves nothing, so it has no move to offer.
+            None if flag.load(Ordering::Relaxed) => {
+                let _ = writeln!(out, "info string search stopped before finding a line");
+                "none".to_string()
+            }
+            _ => "none".to_string(),
+        };
+        let _ = writeln!(out, "bestmove {}", best);
+    });
+    Search {
+        stop,
+        abandon,
+        worker,
+    }
+}
+
+/// A line of `depth` moves along which the goal stays reachable as far as
+/// [`feasibility::check`] can tell, or a shorter one that reaches it.
+/// `dead` holds the positions and depths already known to fail.
+fn lookahead(
+    position: Grid,
+    goal: &Grid,
+    depth: usize,
+    stop: &AtomicBool,
+    stats: &mut SearchStats,
+    dead: &mut HashSet<(u64, usize)>,
+) -> Option<Vec<Action>> {
+    if position == *goal || depth == 0 {
+        return Some(Vec::new());
+    }
+    if stop.load(Ordering::Relaxed) || dead.contains(&(position.encode(), depth)) {
+        return None;
+    }
+    stats.nodes_expanded += 1;
+    for action in position.valid_actions() {
+        let next = position.perform_action(action);
+        if feasibility::check(&next, goal).is_err() {
+            stats.prune("feasibility");
+            continue;
+        }
+        if let Some(mut line) = lookahead(next, goal, depth - 1, stop, stats, dead) {
+            line.insert(0, action);
+            return Some(line);
+        }
+    }
+    dead.insert((position.encode(), depth));
+    None
+}
@@ -0,0 +1,305 @@
+//! The `/play` WebSocket endpoint of `serve`: each connection holds one
+//! game. The client sends JSON text messages with a `type`:
+//!
+//! ```text
+//! {"type": "new", "board": "cross", "goal": ...}   start a new game
+//! {"type": "move", "move": "d2-d4"}                 play a move
+//! {"type": "legal"}                                 list the legal moves
+//! {"type": "hint"}                                  the engine's next move
+//! {"type": "undo"}                                  take back a move
+//! {"type": "state"}                                 repeat the state
+//! ```
+//!
+//! and gets a `state` message after connecting and after every change,
+//! `legal` and `hint` replies, or an `error` with a `message`.
+
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::parallel::{solve_one, Outcome};
+use crate::{Action, Grid, SearchStrategy};
+
+/// How long a connection may sit idle before it is closed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Larger messages close the connection.
+const MAX_MESSAGE: usize = 64 * 1024;
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The `Sec-WebSocket-Accept` answer to a client's `Sec-WebSocket-Key`.
+pub(super) fn accept(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key.trim(), GUID).as_bytes()))
+}
+
+fn sha1(message: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    for block in padded.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t);
+        }
+        for (h, x) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(x);
+        }
+    }
+    let mut digest = [0; 20];
+    for (out, word) in digest.chunks_mut(4).zip(h) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+const TEXT: u8 = 0x1;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xa;
+
+/// Reads one frame, unmasking its payload, as `(fin, opcode, payload)`.
+fn read_frame(reader: &mut impl Read) -> io::Result<(bool, u8, Vec<u8>)> {
+    let mut head = [0; 2];
+    reader.read_exact(&mut head)?;
+    let (fin, opcode) = (head[0] & 0x80 != 0, head[0] & 0x0f);
+    let len = match head[1] & 0x7f {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if head[1] & 0x80 == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unmasked client frame",
+        ));
+    }
+    if len > MAX_MESSAGE as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message too large",
+        ));
+    }
+    let mut mask = [0; 4];
+    reader.read_exact(&mut mask)?;
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((fin, opcode, payload))
+}
+
+fn write_frame(mut stream: &TcpStream, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)
+}
+
+fn send(stream: &TcpStream, message: &Json) -> io::Result<()> {
+    write_frame(stream, TEXT, message.to_string().as_bytes())
+}
+
+/// One connection's game.
+struct Game {
+    start: Grid,
+    goal: Grid,
+    moves: Vec<Action>,
+    position: Grid,
+}
+
+impl Game {
+    fn new(start: Grid, goal: Grid) -> Game {
+        Game {
+            start,
+            goal,
+            moves: Vec::new(),
+            position: start,
+        }
+    }
+
+    fn state(&self) -> Json {
+        let legal = self.position.valid_actions();
+        Json::object([
+            ("type", "state".into()),
+            ("board", self.position.to_json()),
+            ("start", self.start.to_json()),
+            ("goal", self.goal.to_json()),
+            ("moves", Json::array(&self.moves)),
+            ("legal", Json::array(&legal)),
+            ("won", (self.position == self.goal).into()),
+            ("finished", legal.is_empty().into()),
+        ])
+    }
+
+    /// The reply to one client message.
+    fn handle(&mut self, text: &str, timeout: Duration) -> std::result::Result<Json, String> {
+        let message: Json = text.parse().map_err(|e| format!("{}", e))?;
+        match message.get("type").and_then(Json::as_str) {
+            Some("new") => {
+                let start = match message.get("board") {
+                    Some(board) => Grid::from_json(board).map_err(|e| e.to_string())?,
+                    None => Grid::new(),
+                };
+                let goal = match message.get("goal") {
+                    Some(goal) => Grid::from_json(goal).map_err(|e| e.to_string())?,
+                    None => start.single_peg(3, 3),
+                };
+                *self = Game::new(start, goal);
+                Ok(self.state())
+            }
+            Some("move") => {
+                let action = message
+                    .get("move")
+                    .ok_or("a move message needs a `move`")
+                    .map_err(str::to_string)
+                    .and_then(|m| Action::from_json(m).map_err(|e| e.to_string()))?;
+                self.position
+                    .check_action(action)
+                    .map_err(|reason| format!("{} is not legal: {}", action, reason))?;
+                self.position = self.position.perform_action(action);
+                self.moves.push(action);
+                Ok(self.state())
+            }
+            Some("undo") => {
+                let action = self.moves.pop().ok_or("nothing to undo")?;
+                self.position = self.position.unperform_action(action);
+                Ok(self.state())
+            }
+            Some("legal") => Ok(Json::object([
+                ("type", "legal".into()),
+                ("moves", Json::array(&self.position.valid_actions())),
+            ])),
+            Some("hint") => {
+                let strategy = match message.get("strategy").and_then(Json::as_str) {
+                    Some(name) => name.parse().map_err(|e| format!("{}", e))?,
+                    None => SearchStrategy::default(),
+                };
+                match solve_one(self.position, self.goal, strategy, Some(timeout)) {
+                    Outcome::Solved(moves, _) => Ok(Json::object([
+                        ("type", "hint".into()),
+                        ("move", moves.first().map_or(Json::Null, ToJson::to_json)),
+                    ])),
+                    Outcome::Unsolvable(_) => Err("the goal can no longer be reached".to_string()),
+                    Outcome::Timeout => {
+                        Err(format!("no hint within {} seconds", timeout.as_secs_f64()))
+                    }
+                }
+            }
+            Some("state") => Ok(self.state()),
+            Some(other) => Err(format!("unknown message type {:?}", other)),
+            None => Err("a message needs a `type`".to_string()),
+        }
+    }
+}
+
+/// Completes the handshake and plays games on the connection until the
+/// client closes it.
+pub(super) fn play(
+    mut reader: impl BufRead,
+    stream: &TcpStream,
+    accept: &str,
+    timeout: Duration,
+) -> io::Result<()> {
+    write!(
+        &mut &*stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut game = Game::new(Grid::new(), Grid::new().single_peg(3, 3));
+    send(stream, &game.state())?;
+    let mut message = Vec::new();
+    loop {
+        let (fin, opcode, payload) = read_frame(&mut reader)?;
+        match opcode {
+            CLOSE => return write_frame(stream, CLOSE, &payload[..payload.len().min(2)]),
+            PING => write_frame(stream, PONG, &payload)?,
+            PONG => {}
+            // A text frame, or the continuation of one.
+            TEXT | 0x0 => {
+                message.extend_from_slice(&payload);
+                if message.len() > MAX_MESSAGE {
+                    return write_frame(stream, CLOSE, &1009u16.to_be_bytes());
+                }
+                if !fin {
+                    continue;
+                }
+                let reply = match std::str::from_utf8(&message) {
+                    Ok(text) => game.handle(text, timeout),
+                    Err(_) => Err("messages must be UTF-8 text".to_string()),
+                };
+                let reply = reply.unwrap_or_else(|message| {
+                    Json::object([("type", "error".into()), ("message", message.into())])
+                });
+                send(stream, &reply)?;
+                message.clear();
+            }
+            _ => return write_frame(stream, CLOSE, &1003u16.to_be_bytes()),
+        }
+    }
+}
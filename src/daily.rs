@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::generate::{self, GeneratedPuzzle};
+
+/// A proleptic Gregorian calendar date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        let date = Date { year, month, day };
+        date.is_valid().then_some(date)
+    }
+
+    /// Today's date in UTC.
+    pub fn today() -> Date {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Date::from_days((secs / 86_400) as i64)
+    }
+
+    fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month) && self.day >= 1 && self.day <= self.days_in_month()
+    }
+
+    fn days_in_month(&self) -> u32 {
+        match self.month {
+            2 if self.year % 4 == 0 && (self.year % 100 != 0 || self.year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days(&self) -> i64 {
+        let y = self.year as i64 - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+
+    /// Monday is 0 and Sunday is 6.
+    pub fn weekday(&self) -> u32 {
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDateError(String);
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date {:?}, expected YYYY-MM-DD", self.0)
+    }
+}
+
+impl Error for ParseDateError {}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Date, ParseDateError> {
+        let err = || ParseDateError(s.to_string());
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(err);
+        let year = next()?.parse().map_err(|_| err())?;
+        let month = next()?.parse().map_err(|_| err())?;
+        let day = next()?.parse().map_err(|_| err())?;
+        Date::new(year, month, day).ok_or_else(err)
+    }
+}
+
+/// The shared puzzle for one calendar day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Daily {
+    pub date: Date,
+    /// Short identifier people can quote when comparing results.
+    pub id: String,
+    pub puzzle: GeneratedPuzzle,
+}
+
+impl fmt::Display for Daily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Puzzle {} ({} pegs)", self.id, self.puzzle.position.filled_count)?;
+        writeln!(f, "{}", self.puzzle.position.notation())?;
+        write!(f, "{}", self.puzzle.position)
+    }
+}
+
+/// The puzzle of the day for `date`. The peg count grows through the week
+/// from 6 on Mondays to 18 on Sundays, and the board is derived from the
+/// date alone, so everyone gets the same one.
+pub fn daily(date: Date) -> Daily {
+    let pegs = 6 + 2 * date.weekday();
+    let mut seed = date.days() as u64 ^ 0x5045_4753_4f4c_4954;
+    let puzzle = loop {
+        if let Some(puzzle) = generate::random_solvable(pegs, seed) {
+            break puzzle;
+        }
+        seed = seed.wrapping_add(1);
+    };
+    Daily {
+        date,
+        id: format!("{}-{:x}", date, puzzle.position.encode()),
+        puzzle,
+    }
+}
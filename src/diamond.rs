@@ -0,0 +1,534 @@
+//! The diamond-shaped boards sold alongside the English one, with 32 and
+//! 41 holes. Neither fits the 7x7 [`Grid`](crate::Grid), so they are
+//! played on a [`DiamondBoard`] of up to 9x9 cells, with the same jumps
+//! and the same notation, its columns lettered to `i` and its rows
+//! numbered to 9. Each comes with its usual problem and a line that solves
+//! it, as a search takes a long time over so many pegs.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::notation::cell_name;
+use crate::stats::SearchStats;
+use crate::trace::PROGRESS_INTERVAL;
+use crate::{Direction, IllegalMove, ReplayError, Tile};
+
+/// Most columns and rows a [`DiamondBoard`] can have.
+pub const SIZE: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Diamond {
+    /// Rows of 1, 3, 5, 7, 7, 5, 3 and 1 holes, played from the upper of
+    /// the two middle holes back to it.
+    ThirtyTwo,
+    /// Rows of 1, 3, 5, 7, 9, 7, 5, 3 and 1 holes. No single vacancy can
+    /// finish at the centre, so the problem is played from two above the
+    /// centre to two below it.
+    FortyOne,
+}
+
+impl Diamond {
+    pub const ALL: [Diamond; 2] = [Diamond::ThirtyTwo, Diamond::FortyOne];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Diamond::ThirtyTwo => "diamond-32",
+            Diamond::FortyOne => "diamond-41",
+        }
+    }
+
+    fn notation(&self) -> (&'static str, &'static str) {
+        match self {
+            Diamond::ThirtyTwo => (
+                "...O.../..OOO../.OOOOO./OOO-OOO/OOOOOOO/.OOOOO./..OOO../...O...",
+                "...-.../..---../.-----./---O---/-------/.-----./..---../...-...",
+            ),
+            Diamond::FortyOne => (
+                "....O..../...OOO.../..OO-OO../.OOOOOOO./OOOOOOOOO\
+                 /.OOOOOOO./..OOOOO../...OOO.../....O....",
+                "....-..../...---.../..-----../.-------./---------\
+                 /.-------./..--O--../...---.../....-....",
+            ),
+        }
+    }
+
+    pub fn start(&self) -> DiamondBoard {
+        self.notation().0.parse().unwrap()
+    }
+
+    /// The single peg the usual problem is played down to.
+    pub fn goal(&self) -> DiamondBoard {
+        self.notation().1.parse().unwrap()
+    }
+
+    /// A line from the start to the goal.
+    pub fn line(&self) -> Vec<Jump> {
+        let line = match self {
+            Diamond::ThirtyTwo => {
+                "31D 52L 33U 44U 46U 25R 23D 26U 55L 63L 43D 64L 12R 45L 37U \
+                 03R 41D 15U 34L 44U 42L 30D 13R 21D 04R 35L 23D 15R 32D 35U"
+            }
+            Diamond::FortyOne => {
+                "22R 34U 31D 52L 54U 74L 66U 54R 40D 32D 13R 56U 36R 57U 51D \
+                 84L 54R 62D 34D 48U 25U 04R 23D 45R 75L 74L 54D 37U 56L 36U \
+                 34U 43R 73L 32R 52D 54L 15R 34D 26R"
+            }
+        };
+        // Each jump is written as its column, its row and `L`, `R`, `U` or
+        // `D`.
+        line.split_whitespace()
+            .map(|jump| {
+                let digit = |i: usize| (jump.as_bytes()[i] - b'0') as usize;
+                let direction = match jump.as_bytes()[2] {
+                    b'L' => Direction::Left,
+                    b'R' => Direction::Right,
+                    b'U' => Direction::Up,
+                    _ => Direction::Down,
+                };
+                Jump::new(digit(0), digit(1), direction)
+            })
+            .collect()
+    }
+
+    /// The rest of [`Diamond::line`] from `position`, if the line passes
+    /// through it.
+    pub fn line_from(&self, position: &DiamondBoard) -> Option<Vec<Jump>> {
+        let line = self.line();
+        let mut board = self.start();
+        for (i, &jump) in line.iter().enumerate() {
+            if board == *position {
+                return Some(line[i..].to_vec());
+            }
+            board = board.perform_jump(jump);
+        }
+        (board == *position).then(Vec::new)
+    }
+}
+
+impl fmt::Display for Diamond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownDiamond(pub String);
+
+impl fmt::Display for UnknownDiamond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown diamond board {:?}, expected diamond-32 or diamond-41",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownDiamond {}
+
+impl FromStr for Diamond {
+    type Err = UnknownDiamond;
+
+    fn from_str(s: &str) -> Result<Diamond, UnknownDiamond> {
+        Diamond::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownDiamond(s.to_string()))
+    }
+}
+
+/// The jump of the peg at `(x, y)`, written as the cells it leaves and
+/// lands on, `d1-d3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Jump {
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+}
+
+impl Jump {
+    pub fn new(x: usize, y: usize, direction: Direction) -> Jump {
+        Jump { x, y, direction }
+    }
+
+    /// The cell whose peg the jump removes, if it is on the 9x9 grid.
+    pub fn over(&self) -> Option<(usize, usize)> {
+        self.span().map(|(over, _)| over)
+    }
+
+    /// The cell the peg lands on, if it is on the 9x9 grid.
+    pub fn to(&self) -> Option<(usize, usize)> {
+        self.span().map(|(_, to)| to)
+    }
+
+    fn span(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (x, y) = (self.x, self.y);
+        match self.direction {
+            Direction::Up if y >= 2 => Some(((x, y - 1), (x, y - 2))),
+            Direction::Down if y + 2 < SIZE => Some(((x, y + 1), (x, y + 2))),
+            Direction::Left if x >= 2 => Some(((x - 1, y), (x - 2, y))),
+            Direction::Right if x + 2 < SIZE => Some(((x + 1, y), (x + 2, y))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Jump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", cell_name(self.x, self.y))?;
+        match self.to() {
+            Some((tx, ty)) => write!(f, "-{}", cell_name(tx, ty)),
+            None => write!(f, "-?"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseJumpError(String);
+
+impl fmt::Display for ParseJumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid move {:?}, expected cells two apart such as `d1-d3`, or \
+             `x y direction`",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseJumpError {}
+
+fn parse_cell(s: &str) -> Option<(usize, usize)> {
+    let mut chars = s.chars();
+    let column = chars.next()?.to_ascii_lowercase();
+    let row = chars.as_str().parse::<usize>().ok()?;
+    if !('a'..='i').contains(&column) || !(1..=SIZE).contains(&row) {
+        return None;
+    }
+    Some((column as usize - 'a' as usize, row - 1))
+}
+
+/// `d1-d3`, or `3 0 down` with the coordinates counted from 0.
+impl FromStr for Jump {
+    type Err = ParseJumpError;
+
+    fn from_str(s: &str) -> Result<Jump, ParseJumpError> {
+        let err = || ParseJumpError(s.to_string());
+        if let Some((from, to)) = s.trim().split_once('-') {
+            let (x, y) = parse_cell(from.trim()).ok_or_else(err)?;
+            let (tx, ty) = parse_cell(to.trim()).ok_or_else(err)?;
+            let direction = match (tx as isize - x as isize, ty as isize - y as isize) {
+                (0, -2) => Direction::Up,
+                (0, 2) => Direction::Down,
+                (-2, 0) => Direction::Left,
+                (2, 0) => Direction::Right,
+                _ => return Err(err()),
+            };
+            return Ok(Jump::new(x, y, direction));
+        }
+        let cleaned = s.replace(['(', ')', ','], " ");
+        let coord = |part: &str| match part.parse::<usize>() {
+            Ok(n) if n < SIZE => Ok(n),
+            _ => Err(err()),
+        };
+        match cleaned.split_whitespace().collect::<Vec<_>>()[..] {
+            [x, y, direction] => Ok(Jump::new(
+                coord(x)?,
+                coord(y)?,
+                direction.parse().map_err(|_| err())?,
+            )),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseDiamondBoardError {
+    TooManyRows,
+    TooManyColumns { row: usize },
+    UnknownSymbol { row: usize, symbol: char },
+}
+
+impl fmt::Display for ParseDiamondBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDiamondBoardError::TooManyRows => write!(f, "a board has at most 9 rows"),
+            ParseDiamondBoardError::TooManyColumns { row } => {
+                write!(f, "row {} has more than 9 cells", row + 1)
+            }
+            ParseDiamondBoardError::UnknownSymbol { row, symbol } => {
+                write!(f, "unknown cell {:?} in row {}", symbol, row + 1)
+            }
+        }
+    }
+}
+
+impl Error for ParseDiamondBoardError {}
+
+/// A position on a board of up to 9x9 cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DiamondBoard {
+    /// Bit `y * 9 + x` set for every cell that can hold a peg.
+    holes: u128,
+    /// Bit `y * 9 + x` set for every peg.
+    pegs: u128,
+}
+
+fn bit(x: usize, y: usize) -> u128 {
+    1 << (y * SIZE + x)
+}
+
+/// Read as [`Grid`](crate::Grid)s are, but with up to 9 rows of 9 cells.
+impl FromStr for DiamondBoard {
+    type Err = ParseDiamondBoardError;
+
+    fn from_str(s: &str) -> Result<DiamondBoard, ParseDiamondBoardError> {
+        let mut board = DiamondBoard { holes: 0, pegs: 0 };
+        let rows: Vec<&str> = s.trim_end_matches('\n').split(['\n', '/']).collect();
+        if rows.len() > SIZE {
+            return Err(ParseDiamondBoardError::TooManyRows);
+        }
+        for (y, row) in rows.iter().enumerate() {
+            let row = row.trim_end_matches('\r');
+            if row.chars().count() > SIZE {
+                return Err(ParseDiamondBoardError::TooManyColumns { row: y });
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                match symbol {
+                    'O' | 'o' => {
+                        board.holes |= bit(x, y);
+                        board.pegs |= bit(x, y);
+                    }
+                    '-' => board.holes |= bit(x, y),
+                    ' ' | '.' => {}
+                    _ => return Err(ParseDiamondBoardError::UnknownSymbol { row: y, symbol }),
+                }
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl fmt::Display for DiamondBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                write!(f, "{}", self.tile(x, y))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl DiamondBoard {
+    pub fn width(&self) -> usize {
+        (0..SIZE)
+            .rev()
+            .find(|&x| (0..SIZE).any(|y| self.holes & bit(x, y) != 0))
+            .map_or(0, |x| x + 1)
+    }
+
+    pub fn height(&self) -> usize {
+        (0..SIZE)
+            .rev()
+            .find(|&y| (0..SIZE).any(|x| self.holes & bit(x, y) != 0))
+            .map_or(0, |y| y + 1)
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        if x >= SIZE || y >= SIZE || self.holes & bit(x, y) == 0 {
+            Tile::Blocked
+        } else if self.pegs & bit(x, y) != 0 {
+            Tile::Filled
+        } else {
+            Tile::Empty
+        }
+    }
+
+    pub fn filled_count(&self) -> u32 {
+        self.pegs.count_ones()
+    }
+
+    /// Pegs as a bitmask, bit `y * 9 + x` set for every filled cell.
+    pub fn encode(&self) -> u128 {
+        self.pegs
+    }
+
+    /// Whether `other` has the same holes, whatever pegs are in them.
+    pub fn same_layout(&self, other: &DiamondBoard) -> bool {
+        self.holes == other.holes
+    }
+
+    /// Single-line form of the board, rows top to bottom separated by `/`
+    /// with `.` for blocked cells.
+    pub fn notation(&self) -> String {
+        let rows: Vec<String> = (0..self.height())
+            .map(|y| {
+                (0..self.width())
+                    .map(|x| match self.tile(x, y) {
+                        Tile::Blocked => '.',
+                        Tile::Filled => 'O',
+                        Tile::Empty => '-',
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.join("/")
+    }
+
+    /// Says what is wrong with `jump`, if anything.
+    pub fn check_jump(&self, jump: Jump) -> Result<(), IllegalMove> {
+        match self.tile(jump.x, jump.y) {
+            Tile::Blocked => return Err(IllegalMove::OffBoard),
+            Tile::Empty => return Err(IllegalMove::NoPeg),
+            Tile::Filled => {}
+        }
+        let Some(((mx, my), (tx, ty))) = jump.span() else {
+            return Err(IllegalMove::OffBoard);
+        };
+        match (self.tile(mx, my), self.tile(tx, ty)) {
+            (_, Tile::Blocked) => Err(IllegalMove::OffBoard),
+            (Tile::Blocked | Tile::Empty, _) => Err(IllegalMove::NothingToJump),
+            (_, Tile::Filled) => Err(IllegalMove::Occupied),
+            (Tile::Filled, Tile::Empty) => Ok(()),
+        }
+    }
+
+    /// Every legal jump, ordered as [`Grid::valid_actions`](crate::Grid::valid_actions)
+    /// orders them: by column, then by row, then left, right, up and down.
+    pub fn valid_jumps(&self) -> Vec<Jump> {
+        let mut jumps = Vec::new();
+        for x in 0..SIZE {
+            for y in 0..SIZE {
+                if self.pegs & bit(x, y) == 0 {
+                    continue;
+                }
+                for direction in [
+                    Direction::Left,
+                    Direction::Right,
+                    Direction::Up,
+                    Direction::Down,
+                ] {
+                    let jump = Jump::new(x, y, direction);
+                    if self.check_jump(jump).is_ok() {
+                        jumps.push(jump);
+                    }
+                }
+            }
+        }
+        jumps
+    }
+
+    pub fn perform_jump(&self, jump: Jump) -> DiamondBoard {
+        assert!(self.check_jump(jump).is_ok());
+        let ((mx, my), (tx, ty)) = jump.span().unwrap();
+        DiamondBoard {
+            holes: self.holes,
+            pegs: self.pegs ^ bit(jump.x, jump.y) ^ bit(mx, my) ^ bit(tx, ty),
+        }
+    }
+
+    /// Takes `jump` back, restoring the peg it jumped over.
+    pub fn unperform_jump(&self, jump: Jump) -> DiamondBoard {
+        let ((mx, my), (tx, ty)) = jump.span().unwrap();
+        assert!(self.tile(tx, ty) == Tile::Filled);
+        DiamondBoard {
+            holes: self.holes,
+            pegs: self.pegs ^ bit(jump.x, jump.y) ^ bit(mx, my) ^ bit(tx, ty),
+        }
+    }
+
+    /// Plays `jumps` in order, stopping at the first illegal one.
+    pub fn play_jumps(&self, jumps: &[Jump]) -> Result<DiamondBoard, ReplayError<Jump>> {
+        let mut board = *self;
+        for (index, &jump) in jumps.iter().enumerate() {
+            board.check_jump(jump).map_err(|reason| ReplayError {
+                index,
+                action: jump,
+                reason,
+            })?;
+            board = board.perform_jump(jump);
+        }
+        Ok(board)
+    }
+
+    /// Searches depth first for a line down to `goal`, remembering the
+    /// positions that cannot reach it, for at most `timeout`.
+    pub fn search(&self, goal: &DiamondBoard, timeout: Option<Duration>) -> Outcome {
+        let started = Instant::now();
+        let mut search = Search {
+            goal: goal.pegs,
+            dead: HashSet::new(),
+            line: Vec::new(),
+            stats: SearchStats::default(),
+            deadline: timeout.map(|timeout| started + timeout),
+            timed_out: false,
+        };
+        let found = search.visit(self);
+        search.stats.observe_memo(&search.dead);
+        search.stats.elapsed = started.elapsed();
+        match (found, search.timed_out) {
+            (true, _) => Outcome::Solved(search.line, search.stats),
+            (false, false) => Outcome::Unsolvable(search.stats),
+            (false, true) => Outcome::Timeout,
+        }
+    }
+}
+
+/// How [`DiamondBoard::search`] ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Solved(Vec<Jump>, SearchStats),
+    Unsolvable(SearchStats),
+    Timeout,
+}
+
+struct Search {
+    goal: u128,
+    /// Pegs of the positions known not to reach the goal.
+    dead: HashSet<u128>,
+    line: Vec<Jump>,
+    stats: SearchStats,
+    deadline: Option<Instant>,
+    timed_out: bool,
+}
+
+impl Search {
+    fn visit(&mut self, board: &DiamondBoard) -> bool {
+        if board.pegs == self.goal {
+            return true;
+        }
+        if board.pegs.count_ones() <= self.goal.count_ones() {
+            return false;
+        }
+        if self.dead.contains(&board.pegs) {
+            self.stats.duplicates += 1;
+            return false;
+        }
+        self.stats.nodes_expanded += 1;
+        if self.stats.nodes_expanded.is_multiple_of(PROGRESS_INTERVAL) {
+            self.timed_out = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        for jump in board.valid_jumps() {
+            if self.timed_out {
+                return false;
+            }
+            self.line.push(jump);
+            if self.visit(&board.perform_jump(jump)) {
+                return true;
+            }
+            self.line.pop();
+        }
+        if !self.timed_out {
+            self.dead.insert(board.pegs);
+        }
+        false
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::multimove;
+use crate::{Action, Grid, ReplayError};
+
+/// How two lines of moves from the same start compare.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineDiff {
+    pub moves: (usize, usize),
+    pub chained_moves: (usize, usize),
+    /// Moves the two lines agree on before they first differ.
+    pub common_prefix: usize,
+    /// The first moves that differ, `None` for a line that had already
+    /// ended.
+    pub divergence: Option<(Option<Action>, Option<Action>)>,
+    /// Move counts after which both lines are on the same position, not
+    /// counting the start. Every jump removes one peg, so a shared position
+    /// is always reached after the same number of moves in both.
+    pub shared: Vec<usize>,
+    pub finals: (Grid, Grid),
+}
+
+/// Replays `a` and `b` from `start` and compares them. Fails on the first
+/// illegal move of either line.
+pub fn compare(start: &Grid, a: &[Action], b: &[Action]) -> Result<LineDiff, ReplayError> {
+    let positions_a = positions(start, a)?;
+    let positions_b = positions(start, b)?;
+    let common_prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let divergence = (a.len() != b.len() || common_prefix < a.len())
+        .then(|| (a.get(common_prefix).copied(), b.get(common_prefix).copied()));
+    let shared = positions_a
+        .iter()
+        .zip(&positions_b)
+        .enumerate()
+        .skip(1)
+        .filter(|(_, (x, y))| x == y)
+        .map(|(i, _)| i)
+        .collect();
+    Ok(LineDiff {
+        moves: (a.len(), b.len()),
+        chained_moves: (multimove::chain(a).len(), multimove::chain(b).len()),
+        common_prefix,
+        divergence,
+        shared,
+        finals: (*positions_a.last().unwrap(), *positions_b.last().unwrap()),
+    })
+}
+
+/// `start` and the position after every move.
+fn positions(start: &Grid, moves: &[Action]) -> Result<Vec<Grid>, ReplayError> {
+    let mut out = vec![*start];
+    for (index, &action) in moves.iter().enumerate() {
+        let cur = out[index];
+        cur.check_action(action).map_err(|reason| ReplayError {
+            index,
+            action,
+            reason,
+        })?;
+        out.push(cur.perform_action(action));
+    }
+    Ok(out)
+}
+
+impl fmt::Display for LineDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "moves: {} vs {}", self.moves.0, self.moves.1)?;
+        writeln!(
+            f,
+            "chained moves: {} vs {}",
+            self.chained_moves.0, self.chained_moves.1
+        )?;
+        let show = |action: Option<Action>| action.map_or("end".to_string(), |a| a.to_string());
+        match self.divergence {
+            None => writeln!(f, "the lines are identical")?,
+            Some((a, b)) => writeln!(
+                f,
+                "diverge at move {}: {} vs {}",
+                self.common_prefix + 1,
+                show(a),
+                show(b)
+            )?,
+        }
+        let rejoined: Vec<String> = self
+            .shared
+            .iter()
+            .filter(|&&i| i > self.common_prefix)
+            .map(usize::to_string)
+            .collect();
+        match rejoined.len() {
+            0 => writeln!(f, "shared positions after diverging: none")?,
+            n => writeln!(
+                f,
+                "shared positions after diverging: {}, after moves {}",
+                n,
+                rejoined.join(", ")
+            )?,
+        }
+        if self.finals.0 == self.finals.1 {
+            write!(f, "both end on the same position")
+        } else {
+            write!(f, "they end on different positions")
+        }
+    }
+}
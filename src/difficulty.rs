@@ -0,0 +1,74 @@
+use std::fmt;
+
+use crate::playout;
+use crate::{GameTree, Grid};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rating {
+    Easy,
+    Medium,
+    Hard,
+    Unsolvable,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Rating::Easy => "easy",
+            Rating::Medium => "medium",
+            Rating::Hard => "hard",
+            Rating::Unsolvable => "unsolvable",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultyReport {
+    pub rating: Rating,
+    /// Share of random playouts that finish with a single peg.
+    pub solution_density: f64,
+    /// Positions generated by the depth-first search before it finished.
+    pub nodes_to_solution: u64,
+    /// Moves in the solution found, if any.
+    pub depth: Option<usize>,
+}
+
+impl fmt::Display for DifficultyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rating: {}", self.rating)?;
+        writeln!(f, "solution density: {:.4}", self.solution_density)?;
+        writeln!(f, "nodes to first solution: {}", self.nodes_to_solution)?;
+        match self.depth {
+            Some(depth) => write!(f, "depth: {}", depth),
+            None => write!(f, "depth: -"),
+        }
+    }
+}
+
+const DEFAULT_PLAYOUTS: u32 = 2000;
+
+/// Grades `grid` for the centre-peg goal with a fixed playout seed, so the
+/// same position always gets the same report.
+pub fn estimate_difficulty(grid: &Grid) -> DifficultyReport {
+    estimate_difficulty_with(grid, DEFAULT_PLAYOUTS, 0)
+}
+
+pub fn estimate_difficulty_with(grid: &Grid, playouts: u32, seed: u64) -> DifficultyReport {
+    let mut nodes = 0;
+    let solution = GameTree::new(*grid, Vec::new()).search_observed(&mut |_, _, _| nodes += 1);
+    let density = playout::estimate(grid, playouts, seed).success_rate();
+    let depth = solution.map(|s| s.history.len());
+    let rating = match depth {
+        None => Rating::Unsolvable,
+        Some(_) if density >= 0.05 || nodes < 200 => Rating::Easy,
+        Some(moves) if density < 0.001 && (nodes > 20_000 || moves > 15) => Rating::Hard,
+        Some(_) => Rating::Medium,
+    };
+    DifficultyReport {
+        rating,
+        solution_density: density,
+        nodes_to_solution: nodes,
+        depth,
+    }
+}
@@ -0,0 +1,84 @@
+//! Duotaire, peg solitaire for two: the players take turns making one jump
+//! each, and whoever makes the last jump wins. A position is won for the
+//! player to move when some jump leaves one lost for the other, so a
+//! negamax over win and loss settles it; a transposition table keyed by
+//! each position's canonical form under the layout's symmetries keeps
+//! every position, and its rotations and reflections, to one visit.
+
+use std::collections::HashMap;
+
+use crate::bitboard::JumpTable;
+use crate::stats::SearchStats;
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, Grid};
+
+/// A solver for one layout, which remembers the positions it has settled
+/// between questions.
+#[derive(Clone, Debug)]
+pub struct Duotaire {
+    table: JumpTable,
+    group: Vec<Symmetry>,
+    /// Whether the player to move wins, by canonical position.
+    memo: HashMap<u64, bool>,
+    stats: SearchStats,
+}
+
+impl Duotaire {
+    pub fn new(layout: &Grid) -> Duotaire {
+        Duotaire {
+            table: JumpTable::new(layout),
+            group: symmetry::layout_symmetries(layout),
+            memo: HashMap::new(),
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Whether the player to move on `board` wins with best play.
+    pub fn wins(&mut self, board: &Grid) -> bool {
+        self.negamax(board.encode())
+    }
+
+    /// The jumps from `board` that leave the opponent lost.
+    pub fn winning_moves(&mut self, board: &Grid) -> Vec<Action> {
+        let moves: Vec<(Action, u64)> = self.table.forward(board.encode()).collect();
+        moves
+            .into_iter()
+            .filter(|&(_, next)| !self.negamax(next))
+            .map(|(action, _)| action)
+            .collect()
+    }
+
+    /// A winning jump if there is one, otherwise the jump that leaves the
+    /// opponent the most replies to go wrong with; `None` when no jump is
+    /// left.
+    pub fn best_move(&mut self, board: &Grid) -> Option<Action> {
+        if let Some(&action) = self.winning_moves(board).first() {
+            return Some(action);
+        }
+        self.table
+            .forward(board.encode())
+            .max_by_key(|&(_, next)| self.table.forward(next).count())
+            .map(|(action, _)| action)
+    }
+
+    /// Positions expanded and settled so far.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    fn negamax(&mut self, pegs: u64) -> bool {
+        let canon = symmetry::canonical(pegs, &self.group);
+        if let Some(&won) = self.memo.get(&canon) {
+            self.stats.duplicates += 1;
+            return won;
+        }
+        self.stats.nodes_expanded += 1;
+        let moves: Vec<u64> = self.table.forward(pegs).map(|(_, next)| next).collect();
+        // No jump left loses; otherwise a reply the opponent loses wins.
+        let won = moves.into_iter().any(|next| !self.negamax(next));
+        self.memo.insert(canon, won);
+        self.stats
+            .observe_memo_size(self.memo.len(), self.memo.capacity() * 9);
+        won
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::bitboard::JumpTable;
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, Grid};
+
+const MAGIC: &[u8; 8] = b"PEGEND01";
+
+/// Every position with at most `max_pegs` pegs that can still be played
+/// down to `goal`. Positions within the peg limit that are missing from the
+/// database are dead.
+#[derive(Clone, Debug)]
+pub struct EndgameDb {
+    goal: Grid,
+    max_pegs: u32,
+    group: Vec<Symmetry>,
+    table: JumpTable,
+    solvable: HashSet<u64>,
+}
+
+impl EndgameDb {
+    /// Enumerates the database by building `goal` back up with inverse
+    /// jumps, one peg count at a time.
+    pub fn build(goal: &Grid, max_pegs: u32) -> EndgameDb {
+        let group = symmetry::grid_symmetries(goal);
+        let table = JumpTable::new(goal);
+        let mut solvable = HashSet::new();
+        let mut layer = vec![goal.encode()];
+        if goal.filled_count <= max_pegs {
+            solvable.insert(symmetry::canonical(goal.encode(), &group));
+        }
+        for _ in goal.filled_count..max_pegs {
+            let mut next = Vec::new();
+            for bits in layer {
+                for (_, prev) in table.backward(bits) {
+                    if solvable.insert(symmetry::canonical(prev, &group)) {
+                        next.push(prev);
+                    }
+                }
+            }
+            layer = next;
+        }
+        EndgameDb {
+            goal: *goal,
+            max_pegs,
+            group,
+            table,
+            solvable,
+        }
+    }
+
+    pub fn goal(&self) -> &Grid {
+        &self.goal
+    }
+
+    pub fn max_pegs(&self) -> u32 {
+        self.max_pegs
+    }
+
+    /// Number of solvable positions stored, up to symmetry.
+    pub fn len(&self) -> usize {
+        self.solvable.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solvable.is_empty()
+    }
+
+    /// `Some(solvable)` for positions within the peg limit, `None` otherwise.
+    pub fn lookup(&self, grid: &Grid) -> Option<bool> {
+        if grid.filled_count > self.max_pegs {
+            return None;
+        }
+        let key = symmetry::canonical(grid.encode(), &self.group);
+        Some(self.solvable.contains(&key))
+    }
+
+    /// The remaining moves from `grid` to the goal, if `grid` is covered by
+    /// the database and solvable.
+    pub fn finish(&self, grid: &Grid) -> Option<Vec<Action>> {
+        if self.lookup(grid) != Some(true) {
+            return None;
+        }
+        let goal = self.goal.encode();
+        let mut cur = grid.encode();
+        let mut moves = Vec::new();
+        while cur != goal {
+            let (action, next) = self
+                .table
+                .forward(cur)
+                .find(|&(_, n)| self.solvable.contains(&symmetry::canonical(n, &self.group)))?;
+            moves.push(action);
+            cur = next;
+        }
+        Some(moves)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&self.goal.with_pegs(u64::MAX).encode().to_le_bytes())?;
+        out.write_all(&self.goal.encode().to_le_bytes())?;
+        out.write_all(&self.max_pegs.to_le_bytes())?;
+        out.write_all(&(self.solvable.len() as u64).to_le_bytes())?;
+        let mut keys: Vec<u64> = self.solvable.iter().copied().collect();
+        keys.sort_unstable();
+        for key in keys {
+            out.write_all(&key.to_le_bytes())?;
+        }
+        out.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<EndgameDb> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        let mut input = BufReader::new(file);
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an endgame database file",
+            ));
+        }
+        let layout = read_u64(&mut input)?;
+        let pegs = read_u64(&mut input)?;
+        let mut word = [0; 4];
+        input.read_exact(&mut word)?;
+        let max_pegs = u32::from_le_bytes(word);
+        let len = read_u64(&mut input)?;
+        // A corrupt count must not reserve more keys than the rest of the
+        // file, after the 36-byte header, could hold.
+        let remaining = size.saturating_sub(36);
+        let mut solvable = HashSet::with_capacity(len.min(remaining / 8) as usize);
+        for _ in 0..len {
+            solvable.insert(read_u64(&mut input)?);
+        }
+        let goal = Grid::from_masks(layout, pegs);
+        Ok(EndgameDb {
+            goal,
+            max_pegs,
+            group: symmetry::grid_symmetries(&goal),
+            table: JumpTable::new(&goal),
+            solvable,
+        })
+    }
+}
+
+pub(crate) fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut word = [0; 8];
+    input.read_exact(&mut word)?;
+    Ok(u64::from_le_bytes(word))
+}
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+use crate::bitboard::JumpTable;
+use crate::symmetry::{self, Symmetry};
+use crate::trace::PROGRESS_INTERVAL;
+use crate::{Action, Grid};
+
+/// Number of distinct positions reachable from a start position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateSpace {
+    pub start_pegs: u32,
+    /// Whether positions related by a symmetry of the board count once.
+    pub up_to_symmetry: bool,
+    /// `by_depth[d]` positions are reachable in exactly `d` moves.
+    pub by_depth: Vec<u64>,
+    /// `dead_ends[d]` of those have no move left but more than one peg.
+    pub dead_ends: Vec<u64>,
+    /// `branching[k]` positions, at any depth, have exactly `k` moves.
+    pub branching: Vec<u64>,
+}
+
+impl StateSpace {
+    pub fn total(&self) -> u64 {
+        self.by_depth.iter().sum()
+    }
+
+    /// Position counts keyed by the number of pegs left, most pegs first.
+    /// Every move removes one peg, so this is `by_depth` relabelled.
+    pub fn by_pegs(&self) -> Vec<(u32, u64)> {
+        self.by_depth
+            .iter()
+            .enumerate()
+            .map(|(depth, &n)| (self.start_pegs - depth as u32, n))
+            .collect()
+    }
+
+    /// The mean number of moves from positions that have any.
+    pub fn mean_branching(&self) -> f64 {
+        let (moves, positions) = self
+            .branching
+            .iter()
+            .enumerate()
+            .skip(1)
+            .fold((0, 0), |(m, p), (k, &n)| (m + k as u64 * n, p + n));
+        if positions == 0 {
+            0.0
+        } else {
+            moves as f64 / positions as f64
+        }
+    }
+}
+
+impl fmt::Display for StateSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "depth  pegs  positions  dead ends")?;
+        for (depth, (pegs, n)) in self.by_pegs().into_iter().enumerate() {
+            let dead = self.dead_ends[depth];
+            writeln!(f, "{:>5}  {:>4}  {:>9}  {:>9}", depth, pegs, n, dead)?;
+        }
+        let kind = if self.up_to_symmetry {
+            "up to symmetry"
+        } else {
+            "counting symmetric positions separately"
+        };
+        writeln!(f, "total {} ({})", self.total(), kind)?;
+        writeln!(f, "\nmoves  positions")?;
+        for (k, n) in self.branching.iter().enumerate() {
+            writeln!(f, "{:>5}  {:>9}", k, n)?;
+        }
+        write!(f, "mean branching factor {:.2}", self.mean_branching())
+    }
+}
+
+/// Breadth-first enumeration of everything reachable from `start`. Only two
+/// layers are held in memory at a time.
+pub fn enumerate(start: &Grid, up_to_symmetry: bool) -> StateSpace {
+    let table = JumpTable::new(start);
+    let group: Vec<Symmetry> = if up_to_symmetry {
+        symmetry::layout_symmetries(start)
+    } else {
+        vec![Symmetry::Identity]
+    };
+    let mut layer = HashSet::from([symmetry::canonical(start.encode(), &group)]);
+    let mut by_depth = Vec::new();
+    let mut dead_ends = Vec::new();
+    let mut branching = Vec::new();
+    while !layer.is_empty() {
+        by_depth.push(layer.len() as u64);
+        let mut dead = 0;
+        let mut next = HashSet::new();
+        for &bits in &layer {
+            let mut moves = 0;
+            for (_, n) in table.forward(bits) {
+                next.insert(symmetry::canonical(n, &group));
+                moves += 1;
+            }
+            if moves == 0 && bits.count_ones() > 1 {
+                dead += 1;
+            }
+            if branching.len() <= moves {
+                branching.resize(moves + 1, 0);
+            }
+            branching[moves] += 1;
+        }
+        dead_ends.push(dead);
+        layer = next;
+    }
+    StateSpace {
+        start_pegs: start.filled_count,
+        up_to_symmetry,
+        by_depth,
+        dead_ends,
+        branching,
+    }
+}
+
+/// Distinct lines of moves from `start` to `goal`, stopping once `max` have
+/// been found. Positions that cannot reach the goal are remembered up to
+/// the symmetries the goal shares with the layout, so each dead end is
+/// explored only once.
+pub fn solutions(start: &Grid, goal: &Grid, max: Option<usize>) -> Vec<Vec<Action>> {
+    let mut found = Vec::new();
+    walk(start, goal, max, &mut |event| {
+        if let SearchEvent::Solution(moves) = event {
+            found.push(moves);
+        }
+        true
+    });
+    found
+}
+
+/// What [`stream`] reports while it runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+    Solution(Vec<Action>),
+    /// Sent every [`PROGRESS_INTERVAL`] expanded positions.
+    Progress {
+        nodes_expanded: u64,
+        solutions: usize,
+    },
+    /// The last event, sent once the search is over, unless the receiver
+    /// hung up first.
+    Finished {
+        nodes_expanded: u64,
+        solutions: usize,
+    },
+}
+
+/// [`solutions`] that sends each line to `sender` as soon as it is found,
+/// with progress in between. Stops early when the receiver is dropped, and
+/// returns how many solutions were found.
+pub fn stream(
+    start: &Grid,
+    goal: &Grid,
+    max: Option<usize>,
+    sender: &Sender<SearchEvent>,
+) -> usize {
+    let (nodes_expanded, solutions) =
+        walk(start, goal, max, &mut |event| sender.send(event).is_ok());
+    let _ = sender.send(SearchEvent::Finished {
+        nodes_expanded,
+        solutions,
+    });
+    solutions
+}
+
+/// Runs the search, passing every event to `emit` until it returns false.
+/// Returns the positions expanded and solutions found.
+fn walk(
+    start: &Grid,
+    goal: &Grid,
+    max: Option<usize>,
+    emit: &mut dyn FnMut(SearchEvent) -> bool,
+) -> (u64, usize) {
+    let max = max.unwrap_or(usize::MAX);
+    if max == 0 || start.filled_count < goal.filled_count {
+        return (0, 0);
+    }
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let mut walk = Walk {
+        table: JumpTable::new(start),
+        goal: goal.encode(),
+        goal_pegs: goal.filled_count,
+        group,
+        dead: HashSet::new(),
+        line: Vec::new(),
+        max,
+        found: 0,
+        nodes: 0,
+        emit,
+        stopped: false,
+    };
+    walk.visit(start.encode());
+    (walk.nodes, walk.found)
+}
+
+struct Walk<'a> {
+    table: JumpTable,
+    goal: u64,
+    goal_pegs: u32,
+    group: Vec<Symmetry>,
+    dead: HashSet<u64>,
+    line: Vec<Action>,
+    max: usize,
+    found: usize,
+    nodes: u64,
+    emit: &'a mut dyn FnMut(SearchEvent) -> bool,
+    /// Set once `emit` asks to stop.
+    stopped: bool,
+}
+
+impl Walk<'_> {
+    fn send(&mut self, event: SearchEvent) {
+        if !(self.emit)(event) {
+            self.stopped = true;
+        }
+    }
+
+    /// Reports every line through `bits`, returning whether there was at
+    /// least one.
+    fn visit(&mut self, bits: u64) -> bool {
+        if bits == self.goal {
+            self.found += 1;
+            self.send(SearchEvent::Solution(self.line.clone()));
+            return true;
+        }
+        let canon = symmetry::canonical(bits, &self.group);
+        if bits.count_ones() <= self.goal_pegs || self.dead.contains(&canon) {
+            return false;
+        }
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(PROGRESS_INTERVAL) {
+            self.send(SearchEvent::Progress {
+                nodes_expanded: self.nodes,
+                solutions: self.found,
+            });
+        }
+        let mut any = false;
+        let moves: Vec<(Action, u64)> = self.table.forward(bits).collect();
+        for (action, next) in moves {
+            if self.found >= self.max || self.stopped {
+                // Cut short, so this position is not known to be dead.
+                return true;
+            }
+            self.line.push(action);
+            any |= self.visit(next);
+            self.line.pop();
+        }
+        if !any {
+            self.dead.insert(canon);
+        }
+        any
+    }
+}
@@ -0,0 +1,264 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::bitboard::JumpTable;
+use crate::{Grid, Tile};
+
+/// Why a goal can never be reached from a position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Infeasible {
+    LayoutMismatch,
+    TooFewPegs {
+        pegs: u32,
+        goal: u32,
+    },
+    Stuck,
+    /// These pegs can never jump or be jumped over, but the goal has no peg
+    /// on their cells.
+    Stranded {
+        pegs: Vec<(usize, usize)>,
+    },
+    /// Jumps never change the parity signature of the pegs counted along
+    /// the two diagonal directions modulo 3.
+    PositionClass {
+        position: [u8; 4],
+        goal: [u8; 4],
+    },
+    /// A pagoda function never increases under jumps.
+    Pagoda {
+        name: &'static str,
+        position: u32,
+        goal: u32,
+    },
+}
+
+impl fmt::Display for Infeasible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Infeasible::LayoutMismatch => {
+                write!(f, "the position and the goal use different board layouts")
+            }
+            Infeasible::TooFewPegs { pegs, goal } => write!(
+                f,
+                "the position has {} pegs and the goal {}; jumps only remove pegs",
+                pegs, goal
+            ),
+            Infeasible::Stuck => write!(f, "no jump is possible and the goal is not met"),
+            Infeasible::Stranded { pegs } => {
+                let cells: Vec<String> = pegs
+                    .iter()
+                    .map(|(x, y)| format!("({}, {})", x, y))
+                    .collect();
+                write!(
+                    f,
+                    "the pegs at {} can never move or be captured again",
+                    cells.join(", ")
+                )
+            }
+            Infeasible::PositionClass { position, goal } => write!(
+                f,
+                "position class {:?} differs from the goal's {:?}; jumps preserve it",
+                position, goal
+            ),
+            Infeasible::Pagoda {
+                name,
+                position,
+                goal,
+            } => write!(
+                f,
+                "{} pagoda value {} is below the goal's {}; it can never increase",
+                name, position, goal
+            ),
+        }
+    }
+}
+
+/// Parity of the pegs on cells of two of the three residues of `x + y` and
+/// of `x - y` modulo 3. Every jump covers one cell of each residue, so
+/// every jump flips all three counts and leaves these pair parities alone.
+pub fn position_class(grid: &Grid) -> [u8; 4] {
+    let mut sum = [0u32; 3];
+    let mut diff = [0u32; 3];
+    for x in 0..7 {
+        for y in 0..7 {
+            if grid.grid[x][y] == Tile::Filled {
+                sum[(x + y) % 3] += 1;
+                diff[(x + 6 - y) % 3] += 1;
+            }
+        }
+    }
+    [
+        ((sum[0] + sum[1]) % 2) as u8,
+        ((sum[1] + sum[2]) % 2) as u8,
+        ((diff[0] + diff[1]) % 2) as u8,
+        ((diff[1] + diff[2]) % 2) as u8,
+    ]
+}
+
+fn bit((x, y): (usize, usize)) -> u64 {
+    1 << (x * 7 + y)
+}
+
+/// Pegs that can never take part in a jump again, whatever is played. The
+/// cells that may ever hold a peg are over-approximated by closing the
+/// current pegs under jumps while ignoring whether landing cells are free;
+/// a peg with no jump partner among those cells is stuck for good.
+pub fn stranded_pegs(grid: &Grid) -> Vec<(usize, usize)> {
+    let table = JumpTable::new(grid);
+    let spans: Vec<(u64, u64, u64)> = table
+        .jumps()
+        .map(|a| {
+            let (over, to) = a.span().unwrap();
+            (bit(a.origin()), bit(over), bit(to))
+        })
+        .collect();
+    let pegs = grid.encode();
+    let mut may_hold = pegs;
+    loop {
+        let before = may_hold;
+        for &(from, over, to) in &spans {
+            if may_hold & from != 0 && may_hold & over != 0 {
+                may_hold |= to;
+            }
+        }
+        if may_hold == before {
+            break;
+        }
+    }
+    let mut stranded = Vec::new();
+    for x in 0..7 {
+        for y in 0..7 {
+            let peg = bit((x, y));
+            if pegs & peg == 0 {
+                continue;
+            }
+            let movable = spans.iter().any(|&(from, over, _)| {
+                (from == peg && may_hold & over != 0) || (over == peg && may_hold & from != 0)
+            });
+            if !movable {
+                stranded.push((x, y));
+            }
+        }
+    }
+    stranded
+}
+
+const FIBONACCI: [u32; 7] = [5, 3, 2, 1, 1, 0, 1];
+const EDGES: [u32; 7] = [2, 1, 1, 0, 1, 1, 2];
+const EVEN: [u32; 7] = [1, 0, 1, 0, 1, 0, 1];
+const ODD: [u32; 7] = [0, 1, 0, 1, 0, 1, 0];
+
+/// Weights that depend on a single coordinate through `weights`.
+fn line_pagoda(weights: [u32; 7], along_x: bool, reversed: bool) -> [[u32; 7]; 7] {
+    let mut p = [[0; 7]; 7];
+    for (x, column) in p.iter_mut().enumerate() {
+        for (y, value) in column.iter_mut().enumerate() {
+            let i = if along_x { x } else { y };
+            *value = weights[if reversed { 6 - i } else { i }];
+        }
+    }
+    p
+}
+
+/// The built-in pagoda functions that are valid on `layout`: no jump on it
+/// raises their weighted peg count.
+pub fn pagodas(layout: &Grid) -> Vec<(&'static str, [[u32; 7]; 7])> {
+    let candidates = [
+        ("left", line_pagoda(FIBONACCI, true, false)),
+        ("right", line_pagoda(FIBONACCI, true, true)),
+        ("top", line_pagoda(FIBONACCI, false, false)),
+        ("bottom", line_pagoda(FIBONACCI, false, true)),
+        ("side columns", line_pagoda(EDGES, true, false)),
+        ("side rows", line_pagoda(EDGES, false, false)),
+        ("even columns", line_pagoda(EVEN, true, false)),
+        ("odd columns", line_pagoda(ODD, true, false)),
+        ("even rows", line_pagoda(EVEN, false, false)),
+        ("odd rows", line_pagoda(ODD, false, false)),
+    ];
+    let table = JumpTable::new(layout);
+    candidates
+        .into_iter()
+        .filter(|(_, p)| {
+            table.jumps().all(|a| {
+                let ((mx, my), (tx, ty)) = a.span().unwrap();
+                p[a.x][a.y] + p[mx][my] >= p[tx][ty]
+            })
+        })
+        .collect()
+}
+
+fn pagoda_value(grid: &Grid, p: &[[u32; 7]; 7]) -> u32 {
+    let mut total = 0;
+    for (column, weights) in grid.grid.iter().zip(p) {
+        for (tile, weight) in column.iter().zip(weights) {
+            if *tile == Tile::Filled {
+                total += weight;
+            }
+        }
+    }
+    total
+}
+
+/// Cheap necessary conditions for reaching `goal` from `position`. An error
+/// proves the goal unreachable; `Ok` proves nothing.
+pub fn check(position: &Grid, goal: &Grid) -> Result<(), Infeasible> {
+    if position.with_pegs(0) != goal.with_pegs(0) {
+        return Err(Infeasible::LayoutMismatch);
+    }
+    if position == goal {
+        return Ok(());
+    }
+    if position.filled_count <= goal.filled_count {
+        return Err(Infeasible::TooFewPegs {
+            pegs: position.filled_count,
+            goal: goal.filled_count,
+        });
+    }
+    if position.valid_actions().is_empty() {
+        return Err(Infeasible::Stuck);
+    }
+    let stranded: Vec<(usize, usize)> = stranded_pegs(position)
+        .into_iter()
+        .filter(|&(x, y)| goal.grid[x][y] != Tile::Filled)
+        .collect();
+    if !stranded.is_empty() {
+        return Err(Infeasible::Stranded { pegs: stranded });
+    }
+    let (from, to) = (position_class(position), position_class(goal));
+    if from != to {
+        return Err(Infeasible::PositionClass {
+            position: from,
+            goal: to,
+        });
+    }
+    for (name, p) in pagodas(position) {
+        let (from, to) = (pagoda_value(position, &p), pagoda_value(goal, &p));
+        if from < to {
+            return Err(Infeasible::Pagoda {
+                name,
+                position: from,
+                goal: to,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The cells a one-peg finish on could pass [`check`] from `position`,
+/// which by the position class argument are usually only a handful. The
+/// last peg can end nowhere else.
+pub fn final_cells(position: &Grid) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for y in 0..7 {
+        for x in 0..7 {
+            if position.grid[x][y] != Tile::Blocked
+                && check(position, &position.single_peg(x, y)).is_ok()
+            {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
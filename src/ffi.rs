@@ -0,0 +1,258 @@
+//! The C interface declared in `include/puzzlething.h`, built into the
+//! cdylib with the `ffi` feature:
+//!
+//! ```text
+//! cargo rustc --release --lib --crate-type cdylib --features ffi
+//! cc game.c -Iinclude -Ltarget/release -lpuzzlething
+//! ```
+//!
+//! Boards are opaque heap objects owned by the caller. Every function
+//! accepts a null board and treats it as an error rather than crashing.
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::{Action, Direction, GameTree, Grid, SearchStrategy};
+
+pub const PT_SOLVED: i32 = 1;
+pub const PT_UNSOLVABLE: i32 = 0;
+pub const PT_TIMEOUT: i32 = -1;
+pub const PT_ERROR: i32 = -2;
+
+pub struct PtBoard(Grid);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PtMove {
+    pub x: u8,
+    pub y: u8,
+    /// 0 up, 1 down, 2 left, 3 right.
+    pub direction: u8,
+}
+
+impl From<Action> for PtMove {
+    fn from(action: Action) -> PtMove {
+        let direction = Direction::ALL
+            .iter()
+            .position(|&d| d == action.dir)
+            .unwrap();
+        PtMove {
+            x: action.x as u8,
+            y: action.y as u8,
+            direction: direction as u8,
+        }
+    }
+}
+
+impl PtMove {
+    fn action(self) -> Option<Action> {
+        let dir = *Direction::ALL.get(self.direction as usize)?;
+        (self.x < 7 && self.y < 7).then_some(Action {
+            x: self.x as usize,
+            y: self.y as usize,
+            dir,
+        })
+    }
+}
+
+fn boxed(grid: Grid) -> *mut PtBoard {
+    Box::into_raw(Box::new(PtBoard(grid)))
+}
+
+/// # Safety
+///
+/// `board` must be null or come from this library and not be freed.
+unsafe fn grid<'a>(board: *const PtBoard) -> Option<&'a Grid> {
+    board.as_ref().map(|b| &b.0)
+}
+
+/// Copies `items` to `out`, up to `capacity` of them, returning how many
+/// there are in all.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing `capacity` moves.
+unsafe fn write_moves(items: &[Action], out: *mut PtMove, capacity: usize) -> usize {
+    if !out.is_null() {
+        for (i, &action) in items.iter().take(capacity).enumerate() {
+            *out.add(i) = action.into();
+        }
+    }
+    items.len()
+}
+
+/// The English board with every peg but the centre one.
+#[no_mangle]
+pub extern "C" fn pt_board_new() -> *mut PtBoard {
+    boxed(Grid::new())
+}
+
+/// Parses a board in notation, or returns null.
+///
+/// # Safety
+///
+/// `notation` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_from_notation(notation: *const c_char) -> *mut PtBoard {
+    if notation.is_null() {
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(notation).to_str().map(str::parse::<Grid>) {
+        Ok(Ok(grid)) => boxed(grid),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `board` must be null or a live board from this library.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_clone(board: *const PtBoard) -> *mut PtBoard {
+    grid(board).map_or(ptr::null_mut(), |&g| boxed(g))
+}
+
+/// # Safety
+///
+/// `board` must be null or a live board from this library; it is invalid
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_free(board: *mut PtBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+/// # Safety
+///
+/// `board` must be null or a live board from this library.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_pegs(board: *const PtBoard) -> u32 {
+    grid(board).map_or(0, Grid::filled_count)
+}
+
+/// Writes the board's notation to `out` as a NUL-terminated string cut to
+/// `capacity` bytes, like `snprintf`, and returns its full length.
+///
+/// # Safety
+///
+/// `board` must be null or a live board, and `out` null or valid for
+/// writing `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_notation(
+    board: *const PtBoard,
+    out: *mut c_char,
+    capacity: usize,
+) -> usize {
+    let Some(grid) = grid(board) else {
+        return 0;
+    };
+    let text = grid.notation();
+    if !out.is_null() && capacity > 0 {
+        let len = text.len().min(capacity - 1);
+        ptr::copy_nonoverlapping(text.as_ptr().cast(), out, len);
+        *out.add(len) = 0;
+    }
+    text.len()
+}
+
+/// Writes up to `capacity` legal moves to `out` and returns how many there
+/// are; pass a null `out` to only count them.
+///
+/// # Safety
+///
+/// `board` must be null or a live board, and `out` null or valid for
+/// writing `capacity` moves.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_legal_moves(
+    board: *const PtBoard,
+    out: *mut PtMove,
+    capacity: usize,
+) -> usize {
+    match grid(board) {
+        Some(grid) => write_moves(&grid.valid_actions(), out, capacity),
+        None => 0,
+    }
+}
+
+/// Plays `mv` on the board in place. Returns 0, or -1 when the move is not
+/// legal and the board is unchanged.
+///
+/// # Safety
+///
+/// `board` must be null or a live board from this library.
+#[no_mangle]
+pub unsafe extern "C" fn pt_board_apply(board: *mut PtBoard, mv: PtMove) -> i32 {
+    let Some(board) = board.as_mut() else {
+        return -1;
+    };
+    match mv.action() {
+        Some(action) if board.0.check_action(action).is_ok() => {
+            board.0 = board.0.perform_action(action);
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Searches for a line from `start` to `goal`, or to the centre peg when
+/// `goal` is null. `strategy` is 0 for depth-first, 1 bidirectional, 2
+/// backward and 3 proof-number search; `timeout_ms` of 0 waits for ever.
+/// Writes up to `capacity` moves to `out` and the full length to `len`.
+/// Returns [`PT_SOLVED`], [`PT_UNSOLVABLE`], [`PT_TIMEOUT`] or
+/// [`PT_ERROR`].
+///
+/// # Safety
+///
+/// `start` and `goal` must be null or live boards, `out` null or valid for
+/// writing `capacity` moves and `len` null or valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn pt_solve(
+    start: *const PtBoard,
+    goal: *const PtBoard,
+    strategy: u32,
+    timeout_ms: u32,
+    out: *mut PtMove,
+    capacity: usize,
+    len: *mut usize,
+) -> i32 {
+    let (Some(&start), Some(&strategy)) = (grid(start), SearchStrategy::ALL.get(strategy as usize))
+    else {
+        return PT_ERROR;
+    };
+    let goal = grid(goal).copied().unwrap_or(start.single_peg(3, 3));
+    let stop = AtomicBool::new(false);
+    let (found, timed_out) = thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel::<()>();
+        let watchdog = (timeout_ms > 0).then(|| {
+            let stop = &stop;
+            scope.spawn(move || {
+                let timeout = Duration::from_millis(timeout_ms as u64);
+                let expired = rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout);
+                if expired {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                expired
+            })
+        });
+        let found = GameTree::new(start, Vec::new())
+            .search_cancellable(&goal, strategy, &stop)
+            .0;
+        drop(tx);
+        let timed_out = watchdog.is_some_and(|w| w.join().unwrap_or(false));
+        (found, timed_out)
+    });
+    let moves = found.as_ref().map_or(&[][..], GameTree::history);
+    let total = write_moves(moves, out, capacity);
+    if !len.is_null() {
+        *len = total;
+    }
+    match found {
+        Some(_) => PT_SOLVED,
+        None if timed_out => PT_TIMEOUT,
+        None => PT_UNSOLVABLE,
+    }
+}
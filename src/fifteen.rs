@@ -0,0 +1,255 @@
+//! The sliding fifteen puzzle and its smaller and larger square cousins:
+//! tiles numbered from 1 slide into the one blank until they read in order
+//! with the blank last.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+use crate::rng::Rng;
+
+/// The way a tile slides into the blank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Slide {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Slide {
+    pub const ALL: [Slide; 4] = [Slide::Up, Slide::Down, Slide::Left, Slide::Right];
+
+    fn opposite(self) -> Slide {
+        match self {
+            Slide::Up => Slide::Down,
+            Slide::Down => Slide::Up,
+            Slide::Left => Slide::Right,
+            Slide::Right => Slide::Left,
+        }
+    }
+}
+
+impl fmt::Display for Slide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Slide::Up => "up",
+            Slide::Down => "down",
+            Slide::Left => "left",
+            Slide::Right => "right",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A square of tiles read row by row, 0 standing for the blank.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Board {
+    size: usize,
+    tiles: Vec<u8>,
+}
+
+impl Board {
+    /// The solved board of `size` by `size` tiles.
+    pub fn solved(size: usize) -> Board {
+        let mut tiles: Vec<u8> = (1..(size * size) as u8).collect();
+        tiles.push(0);
+        Board { size, tiles }
+    }
+
+    /// The solved board after `moves` random slides, never straight back.
+    pub fn scrambled(size: usize, moves: usize, seed: u64) -> Board {
+        let mut rng = Rng::new(seed);
+        let mut board = Board::solved(size);
+        let mut last: Option<Slide> = None;
+        for _ in 0..moves {
+            let options: Vec<Slide> = Slide::ALL
+                .into_iter()
+                .filter(|&s| Some(s.opposite()) != last && board.slide(s).is_some())
+                .collect();
+            let slide = options[rng.below(options.len())];
+            board = board.slide(slide).unwrap();
+            last = Some(slide);
+        }
+        board
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> u8 {
+        self.tiles[y * self.size + x]
+    }
+
+    fn blank(&self) -> usize {
+        self.tiles.iter().position(|&t| t == 0).unwrap()
+    }
+
+    /// The board after sliding a tile next to the blank into it, if there
+    /// is one on that side.
+    pub fn slide(&self, slide: Slide) -> Option<Board> {
+        let n = self.size;
+        let blank = self.blank();
+        let (x, y) = (blank % n, blank / n);
+        // The tile that moves sits on the far side of the blank.
+        let from = match slide {
+            Slide::Up if y + 1 < n => blank + n,
+            Slide::Down if y > 0 => blank - n,
+            Slide::Left if x + 1 < n => blank + 1,
+            Slide::Right if x > 0 => blank - 1,
+            _ => return None,
+        };
+        let mut next = self.clone();
+        next.tiles.swap(blank, from);
+        Some(next)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Board::solved(self.size)
+    }
+
+    /// Whether the tiles can be put in order at all. Half of all
+    /// arrangements cannot, by the parity of their inversions and of the
+    /// blank's row.
+    pub fn is_solvable(&self) -> bool {
+        let tiles: Vec<u8> = self.tiles.iter().copied().filter(|&t| t != 0).collect();
+        let mut inversions = 0;
+        for (i, a) in tiles.iter().enumerate() {
+            inversions += tiles[i + 1..].iter().filter(|&b| b < a).count();
+        }
+        if !self.size.is_multiple_of(2) {
+            inversions.is_multiple_of(2)
+        } else {
+            let row_from_bottom = self.size - self.blank() / self.size;
+            !(inversions + row_from_bottom).is_multiple_of(2)
+        }
+    }
+
+    /// The sum over the tiles of how far each is from its place, counting
+    /// rows and columns; no solution is shorter.
+    pub fn manhattan(&self) -> usize {
+        let n = self.size;
+        let mut distance = 0;
+        for (i, &tile) in self.tiles.iter().enumerate() {
+            if tile == 0 {
+                continue;
+            }
+            let home = tile as usize - 1;
+            distance += (i % n).abs_diff(home % n) + (i / n).abs_diff(home / n);
+        }
+        distance
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBoardError(pub String);
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseBoardError {}
+
+/// Rows separated by `/` or new lines, tiles by spaces or commas, with the
+/// blank written as `0`, `.`, `-` or `_`: `1 2 3/4 5 6/7 8 .`.
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Board, ParseBoardError> {
+        let rows: Vec<&str> = s
+            .split(['/', '\n'])
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .collect();
+        let size = rows.len();
+        if !(2..=15).contains(&size) {
+            return Err(ParseBoardError(format!(
+                "expected 2 to 15 rows, found {}",
+                size
+            )));
+        }
+        let mut tiles = Vec::with_capacity(size * size);
+        for (y, row) in rows.iter().enumerate() {
+            let cells: Vec<&str> = row
+                .split([' ', ',', '\t'])
+                .filter(|c| !c.is_empty())
+                .collect();
+            if cells.len() != size {
+                return Err(ParseBoardError(format!(
+                    "row {} has {} tiles, expected {}",
+                    y + 1,
+                    cells.len(),
+                    size
+                )));
+            }
+            for cell in cells {
+                let tile = match cell {
+                    "." | "-" | "_" => 0,
+                    _ => cell
+                        .parse()
+                        .map_err(|_| ParseBoardError(format!("unknown tile {:?}", cell)))?,
+                };
+                tiles.push(tile);
+            }
+        }
+        let mut sorted = tiles.clone();
+        sorted.sort_unstable();
+        if sorted.iter().enumerate().any(|(i, &t)| t as usize != i) {
+            return Err(ParseBoardError(format!(
+                "expected each of 0 to {} once",
+                size * size - 1
+            )));
+        }
+        Ok(Board { size, tiles })
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (self.size * self.size - 1).to_string().len();
+        for y in 0..self.size {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.size {
+                if x > 0 {
+                    write!(f, " ")?;
+                }
+                match self.tile(x, y) {
+                    0 => write!(f, "{:>width$}", ".", width = width)?,
+                    tile => write!(f, "{:>width$}", tile, width = width)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The sliding puzzle, solved when the tiles are in order. A* guided by
+/// [`Board::manhattan`] finds shortest solutions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fifteen;
+
+impl Puzzle for Fifteen {
+    type State = Board;
+    type Move = Slide;
+
+    fn successors(&self, state: &Board) -> Vec<(Slide, Board)> {
+        Slide::ALL
+            .into_iter()
+            .filter_map(|s| Some((s, state.slide(s)?)))
+            .collect()
+    }
+
+    fn is_goal(&self, state: &Board) -> bool {
+        state.is_solved()
+    }
+
+    fn estimate(&self, state: &Board) -> usize {
+        state.manhattan()
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use crate::bitboard::JumpTable;
+use crate::symmetry;
+use crate::{Action, GameTree, Grid};
+
+/// Fool's solitaire: strands as many pegs as possible with no legal move
+/// left and returns the moves that get there.
+///
+/// Every jump removes one peg, so the most pegs are stranded by the fewest
+/// moves; a breadth-first sweep stops at the first layer holding a stuck
+/// position and walks back through the earlier layers.
+pub fn search(start: &Grid) -> Option<Vec<Action>> {
+    let table = JumpTable::new(start);
+    let group = symmetry::layout_symmetries(start);
+    let canon = |bits| symmetry::canonical(bits, &group);
+
+    let start_bits = start.encode();
+    let mut layers = vec![HashSet::from([canon(start_bits)])];
+    let stuck = loop {
+        let layer = layers.last().unwrap();
+        if let Some(&bits) = layer.iter().find(|&&b| table.forward(b).next().is_none()) {
+            break bits;
+        }
+        let mut next = HashSet::new();
+        for &bits in layer {
+            next.extend(table.forward(bits).map(|(_, n)| canon(n)));
+        }
+        if next.is_empty() {
+            return None;
+        }
+        layers.push(next);
+    };
+
+    let known = |bits| layers.iter().any(|layer| layer.contains(&bits));
+    Some(table.line_between(start_bits, stuck, &group, known))
+}
+
+impl GameTree {
+    /// The shortest game that leaves no legal move, stranding the most pegs.
+    pub fn search_fools(&self) -> Option<GameTree> {
+        let moves = search(&self.state)?;
+        Some(self.extend(&moves))
+    }
+}
@@ -0,0 +1,166 @@
+//! Game state, move requests and events shaped for an entity component
+//! system such as Bevy, built with the `game` feature. Nothing here
+//! depends on an engine: [`GameState`] is meant to live in a resource,
+//! [`Cell`] on the entity drawn for each cell, and [`apply_requests`] to be
+//! called from a system that forwards events both ways:
+//!
+//! ```text
+//! fn play(
+//!     mut state: ResMut<Game>,
+//!     mut requests: EventReader<Request>,
+//!     mut events: EventWriter<Event>,
+//! ) {
+//!     let mut out = Vec::new();
+//!     apply_requests(&mut state.0, requests.read().map(|r| r.0), &mut out);
+//!     events.send_batch(out.into_iter().map(Event));
+//! }
+//! ```
+
+use crate::{Action, Grid, IllegalMove, Tile};
+
+/// A game in progress.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
+    pub start: Grid,
+    pub goal: Grid,
+    pub position: Grid,
+    pub moves: Vec<Action>,
+}
+
+impl GameState {
+    pub fn new(start: Grid, goal: Grid) -> GameState {
+        GameState {
+            start,
+            goal,
+            position: start,
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.position == self.goal
+    }
+
+    /// No legal move is left and the goal was not reached.
+    pub fn is_stuck(&self) -> bool {
+        !self.is_won() && self.position.valid_actions().is_empty()
+    }
+}
+
+/// The component for the entity of one playable cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Every playable cell of `grid` with what is on it, to spawn or update
+/// the cell entities from.
+pub fn cells(grid: &Grid) -> Vec<(Cell, Tile)> {
+    let mut out = Vec::new();
+    for y in 0..7 {
+        for x in 0..7 {
+            match grid.tile(x, y) {
+                Tile::Blocked => {}
+                tile => out.push((Cell { x, y }, tile)),
+            }
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveRequest {
+    Jump(Action),
+    Undo,
+    /// Back to the start position.
+    Restart,
+}
+
+/// What happened to the game, in the order it happened. A jump produces
+/// [`GameEvent::Jumped`] then [`GameEvent::Captured`], followed by
+/// [`GameEvent::Won`] or [`GameEvent::Stuck`] when it ends the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Jumped {
+        action: Action,
+        from: Cell,
+        to: Cell,
+    },
+    Captured(Cell),
+    /// A jump was taken back; the captured peg is on `restored` again.
+    Undone {
+        action: Action,
+        restored: Cell,
+    },
+    Rejected {
+        request: MoveRequest,
+        reason: Option<IllegalMove>,
+    },
+    Restarted,
+    Won,
+    Stuck,
+}
+
+fn cell((x, y): (usize, usize)) -> Cell {
+    Cell { x, y }
+}
+
+/// Applies `requests` in order, writing an event for each change to
+/// `events`. A request that cannot be carried out leaves the state as it
+/// is and writes [`GameEvent::Rejected`], with the reason for an illegal
+/// jump or `None` for an undo with nothing to undo.
+pub fn apply_requests(
+    state: &mut GameState,
+    requests: impl IntoIterator<Item = MoveRequest>,
+    events: &mut impl Extend<GameEvent>,
+) {
+    for request in requests {
+        match request {
+            MoveRequest::Jump(action) => {
+                if let Err(reason) = state.position.check_action(action) {
+                    events.extend([GameEvent::Rejected {
+                        request,
+                        reason: Some(reason),
+                    }]);
+                    continue;
+                }
+                state.position = state.position.perform_action(action);
+                state.moves.push(action);
+                // A legal jump always has both cells.
+                let over = cell(action.captured().unwrap());
+                let to = cell(action.landing().unwrap());
+                events.extend([
+                    GameEvent::Jumped {
+                        action,
+                        from: cell(action.origin()),
+                        to,
+                    },
+                    GameEvent::Captured(over),
+                ]);
+                if state.is_won() {
+                    events.extend([GameEvent::Won]);
+                } else if state.is_stuck() {
+                    events.extend([GameEvent::Stuck]);
+                }
+            }
+            MoveRequest::Undo => match state.moves.pop() {
+                Some(action) => {
+                    state.position = state.position.unperform_action(action);
+                    events.extend([GameEvent::Undone {
+                        action,
+                        restored: cell(action.captured().unwrap()),
+                    }]);
+                }
+                None => events.extend([GameEvent::Rejected {
+                    request,
+                    reason: None,
+                }]),
+            },
+            MoveRequest::Restart => {
+                *state = GameState::new(state.start, state.goal);
+                events.extend([GameEvent::Restarted]);
+            }
+        }
+    }
+}
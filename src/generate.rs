@@ -0,0 +1,53 @@
+use crate::rng::Rng;
+use crate::{Action, Grid};
+
+/// A generated position together with a line that solves it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedPuzzle {
+    pub position: Grid,
+    pub goal: Grid,
+    pub solution: Vec<Action>,
+    pub seed: u64,
+}
+
+const ATTEMPTS: u32 = 1000;
+
+/// A random position with `pegs` pegs on the English board that can be
+/// played down to the centre peg.
+pub fn random_solvable(pegs: u32, seed: u64) -> Option<GeneratedPuzzle> {
+    let layout = Grid::new();
+    random_solvable_from(&layout.single_peg(3, 3), pegs, seed)
+}
+
+/// Builds `goal` back up with random inverse jumps until it holds `pegs`
+/// pegs. Walks that get stuck early are retried with the same generator,
+/// so the result depends only on the arguments. Returns `None` if no walk
+/// reaches `pegs` within the attempt budget, which is typical for counts
+/// close to a full board.
+pub fn random_solvable_from(goal: &Grid, pegs: u32, seed: u64) -> Option<GeneratedPuzzle> {
+    let mut rng = Rng::new(seed);
+    'attempt: for _ in 0..ATTEMPTS {
+        let mut position = *goal;
+        let mut solution = Vec::new();
+        while position.filled_count < pegs {
+            let actions = position.inverse_actions();
+            if actions.is_empty() {
+                continue 'attempt;
+            }
+            let action = actions[rng.below(actions.len())];
+            position = position.unperform_action(action);
+            solution.push(action);
+        }
+        if position.filled_count != pegs {
+            return None;
+        }
+        solution.reverse();
+        return Some(GeneratedPuzzle {
+            position,
+            goal: *goal,
+            solution,
+            seed,
+        });
+    }
+    None
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::raster::{self, Image, Palette};
+use crate::{Action, Grid};
+
+/// Bits per palette index; the table holds `1 << DEPTH` colours.
+const DEPTH: u8 = 3;
+const MAX_CODE: u16 = 4095;
+
+/// Packs variable-width codes least significant bit first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// LZW-compresses palette indices the way GIF image data expects.
+fn lzw(pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << DEPTH;
+    let end = clear + 1;
+    let mut out = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut width = DEPTH + 1;
+    out.write(clear, width);
+    let mut current: Option<u16> = None;
+    for &pixel in pixels {
+        let Some(prefix) = current else {
+            current = Some(pixel as u16);
+            continue;
+        };
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            current = Some(code);
+            continue;
+        }
+        out.write(prefix, width);
+        if next >= MAX_CODE {
+            out.write(clear, width);
+            table.clear();
+            next = end + 1;
+            width = DEPTH + 1;
+        } else {
+            table.insert((prefix, pixel), next);
+            if next == 1 << width && width < 12 {
+                width += 1;
+            }
+            next += 1;
+        }
+        current = Some(pixel as u16);
+    }
+    if let Some(prefix) = current {
+        out.write(prefix, width);
+    }
+    out.write(end, width);
+    out.finish()
+}
+
+/// Encodes `frames`, each shown for the paired number of hundredths of a
+/// second, as a looping animation.
+pub fn encode(frames: &[(Image, u16)], palette: &Palette) -> Vec<u8> {
+    let (width, height) = frames.first().map_or((0, 0), |(image, _)| {
+        (image.width as u16, image.height as u16)
+    });
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    // A global colour table of 1 << DEPTH entries.
+    out.extend([0x80 | (DEPTH - 1) << 4 | (DEPTH - 1), 0, 0]);
+    let colors = palette.colors();
+    for i in 0..1 << DEPTH {
+        out.extend(colors.get(i).copied().unwrap_or([0, 0, 0]));
+    }
+    // Loop forever.
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+    for (image, delay) in frames {
+        out.extend([0x21, 0xf9, 0x04, 0x00]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend((image.width as u16).to_le_bytes());
+        out.extend((image.height as u16).to_le_bytes());
+        out.push(0);
+        out.push(DEPTH);
+        for block in lzw(&image.pixels).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    out
+}
+
+/// Animates `moves` from `start`: every frame shows the next jump as an
+/// arrow, and the final position is held three times as long.
+pub fn solution(
+    start: &Grid,
+    moves: &[Action],
+    cell: usize,
+    delay: u16,
+    palette: &Palette,
+) -> Vec<u8> {
+    let mut frames = Vec::with_capacity(moves.len() + 1);
+    let mut state = *start;
+    for &action in moves {
+        frames.push((raster::draw(&state, cell, Some(action)), delay));
+        state = state.perform_action(action);
+    }
+    frames.push((raster::draw(&state, cell, None), delay.saturating_mul(3)));
+    encode(&frames, palette)
+}
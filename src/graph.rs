@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::{Action, GameTree, Grid};
+
+/// The part of the state graph a depth-first search touched: every
+/// position it generated and every jump it tried.
+#[derive(Clone, Debug)]
+pub struct SearchGraph {
+    nodes: Vec<Grid>,
+    index: HashMap<Grid, usize>,
+    edges: Vec<(usize, Action, usize)>,
+    solution: Vec<Action>,
+    start: Grid,
+}
+
+impl SearchGraph {
+    fn new(start: Grid) -> SearchGraph {
+        let mut graph = SearchGraph {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+            solution: Vec::new(),
+            start,
+        };
+        graph.node(&start);
+        graph
+    }
+
+    fn node(&mut self, grid: &Grid) -> usize {
+        if let Some(&i) = self.index.get(grid) {
+            return i;
+        }
+        self.nodes.push(*grid);
+        self.index.insert(*grid, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Graphviz source with one node per position and one edge per jump.
+    /// Positions and jumps along the solution, if any, are drawn in red.
+    pub fn to_dot(&self) -> String {
+        let mut on_path = HashSet::from([0]);
+        let mut path_edges = HashSet::new();
+        let mut cur = self.start;
+        for &action in &self.solution {
+            let next = cur.perform_action(action);
+            let (from, to) = (self.index[&cur], self.index[&next]);
+            path_edges.insert((from, to));
+            on_path.insert(to);
+            cur = next;
+        }
+
+        let mut out = String::new();
+        out.push_str("digraph search {\n");
+        out.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+        for (i, grid) in self.nodes.iter().enumerate() {
+            let label = board_label(grid);
+            let style = if on_path.contains(&i) {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            writeln!(out, "  n{} [label=\"{}\"{}];", i, label, style).unwrap();
+        }
+        for &(from, action, to) in &self.edges {
+            let style = if path_edges.contains(&(from, to)) {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "  n{} -> n{} [label=\"{}\"{}];",
+                from, to, action, style
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn board_label(grid: &Grid) -> String {
+    grid.to_string()
+        .lines()
+        .map(|line| format!("{}\\l", line))
+        .collect()
+}
+
+/// Graphviz source for the line of `moves` from `start`: one node per
+/// position, top to bottom, joined by edges labelled with the jumps.
+pub fn solution_dot(start: &Grid, moves: &[Action]) -> String {
+    let mut out = String::new();
+    out.push_str("digraph solution {\n");
+    out.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+    let mut state = *start;
+    writeln!(out, "  n0 [label=\"{}\"];", board_label(&state)).unwrap();
+    for (i, &action) in moves.iter().enumerate() {
+        state = state.perform_action(action);
+        writeln!(out, "  n{} [label=\"{}\"];", i + 1, board_label(&state)).unwrap();
+        writeln!(
+            out,
+            "  n{} -> n{} [label=\"{}. {}\"];",
+            i,
+            i + 1,
+            i + 1,
+            action
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+impl GameTree {
+    /// Runs [`GameTree::search`] while recording the explored graph.
+    pub fn search_graph(&self) -> (Option<GameTree>, SearchGraph) {
+        let mut graph = SearchGraph::new(self.state);
+        let result = self.search_observed(&mut |from, action, to| {
+            let from = graph.node(from);
+            let to = graph.node(to);
+            graph.edges.push((from, action, to));
+        });
+        if let Some(found) = &result {
+            graph.solution = found.history[self.history.len()..].to_vec();
+        }
+        (result, graph)
+    }
+}
@@ -0,0 +1,191 @@
+//! The Towers of Hanoi with any number of disks and of towers: move the
+//! stack from the first tower to the last, one disk at a time and never a
+//! disk onto a smaller one. [`frame_stewart`] writes the solution down
+//! directly; [`Hanoi`] hands the puzzle to the generic searches, which on
+//! small stacks confirm that it is the shortest.
+
+use std::fmt;
+
+use crate::puzzle::Puzzle;
+
+/// The tower each disk is on, smallest disk first. Towers are numbered
+/// from 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Towers {
+    towers: usize,
+    disks: Vec<u8>,
+}
+
+/// The top disk of tower `from` moves to tower `to`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+}
+
+fn name(tower: usize) -> char {
+    (b'A' + tower as u8) as char
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", name(self.from), name(self.to))
+    }
+}
+
+impl Towers {
+    /// `disks` disks stacked on the first of `towers` towers, which must
+    /// number from 3 to 26.
+    pub fn new(disks: usize, towers: usize) -> Towers {
+        assert!((3..=26).contains(&towers), "from 3 to 26 towers");
+        Towers {
+            towers,
+            disks: vec![0; disks],
+        }
+    }
+
+    pub fn towers(&self) -> usize {
+        self.towers
+    }
+
+    /// The smallest disk on `tower`.
+    fn top(&self, tower: usize) -> Option<usize> {
+        self.disks.iter().position(|&t| t as usize == tower)
+    }
+
+    /// The towers after `step`, if its tower has a disk that may go there.
+    pub fn apply(&self, step: Move) -> Option<Towers> {
+        let disk = self.top(step.from)?;
+        if step.to >= self.towers || self.top(step.to).is_some_and(|top| top < disk) {
+            return None;
+        }
+        let mut next = self.clone();
+        next.disks[disk] = step.to as u8;
+        Some(next)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.disks.iter().all(|&t| t as usize == self.towers - 1)
+    }
+}
+
+/// One line per tower with its disks from the bottom up, the smallest disk
+/// being 1.
+impl fmt::Display for Towers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tower in 0..self.towers {
+            if tower > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} |", name(tower))?;
+            for disk in (0..self.disks.len()).rev() {
+                if self.disks[disk] as usize == tower {
+                    write!(f, " {}", disk + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The moves of the Frame–Stewart algorithm, which with three towers is
+/// the classic recursion of 2^n - 1 moves. With more, it moves the top `k`
+/// disks aside using every tower, the rest across without that one, and
+/// the `k` on top, picking `k` to make the total least; this is optimal
+/// for four towers and conjectured to be for more.
+pub fn frame_stewart(disks: usize, towers: usize) -> Vec<Move> {
+    assert!((3..=26).contains(&towers), "from 3 to 26 towers");
+    // cost[t][n]: moves for n disks with t + 3 towers; split: the best k.
+    let mut cost = vec![vec![0u128; disks + 1]; towers - 2];
+    let mut split = vec![vec![0usize; disks + 1]; towers - 2];
+    for n in 1..=disks {
+        cost[0][n] = cost[0][n - 1].saturating_mul(2).saturating_add(1);
+    }
+    for t in 1..towers - 2 {
+        for n in 1..=disks {
+            let (best, k) = (1..n)
+                .map(|k| {
+                    let total = cost[t][k]
+                        .saturating_mul(2)
+                        .saturating_add(cost[t - 1][n - k]);
+                    (total, k)
+                })
+                .min()
+                .unwrap_or((1, 0));
+            cost[t][n] = best;
+            split[t][n] = k;
+        }
+    }
+    let spares: Vec<usize> = (1..towers - 1).collect();
+    let mut out = Vec::new();
+    shift(disks, 0, towers - 1, &spares, &split, &mut out);
+    out
+}
+
+/// Moves the top `n` disks from `from` to `to` using the towers in
+/// `spares`.
+fn shift(
+    n: usize,
+    from: usize,
+    to: usize,
+    spares: &[usize],
+    split: &[Vec<usize>],
+    out: &mut Vec<Move>,
+) {
+    match n {
+        0 => {}
+        1 => out.push(Move { from, to }),
+        _ if spares.len() == 1 => {
+            let spare = spares[0];
+            shift(n - 1, from, spare, &[to], split, out);
+            out.push(Move { from, to });
+            shift(n - 1, spare, to, &[from], split, out);
+        }
+        _ => {
+            let k = split[spares.len() - 1][n];
+            let (aside, rest) = (spares[0], &spares[1..]);
+            let mut around: Vec<usize> = rest.to_vec();
+            around.push(to);
+            shift(k, from, aside, &around, split, out);
+            shift(n - k, from, to, rest, split, out);
+            around.pop();
+            around.push(from);
+            shift(k, aside, to, &around, split, out);
+        }
+    }
+}
+
+/// The Towers of Hanoi for the generic searches. A* counts each disk not
+/// yet on the last tower as at least one move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Hanoi;
+
+impl Puzzle for Hanoi {
+    type State = Towers;
+    type Move = Move;
+
+    fn successors(&self, state: &Towers) -> Vec<(Move, Towers)> {
+        let mut out = Vec::new();
+        for from in 0..state.towers {
+            for to in 0..state.towers {
+                let step = Move { from, to };
+                if from == to {
+                    continue;
+                }
+                if let Some(next) = state.apply(step) {
+                    out.push((step, next));
+                }
+            }
+        }
+        out
+    }
+
+    fn is_goal(&self, state: &Towers) -> bool {
+        state.is_solved()
+    }
+
+    fn estimate(&self, state: &Towers) -> usize {
+        let last = (state.towers - 1) as u8;
+        state.disks.iter().filter(|&&t| t != last).count()
+    }
+}
@@ -0,0 +1,165 @@
+//! How often each cell is jumped from, over and into across many solutions
+//! of a board, for spotting the cells a strategy guide should talk about.
+
+use std::fmt::Write;
+
+use crate::enumerate;
+use crate::notation;
+use crate::render::RenderOptions;
+use crate::{Action, GameTree, Grid, SearchStrategy, Tile};
+
+/// The part a cell plays in a jump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    From,
+    Over,
+    To,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::From, Role::Over, Role::To];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::From => "from",
+            Role::Over => "over",
+            Role::To => "to",
+        }
+    }
+}
+
+/// Jump counts per cell, summed over every line added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heatmap {
+    layout: Grid,
+    lines: usize,
+    counts: [[[u64; 7]; 7]; 3],
+}
+
+/// Background colours for levels 0 to 9, from black through red to
+/// yellow.
+const RAMP: [u8; 10] = [16, 52, 88, 124, 160, 196, 202, 208, 214, 220];
+
+impl Heatmap {
+    pub fn new(layout: &Grid) -> Heatmap {
+        Heatmap {
+            layout: *layout,
+            lines: 0,
+            counts: [[[0; 7]; 7]; 3],
+        }
+    }
+
+    pub fn add(&mut self, moves: &[Action]) {
+        self.lines += 1;
+        for action in moves {
+            let cells = [Some(action.origin()), action.captured(), action.landing()];
+            for (role, cell) in cells.into_iter().enumerate() {
+                if let Some((x, y)) = cell {
+                    self.counts[role][x][y] += 1;
+                }
+            }
+        }
+    }
+
+    /// How many lines were added.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn count(&self, role: Role, x: usize, y: usize) -> u64 {
+        self.counts[role as usize][x][y]
+    }
+
+    pub fn max(&self, role: Role) -> u64 {
+        self.counts[role as usize]
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The board with each playable cell's count for `role` scaled to a
+    /// digit, 9 for the busiest cell and 0 only for cells never used,
+    /// shaded from black to yellow when `options.color` is set.
+    pub fn render(&self, role: Role, options: &RenderOptions) -> String {
+        let max = self.max(role);
+        let mut out = String::new();
+        if options.coords {
+            out.push_str("  ");
+            for x in 0..7 {
+                let _ = write!(out, "{} ", x);
+            }
+            out.push('\n');
+        }
+        for y in 0..7 {
+            if options.coords {
+                let _ = write!(out, "{} ", y);
+            }
+            for x in 0..7 {
+                if self.layout.tile(x, y) == Tile::Blocked {
+                    out.push_str("  ");
+                    continue;
+                }
+                let count = self.count(role, x, y);
+                let level = match count {
+                    0 => 0,
+                    _ => (count * 9).div_ceil(max) as usize,
+                };
+                if options.color {
+                    let ink = if level > 6 { 30 } else { 97 };
+                    let _ = write!(out, "\x1b[{};48;5;{}m{} \x1b[0m", ink, RAMP[level], level);
+                } else {
+                    let _ = write!(out, "{} ", level);
+                }
+            }
+            out.push('\n');
+        }
+        let _ = writeln!(out, "9 = {} jumps {}", max, role.name());
+        out
+    }
+
+    /// One row per playable cell: `cell,x,y,from,over,to`.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("cell,x,y,from,over,to\n");
+        for y in 0..7 {
+            for x in 0..7 {
+                if self.layout.tile(x, y) == Tile::Blocked {
+                    continue;
+                }
+                let _ = write!(out, "{},{},{}", notation::cell_name(x, y), x, y);
+                for role in Role::ALL {
+                    let _ = write!(out, ",{}", self.count(role, x, y));
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// The heatmap of every distinct line from `start` to `goal`, or of the
+/// first `max` found.
+pub fn of_solutions(start: &Grid, goal: &Grid, max: Option<usize>) -> Heatmap {
+    let mut heatmap = Heatmap::new(start);
+    for line in enumerate::solutions(start, goal, max) {
+        heatmap.add(&line);
+    }
+    heatmap
+}
+
+/// The heatmap of `samples` lines found by backward searches whose move
+/// order is shuffled by `seed`, `seed + 1` and so on, which spread across
+/// the solutions where [`of_solutions`] finds many near copies of one
+/// line. Each takes seconds on the full English board, and different seeds
+/// can find the same line, which then counts more than once.
+pub fn sampled(start: &Grid, goal: &Grid, samples: usize, seed: u64) -> Heatmap {
+    let mut heatmap = Heatmap::new(start);
+    for i in 0..samples as u64 {
+        let tree = GameTree::new(*start, Vec::new()).with_seed(seed.wrapping_add(i));
+        if let Some(found) = tree.search_for(goal, SearchStrategy::Backward) {
+            heatmap.add(found.history());
+        }
+    }
+    heatmap
+}
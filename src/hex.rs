@@ -0,0 +1,394 @@
+//! Peg solitaire on a hexagonal lattice, where every hole has six
+//! neighbours and a peg can jump in any of six directions: east and west
+//! along its row and diagonally onto the rows above and below.
+//!
+//! A board is drawn with offset rows, a row per line or with rows separated
+//! by `/`. Cells sit two characters apart and each row is shifted half a
+//! cell from the next, `O` for a peg, `-` for a hole and spaces between:
+//!
+//! ```text
+//!     -
+//!    O O
+//!   O O O
+//!  O O O O
+//! O O O O O
+//! ```
+//!
+//! A cell is named by its row's letter, from `a` at the top, and how many
+//! whole cells it is from the left edge of the board, from 1: the hole
+//! above is `a3` and the peg below it on the left `b2`, so the jump from
+//! `c2` over `b2` into it is `c2-a3`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+
+/// The most cells a board can have, one bit of [`Pegs`] each.
+pub const MAX_CELLS: usize = 128;
+/// The most rows a board can have, one letter each.
+pub const MAX_ROWS: usize = 26;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::West,
+        HexDirection::NorthEast,
+        HexDirection::NorthWest,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HexDirection::East => "east",
+            HexDirection::West => "west",
+            HexDirection::NorthEast => "northeast",
+            HexDirection::NorthWest => "northwest",
+            HexDirection::SouthEast => "southeast",
+            HexDirection::SouthWest => "southwest",
+        }
+    }
+
+    /// One step, in half cells across and rows down.
+    fn offset(self) -> (isize, isize) {
+        match self {
+            HexDirection::East => (2, 0),
+            HexDirection::West => (-2, 0),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::NorthWest => (-1, -1),
+            HexDirection::SouthEast => (1, 1),
+            HexDirection::SouthWest => (-1, 1),
+        }
+    }
+}
+
+fn step((x, y): (usize, usize), direction: HexDirection) -> Option<(usize, usize)> {
+    let (dx, dy) = direction.offset();
+    Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+}
+
+/// `c1`, for the cell `x` half cells from the left edge on row `y`.
+fn cell_name((x, y): (usize, usize)) -> String {
+    format!("{}{}", (b'a' + y as u8) as char, x / 2 + 1)
+}
+
+/// The peg at `from`, in half cells across and rows down, jumps towards
+/// `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexJump {
+    pub from: (usize, usize),
+    pub direction: HexDirection,
+}
+
+impl HexJump {
+    /// The cell jumped over and the one landed on, unless they would be
+    /// off the top or the left.
+    pub fn span(&self) -> Option<((usize, usize), (usize, usize))> {
+        let over = step(self.from, self.direction)?;
+        Some((over, step(over, self.direction)?))
+    }
+}
+
+/// `c2-a3`.
+impl fmt::Display for HexJump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", cell_name(self.from))?;
+        match self.span() {
+            Some((_, to)) => write!(f, "-{}", cell_name(to)),
+            None => write!(f, "-?"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseHexError(pub String);
+
+impl fmt::Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseHexError {}
+
+/// Two cell names a jump apart, `c2-a3`. A name covers both half cells of
+/// its whole cell, and the jump is the one from either that lands in the
+/// second.
+impl FromStr for HexJump {
+    type Err = ParseHexError;
+
+    fn from_str(s: &str) -> Result<HexJump, ParseHexError> {
+        let err = || ParseHexError(format!("invalid hex move {:?}, expected e.g. `c2-a3`", s));
+        let (from, to) = s.trim().split_once('-').ok_or_else(err)?;
+        let parse = |name: &str| -> Option<(usize, usize)> {
+            let mut chars = name.trim().chars();
+            let row = chars.next()?.to_ascii_lowercase();
+            let place: usize = chars.as_str().parse().ok()?;
+            if !row.is_ascii_lowercase() || place == 0 {
+                return None;
+            }
+            Some(((place - 1) * 2, (row as u8 - b'a') as usize))
+        };
+        let ((fx, fy), (tx, ty)) = (parse(from).ok_or_else(err)?, parse(to).ok_or_else(err)?);
+        // A name covers the two half cells of its whole cell.
+        for from in [(fx, fy), (fx + 1, fy)] {
+            for direction in HexDirection::ALL {
+                let jump = HexJump { from, direction };
+                if let Some((_, (x, y))) = jump.span() {
+                    if y == ty && x / 2 == tx / 2 {
+                        return Ok(jump);
+                    }
+                }
+            }
+        }
+        Err(err())
+    }
+}
+
+/// The pegs of a position, one bit per cell of the board in reading
+/// order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pegs(pub u128);
+
+impl Pegs {
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Jump {
+    jump: HexJump,
+    from: u128,
+    over: u128,
+    to: u128,
+}
+
+/// A hexagonal board: its cells, every jump between them and the pegs it
+/// starts with, solved down to a goal position or else to a single peg
+/// anywhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexBoard {
+    /// Half cells across and rows down of each cell, in reading order.
+    cells: Vec<(usize, usize)>,
+    jumps: Vec<Jump>,
+    start: Pegs,
+    goal: Option<Pegs>,
+}
+
+impl HexBoard {
+    fn new(cells: Vec<(usize, usize)>, start: Pegs) -> HexBoard {
+        let index = |cell: (usize, usize)| cells.iter().position(|&c| c == cell);
+        let mut jumps = Vec::new();
+        for (i, &from) in cells.iter().enumerate() {
+            for direction in HexDirection::ALL {
+                let jump = HexJump { from, direction };
+                let Some((over, to)) = jump.span() else {
+                    continue;
+                };
+                if let (Some(over), Some(to)) = (index(over), index(to)) {
+                    jumps.push(Jump {
+                        jump,
+                        from: 1 << i,
+                        over: 1 << over,
+                        to: 1 << to,
+                    });
+                }
+            }
+        }
+        HexBoard {
+            cells,
+            jumps,
+            start,
+            goal: None,
+        }
+    }
+
+    /// The triangle with `side` cells on each side, full but for its top
+    /// corner: with five, the fifteen-hole board of the classic puzzle.
+    pub fn triangle(side: usize) -> HexBoard {
+        let cells: Vec<(usize, usize)> = (0..side)
+            .flat_map(|y| (0..=y).map(move |i| (side - 1 - y + 2 * i, y)))
+            .collect();
+        let start = Pegs(full(cells.len()) & !1);
+        HexBoard::new(cells, start)
+    }
+
+    /// The hexagon with `side` cells on each side, full but for its centre.
+    pub fn hexagon(side: usize) -> HexBoard {
+        let rows = 2 * side - 1;
+        let cells: Vec<(usize, usize)> = (0..rows)
+            .flat_map(|y| {
+                let indent = y.abs_diff(side - 1);
+                (0..rows - indent).map(move |i| (indent + 2 * i, y))
+            })
+            .collect();
+        let centre = cells.len() / 2;
+        let start = Pegs(full(cells.len()) & !(1 << centre));
+        HexBoard::new(cells, start)
+    }
+
+    /// The named shape with `side` cells to a side, `triangle` or
+    /// `hexagon`, unless it would have no cells or more than
+    /// [`MAX_CELLS`].
+    pub fn preset(name: &str, side: usize) -> Option<HexBoard> {
+        let board = match name {
+            "triangle" if side * (side + 1) / 2 <= MAX_CELLS => HexBoard::triangle(side),
+            "hexagon" if 3 * side * side.saturating_sub(1) < MAX_CELLS => HexBoard::hexagon(side),
+            _ => return None,
+        };
+        (side > 0).then_some(board)
+    }
+
+    pub fn cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn start(&self) -> &Pegs {
+        &self.start
+    }
+
+    /// Solved when the pegs are those of `goal`, a position on the same
+    /// cells, instead of when one peg is left.
+    pub fn with_goal(mut self, goal: &HexBoard) -> Result<HexBoard, ParseHexError> {
+        if goal.cells != self.cells {
+            return Err(ParseHexError(
+                "the goal is drawn on different cells from the board".to_string(),
+            ));
+        }
+        self.goal = Some(goal.start);
+        Ok(self)
+    }
+
+    /// The jumps that can be made from `pegs`, with where each leads.
+    pub fn moves(&self, pegs: &Pegs) -> Vec<(HexJump, Pegs)> {
+        let Pegs(bits) = *pegs;
+        self.jumps
+            .iter()
+            .filter(|j| bits & j.from != 0 && bits & j.over != 0 && bits & j.to == 0)
+            .map(|j| (j.jump, Pegs(bits & !j.from & !j.over | j.to)))
+            .collect()
+    }
+
+    /// `pegs`, drawn as the board is written.
+    pub fn render(&self, pegs: &Pegs) -> String {
+        let rows = self.cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        let width = self.cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let mut lines = vec![vec![' '; width]; rows];
+        for (i, &(x, y)) in self.cells.iter().enumerate() {
+            lines[y][x] = if pegs.0 & (1 << i) != 0 { 'O' } else { '-' };
+        }
+        let lines: Vec<String> = lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>().trim_end().to_string())
+            .collect();
+        lines.join("\n")
+    }
+}
+
+fn full(cells: usize) -> u128 {
+    match cells {
+        MAX_CELLS => u128::MAX,
+        _ => (1 << cells) - 1,
+    }
+}
+
+impl fmt::Display for HexBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&self.start))
+    }
+}
+
+/// The drawing described at the top of this module, in which `o`, `x`
+/// and `*` are also pegs and `.` and `_` holes. Leading blank rows and the
+/// margin shared by every row are ignored.
+impl FromStr for HexBoard {
+    type Err = ParseHexError;
+
+    fn from_str(s: &str) -> Result<HexBoard, ParseHexError> {
+        let rows: Vec<&str> = s.split(['/', '\n']).map(str::trim_end).collect();
+        let first = rows.iter().position(|r| !r.trim().is_empty()).unwrap_or(0);
+        let last = rows.iter().rposition(|r| !r.trim().is_empty()).unwrap_or(0);
+        let rows = &rows[first..=last];
+        if rows.len() > MAX_ROWS {
+            return Err(ParseHexError(format!(
+                "expected at most {} rows, found {}",
+                MAX_ROWS,
+                rows.len()
+            )));
+        }
+        let mut cells = Vec::new();
+        let mut pegs = 0u128;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let peg = match c {
+                    ' ' | '\t' | '\r' => continue,
+                    'O' | 'o' | 'x' | '*' => true,
+                    '-' | '.' | '_' => false,
+                    _ => return Err(ParseHexError(format!("unknown cell {:?}", c))),
+                };
+                if cells.len() == MAX_CELLS {
+                    return Err(ParseHexError(format!(
+                        "expected at most {} cells",
+                        MAX_CELLS
+                    )));
+                }
+                if peg {
+                    pegs |= 1 << cells.len();
+                }
+                cells.push((x, y));
+            }
+        }
+        let Some(&(x0, y0)) = cells.first() else {
+            return Err(ParseHexError("the board has no cells".to_string()));
+        };
+        if let Some(&(x, y)) = cells.iter().find(|&&(x, y)| (x + y) % 2 != (x0 + y0) % 2) {
+            return Err(ParseHexError(format!(
+                "the cell on row {} column {} is off the lattice: each row's cells \
+                 sit halfway between those of the rows next to it",
+                y + 1,
+                x + 1
+            )));
+        }
+        let margin = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        for cell in &mut cells {
+            cell.0 -= margin;
+        }
+        Ok(HexBoard::new(cells, Pegs(pegs)))
+    }
+}
+
+impl Puzzle for HexBoard {
+    type State = Pegs;
+    type Move = HexJump;
+
+    fn successors(&self, state: &Pegs) -> Vec<(HexJump, Pegs)> {
+        self.moves(state)
+    }
+
+    fn is_goal(&self, state: &Pegs) -> bool {
+        match self.goal {
+            Some(goal) => *state == goal,
+            None => state.count() == 1,
+        }
+    }
+
+    /// Exact when the goal can be reached at all, as every jump takes one
+    /// peg.
+    fn estimate(&self, state: &Pegs) -> usize {
+        let left = self.goal.map_or(1, |goal| goal.count());
+        state.count().saturating_sub(left) as usize
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::Ordering;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diamond::Jump;
+use crate::feasibility::{self, Infeasible};
+use crate::playout;
+use crate::rng::Rng;
+use crate::{Action, Direction, GameTree, Grid, IllegalMove, ReplayError, SearchStrategy};
+
+const PLAYOUTS: u32 = 500;
+
+/// How promising one legal move is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveEvaluation {
+    pub action: Action,
+    /// Whether the goal can still be reached after the move.
+    pub solvable: bool,
+    /// Share of random playouts after the move that end exactly on the
+    /// goal, a rough measure of how forgiving the resulting position is.
+    pub finish_rate: f64,
+}
+
+impl fmt::Display for MoveEvaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.solvable { "solvable" } else { "lost" };
+        write!(
+            f,
+            "{}  {:<8}  random finish {:.1}%",
+            self.action,
+            verdict,
+            self.finish_rate * 100.0
+        )
+    }
+}
+
+/// Every legal move from `position`, best first: moves that keep the goal
+/// reachable come before the others, then higher finish rates first. Ties
+/// keep the order of [`Grid::valid_actions`].
+pub fn evaluate_moves(
+    position: &Grid,
+    goal: &Grid,
+    strategy: SearchStrategy,
+) -> Vec<MoveEvaluation> {
+    let solvable = |next: &Grid| {
+        Ok::<_, Infallible>(
+            GameTree::new(*next, Vec::new())
+                .search_for(goal, strategy)
+                .is_some(),
+        )
+    };
+    match evaluate_moves_with(position, goal, solvable) {
+        Ok(evaluations) => evaluations,
+        Err(never) => match never {},
+    }
+}
+
+/// [`evaluate_moves`], asking `solvable` whether the goal can be reached
+/// after each move, and failing as soon as it does.
+pub fn evaluate_moves_with<E>(
+    position: &Grid,
+    goal: &Grid,
+    mut solvable: impl FnMut(&Grid) -> Result<bool, E>,
+) -> Result<Vec<MoveEvaluation>, E> {
+    let mut evaluations = position
+        .valid_actions()
+        .into_iter()
+        .map(|action| {
+            let next = position.perform_action(action);
+            Ok(MoveEvaluation {
+                action,
+                solvable: solvable(&next)?,
+                finish_rate: finish_rate(&next, goal),
+            })
+        })
+        .collect::<Result<Vec<_>, E>>()?;
+    evaluations.sort_by(|a, b| {
+        b.solvable.cmp(&a.solvable).then(
+            b.finish_rate
+                .partial_cmp(&a.finish_rate)
+                .unwrap_or(Ordering::Equal),
+        )
+    });
+    Ok(evaluations)
+}
+
+fn finish_rate(position: &Grid, goal: &Grid) -> f64 {
+    let mut rng = Rng::new(0);
+    let hits = (0..PLAYOUTS)
+        .filter(|_| playout::random_playout(position, &mut rng) == *goal)
+        .count();
+    hits as f64 / PLAYOUTS as f64
+}
+
+/// What playing one move does to the chances of reaching the goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Solvable,
+    /// The position after the move fails a cheap necessary condition.
+    Doomed(Infeasible),
+    /// The search found no line, but none of the cheap checks explains why.
+    Lost,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Solvable => write!(f, "the goal can still be reached"),
+            Verdict::Doomed(reason) => write!(f, "the goal is out of reach: {}", reason),
+            Verdict::Lost => write!(
+                f,
+                "the goal is out of reach, although no simple reason was found"
+            ),
+        }
+    }
+}
+
+/// Judges playing `action` from `position` on the way to `goal`.
+pub fn critique(
+    position: &Grid,
+    goal: &Grid,
+    action: Action,
+    strategy: SearchStrategy,
+) -> Result<Verdict, IllegalMove> {
+    position.check_action(action)?;
+    let after = position.perform_action(action);
+    if after == *goal {
+        return Ok(Verdict::Solvable);
+    }
+    if let Err(reason) = feasibility::check(&after, goal) {
+        return Ok(Verdict::Doomed(reason));
+    }
+    let solved = GameTree::new(after, Vec::new()).search_for(goal, strategy);
+    Ok(match solved {
+        Some(_) => Verdict::Solvable,
+        None => Verdict::Lost,
+    })
+}
+
+/// What one move of a played line did to the chances of reaching the goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Annotation {
+    Fine,
+    /// The move that put the goal out of reach, and why it is.
+    Losing(Verdict),
+    /// A move played after the goal was already out of reach.
+    Lost,
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Annotation::Fine => write!(f, "fine"),
+            Annotation::Losing(verdict) => write!(f, "losing, {}", verdict),
+            Annotation::Lost => write!(f, "already lost"),
+        }
+    }
+}
+
+/// Annotates every move of `moves` played from `start`, the way a chess
+/// program looks over a finished game. Once a move loses, the rest are
+/// [`Annotation::Lost`] without searching further.
+pub fn annotate(
+    start: &Grid,
+    goal: &Grid,
+    moves: &[Action],
+    strategy: SearchStrategy,
+) -> Result<Vec<Annotation>, ReplayError> {
+    let mut lost = *start != *goal
+        && GameTree::new(*start, Vec::new())
+            .search_for(goal, strategy)
+            .is_none();
+    let mut position = *start;
+    let mut notes = Vec::with_capacity(moves.len());
+    for (index, &action) in moves.iter().enumerate() {
+        let illegal = |reason| ReplayError {
+            index,
+            action,
+            reason,
+        };
+        if lost {
+            position.check_action(action).map_err(illegal)?;
+            notes.push(Annotation::Lost);
+        } else {
+            match critique(&position, goal, action, strategy).map_err(illegal)? {
+                Verdict::Solvable => notes.push(Annotation::Fine),
+                verdict => {
+                    lost = true;
+                    notes.push(Annotation::Losing(verdict));
+                }
+            }
+        }
+        position = position.perform_action(action);
+    }
+    Ok(notes)
+}
+
+/// How much of a solution a hint gives away, from least to most.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HintLevel {
+    /// Only which peg to move.
+    Peg,
+    /// The peg and the direction it jumps.
+    Direction,
+    /// The whole next move.
+    #[default]
+    Move,
+    /// The next [`LINE_HINT`] moves.
+    Line,
+}
+
+/// How many moves a [`HintLevel::Line`] hint shows.
+pub const LINE_HINT: usize = 3;
+
+impl HintLevel {
+    pub const ALL: [HintLevel; 4] = [
+        HintLevel::Peg,
+        HintLevel::Direction,
+        HintLevel::Move,
+        HintLevel::Line,
+    ];
+
+    /// The level's number, 1 to 4.
+    pub fn number(&self) -> usize {
+        *self as usize + 1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownHintLevel(pub String);
+
+impl fmt::Display for UnknownHintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown hint level {:?}, expected 1 to 4 or peg, direction, move or line",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownHintLevel {}
+
+impl FromStr for HintLevel {
+    type Err = UnknownHintLevel;
+
+    fn from_str(s: &str) -> Result<HintLevel, UnknownHintLevel> {
+        match s.to_ascii_lowercase().as_str() {
+            "1" | "peg" => Ok(HintLevel::Peg),
+            "2" | "direction" => Ok(HintLevel::Direction),
+            "3" | "move" => Ok(HintLevel::Move),
+            "4" | "line" => Ok(HintLevel::Line),
+            _ => Err(UnknownHintLevel(s.to_string())),
+        }
+    }
+}
+
+/// A jump a [`Hint`] can give part of away: an [`Action`], or the
+/// [`Jump`] of a diamond board.
+pub trait HintMove: Copy {
+    /// The cell the jumping peg starts on.
+    fn origin(&self) -> (usize, usize);
+
+    fn direction(&self) -> Direction;
+}
+
+impl HintMove for Action {
+    fn origin(&self) -> (usize, usize) {
+        Action::origin(self)
+    }
+
+    fn direction(&self) -> Direction {
+        Action::direction(self)
+    }
+}
+
+impl HintMove for Jump {
+    fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+/// What a hint at one [`HintLevel`] reveals of a solution line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint<M = Action> {
+    Peg((usize, usize)),
+    Direction((usize, usize), Direction),
+    Move(M),
+    Line(Vec<M>),
+}
+
+impl<M: HintMove> Hint<M> {
+    /// The hint for a position whose solution continues with `line`, or
+    /// `None` when the line is empty.
+    pub fn new(line: &[M], level: HintLevel) -> Option<Hint<M>> {
+        let &next = line.first()?;
+        Some(match level {
+            HintLevel::Peg => Hint::Peg(next.origin()),
+            HintLevel::Direction => Hint::Direction(next.origin(), next.direction()),
+            HintLevel::Move => Hint::Move(next),
+            HintLevel::Line => Hint::Line(line.iter().copied().take(LINE_HINT).collect()),
+        })
+    }
+}
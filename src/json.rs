@@ -0,0 +1,756 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::daily::Daily;
+use crate::diamond::{DiamondBoard, Jump};
+use crate::diff::LineDiff;
+use crate::enumerate::StateSpace;
+use crate::generate::GeneratedPuzzle;
+use crate::heatmap::{Heatmap, Role};
+use crate::hint::{MoveEvaluation, Verdict};
+use crate::last_peg::Endings;
+use crate::notation::Standard;
+use crate::presets::Preset;
+use crate::profile::{ChallengeResult, Profile};
+use crate::stats::SearchStats;
+use crate::topology::Topology;
+use crate::{Action, Direction, Grid};
+
+/// A JSON value. Objects keep their fields in insertion order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn array<T: ToJson>(items: &[T]) -> Json {
+        Json::Array(items.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Json {
+        Json::Number(n)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Json {
+        Json::Integer(n as i128)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(n: u64) -> Json {
+        Json::Integer(n as i128)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Integer(n as i128)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Compact serialisation on a single line.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Integer(n) => write!(f, "{}", n),
+            Json::Number(n) if !n.is_finite() => f.write_str("null"),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+impl Json {
+    /// The field called `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Json::Integer(n) => u64::try_from(n).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseJsonError {
+    /// Byte offset of the problem in the input.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl Error for ParseJsonError {}
+
+/// Reads one JSON value, surrounded by nothing but whitespace. Numbers
+/// without a fraction or exponent become [`Json::Integer`].
+impl FromStr for Json {
+    type Err = ParseJsonError;
+
+    fn from_str(s: &str) -> Result<Json, ParseJsonError> {
+        let mut parser = Parser { text: s, pos: 0 };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        if parser.pos < s.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+/// Deeper nesting is rejected rather than risking the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &'static str) -> ParseJsonError {
+        ParseJsonError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), ParseJsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(message));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, ParseJsonError> {
+        if !self.text[self.pos..].starts_with(word) {
+            return Err(self.error("unknown keyword"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, ParseJsonError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value(depth + 1)?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err(self.error("expected `,` or `]`")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error("expected a field name"));
+                    }
+                    let key = self.string()?;
+                    self.expect(b':', "expected `:`")?;
+                    fields.push((key, self.value(depth + 1)?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(self.error("expected `,` or `}`")),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, ParseJsonError> {
+        let start = self.pos;
+        let mut integer = true;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'0'..=b'9' | b'-' | b'+' => {}
+                b'.' | b'e' | b'E' => integer = false,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let digits = &self.text[start..self.pos];
+        let parsed = if integer {
+            digits.parse().map(Json::Integer).ok()
+        } else {
+            digits.parse().map(Json::Number).ok()
+        };
+        parsed.ok_or(ParseJsonError {
+            offset: start,
+            message: "invalid number",
+        })
+    }
+
+    fn string(&mut self) -> Result<String, ParseJsonError> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(c) = rest.chars().next() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    out.push(match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error("unknown escape")),
+                    });
+                }
+                c if (c as u32) < 0x20 => return Err(self.error("control character in string")),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// The character after `\u`, joining a surrogate pair when one follows.
+    fn unicode_escape(&mut self) -> Result<char, ParseJsonError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) && self.text[self.pos..].starts_with("\\u") {
+            self.pos += 2;
+            let low = self.hex4()?;
+            0x10000 + ((high - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseJsonError> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("short unicode escape"))?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| self.error("bad unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl ToJson for Direction {
+    fn to_json(&self) -> Json {
+        self.name().into()
+    }
+}
+
+fn cell((x, y): (usize, usize)) -> Json {
+    Json::Array(vec![x.into(), y.into()])
+}
+
+impl ToJson for Action {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("x", self.x.into()),
+            ("y", self.y.into()),
+            ("direction", self.dir.to_json()),
+            ("over", self.captured().map_or(Json::Null, cell)),
+            ("to", self.landing().map_or(Json::Null, cell)),
+            ("notation", Standard(*self).to_string().into()),
+        ])
+    }
+}
+
+/// The board in notation, the peg bits from [`Grid::encode`] and the peg
+/// count.
+impl ToJson for Grid {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("notation", self.notation().into()),
+            ("encoding", self.encode().into()),
+            ("pegs", self.filled_count.into()),
+        ])
+    }
+}
+
+impl ToJson for Jump {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("x", self.x.into()),
+            ("y", self.y.into()),
+            ("direction", self.direction.to_json()),
+            ("over", self.over().map_or(Json::Null, cell)),
+            ("to", self.to().map_or(Json::Null, cell)),
+            ("notation", self.to_string().into()),
+        ])
+    }
+}
+
+/// The board in notation, the peg bits from [`DiamondBoard::encode`] and
+/// the peg count. The bits can pass 2^64, more than a JSON number is sure
+/// to hold, so they are written as a decimal string.
+impl ToJson for DiamondBoard {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("notation", self.notation().into()),
+            ("encoding", self.encode().to_string().into()),
+            ("pegs", self.filled_count().into()),
+        ])
+    }
+}
+
+/// [`line`] for jumps on a diamond board, with the encodings as strings
+/// as [`DiamondBoard::to_json`] writes them.
+pub fn diamond_line(start: &DiamondBoard, jumps: &[Jump]) -> Json {
+    let mut state = *start;
+    let mut items = Vec::with_capacity(jumps.len());
+    for &jump in jumps {
+        state = state.perform_jump(jump);
+        let Json::Object(mut fields) = jump.to_json() else {
+            unreachable!()
+        };
+        fields.push(("after".to_string(), state.encode().to_string().into()));
+        items.push(Json::Object(fields));
+    }
+    Json::Array(items)
+}
+
+/// `moves` played from `start`, each with the encoding of the position it
+/// leads to.
+pub fn line(start: &Grid, moves: &[Action]) -> Json {
+    line_on(start, moves, Topology::Flat)
+}
+
+/// [`line`] for moves made under `topology`.
+pub fn line_on(start: &Grid, moves: &[Action], topology: Topology) -> Json {
+    let mut state = *start;
+    let mut items = Vec::with_capacity(moves.len());
+    for &action in moves {
+        state = state.perform_action_on(action, topology);
+        let Json::Object(mut fields) = action.to_json() else {
+            unreachable!()
+        };
+        fields.push(("after".to_string(), state.encode().into()));
+        items.push(Json::Object(fields));
+    }
+    Json::Array(items)
+}
+
+impl ToJson for MoveEvaluation {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("move", self.action.to_json()),
+            ("solvable", self.solvable.into()),
+            ("finish_rate", self.finish_rate.into()),
+        ])
+    }
+}
+
+/// The number of lines and, for every cell used at all, its counts.
+impl ToJson for Heatmap {
+    fn to_json(&self) -> Json {
+        let mut cells = Vec::new();
+        for y in 0..7 {
+            for x in 0..7 {
+                let counts = Role::ALL.map(|role| self.count(role, x, y));
+                if counts.iter().all(|&n| n == 0) {
+                    continue;
+                }
+                let mut fields = vec![("cell", cell((x, y)))];
+                fields.extend(
+                    Role::ALL
+                        .iter()
+                        .zip(counts)
+                        .map(|(r, n)| (r.name(), n.into())),
+                );
+                cells.push(Json::object(fields));
+            }
+        }
+        Json::object([
+            ("lines", self.lines().into()),
+            ("cells", Json::Array(cells)),
+        ])
+    }
+}
+
+impl ToJson for Verdict {
+    fn to_json(&self) -> Json {
+        let (verdict, reason) = match self {
+            Verdict::Solvable => ("solvable", None),
+            Verdict::Doomed(reason) => ("doomed", Some(reason.to_string())),
+            Verdict::Lost => ("lost", None),
+        };
+        Json::object([("verdict", verdict.into()), ("reason", reason.into())])
+    }
+}
+
+impl ToJson for GeneratedPuzzle {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("seed", self.seed.into()),
+            ("position", self.position.to_json()),
+            ("goal", self.goal.to_json()),
+            ("solution", line(&self.position, &self.solution)),
+        ])
+    }
+}
+
+impl ToJson for Daily {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("date", self.date.to_string().into()),
+            ("id", self.id.clone().into()),
+            ("puzzle", self.puzzle.to_json()),
+        ])
+    }
+}
+
+impl ToJson for ChallengeResult {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("score", self.score.into()),
+            ("pegs", self.pegs.into()),
+            ("seconds_left", self.seconds_left.into()),
+            ("start", self.start.notation().into()),
+        ])
+    }
+}
+
+impl ToJson for Profile {
+    fn to_json(&self) -> Json {
+        let leaderboard = self.leaderboard.iter().map(|solve| {
+            Json::object([
+                ("seconds", solve.seconds.into()),
+                ("start", solve.start.notation().into()),
+            ])
+        });
+        Json::object([
+            ("games_finished", self.games_finished.into()),
+            ("games_solved", self.games_solved.into()),
+            ("best_pegs", self.best_pegs.into()),
+            ("fastest_solve", self.fastest_solve().into()),
+            ("daily_streak", self.daily_streak.into()),
+            ("best_daily_streak", self.best_daily_streak.into()),
+            ("last_daily", self.last_daily.map(|d| d.to_string()).into()),
+            ("leaderboard", Json::Array(leaderboard.collect())),
+            ("challenges", Json::array(&self.challenges)),
+        ])
+    }
+}
+
+impl ToJson for SearchStats {
+    fn to_json(&self) -> Json {
+        let pruned = self
+            .pruned
+            .iter()
+            .map(|&(rule, count)| (rule, count.into()));
+        Json::object([
+            ("nodes_expanded", self.nodes_expanded.into()),
+            ("duplicates", self.duplicates.into()),
+            ("pruned", Json::object(pruned)),
+            ("memo_peak", self.memo_peak.into()),
+            ("memo_bytes", self.memo_bytes.into()),
+            ("frontier_peak", self.frontier_peak.into()),
+            ("seconds", self.elapsed.as_secs_f64().into()),
+        ])
+    }
+}
+
+/// One entry per depth with its peg, position and dead end counts, and the
+/// branching factor histogram.
+impl ToJson for StateSpace {
+    fn to_json(&self) -> Json {
+        let depths = self
+            .by_pegs()
+            .into_iter()
+            .zip(&self.dead_ends)
+            .enumerate()
+            .map(|(depth, ((pegs, n), &dead))| {
+                Json::object([
+                    ("depth", depth.into()),
+                    ("pegs", pegs.into()),
+                    ("positions", n.into()),
+                    ("dead_ends", dead.into()),
+                ])
+            })
+            .collect();
+        let branching = self.branching.iter().map(|&n| n.into()).collect();
+        Json::object([
+            ("up_to_symmetry", self.up_to_symmetry.into()),
+            ("total", self.total().into()),
+            ("depths", Json::Array(depths)),
+            ("branching", Json::Array(branching)),
+            ("mean_branching", self.mean_branching().into()),
+        ])
+    }
+}
+
+impl ToJson for Endings {
+    fn to_json(&self) -> Json {
+        let cells =
+            |cells: &[(usize, usize)]| Json::Array(cells.iter().map(|&c| cell(c)).collect());
+        Json::object([
+            (
+                "class",
+                Json::Array(self.class.iter().map(|&b| u32::from(b).into()).collect()),
+            ),
+            ("candidates", cells(&self.candidates)),
+            ("reachable", cells(&self.reachable)),
+        ])
+    }
+}
+
+impl ToJson for LineDiff {
+    fn to_json(&self) -> Json {
+        let divergence = self.divergence.map(|(a, b)| {
+            Json::object([
+                ("index", (self.common_prefix + 1).into()),
+                ("a", a.map(|a| a.to_json()).into()),
+                ("b", b.map(|b| b.to_json()).into()),
+            ])
+        });
+        Json::object([
+            (
+                "moves",
+                Json::Array(vec![self.moves.0.into(), self.moves.1.into()]),
+            ),
+            (
+                "chained_moves",
+                Json::Array(vec![
+                    self.chained_moves.0.into(),
+                    self.chained_moves.1.into(),
+                ]),
+            ),
+            ("common_prefix", self.common_prefix.into()),
+            ("divergence", divergence.into()),
+            (
+                "shared",
+                Json::Array(self.shared.iter().map(|&i| i.into()).collect()),
+            ),
+            ("same_final", (self.finals.0 == self.finals.1).into()),
+        ])
+    }
+}
+
+/// Why a JSON value could not be read as a board or a move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromJsonError(pub String);
+
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for FromJsonError {}
+
+/// The reverse of [`ToJson`], for the types a client sends.
+pub trait FromJson: Sized {
+    fn from_json(value: &Json) -> Result<Self, FromJsonError>;
+}
+
+/// A preset name or a board in notation, a peg encoding on the English
+/// board, or an object like the one [`Grid::to_json`] writes, which is
+/// read by its `notation` or failing that its `encoding`.
+impl FromJson for Grid {
+    fn from_json(value: &Json) -> Result<Grid, FromJsonError> {
+        match value {
+            Json::String(spec) => match spec.parse::<Preset>() {
+                Ok(preset) => preset.position().ok_or_else(|| {
+                    FromJsonError(format!("{} is too big for the 7x7 grid", preset))
+                }),
+                Err(_) => spec.parse().map_err(|e| FromJsonError(format!("{}", e))),
+            },
+            Json::Integer(_) => match value.as_u64() {
+                Some(bits) if bits < 1 << 49 => Ok(Grid::new().with_pegs(bits)),
+                _ => Err(FromJsonError("a board encoding has 49 bits".to_string())),
+            },
+            Json::Object(_) => match (value.get("notation"), value.get("encoding")) {
+                (Some(notation), _) => Grid::from_json(notation),
+                (None, Some(encoding)) => Grid::from_json(encoding),
+                (None, None) => Err(FromJsonError(
+                    "a board object needs a notation or an encoding".to_string(),
+                )),
+            },
+            _ => Err(FromJsonError(
+                "a board is a string, a number or an object".to_string(),
+            )),
+        }
+    }
+}
+
+/// A move in any form [`Action`] parses from, or an object with `x`, `y`
+/// and `direction` like the one [`Action::to_json`] writes.
+impl FromJson for Action {
+    fn from_json(value: &Json) -> Result<Action, FromJsonError> {
+        let invalid = || FromJsonError(format!("invalid move {}", value));
+        match value {
+            Json::String(s) => s.parse().map_err(|e| FromJsonError(format!("{}", e))),
+            Json::Object(_) => {
+                let coord = |key| match value.get(key).and_then(Json::as_u64) {
+                    Some(n) if n < 7 => Ok(n as usize),
+                    _ => Err(invalid()),
+                };
+                let dir = value
+                    .get("direction")
+                    .and_then(Json::as_str)
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(invalid)?;
+                Ok(Action {
+                    x: coord("x")?,
+                    y: coord("y")?,
+                    dir,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
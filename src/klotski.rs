@@ -0,0 +1,274 @@
+//! Klotski and other sliding-block puzzles: rectangular pieces slide in a
+//! walled box until a target piece reaches its goal. Other pieces of the
+//! same size are interchangeable, so positions are stored in a canonical
+//! form that lists them in order, and the searches' tables treat
+//! positions that only swap such pieces as one.
+//!
+//! A board is drawn a row per line, or with rows separated by `/`, each
+//! piece as a rectangle of one letter or digit, `.` or a space for an
+//! empty cell and `#` for a wall:
+//!
+//! ```text
+//! ABBC
+//! ABBC
+//! DEEF
+//! DGHF
+//! I..J
+//! ```
+//!
+//! The target is the largest piece, the first in reading order among
+//! equals, and its goal is the bottom centre of the box.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+use crate::Direction;
+
+/// The top left corner of every piece: the target first, then the rest
+/// grouped by size and sorted within each size.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Blocks {
+    corners: Vec<(u8, u8)>,
+}
+
+/// The piece whose top left corner is at `(x, y)` slides `distance` cells
+/// towards `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Slide {
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+    pub distance: usize,
+}
+
+impl fmt::Display for Slide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = match self.direction {
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+            Direction::Left => "←",
+            Direction::Right => "→",
+        };
+        write!(f, "({}, {}) {} {}", self.x, self.y, dir, self.distance)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Klotski {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    /// Width and height of each piece, in the order of [`Blocks`].
+    sizes: Vec<(usize, usize)>,
+    /// The pieces' letters, to draw them by.
+    labels: Vec<char>,
+    /// Where the target's corner must go.
+    goal: (usize, usize),
+    start: Blocks,
+}
+
+impl Klotski {
+    pub fn start(&self) -> &Blocks {
+        &self.start
+    }
+
+    /// Which piece covers each cell, if any.
+    fn occupancy(&self, blocks: &Blocks) -> Vec<Option<usize>> {
+        let mut cells = vec![None; self.width * self.height];
+        for (piece, &(x, y)) in blocks.corners.iter().enumerate() {
+            let (w, h) = self.sizes[piece];
+            for dy in 0..h {
+                for dx in 0..w {
+                    cells[(y as usize + dy) * self.width + x as usize + dx] = Some(piece);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Whether `piece` could stand with its corner at `(x, y)`, given the
+    /// cells other pieces cover.
+    fn fits(&self, cells: &[Option<usize>], piece: usize, x: isize, y: isize) -> bool {
+        let (w, h) = self.sizes[piece];
+        if x < 0 || y < 0 || x as usize + w > self.width || y as usize + h > self.height {
+            return false;
+        }
+        (0..h).all(|dy| {
+            (0..w).all(|dx| {
+                let i = (y as usize + dy) * self.width + x as usize + dx;
+                !self.walls[i] && cells[i].is_none_or(|p| p == piece)
+            })
+        })
+    }
+
+    /// `blocks` with the same-sized pieces put back in order.
+    fn canonical(&self, mut corners: Vec<(u8, u8)>) -> Blocks {
+        let mut start = 1;
+        while start < corners.len() {
+            let end = (start..corners.len())
+                .find(|&i| self.sizes[i] != self.sizes[start])
+                .unwrap_or(corners.len());
+            corners[start..end].sort_unstable();
+            start = end;
+        }
+        Blocks { corners }
+    }
+
+    pub fn render(&self, blocks: &Blocks) -> String {
+        let cells = self.occupancy(blocks);
+        let mut out = String::new();
+        for y in 0..self.height {
+            if y > 0 {
+                out.push('\n');
+            }
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                out.push(match cells[i] {
+                    Some(piece) => self.labels[piece],
+                    None if self.walls[i] => '#',
+                    None => '.',
+                });
+            }
+        }
+        out
+    }
+}
+
+impl Puzzle for Klotski {
+    type State = Blocks;
+    type Move = Slide;
+
+    fn successors(&self, state: &Blocks) -> Vec<(Slide, Blocks)> {
+        let cells = self.occupancy(state);
+        let mut out = Vec::new();
+        for (piece, &(x, y)) in state.corners.iter().enumerate() {
+            for direction in Direction::ALL {
+                let (dx, dy) = match direction {
+                    Direction::Up => (0, -1),
+                    Direction::Down => (0, 1),
+                    Direction::Left => (-1, 0),
+                    Direction::Right => (1, 0),
+                };
+                let mut distance = 1;
+                loop {
+                    let nx = x as isize + dx * distance;
+                    let ny = y as isize + dy * distance;
+                    if !self.fits(&cells, piece, nx, ny) {
+                        break;
+                    }
+                    let mut corners = state.corners.clone();
+                    corners[piece] = (nx as u8, ny as u8);
+                    let slide = Slide {
+                        x: x as usize,
+                        y: y as usize,
+                        direction,
+                        distance: distance as usize,
+                    };
+                    out.push((slide, self.canonical(corners)));
+                    distance += 1;
+                }
+            }
+        }
+        out
+    }
+
+    fn is_goal(&self, state: &Blocks) -> bool {
+        state.corners[0] == (self.goal.0 as u8, self.goal.1 as u8)
+    }
+
+    fn estimate(&self, state: &Blocks) -> usize {
+        usize::from(!self.is_goal(state))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseKlotskiError(pub String);
+
+impl fmt::Display for ParseKlotskiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseKlotskiError {}
+
+impl FromStr for Klotski {
+    type Err = ParseKlotskiError;
+
+    fn from_str(s: &str) -> Result<Klotski, ParseKlotskiError> {
+        let err = |message: String| Err(ParseKlotskiError(message));
+        let rows: Vec<Vec<char>> = s
+            .split(['/', '\n'])
+            .map(|r| r.trim_end().chars().collect::<Vec<char>>())
+            .filter(|r| !r.is_empty())
+            .collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        if width == 0 || width > 255 || height > 255 {
+            return err("expected a board of at most 255 by 255 cells".to_string());
+        }
+        let mut walls = vec![false; width * height];
+        // Each letter with the cells it covers, in reading order.
+        let mut pieces: Vec<(char, Vec<(usize, usize)>)> = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                match c {
+                    '.' | ' ' => {}
+                    '#' => walls[y * width + x] = true,
+                    c if c.is_ascii_alphanumeric() => match pieces.iter_mut().find(|p| p.0 == c) {
+                        Some((_, cells)) => cells.push((x, y)),
+                        None => pieces.push((c, vec![(x, y)])),
+                    },
+                    _ => return err(format!("unknown cell {:?}", c)),
+                }
+            }
+        }
+        if pieces.is_empty() {
+            return err("no pieces".to_string());
+        }
+        // (size, corner, label) of each piece.
+        let mut found = Vec::new();
+        for (label, cells) in &pieces {
+            let x0 = cells.iter().map(|c| c.0).min().unwrap();
+            let y0 = cells.iter().map(|c| c.1).min().unwrap();
+            let x1 = cells.iter().map(|c| c.0).max().unwrap();
+            let y1 = cells.iter().map(|c| c.1).max().unwrap();
+            let (w, h) = (x1 - x0 + 1, y1 - y0 + 1);
+            if cells.len() != w * h {
+                return err(format!("piece {} is not a rectangle", label));
+            }
+            found.push(((w, h), (x0 as u8, y0 as u8), *label));
+        }
+        let mut target = 0;
+        for (i, &((w, h), ..)) in found.iter().enumerate() {
+            let (tw, th) = found[target].0;
+            if w * h > tw * th {
+                target = i;
+            }
+        }
+        let target = found.remove(target);
+        found.sort_by_key(|&(size, corner, _)| (size, corner));
+        found.insert(0, target);
+        let (tw, th) = target.0;
+        let goal = ((width - tw) / 2, height - th);
+        Ok(Klotski {
+            width,
+            height,
+            walls,
+            sizes: found.iter().map(|p| p.0).collect(),
+            labels: found.iter().map(|p| p.2).collect(),
+            goal,
+            start: Blocks {
+                corners: found.iter().map(|p| p.1).collect(),
+            },
+        })
+    }
+}
+
+impl fmt::Display for Klotski {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&self.start))
+    }
+}
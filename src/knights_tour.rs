@@ -0,0 +1,224 @@
+//! Knight's tours: a knight visits every square of an N by N board exactly
+//! once. [`find`] follows Warnsdorff's rule, always jumping to the square
+//! with the fewest onward jumps, and backtracks out of dead ends, so it
+//! finds a tour whenever there is one. On all but the smallest boards the
+//! rule alone almost never needs to back up.
+
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::notation::cell_name;
+use crate::render::RenderOptions;
+use crate::stats::SearchStats;
+
+const JUMPS: [(isize, isize); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+/// A square named like a peg solitaire cell: a column letter from `a` and
+/// a row number from 1 at the top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Square {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", cell_name(self.x, self.y))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSquareError(pub String);
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Square, ParseSquareError> {
+        let err = || ParseSquareError(format!("expected a square such as a1, found {:?}", s));
+        let mut chars = s.trim().chars();
+        let column = chars.next().ok_or_else(err)?.to_ascii_lowercase();
+        let row: usize = chars.as_str().parse().map_err(|_| err())?;
+        if !column.is_ascii_lowercase() || row == 0 {
+            return Err(err());
+        }
+        Ok(Square {
+            x: column as usize - 'a' as usize,
+            y: row - 1,
+        })
+    }
+}
+
+/// The squares of a tour in the order the knight visits them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tour {
+    size: usize,
+    squares: Vec<Square>,
+}
+
+impl Tour {
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn squares(&self) -> &[Square] {
+        &self.squares
+    }
+
+    /// Whether the last square is a jump away from the first, so the tour
+    /// can start again.
+    pub fn is_closed(&self) -> bool {
+        let (first, last) = (self.squares[0], self.squares[self.squares.len() - 1]);
+        jumps(self.size, last).any(|s| s == first)
+    }
+
+    /// The board with each square numbered by when the knight lands on it.
+    /// Of `options`, `coords` labels the columns and rows as squares are
+    /// named, and `color` shows the first square in the theme's jump colour
+    /// and the last in its last-move colour.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let mut order = vec![0; self.size * self.size];
+        for (i, square) in self.squares.iter().enumerate() {
+            order[square.y * self.size + square.x] = i + 1;
+        }
+        let width = (self.size * self.size).to_string().len();
+        let margin = self.size.to_string().len();
+        let theme = &options.theme;
+        let mut out = String::new();
+        if options.coords {
+            let _ = write!(out, "{:margin$}", "");
+            for x in 0..self.size {
+                let _ = write!(out, " {:>width$}", (b'a' + x as u8) as char);
+            }
+            out.push('\n');
+        }
+        for y in 0..self.size {
+            if options.coords {
+                let _ = write!(out, "{:>margin$}", y + 1);
+            }
+            for x in 0..self.size {
+                let step = order[y * self.size + x];
+                if !options.color {
+                    let _ = write!(out, " {:>width$}", step);
+                    continue;
+                }
+                let color = match step {
+                    1 => &theme.jump_color,
+                    _ if step == self.squares.len() => &theme.last_color,
+                    _ => &theme.peg_color,
+                };
+                let _ = write!(out, " \x1b[{}m{:>width$}\x1b[0m", color, step);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The squares a knight on `from` can jump to.
+fn jumps(size: usize, from: Square) -> impl Iterator<Item = Square> {
+    JUMPS.iter().filter_map(move |&(dx, dy)| {
+        let x = from.x.checked_add_signed(dx)?;
+        let y = from.y.checked_add_signed(dy)?;
+        (x < size && y < size).then_some(Square { x, y })
+    })
+}
+
+/// A tour of the `size` by `size` board from `start`, ending a jump away
+/// from it if `closed`. The stats count the squares the knight stepped on,
+/// backtracking included.
+pub fn find(size: usize, start: Square, closed: bool) -> (Option<Tour>, SearchStats) {
+    let started = Instant::now();
+    let mut stats = SearchStats::default();
+    let squares = size * size;
+    // A jump always changes the colour of the square, so on an odd board
+    // a tour starts and ends on the colour with one square more.
+    let odd = !squares.is_multiple_of(2);
+    let hopeless = start.x >= size
+        || start.y >= size
+        || (odd && !(start.x + start.y).is_multiple_of(2))
+        || (closed && odd && squares > 1);
+    if hopeless {
+        stats.elapsed = started.elapsed();
+        return (None, stats);
+    }
+    let mut visited = vec![false; squares];
+    let index = |s: Square| s.y * size + s.x;
+    let onward =
+        |visited: &[bool], s: Square| jumps(size, s).filter(|&t| !visited[index(t)]).count();
+    let centre = size as isize - 1;
+    // Doubled distance from the centre, for breaking ties towards the edge.
+    let spread = |s: Square| (2 * s.x as isize - centre).abs() + (2 * s.y as isize - centre).abs();
+    // How far a square is from the start; closed tours leave the squares
+    // near it for last.
+    let apart = |s: Square| s.x.abs_diff(start.x) + s.y.abs_diff(start.y);
+    let mut path = vec![start];
+    visited[index(start)] = true;
+    // The squares still to try from each square of the path, best last.
+    let mut choices: Vec<Vec<Square>> = Vec::new();
+    loop {
+        let here = path[path.len() - 1];
+        let done = path.len() == squares;
+        if done && (!closed || jumps(size, here).any(|s| s == start)) {
+            break;
+        }
+        let mut next: Vec<Square> = Vec::new();
+        if !done {
+            stats.nodes_expanded += 1;
+            next = jumps(size, here).filter(|&s| !visited[index(s)]).collect();
+            next.sort_by_key(|&s| {
+                let away = if closed { apart(s) } else { 0 };
+                (Reverse(onward(&visited, s)), away, spread(s))
+            });
+            // A closed tour must leave a way back to the start.
+            if closed && path.len() > 1 && onward(&visited, start) == 0 {
+                stats.prune("no way back");
+                next.clear();
+            }
+        }
+        choices.push(next);
+        stats.observe_frontier(choices.iter().map(Vec::len).sum());
+        // Back up until some square of the path has a move left to try.
+        loop {
+            if let Some(square) = choices.last_mut().and_then(Vec::pop) {
+                visited[index(square)] = true;
+                path.push(square);
+                break;
+            }
+            choices.pop();
+            if choices.is_empty() {
+                stats.elapsed = started.elapsed();
+                return (None, stats);
+            }
+            let last = path.pop().unwrap();
+            visited[index(last)] = false;
+        }
+    }
+    stats.elapsed = started.elapsed();
+    (
+        Some(Tour {
+            size,
+            squares: path,
+        }),
+        stats,
+    )
+}
@@ -0,0 +1,97 @@
+//! Where the last peg can end from a position. The position class, which
+//! no jump changes, rules out all but a few cells at once; a search for
+//! each of the rest settles which can really be reached.
+
+use std::fmt::Write;
+
+use crate::feasibility;
+use crate::render::{display_width, RenderOptions};
+use crate::symmetry;
+use crate::{GameTree, Grid, SearchStrategy};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endings {
+    /// The position class shared by every cell the last peg can end on,
+    /// see [`feasibility::position_class`].
+    pub class: [u8; 4],
+    /// The cells no cheap argument rules out, from [`feasibility::final_cells`].
+    pub candidates: Vec<(usize, usize)>,
+    /// The candidates a search found a finish on, in the same order.
+    pub reachable: Vec<(usize, usize)>,
+}
+
+/// Searches with `strategy` for a one-peg finish on every candidate cell,
+/// except those a symmetry of `position` maps onto a cell already settled.
+pub fn endings(position: &Grid, strategy: SearchStrategy) -> Endings {
+    let candidates = feasibility::final_cells(position);
+    let group = symmetry::grid_symmetries(position);
+    let tree = GameTree::new(*position, Vec::new());
+    let mut settled: Vec<((usize, usize), bool)> = Vec::new();
+    for &(x, y) in &candidates {
+        let known = group.iter().find_map(|s| {
+            let image = s.apply_point(x, y);
+            settled.iter().find(|(cell, _)| *cell == image)
+        });
+        let found = match known {
+            Some(&(_, found)) => found,
+            None => {
+                let goal = position.single_peg(x, y);
+                tree.search_for(&goal, strategy).is_some()
+            }
+        };
+        settled.push(((x, y), found));
+    }
+    let reachable = settled
+        .into_iter()
+        .filter(|&(_, found)| found)
+        .map(|(cell, _)| cell)
+        .collect();
+    Endings {
+        class: feasibility::position_class(position),
+        candidates,
+        reachable,
+    }
+}
+
+/// `position` with a `*` on each cell the last peg can end on, in the goal
+/// colour when `options.color` is set, and a `?` on candidates the search
+/// ruled out.
+pub fn render(position: &Grid, endings: &Endings, options: &RenderOptions) -> String {
+    let theme = &options.theme;
+    let width = theme.cell_width();
+    let mut out = String::new();
+    if options.coords {
+        out.push_str("  ");
+        for x in 0..7 {
+            let _ = write!(out, "{:<width$}", x, width = width);
+        }
+        out.push('\n');
+    }
+    for y in 0..7 {
+        if options.coords {
+            let _ = write!(out, "{} ", y);
+        }
+        for x in 0..7 {
+            let tile = position.tile(x, y);
+            let (symbol, marked) = if endings.reachable.contains(&(x, y)) {
+                ("*", true)
+            } else if endings.candidates.contains(&(x, y)) {
+                ("?", false)
+            } else {
+                (theme.symbol(tile), false)
+            };
+            let padding = " ".repeat(width.saturating_sub(display_width(symbol)));
+            if options.color && marked {
+                let _ = write!(
+                    out,
+                    "\x1b[{}m{}\x1b[0m{}",
+                    theme.goal_color, symbol, padding
+                );
+            } else {
+                let _ = write!(out, "{}{}", symbol, padding);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
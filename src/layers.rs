@@ -0,0 +1,372 @@
+//! Peg solitaire in three dimensions: boards stacked in layers, where a
+//! peg can also jump straight up over the peg above it into the layer
+//! beyond, or down the same way.
+//!
+//! A board is written a layer at a time from the bottom, the layers
+//! separated by `|` or a blank line and each drawn like a flat board, a row
+//! per line or with rows separated by `/`: `O` for a peg, `-` for a hole
+//! and `.` or a space where there is no cell. Every layer has the size of
+//! the largest, the missing cells filled with nothing. The 3x3x2 box with
+//! a hole in the corner of its bottom layer is
+//!
+//! ```text
+//! -OO/OOO/OOO|OOO/OOO/OOO
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+
+/// The most cells a board can have, one bit of [`Pegs`] each.
+pub const MAX_CELLS: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction3 {
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Into the layer above.
+    Above,
+    /// Into the layer below.
+    Below,
+}
+
+impl Direction3 {
+    pub const ALL: [Direction3; 6] = [
+        Direction3::Left,
+        Direction3::Right,
+        Direction3::Up,
+        Direction3::Down,
+        Direction3::Above,
+        Direction3::Below,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction3::Left => "left",
+            Direction3::Right => "right",
+            Direction3::Up => "up",
+            Direction3::Down => "down",
+            Direction3::Above => "above",
+            Direction3::Below => "below",
+        }
+    }
+
+    /// One step in columns, rows and layers.
+    fn offset(self) -> (isize, isize, isize) {
+        match self {
+            Direction3::Left => (-1, 0, 0),
+            Direction3::Right => (1, 0, 0),
+            Direction3::Up => (0, -1, 0),
+            Direction3::Down => (0, 1, 0),
+            Direction3::Above => (0, 0, 1),
+            Direction3::Below => (0, 0, -1),
+        }
+    }
+}
+
+/// The peg at column `x`, row `y` of layer `z`, all from 0, jumps towards
+/// `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Jump3 {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+    pub direction: Direction3,
+}
+
+/// `(1, 1, 0) above`.
+impl fmt::Display for Jump3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}, {}) {}",
+            self.x,
+            self.y,
+            self.z,
+            self.direction.name()
+        )
+    }
+}
+
+/// The pegs of a position, one bit per cell of the board, layer by layer
+/// from the bottom and each in reading order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pegs(pub u128);
+
+impl Pegs {
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Jump {
+    jump: Jump3,
+    from: u128,
+    over: u128,
+    to: u128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLayersError(pub String);
+
+impl fmt::Display for ParseLayersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseLayersError {}
+
+/// A stack of layers: which cells exist, every jump between them and the
+/// pegs it starts with, solved down to a goal position or else to a single
+/// peg anywhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layers {
+    width: usize,
+    height: usize,
+    depth: usize,
+    /// The bit of each cell's peg, layer by layer, or `None` where there
+    /// is no cell.
+    cells: Vec<Option<u32>>,
+    jumps: Vec<Jump>,
+    start: Pegs,
+    goal: Option<Pegs>,
+}
+
+impl Layers {
+    /// `width` by `height` by `depth` cells, of which `exists` says which
+    /// are on the board and `peg` which start with one.
+    fn new(
+        (width, height, depth): (usize, usize, usize),
+        exists: impl Fn(usize, usize, usize) -> bool,
+        peg: impl Fn(usize, usize, usize) -> bool,
+    ) -> Result<Layers, ParseLayersError> {
+        let mut cells = Vec::with_capacity(width * height * depth);
+        let mut start = 0u128;
+        let mut count = 0;
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    if !exists(x, y, z) {
+                        cells.push(None);
+                        continue;
+                    }
+                    if count == MAX_CELLS {
+                        return Err(ParseLayersError(format!(
+                            "expected at most {} cells",
+                            MAX_CELLS
+                        )));
+                    }
+                    if peg(x, y, z) {
+                        start |= 1 << count;
+                    }
+                    cells.push(Some(count as u32));
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            return Err(ParseLayersError("the board has no cells".to_string()));
+        }
+        let mut board = Layers {
+            width,
+            height,
+            depth,
+            cells,
+            jumps: Vec::new(),
+            start: Pegs(start),
+            goal: None,
+        };
+        board.jumps = board.find_jumps();
+        Ok(board)
+    }
+
+    fn bit(&self, x: usize, y: usize, z: usize) -> Option<u32> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return None;
+        }
+        self.cells[(z * self.height + y) * self.width + x]
+    }
+
+    fn find_jumps(&self) -> Vec<Jump> {
+        let mut jumps = Vec::new();
+        for z in 0..self.depth {
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let Some(from) = self.bit(x, y, z) else {
+                        continue;
+                    };
+                    for direction in Direction3::ALL {
+                        let (dx, dy, dz) = direction.offset();
+                        let at = |steps: isize| {
+                            self.bit(
+                                x.checked_add_signed(dx * steps)?,
+                                y.checked_add_signed(dy * steps)?,
+                                z.checked_add_signed(dz * steps)?,
+                            )
+                        };
+                        if let (Some(over), Some(to)) = (at(1), at(2)) {
+                            jumps.push(Jump {
+                                jump: Jump3 { x, y, z, direction },
+                                from: 1 << from,
+                                over: 1 << over,
+                                to: 1 << to,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        jumps
+    }
+
+    /// The box of `width` by `height` cells and `depth` layers, full but
+    /// for the corner at the top left of the bottom layer. Three by three
+    /// and four high, it can be cleared to one peg; the cube three to a
+    /// side cannot, from any hole.
+    pub fn cuboid(width: usize, height: usize, depth: usize) -> Result<Layers, ParseLayersError> {
+        Layers::new(
+            (width, height, depth),
+            |_, _, _| true,
+            |x, y, z| (x, y, z) != (0, 0, 0),
+        )
+    }
+
+    pub fn start(&self) -> &Pegs {
+        &self.start
+    }
+
+    /// Solved when the pegs are those of `goal`, a position on the same
+    /// cells, instead of when one peg is left.
+    pub fn with_goal(mut self, goal: &Layers) -> Result<Layers, ParseLayersError> {
+        if (goal.width, goal.height, goal.depth, &goal.cells)
+            != (self.width, self.height, self.depth, &self.cells)
+        {
+            return Err(ParseLayersError(
+                "the goal is drawn on different cells from the board".to_string(),
+            ));
+        }
+        self.goal = Some(goal.start);
+        Ok(self)
+    }
+
+    /// The jumps that can be made from `pegs`, with where each leads.
+    pub fn moves(&self, pegs: &Pegs) -> Vec<(Jump3, Pegs)> {
+        let Pegs(bits) = *pegs;
+        self.jumps
+            .iter()
+            .filter(|j| bits & j.from != 0 && bits & j.over != 0 && bits & j.to == 0)
+            .map(|j| (j.jump, Pegs(bits & !j.from & !j.over | j.to)))
+            .collect()
+    }
+
+    /// `pegs` a layer at a time, each under a heading and the top layer
+    /// first, as the stack is seen from above.
+    pub fn render(&self, pegs: &Pegs) -> String {
+        let mut blocks = Vec::with_capacity(self.depth);
+        for z in (0..self.depth).rev() {
+            let mut block = format!("Layer {}", z + 1);
+            if self.depth > 1 && z == 0 {
+                block.push_str(" (bottom)");
+            } else if self.depth > 1 && z + 1 == self.depth {
+                block.push_str(" (top)");
+            }
+            for y in 0..self.height {
+                let row: String = (0..self.width)
+                    .map(|x| match self.bit(x, y, z) {
+                        Some(bit) if pegs.0 & (1 << bit) != 0 => 'O',
+                        Some(_) => '-',
+                        None => ' ',
+                    })
+                    .collect();
+                block.push('\n');
+                block.push_str(row.trim_end());
+            }
+            blocks.push(block);
+        }
+        blocks.join("\n\n")
+    }
+}
+
+impl fmt::Display for Layers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&self.start))
+    }
+}
+
+/// The notation described at the top of this module, in which `o`, `x`
+/// and `*` are also pegs and `_` a hole.
+impl FromStr for Layers {
+    type Err = ParseLayersError;
+
+    fn from_str(s: &str) -> Result<Layers, ParseLayersError> {
+        let text = s.replace("\r\n", "\n");
+        let layers: Vec<Vec<&str>> = text
+            .split('|')
+            .flat_map(|part| part.split("\n\n"))
+            .map(|layer| {
+                layer
+                    .split(['/', '\n'])
+                    .map(str::trim_end)
+                    .filter(|row| !row.trim().is_empty())
+                    .collect::<Vec<&str>>()
+            })
+            .filter(|rows| !rows.is_empty())
+            .collect();
+        let depth = layers.len();
+        let height = layers.iter().map(Vec::len).max().unwrap_or(0);
+        let width = layers
+            .iter()
+            .flatten()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut grid = vec![None; width * height * depth];
+        for (z, rows) in layers.iter().enumerate() {
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    grid[(z * height + y) * width + x] = match c {
+                        ' ' | '.' | '\t' => None,
+                        'O' | 'o' | 'x' | '*' => Some(true),
+                        '-' | '_' => Some(false),
+                        _ => return Err(ParseLayersError(format!("unknown cell {:?}", c))),
+                    };
+                }
+            }
+        }
+        let at = |x, y, z| grid[(z * height + y) * width + x];
+        Layers::new(
+            (width, height, depth),
+            |x, y, z| at(x, y, z).is_some(),
+            |x, y, z| at(x, y, z) == Some(true),
+        )
+    }
+}
+
+impl Puzzle for Layers {
+    type State = Pegs;
+    type Move = Jump3;
+
+    fn successors(&self, state: &Pegs) -> Vec<(Jump3, Pegs)> {
+        self.moves(state)
+    }
+
+    fn is_goal(&self, state: &Pegs) -> bool {
+        match self.goal {
+            Some(goal) => *state == goal,
+            None => state.count() == 1,
+        }
+    }
+
+    /// Exact when the goal can be reached at all, as every jump takes one
+    /// peg.
+    fn estimate(&self, state: &Pegs) -> usize {
+        let left = self.goal.map_or(1, |goal| goal.count());
+        state.count().saturating_sub(left) as usize
+    }
+}
@@ -0,0 +1,1135 @@
+//! The board and its rules, [`Grid`] and [`Action`] with the [`bitboard`],
+//! [`book`], [`feasibility`], [`notation`], [`rng`], [`symmetry`] and
+//! [`topology`] modules, need only `core` and `alloc`; everything else needs
+//! the default `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp;
+use core::error::Error;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::sync::mpsc::Sender;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::rng::Rng;
+#[cfg(feature = "std")]
+use crate::stats::SearchStats;
+#[cfg(feature = "std")]
+use crate::trace::{Level, Span};
+
+#[cfg(feature = "std")]
+pub mod anytime;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "std")]
+pub mod backward;
+#[cfg(feature = "std")]
+pub mod bidirectional;
+pub mod bitboard;
+pub mod book;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod challenge;
+#[cfg(feature = "std")]
+pub mod chunked;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "std")]
+pub mod daily;
+#[cfg(feature = "std")]
+pub mod diamond;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod difficulty;
+#[cfg(feature = "puzzles")]
+pub mod duotaire;
+#[cfg(feature = "std")]
+pub mod endgame;
+#[cfg(feature = "std")]
+pub mod enumerate;
+pub mod feasibility;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "puzzles")]
+pub mod fifteen;
+#[cfg(feature = "std")]
+pub mod fools;
+#[cfg(feature = "game")]
+pub mod game;
+#[cfg(feature = "std")]
+pub mod generate;
+#[cfg(feature = "export")]
+pub mod gif;
+#[cfg(feature = "export")]
+pub mod graph;
+#[cfg(feature = "puzzles")]
+pub mod hanoi;
+#[cfg(feature = "std")]
+pub mod heatmap;
+#[cfg(feature = "puzzles")]
+pub mod hex;
+#[cfg(feature = "std")]
+pub mod hint;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "puzzles")]
+pub mod klotski;
+#[cfg(feature = "puzzles")]
+pub mod knights_tour;
+#[cfg(feature = "std")]
+pub mod last_peg;
+#[cfg(feature = "puzzles")]
+pub mod layers;
+#[cfg(feature = "puzzles")]
+pub mod lights_out;
+#[cfg(feature = "std")]
+pub mod longest;
+#[cfg(feature = "std")]
+pub mod multimove;
+#[cfg(feature = "puzzles")]
+pub mod nonogram;
+pub mod notation;
+#[cfg(feature = "std")]
+pub mod packages;
+#[cfg(feature = "std")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod playout;
+#[cfg(feature = "export")]
+pub mod png;
+#[cfg(feature = "std")]
+pub mod portfolio;
+#[cfg(feature = "std")]
+pub mod presets;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod proof_number;
+#[cfg(feature = "puzzles")]
+pub mod puzzle;
+#[cfg(feature = "puzzles")]
+pub mod queens;
+#[cfg(feature = "export")]
+pub mod raster;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod retrograde;
+#[cfg(feature = "puzzles")]
+pub mod river;
+pub mod rng;
+#[cfg(feature = "puzzles")]
+pub mod rush_hour;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "puzzles")]
+pub mod sokoban;
+#[cfg(feature = "std")]
+pub mod speech;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "puzzles")]
+pub mod sudoku;
+#[cfg(feature = "export")]
+pub mod svg;
+#[cfg(feature = "std")]
+pub mod symmetric;
+pub mod symmetry;
+pub mod topology;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod train;
+#[cfg(feature = "std")]
+pub mod travel;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Blocked,
+    Filled,
+    Empty,
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Tile::Blocked => ' ',
+            Tile::Filled => 'O',
+            Tile::Empty => '-',
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub struct Grid {
+    grid: [[Tile; 7]; 7],
+    filled_count: u32,
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..7 {
+            for x in 0..7 {
+                write!(f, "{}", self.grid[x][y])?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Action {
+    x: usize,
+    y: usize,
+    dir: Direction,
+}
+
+impl Action {
+    /// The jump of the peg at `(x, y)` in direction `dir`. Whether it is
+    /// legal depends on the position, see [`Grid::check_action`].
+    pub fn new(x: usize, y: usize, dir: Direction) -> Action {
+        Action { x, y, dir }
+    }
+
+    /// The cell the jumping peg starts on.
+    pub fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.dir
+    }
+
+    /// The cell whose peg this move removes, if the jump stays inside the
+    /// 7x7 grid.
+    pub fn captured(&self) -> Option<(usize, usize)> {
+        self.span().map(|(over, _)| over)
+    }
+
+    /// The cell the peg lands on, if it is inside the 7x7 grid.
+    pub fn landing(&self) -> Option<(usize, usize)> {
+        self.span().map(|(_, to)| to)
+    }
+
+    /// The jumped-over cell and the landing cell, if both are on the board.
+    fn span(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (x, y) = (self.x, self.y);
+        match self.dir {
+            Direction::Up if y >= 2 => Some(((x, y - 1), (x, y - 2))),
+            Direction::Down if y <= 4 => Some(((x, y + 1), (x, y + 2))),
+            Direction::Left if x >= 2 => Some(((x - 1, y), (x - 2, y))),
+            Direction::Right if x <= 4 => Some(((x + 1, y), (x + 2, y))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = match self.dir {
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+            Direction::Left => "←",
+            Direction::Right => "→",
+        };
+        write!(f, "({}, {}) {}", self.x, self.y, dir)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseActionError(String);
+
+impl fmt::Display for ParseActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid move {:?}, expected `x y direction` such as `3 1 down` or `d2-d4`",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseActionError {}
+
+impl FromStr for Direction {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Direction, ParseActionError> {
+        match s.to_ascii_lowercase().as_str() {
+            "u" | "up" | "↑" => Ok(Direction::Up),
+            "d" | "down" | "↓" => Ok(Direction::Down),
+            "l" | "left" | "←" => Ok(Direction::Left),
+            "r" | "right" | "→" => Ok(Direction::Right),
+            _ => Err(ParseActionError(s.to_string())),
+        }
+    }
+}
+
+/// Accepts the `Display` form, `(3, 1) ↓`, looser spellings like
+/// `3 1 down` or `3,1,d`, and the start and landing cells as in
+/// `(3, 1) -> (3, 3)` or `3 1 3 3`.
+impl FromStr for Action {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Action, ParseActionError> {
+        let err = || ParseActionError(s.to_string());
+        if let Ok(jumps) = notation::parse(s) {
+            if let [action] = jumps[..] {
+                return Ok(action);
+            }
+        }
+        let cleaned = s.replace(['(', ')', ',', '-', '>'], " ");
+        let parts: Vec<&str> = cleaned.split_whitespace().collect();
+        let coord = |part: &str| match part.parse::<usize>() {
+            Ok(n) if n < 7 => Ok(n),
+            _ => Err(err()),
+        };
+        let (x, y, dir) = match parts[..] {
+            [x, y, dir] => (coord(x)?, coord(y)?, dir.parse().map_err(|_| err())?),
+            [x, y, tx, ty] => {
+                let (x, y, tx, ty) = (coord(x)?, coord(y)?, coord(tx)?, coord(ty)?);
+                let dir = match (tx as isize - x as isize, ty as isize - y as isize) {
+                    (0, -2) => Direction::Up,
+                    (0, 2) => Direction::Down,
+                    (-2, 0) => Direction::Left,
+                    (2, 0) => Direction::Right,
+                    _ => return Err(err()),
+                };
+                (x, y, dir)
+            }
+            _ => return Err(err()),
+        };
+        Ok(Action { x, y, dir })
+    }
+}
+
+/// Why [`Grid::check_action`] rejected a move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IllegalMove {
+    NoPeg,
+    OffBoard,
+    NothingToJump,
+    Occupied,
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            IllegalMove::NoPeg => "there is no peg on the starting cell",
+            IllegalMove::OffBoard => "the move starts or lands off the board",
+            IllegalMove::NothingToJump => "there is no peg to jump over",
+            IllegalMove::Occupied => "the landing cell already holds a peg",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl Error for IllegalMove {}
+
+/// The first move of a sequence that could not be played, an [`Action`]
+/// on the English board or the move of another board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReplayError<M = Action> {
+    /// Zero-based position of the move in the sequence.
+    pub index: usize,
+    pub action: M,
+    pub reason: IllegalMove,
+}
+
+impl<M: fmt::Display> fmt::Display for ReplayError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {}, {}, is not legal: {}",
+            self.index + 1,
+            self.action,
+            self.reason
+        )
+    }
+}
+
+impl<M: fmt::Debug + fmt::Display> Error for ReplayError<M> {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    TooManyRows,
+    TooManyColumns { row: usize },
+    UnknownSymbol { row: usize, symbol: char },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::TooManyRows => write!(f, "a board has at most 7 rows"),
+            ParseGridError::TooManyColumns { row } => {
+                write!(f, "row {} has more than 7 cells", row + 1)
+            }
+            ParseGridError::UnknownSymbol { row, symbol } => {
+                write!(f, "unknown cell {:?} in row {}", symbol, row + 1)
+            }
+        }
+    }
+}
+
+impl Error for ParseGridError {}
+
+/// Parses either the multi-line form printed by `Display` or the compact
+/// form from [`Grid::notation`]. Rows are separated by newlines or `/`;
+/// `O` is a peg, `-` a hole and a space or `.` a blocked cell. Missing
+/// cells at the end of a row, and missing rows, are blocked.
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Grid, ParseGridError> {
+        let mut grid = [[Tile::Blocked; 7]; 7];
+        let mut filled_count = 0;
+        let rows: Vec<&str> = s.trim_end_matches('\n').split(['\n', '/']).collect();
+        if rows.len() > 7 {
+            return Err(ParseGridError::TooManyRows);
+        }
+        for (y, row) in rows.iter().enumerate() {
+            let row = row.trim_end_matches('\r');
+            if row.chars().count() > 7 {
+                return Err(ParseGridError::TooManyColumns { row: y });
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                grid[x][y] = match symbol {
+                    'O' | 'o' => {
+                        filled_count += 1;
+                        Tile::Filled
+                    }
+                    '-' => Tile::Empty,
+                    ' ' | '.' => Tile::Blocked,
+                    _ => return Err(ParseGridError::UnknownSymbol { row: y, symbol }),
+                };
+            }
+        }
+        Ok(Grid { grid, filled_count })
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Grid {
+    pub fn new() -> Grid {
+        let mut grid = [[Tile::Filled; 7]; 7];
+        grid[3][3] = Tile::Empty;
+        grid[0][0] = Tile::Blocked;
+        grid[0][1] = Tile::Blocked;
+        grid[1][0] = Tile::Blocked;
+        grid[1][1] = Tile::Blocked;
+        grid[5][0] = Tile::Blocked;
+        grid[5][1] = Tile::Blocked;
+        grid[6][0] = Tile::Blocked;
+        grid[6][1] = Tile::Blocked;
+        grid[0][5] = Tile::Blocked;
+        grid[0][6] = Tile::Blocked;
+        grid[1][5] = Tile::Blocked;
+        grid[1][6] = Tile::Blocked;
+        grid[5][5] = Tile::Blocked;
+        grid[5][6] = Tile::Blocked;
+        grid[6][5] = Tile::Blocked;
+        grid[6][6] = Tile::Blocked;
+        Grid {
+            grid,
+            filled_count: 32,
+        }
+    }
+
+    pub fn tile_actions(&self, x: usize, y: usize) -> Vec<Action> {
+        if self.grid[x][y] != Tile::Filled {
+            return Vec::new();
+        }
+        let mut actions = Vec::new();
+        if x > 1 && self.grid[x - 1][y] == Tile::Filled && self.grid[x - 2][y] == Tile::Empty {
+            actions.push(Action {
+                x,
+                y,
+                dir: Direction::Left,
+            });
+        }
+        if x < 5 && self.grid[x + 1][y] == Tile::Filled && self.grid[x + 2][y] == Tile::Empty {
+            actions.push(Action {
+                x,
+                y,
+                dir: Direction::Right,
+            });
+        }
+        if y > 1 && self.grid[x][y - 1] == Tile::Filled && self.grid[x][y - 2] == Tile::Empty {
+            actions.push(Action {
+                x,
+                y,
+                dir: Direction::Up,
+            });
+        }
+        if y < 5 && self.grid[x][y + 1] == Tile::Filled && self.grid[x][y + 2] == Tile::Empty {
+            actions.push(Action {
+                x,
+                y,
+                dir: Direction::Down,
+            });
+        }
+        actions
+    }
+
+    /// Every legal jump, in an order that is part of the API so recorded
+    /// solutions stay the same across versions and platforms: by column
+    /// from left to right, then by row from the top (`a1`, `a2`, ...,
+    /// `b1`), and for each peg left, right, up, then down.
+    pub fn valid_actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for x in 0..7 {
+            for y in 0..7 {
+                actions.append(&mut self.tile_actions(x, y));
+            }
+        }
+        actions
+    }
+
+    /// [`Grid::valid_actions`] sorted by `compare`, keeping the documented
+    /// order between jumps it considers equal.
+    pub fn valid_actions_sorted_by(
+        &self,
+        compare: impl FnMut(&Action, &Action) -> cmp::Ordering,
+    ) -> Vec<Action> {
+        let mut actions = self.valid_actions();
+        actions.sort_by(compare);
+        actions
+    }
+
+    pub fn verify_action(&self, action: Action) -> bool {
+        match action.dir {
+            Direction::Up => {
+                if action.y < 2 {
+                    return false;
+                }
+                if self.grid[action.x][action.y - 1] != Tile::Filled {
+                    return false;
+                }
+                if self.grid[action.x][action.y - 2] != Tile::Empty {
+                    return false;
+                }
+            }
+            Direction::Down => {
+                if action.y > 4 {
+                    return false;
+                }
+                if self.grid[action.x][action.y + 1] != Tile::Filled {
+                    return false;
+                }
+                if self.grid[action.x][action.y + 2] != Tile::Empty {
+                    return false;
+                }
+            }
+            Direction::Left => {
+                if action.x < 2 {
+                    return false;
+                }
+                if self.grid[action.x - 1][action.y] != Tile::Filled {
+                    return false;
+                }
+                if self.grid[action.x - 2][action.y] != Tile::Empty {
+                    return false;
+                }
+            }
+            Direction::Right => {
+                if action.x > 4 {
+                    return false;
+                }
+                if self.grid[action.x + 1][action.y] != Tile::Filled {
+                    return false;
+                }
+                if self.grid[action.x + 2][action.y] != Tile::Empty {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Like [`Grid::verify_action`], but also requires a peg on the
+    /// starting cell and says what is wrong with an illegal move.
+    pub fn check_action(&self, action: Action) -> Result<(), IllegalMove> {
+        if action.x >= 7 || action.y >= 7 {
+            return Err(IllegalMove::OffBoard);
+        }
+        match self.grid[action.x][action.y] {
+            Tile::Blocked => return Err(IllegalMove::OffBoard),
+            Tile::Empty => return Err(IllegalMove::NoPeg),
+            Tile::Filled => {}
+        }
+        let Some(((mx, my), (tx, ty))) = action.span() else {
+            return Err(IllegalMove::OffBoard);
+        };
+        if self.grid[tx][ty] == Tile::Blocked {
+            return Err(IllegalMove::OffBoard);
+        }
+        if self.grid[mx][my] != Tile::Filled {
+            return Err(IllegalMove::NothingToJump);
+        }
+        if self.grid[tx][ty] == Tile::Filled {
+            return Err(IllegalMove::Occupied);
+        }
+        Ok(())
+    }
+
+    /// Plays `moves` in order, stopping at the first illegal one.
+    pub fn play_moves(&self, moves: &[Action]) -> Result<Grid, ReplayError> {
+        let mut state = *self;
+        for (index, &action) in moves.iter().enumerate() {
+            state.check_action(action).map_err(|reason| ReplayError {
+                index,
+                action,
+                reason,
+            })?;
+            state = state.perform_action(action);
+        }
+        Ok(state)
+    }
+
+    pub fn perform_action(&self, action: Action) -> Self {
+        assert!(self.verify_action(action));
+        let mut new_grid = self.grid;
+        new_grid[action.x][action.y] = Tile::Empty;
+        match action.dir {
+            Direction::Up => {
+                new_grid[action.x][action.y - 1] = Tile::Empty;
+                new_grid[action.x][action.y - 2] = Tile::Filled;
+            }
+            Direction::Down => {
+                new_grid[action.x][action.y + 1] = Tile::Empty;
+                new_grid[action.x][action.y + 2] = Tile::Filled;
+            }
+            Direction::Left => {
+                new_grid[action.x - 1][action.y] = Tile::Empty;
+                new_grid[action.x - 2][action.y] = Tile::Filled;
+            }
+            Direction::Right => {
+                new_grid[action.x + 1][action.y] = Tile::Empty;
+                new_grid[action.x + 2][action.y] = Tile::Filled;
+            }
+        }
+        Grid {
+            grid: new_grid,
+            filled_count: self.filled_count - 1,
+        }
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.grid[x][y]
+    }
+
+    pub fn filled_count(&self) -> u32 {
+        let mut count = 0;
+        for x in 0..7 {
+            for y in 0..7 {
+                if self.grid[x][y] == Tile::Filled {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Whether `action` undoes a jump, i.e. the peg sitting on the landing
+    /// cell can hop back over the empty middle cell to its empty origin.
+    pub fn verify_inverse_action(&self, action: Action) -> bool {
+        if action.x > 6 || action.y > 6 || self.grid[action.x][action.y] != Tile::Empty {
+            return false;
+        }
+        match action.span() {
+            Some(((mx, my), (tx, ty))) => {
+                self.grid[mx][my] == Tile::Empty && self.grid[tx][ty] == Tile::Filled
+            }
+            None => false,
+        }
+    }
+
+    pub fn inverse_actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for x in 0..7 {
+            for y in 0..7 {
+                for dir in Direction::ALL {
+                    let action = Action { x, y, dir };
+                    if self.verify_inverse_action(action) {
+                        actions.push(action);
+                    }
+                }
+            }
+        }
+        actions
+    }
+
+    /// Reverts `action`, returning the position it was played from.
+    pub fn unperform_action(&self, action: Action) -> Self {
+        assert!(self.verify_inverse_action(action));
+        let ((mx, my), (tx, ty)) = action.span().unwrap();
+        let mut new_grid = self.grid;
+        new_grid[action.x][action.y] = Tile::Filled;
+        new_grid[mx][my] = Tile::Filled;
+        new_grid[tx][ty] = Tile::Empty;
+        Grid {
+            grid: new_grid,
+            filled_count: self.filled_count + 1,
+        }
+    }
+
+    /// Pegs as a bitmask, bit `x * 7 + y` set for every filled cell.
+    pub fn encode(&self) -> u64 {
+        let mut bits = 0;
+        for x in 0..7 {
+            for y in 0..7 {
+                if self.grid[x][y] == Tile::Filled {
+                    bits |= 1 << (x * 7 + y);
+                }
+            }
+        }
+        bits
+    }
+
+    /// A position on the same layout as `self` with pegs given by `bits`
+    /// (as produced by [`Grid::encode`]). Bits on blocked cells are ignored.
+    pub fn with_pegs(&self, bits: u64) -> Grid {
+        let mut grid = self.grid;
+        let mut filled_count = 0;
+        for (x, column) in grid.iter_mut().enumerate() {
+            for (y, tile) in column.iter_mut().enumerate() {
+                if *tile == Tile::Blocked {
+                    continue;
+                }
+                if bits & (1 << (x * 7 + y)) != 0 {
+                    *tile = Tile::Filled;
+                    filled_count += 1;
+                } else {
+                    *tile = Tile::Empty;
+                }
+            }
+        }
+        Grid { grid, filled_count }
+    }
+
+    /// A copy with the cell at `(x, y)` replaced by `tile`, which may change
+    /// the layout.
+    pub fn with_tile(&self, x: usize, y: usize, tile: Tile) -> Grid {
+        let mut grid = self.grid;
+        let mut filled_count = self.filled_count;
+        if grid[x][y] == Tile::Filled {
+            filled_count -= 1;
+        }
+        if tile == Tile::Filled {
+            filled_count += 1;
+        }
+        grid[x][y] = tile;
+        Grid { grid, filled_count }
+    }
+
+    /// Builds a position from a mask of playable cells and a mask of pegs,
+    /// both laid out as in [`Grid::encode`].
+    pub fn from_masks(layout: u64, pegs: u64) -> Grid {
+        let mut grid = [[Tile::Blocked; 7]; 7];
+        let mut filled_count = 0;
+        for (x, column) in grid.iter_mut().enumerate() {
+            for (y, tile) in column.iter_mut().enumerate() {
+                let bit = 1 << (x * 7 + y);
+                if layout & bit == 0 {
+                    continue;
+                }
+                if pegs & bit != 0 {
+                    *tile = Tile::Filled;
+                    filled_count += 1;
+                } else {
+                    *tile = Tile::Empty;
+                }
+            }
+        }
+        Grid { grid, filled_count }
+    }
+
+    /// Single-line form of the board, rows top to bottom separated by `/`
+    /// with `.` for blocked cells, e.g. `..OOO../..OOO../OOOOOOO/...`.
+    pub fn notation(&self) -> String {
+        let mut out = String::with_capacity(55);
+        for y in 0..7 {
+            if y > 0 {
+                out.push('/');
+            }
+            for x in 0..7 {
+                out.push(match self.grid[x][y] {
+                    Tile::Blocked => '.',
+                    Tile::Filled => 'O',
+                    Tile::Empty => '-',
+                });
+            }
+        }
+        out
+    }
+
+    /// The position on this layout holding a single peg at `(x, y)`.
+    pub fn single_peg(&self, x: usize, y: usize) -> Grid {
+        self.with_pegs(1 << (x * 7 + y))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SearchStrategy {
+    #[default]
+    DepthFirst,
+    Bidirectional,
+    Backward,
+    ProofNumber,
+}
+
+impl SearchStrategy {
+    pub const ALL: [SearchStrategy; 4] = [
+        SearchStrategy::DepthFirst,
+        SearchStrategy::Bidirectional,
+        SearchStrategy::Backward,
+        SearchStrategy::ProofNumber,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchStrategy::DepthFirst => "dfs",
+            SearchStrategy::Bidirectional => "bidirectional",
+            SearchStrategy::Backward => "backward",
+            SearchStrategy::ProofNumber => "pns",
+        }
+    }
+}
+
+impl fmt::Display for SearchStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownStrategy(pub String);
+
+impl fmt::Display for UnknownStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown strategy {:?}, expected dfs, bidirectional, backward or pns",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownStrategy {}
+
+impl FromStr for SearchStrategy {
+    type Err = UnknownStrategy;
+
+    fn from_str(s: &str) -> Result<SearchStrategy, UnknownStrategy> {
+        match s.to_ascii_lowercase().as_str() {
+            "dfs" | "depth-first" => Ok(SearchStrategy::DepthFirst),
+            "bidirectional" | "bidi" => Ok(SearchStrategy::Bidirectional),
+            "backward" => Ok(SearchStrategy::Backward),
+            "pns" | "proof-number" => Ok(SearchStrategy::ProofNumber),
+            _ => Err(UnknownStrategy(s.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct GameTree {
+    state: Grid,
+    history: Vec<Action>,
+    endgame: Option<Arc<endgame::EndgameDb>>,
+    seed: Option<u64>,
+}
+
+#[cfg(feature = "std")]
+impl Default for GameTree {
+    fn default() -> Self {
+        GameTree::new(Grid::new(), Vec::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl GameTree {
+    pub fn new(state: Grid, history: Vec<Action>) -> GameTree {
+        GameTree {
+            state,
+            history,
+            endgame: None,
+            seed: None,
+        }
+    }
+
+    /// Lets [`GameTree::search`] cut off positions the database knows are
+    /// dead and finish lines it knows are solvable. Ignored when `db` was
+    /// built for a different goal than the centre peg on this layout.
+    pub fn with_endgame(mut self, db: Arc<endgame::EndgameDb>) -> GameTree {
+        self.endgame = Some(db);
+        self
+    }
+
+    /// Makes the searches try moves in an order shuffled by `seed`, so that
+    /// different seeds can find different lines, each reproducibly.
+    pub fn with_seed(mut self, seed: u64) -> GameTree {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn state(&self) -> &Grid {
+        &self.state
+    }
+
+    pub fn history(&self) -> &[Action] {
+        &self.history
+    }
+
+    /// Runs the cheap checks in [`feasibility::check`] against the centre
+    /// peg goal, explaining why the position is hopeless if they fail.
+    pub fn feasibility(&self) -> Result<(), feasibility::Infeasible> {
+        feasibility::check(&self.state, &self.state.single_peg(3, 3))
+    }
+
+    pub fn search_with(&self, strategy: SearchStrategy) -> Option<GameTree> {
+        self.search_for(&self.state.single_peg(3, 3), strategy)
+    }
+
+    /// Searches for a line from this position to `goal`, which must use the
+    /// same board layout.
+    pub fn search_for(&self, goal: &Grid, strategy: SearchStrategy) -> Option<GameTree> {
+        self.search_stats(goal, strategy).0
+    }
+
+    /// [`GameTree::search_for`], also reporting how much work the search
+    /// did.
+    pub fn search_stats(
+        &self,
+        goal: &Grid,
+        strategy: SearchStrategy,
+    ) -> (Option<GameTree>, SearchStats) {
+        self.search_cancellable(goal, strategy, &AtomicBool::new(false))
+    }
+
+    /// [`GameTree::search_stats`] that gives up, finding nothing, soon after
+    /// another thread sets `stop`.
+    pub fn search_cancellable(
+        &self,
+        goal: &Grid,
+        strategy: SearchStrategy,
+        stop: &AtomicBool,
+    ) -> (Option<GameTree>, SearchStats) {
+        let started = Instant::now();
+        let mut stats = SearchStats::default();
+        let found = self.run_search(goal, strategy, &mut stats, stop);
+        stats.elapsed = started.elapsed();
+        (found, stats)
+    }
+
+    fn run_search(
+        &self,
+        goal: &Grid,
+        strategy: SearchStrategy,
+        stats: &mut SearchStats,
+        stop: &AtomicBool,
+    ) -> Option<GameTree> {
+        let strategy_name = strategy.to_string();
+        let pegs = self.state.filled_count();
+        let _span = Span::enter(
+            Level::Info,
+            "search",
+            &[("strategy", &strategy_name), ("pegs", &pegs)],
+        );
+        if let Err(reason) = feasibility::check(&self.state, goal) {
+            trace::event(Level::Info, "search", "pruned", &[("reason", &reason)]);
+            stats.prune("feasibility");
+            return None;
+        }
+        let moves = match strategy {
+            SearchStrategy::DepthFirst => {
+                return self.depth_first(goal, &mut |_, _, _| {}, stats, stop)
+            }
+            SearchStrategy::Bidirectional => {
+                bidirectional::search_counted(&self.state, goal, stats, stop, self.seed)?
+            }
+            SearchStrategy::Backward => {
+                backward::search_counted(&self.state, goal, stats, stop, self.seed)?
+            }
+            SearchStrategy::ProofNumber => {
+                proof_number::search_counted(&self.state, goal, stats, stop, self.seed)?
+            }
+        };
+        Some(self.extend(&moves))
+    }
+
+    /// Plays `moves` on top of this node, recording them in the history.
+    fn extend(&self, moves: &[Action]) -> GameTree {
+        let mut state = self.state;
+        let mut history = self.history.clone();
+        for &action in moves {
+            state = state.perform_action(action);
+            history.push(action);
+        }
+        GameTree {
+            state,
+            history,
+            endgame: self.endgame.clone(),
+            seed: self.seed,
+        }
+    }
+
+    /// A depth-first search for `goal` that can be awaited, yielding to
+    /// the executor every `yield_every` expanded positions.
+    pub fn solve_async(&self, goal: &Grid, yield_every: u64) -> chunked::SolveFuture {
+        chunked::SolveFuture::new(self, goal, yield_every)
+    }
+
+    /// Sends every distinct line from this position to `goal` to `sender`
+    /// as it is found, with progress events in between, until the search
+    /// is done or the receiver hangs up. See [`enumerate::stream`].
+    pub fn solve_streaming(&self, goal: &Grid, sender: &Sender<enumerate::SearchEvent>) -> usize {
+        enumerate::stream(&self.state, goal, None, sender)
+    }
+
+    pub fn search(&self) -> Option<GameTree> {
+        self.search_observed(&mut |_, _, _| {})
+    }
+
+    /// The depth-first search behind [`GameTree::search`], reporting every
+    /// generated edge `(from, action, to)` to `observe`, including edges
+    /// into positions that were already visited.
+    pub(crate) fn search_observed(
+        &self,
+        observe: &mut dyn FnMut(&Grid, Action, &Grid),
+    ) -> Option<GameTree> {
+        let goal = self.state.single_peg(3, 3);
+        self.depth_first(
+            &goal,
+            observe,
+            &mut SearchStats::default(),
+            &AtomicBool::new(false),
+        )
+    }
+
+    fn depth_first(
+        &self,
+        goal: &Grid,
+        observe: &mut dyn FnMut(&Grid, Action, &Grid),
+        stats: &mut SearchStats,
+        stop: &AtomicBool,
+    ) -> Option<GameTree> {
+        feasibility::check(&self.state, goal).ok()?;
+        let endgame = self.endgame.as_deref().filter(|db| db.goal() == goal);
+        let mut rng = self.seed.map(Rng::new);
+        let mut memo = HashSet::new();
+        let mut queue: Vec<GameTree> = Vec::new();
+        memo.insert(self.state);
+        queue.push(self.clone());
+        loop {
+            if trace::enabled(Level::Debug)
+                && stats
+                    .nodes_expanded
+                    .is_multiple_of(trace::PROGRESS_INTERVAL)
+            {
+                trace::event(
+                    Level::Debug,
+                    "dfs",
+                    "progress",
+                    &[
+                        ("expanded", &stats.nodes_expanded),
+                        ("frontier", &queue.len()),
+                        ("memo", &memo.len()),
+                        ("memo_hits", &stats.duplicates),
+                        ("pruned", &stats.pruned_total()),
+                    ],
+                );
+            }
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            match queue.pop() {
+                None => return None,
+                Some(cur) => {
+                    if let Some(moves) = endgame.and_then(|db| db.finish(&cur.state)) {
+                        return Some(cur.extend(&moves));
+                    }
+                    stats.nodes_expanded += 1;
+                    let mut actions = cur.state.valid_actions();
+                    if let Some(rng) = &mut rng {
+                        rng.shuffle(&mut actions);
+                    }
+                    for action in actions {
+                        let new_state = &cur.state.perform_action(action);
+                        observe(&cur.state, action, new_state);
+                        if !memo.contains(new_state) {
+                            memo.insert(*new_state);
+                            if endgame.is_some_and(|db| db.lookup(new_state) == Some(false)) {
+                                stats.prune("endgame");
+                                continue;
+                            }
+                            let mut new_history = cur.history.clone();
+                            new_history.push(action);
+                            queue.push(GameTree::new(*new_state, new_history));
+                        } else {
+                            stats.duplicates += 1;
+                        }
+                    }
+                    stats.observe_memo(&memo);
+                    stats.observe_frontier(queue.len());
+                    if cur.state == *goal {
+                        return Some(cur);
+                    }
+                }
+            }
+        }
+    }
+}
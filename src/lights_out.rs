@@ -0,0 +1,240 @@
+//! Lights Out: pressing a light toggles it and its four neighbours, and
+//! the puzzle is solved when every light is off. Since presses commute and
+//! pressing twice undoes a press, a solution is a set of cells, found
+//! directly by [`solve`] as a linear system over GF(2) or by any of the
+//! generic searches.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+
+/// A board of at most 64 lights, bit `y * width + x` set when lit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Lights {
+    width: usize,
+    height: usize,
+    lit: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Press {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for Press {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Lights {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.lit >> (y * self.width + x) & 1 == 1
+    }
+
+    pub fn lit_count(&self) -> u32 {
+        self.lit.count_ones()
+    }
+
+    /// The lights a press at `press` toggles.
+    fn pattern(&self, press: Press) -> u64 {
+        let Press { x, y } = press;
+        let bit = |x: usize, y: usize| 1u64 << (y * self.width + x);
+        let mut mask = bit(x, y);
+        if x > 0 {
+            mask |= bit(x - 1, y);
+        }
+        if x + 1 < self.width {
+            mask |= bit(x + 1, y);
+        }
+        if y > 0 {
+            mask |= bit(x, y - 1);
+        }
+        if y + 1 < self.height {
+            mask |= bit(x, y + 1);
+        }
+        mask
+    }
+
+    pub fn press(&self, press: Press) -> Lights {
+        Lights {
+            lit: self.lit ^ self.pattern(press),
+            ..*self
+        }
+    }
+
+    fn presses(&self) -> impl Iterator<Item = Press> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Press { x, y }))
+    }
+}
+
+/// The fewest presses that turn every light off, or `None` when no set of
+/// presses does. Gaussian elimination finds one solution; when the board
+/// has several, up to 2^20 of them are compared for the smallest.
+pub fn solve(lights: &Lights) -> Option<Vec<Press>> {
+    let cells = lights.width * lights.height;
+    let presses: Vec<Press> = lights.presses().collect();
+    // Row i: which presses toggle light i, and whether it is lit. The
+    // toggle relation is symmetric, so the row is light i's own pattern.
+    let mut rows: Vec<(u64, bool)> = presses
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| (lights.pattern(p), lights.lit >> i & 1 == 1))
+        .collect();
+    let mut pivots = Vec::new();
+    for column in 0..cells {
+        let Some(found) = (pivots.len()..cells).find(|&r| rows[r].0 >> column & 1 == 1) else {
+            continue;
+        };
+        let rank = pivots.len();
+        rows.swap(rank, found);
+        let pivot = rows[rank];
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.0 >> column & 1 == 1 {
+                row.0 ^= pivot.0;
+                row.1 ^= pivot.1;
+            }
+        }
+        pivots.push(column);
+    }
+    if rows[pivots.len()..].iter().any(|&(_, lit)| lit) {
+        return None;
+    }
+    let mut solution = 0u64;
+    for (r, &column) in pivots.iter().enumerate() {
+        if rows[r].1 {
+            solution |= 1 << column;
+        }
+    }
+    // Each free column gives a set of presses that changes nothing.
+    let free: Vec<u64> = (0..cells)
+        .filter(|c| !pivots.contains(c))
+        .map(|f| {
+            let mut null = 1u64 << f;
+            for (r, &column) in pivots.iter().enumerate() {
+                if rows[r].0 >> f & 1 == 1 {
+                    null |= 1 << column;
+                }
+            }
+            null
+        })
+        .collect();
+    if free.len() <= 20 {
+        solution = (0u64..1 << free.len())
+            .map(|combo| {
+                free.iter()
+                    .enumerate()
+                    .filter(|&(i, _)| combo >> i & 1 == 1)
+                    .fold(solution, |s, (_, null)| s ^ null)
+            })
+            .min_by_key(|s| s.count_ones())
+            .unwrap();
+    }
+    Some(
+        presses
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| solution >> i & 1 == 1)
+            .map(|(_, p)| p)
+            .collect(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLightsError(pub String);
+
+impl fmt::Display for ParseLightsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseLightsError {}
+
+/// Rows separated by `/` or new lines, with `O`, `#`, `*` or `1` for a lit
+/// light and `.`, `-` or `0` for one that is off.
+impl FromStr for Lights {
+    type Err = ParseLightsError;
+
+    fn from_str(s: &str) -> Result<Lights, ParseLightsError> {
+        let rows: Vec<&str> = s
+            .split(['/', '\n'])
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |r| r.chars().count());
+        let height = rows.len();
+        if width == 0 || width * height > 64 {
+            return Err(ParseLightsError(format!(
+                "expected between 1 and 64 lights, found {}",
+                width * height
+            )));
+        }
+        let mut lit = 0u64;
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(ParseLightsError(format!(
+                    "row {} has {} lights, expected {}",
+                    y + 1,
+                    row.chars().count(),
+                    width
+                )));
+            }
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    'O' | 'o' | '#' | '*' | '1' => lit |= 1 << (y * width + x),
+                    '.' | '-' | '0' => {}
+                    _ => return Err(ParseLightsError(format!("unknown light {:?}", c))),
+                }
+            }
+        }
+        Ok(Lights { width, height, lit })
+    }
+}
+
+impl fmt::Display for Lights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                write!(f, "{}", if self.is_lit(x, y) { 'O' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lights Out for the generic searches. A* counts a press as turning off
+/// at most five lights.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LightsOut;
+
+impl Puzzle for LightsOut {
+    type State = Lights;
+    type Move = Press;
+
+    fn successors(&self, state: &Lights) -> Vec<(Press, Lights)> {
+        state.presses().map(|p| (p, state.press(p))).collect()
+    }
+
+    fn is_goal(&self, state: &Lights) -> bool {
+        state.lit == 0
+    }
+
+    fn estimate(&self, state: &Lights) -> usize {
+        (state.lit_count() as usize).div_ceil(5)
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+
+use crate::bitboard::JumpTable;
+use crate::symmetry;
+use crate::{Action, GameTree, Grid};
+
+/// The longest sequence of legal moves from `start`, ending in a dead end.
+///
+/// Every jump removes one peg, so the longest game is the one stranding the
+/// fewest pegs. Reachable positions are explored depth-first, up to
+/// symmetry, remembering the emptiest board seen; the sweep stops early if a
+/// single peg is reached since nothing can go further.
+pub fn search(start: &Grid) -> Vec<Action> {
+    let table = JumpTable::new(start);
+    let group = symmetry::layout_symmetries(start);
+    let canon = |bits| symmetry::canonical(bits, &group);
+
+    let start_bits = start.encode();
+    let mut seen = HashSet::from([canon(start_bits)]);
+    let mut stack = vec![start_bits];
+    let mut deepest = start_bits;
+    while let Some(cur) = stack.pop() {
+        if cur.count_ones() < deepest.count_ones() {
+            deepest = cur;
+            if cur.count_ones() <= 1 {
+                break;
+            }
+        }
+        for (_, next) in table.forward(cur) {
+            if seen.insert(canon(next)) {
+                stack.push(next);
+            }
+        }
+    }
+    table.line_between(start_bits, deepest, &group, |bits| seen.contains(&bits))
+}
+
+impl GameTree {
+    /// Plays the longest possible game from this position.
+    pub fn search_longest(&self) -> GameTree {
+        self.extend(&search(&self.state))
+    }
+}
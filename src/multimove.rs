@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::bitboard::JumpTable;
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, Direction, GameTree, Grid};
+
+/// A run of consecutive jumps by the same peg, which standard scoring
+/// counts as a single move.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultiMove {
+    x: usize,
+    y: usize,
+    dirs: Vec<Direction>,
+}
+
+impl MultiMove {
+    pub fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    pub fn directions(&self) -> &[Direction] {
+        &self.dirs
+    }
+
+    /// The individual jumps making up this move.
+    pub fn jumps(&self) -> Vec<Action> {
+        let mut jumps = Vec::with_capacity(self.dirs.len());
+        let (mut x, mut y) = (self.x, self.y);
+        for &dir in &self.dirs {
+            let action = Action { x, y, dir };
+            let (_, (tx, ty)) = action.span().unwrap();
+            jumps.push(action);
+            (x, y) = (tx, ty);
+        }
+        jumps
+    }
+}
+
+impl fmt::Display for MultiMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}) ", self.x, self.y)?;
+        for dir in &self.dirs {
+            let arrow = match dir {
+                Direction::Up => "↑",
+                Direction::Down => "↓",
+                Direction::Left => "←",
+                Direction::Right => "→",
+            };
+            write!(f, "{}", arrow)?;
+        }
+        Ok(())
+    }
+}
+
+/// Groups a jump sequence into chained moves.
+pub fn chain(actions: &[Action]) -> Vec<MultiMove> {
+    let mut moves: Vec<MultiMove> = Vec::new();
+    let mut landing = None;
+    for action in actions {
+        match moves.last_mut() {
+            Some(last) if landing == Some((action.x, action.y)) => last.dirs.push(action.dir),
+            _ => moves.push(MultiMove {
+                x: action.x,
+                y: action.y,
+                dirs: vec![action.dir],
+            }),
+        }
+        landing = action.span().map(|(_, to)| to);
+    }
+    moves
+}
+
+const UNSOLVABLE: u8 = u8::MAX;
+
+struct MinMoves {
+    table: JumpTable,
+    group: Vec<Symmetry>,
+    /// Fewest chained moves to the goal when the next jump starts a new move.
+    fresh: HashMap<u64, u8>,
+}
+
+impl MinMoves {
+    /// Fewest chained moves to the goal when the peg on `landing` has just
+    /// jumped and may keep going for free.
+    fn continuing(&self, bits: u64, landing: (usize, usize)) -> u8 {
+        let mut best = self.fresh(bits);
+        for (action, next) in self.table.forward(bits) {
+            if (action.x, action.y) == landing {
+                let (_, to) = action.span().unwrap();
+                best = best.min(self.continuing(next, to));
+            }
+        }
+        best
+    }
+
+    fn fresh(&self, bits: u64) -> u8 {
+        self.fresh[&symmetry::canonical(bits, &self.group)]
+    }
+
+    fn step_cost(&self, action: Action, next: u64, landing: Option<(usize, usize)>) -> u8 {
+        let (_, to) = action.span().unwrap();
+        let extra = u8::from(landing != Some((action.x, action.y)));
+        self.continuing(next, to).saturating_add(extra)
+    }
+}
+
+/// A line from `start` to `goal` using as few chained moves as possible.
+///
+/// Every position reachable from `start` is enumerated and, from the fewest
+/// pegs upwards, labelled with the fewest chained moves needed from it when
+/// the next jump starts a new move. Continuing a chain is cheap to evaluate
+/// on demand from those labels since chains are short.
+pub fn min_moves(start: &Grid, goal: &Grid) -> Option<Vec<Action>> {
+    let table = JumpTable::new(start);
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let canon = |bits| symmetry::canonical(bits, &group);
+
+    let mut layers = vec![vec![canon(start.encode())]];
+    loop {
+        let mut seen = HashSet::new();
+        for &bits in layers.last().unwrap() {
+            seen.extend(table.forward(bits).map(|(_, n)| canon(n)));
+        }
+        if seen.is_empty() {
+            break;
+        }
+        layers.push(seen.into_iter().collect());
+    }
+
+    let goal_bits = goal.encode();
+    let goal_key = canon(goal_bits);
+    let mut solver = MinMoves {
+        table,
+        group,
+        fresh: HashMap::new(),
+    };
+    while let Some(layer) = layers.pop() {
+        for bits in layer {
+            let best = if bits == goal_key {
+                0
+            } else {
+                solver
+                    .table
+                    .forward(bits)
+                    .map(|(action, next)| solver.step_cost(action, next, None))
+                    .min()
+                    .unwrap_or(UNSOLVABLE)
+            };
+            solver.fresh.insert(bits, best);
+        }
+    }
+
+    let mut cur = start.encode();
+    let mut landing = None;
+    let mut remaining = solver.fresh(cur);
+    if remaining == UNSOLVABLE {
+        return None;
+    }
+    let mut moves = Vec::new();
+    while cur != goal_bits {
+        let (action, next) = solver
+            .table
+            .forward(cur)
+            .find(|&(a, n)| solver.step_cost(a, n, landing) == remaining)
+            .unwrap();
+        if landing != Some((action.x, action.y)) {
+            remaining -= 1;
+        }
+        moves.push(action);
+        landing = action.span().map(|(_, to)| to);
+        cur = next;
+    }
+    Some(moves)
+}
+
+impl GameTree {
+    /// Solves for the centre peg using the fewest chained moves.
+    pub fn search_min_moves(&self) -> Option<GameTree> {
+        let goal = self.state.single_peg(3, 3);
+        let moves = min_moves(&self.state, &goal)?;
+        Some(self.extend(&moves))
+    }
+}
@@ -0,0 +1,402 @@
+//! Nonograms: shade cells of a grid so that each row and column has runs
+//! of shaded cells of the lengths its clue lists, in order. The solver
+//! settles one line at a time, working out which cells every arrangement
+//! of the line's runs agrees on, until no line changes; when that leaves
+//! cells unknown it guesses one and carries on both ways.
+//!
+//! Clues are read in the `.non` format used by most puzzle collections:
+//!
+//! ```text
+//! width 5
+//! height 3
+//! rows
+//! 1,1
+//! 5
+//! 0
+//! columns
+//! 1
+//! 2
+//! 1
+//! 2
+//! 1
+//! ```
+//!
+//! Runs are separated by commas or spaces, `0` or an empty line is a line
+//! with none, and other keys such as `title` or `goal` are skipped.
+
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::render::{display_width, RenderOptions};
+use crate::stats::SearchStats;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Clues {
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+}
+
+/// A solved grid, row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Picture {
+    width: usize,
+    shaded: Vec<bool>,
+}
+
+impl Picture {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.shaded.len() / self.width.max(1)
+    }
+
+    pub fn is_shaded(&self, x: usize, y: usize) -> bool {
+        self.shaded[y * self.width + x]
+    }
+
+    /// Each row as `#` for a shaded cell and `.` for a blank one.
+    pub fn rows(&self) -> Vec<String> {
+        self.shaded
+            .chunks(self.width.max(1))
+            .map(|row| row.iter().map(|&s| if s { '#' } else { '.' }).collect())
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Unknown,
+    Shaded,
+    Blank,
+}
+
+impl Clues {
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The first solution, if any.
+    pub fn solve(&self) -> (Option<Picture>, SearchStats) {
+        let (mut found, stats) = self.solutions(1);
+        (found.pop(), stats)
+    }
+
+    /// Up to `limit` solutions; a proper puzzle has exactly one. The stats
+    /// count the cells guessed.
+    pub fn solutions(&self, limit: usize) -> (Vec<Picture>, SearchStats) {
+        let started = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut found = Vec::new();
+        let cells = vec![Cell::Unknown; self.width() * self.height()];
+        if limit > 0 {
+            self.search(cells, limit, &mut found, &mut stats);
+        }
+        stats.elapsed = started.elapsed();
+        (found, stats)
+    }
+
+    fn search(
+        &self,
+        mut cells: Vec<Cell>,
+        limit: usize,
+        found: &mut Vec<Picture>,
+        stats: &mut SearchStats,
+    ) {
+        if !self.propagate(&mut cells) {
+            stats.prune("contradiction");
+            return;
+        }
+        let Some(guess) = cells.iter().position(|&c| c == Cell::Unknown) else {
+            found.push(Picture {
+                width: self.width(),
+                shaded: cells.iter().map(|&c| c == Cell::Shaded).collect(),
+            });
+            return;
+        };
+        stats.nodes_expanded += 1;
+        stats.observe_frontier(found.len() + 1);
+        for value in [Cell::Shaded, Cell::Blank] {
+            if found.len() >= limit {
+                return;
+            }
+            let mut next = cells.clone();
+            next[guess] = value;
+            self.search(next, limit, found, stats);
+        }
+    }
+
+    /// Settles lines until none changes, returning false if one can no
+    /// longer be arranged.
+    fn propagate(&self, cells: &mut [Cell]) -> bool {
+        let (width, height) = (self.width(), self.height());
+        // Rows are lines 0..height, columns the rest.
+        let mut dirty = vec![true; height + width];
+        while let Some(line) = dirty.iter().position(|&d| d) {
+            dirty[line] = false;
+            let (clue, indices): (&[usize], Vec<usize>) = if line < height {
+                (
+                    &self.rows[line],
+                    (0..width).map(|x| line * width + x).collect(),
+                )
+            } else {
+                let x = line - height;
+                (
+                    &self.columns[x],
+                    (0..height).map(|y| y * width + x).collect(),
+                )
+            };
+            let current: Vec<Cell> = indices.iter().map(|&i| cells[i]).collect();
+            let Some(settled) = settle(clue, &current) else {
+                return false;
+            };
+            for (k, &i) in indices.iter().enumerate() {
+                if cells[i] != settled[k] {
+                    cells[i] = settled[k];
+                    let (x, y) = (i % width, i / width);
+                    dirty[if line < height { height + x } else { y }] = true;
+                }
+            }
+        }
+        true
+    }
+
+    /// The clues around the picture: column clues stacked above it, row
+    /// clues to its left, and the cells in the theme's peg symbol when
+    /// shaded and its hole symbol when not.
+    pub fn render(&self, picture: &Picture, options: &RenderOptions) -> String {
+        let theme = &options.theme;
+        let widest = self.rows.iter().chain(&self.columns).flatten().max();
+        let digits = widest.map_or(1, |n| n.to_string().len());
+        let cell = theme.cell_width().max(digits);
+        let row_clues: Vec<String> = self.rows.iter().map(|c| join(c)).collect();
+        let margin = row_clues.iter().map(String::len).max().unwrap_or(0);
+        let depth = self.columns.iter().map(Vec::len).max().unwrap_or(0);
+        let mut out = String::new();
+        for level in 0..depth {
+            let _ = write!(out, "{:margin$}", "");
+            for clue in &self.columns {
+                // Clues sit at the bottom of the stack, next to the grid.
+                match (level + clue.len()).checked_sub(depth) {
+                    Some(i) => {
+                        let _ = write!(out, " {:>cell$}", clue[i]);
+                    }
+                    None => out.push_str(&" ".repeat(cell + 1)),
+                }
+            }
+            out.push('\n');
+        }
+        for (y, clue) in row_clues.iter().enumerate() {
+            let _ = write!(out, "{:>margin$}", clue);
+            for x in 0..self.width() {
+                let (symbol, color) = if picture.is_shaded(x, y) {
+                    (&theme.peg, &theme.peg_color)
+                } else {
+                    (&theme.hole, &theme.hole_color)
+                };
+                let padding = " ".repeat(cell + 1 - display_width(symbol));
+                if options.color {
+                    let _ = write!(out, "{}\x1b[{}m{}\x1b[0m", padding, color, symbol);
+                } else {
+                    let _ = write!(out, "{}{}", padding, symbol);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn join(clue: &[usize]) -> String {
+    if clue.is_empty() {
+        return "0".to_string();
+    }
+    let runs: Vec<String> = clue.iter().map(usize::to_string).collect();
+    runs.join(" ")
+}
+
+/// `line` with every cell that all arrangements of `clue`'s runs agree on
+/// filled in, or `None` if no arrangement fits what is known.
+fn settle(clue: &[usize], line: &[Cell]) -> Option<Vec<Cell>> {
+    let (n, k) = (line.len(), clue.len());
+    // fits[i][j]: runs j.. can be laid out in cells i.., where a run may
+    // start at i.
+    let mut fits = vec![vec![false; k + 1]; n + 2];
+    let clear = |from: usize, to: usize| line[from..to].iter().all(|&c| c != Cell::Shaded);
+    for i in (0..=n).rev() {
+        fits[i][k] = clear(i, n);
+        for j in (0..k).rev() {
+            let skip = i < n && line[i] != Cell::Shaded && fits[i + 1][j];
+            fits[i][j] = skip || place(clue[j], line, i).is_some_and(|e| fits[e][j + 1]);
+        }
+    }
+    if !fits[0][0] {
+        return None;
+    }
+    let (mut can_shade, mut can_blank) = (vec![false; n], vec![false; n]);
+    let mut reached = vec![vec![false; k + 1]; n + 2];
+    reached[0][0] = true;
+    for i in 0..=n {
+        for j in 0..=k {
+            if !reached[i][j] || !fits[i][j] {
+                continue;
+            }
+            if j == k {
+                can_blank[i..].iter_mut().for_each(|c| *c = true);
+                continue;
+            }
+            if i < n && line[i] != Cell::Shaded && fits[i + 1][j] {
+                can_blank[i] = true;
+                reached[i + 1][j] = true;
+            }
+            if let Some(e) = place(clue[j], line, i).filter(|&e| fits[e][j + 1]) {
+                let end = i + clue[j];
+                can_shade[i..end].iter_mut().for_each(|c| *c = true);
+                if end < n {
+                    can_blank[end] = true;
+                }
+                reached[e][j + 1] = true;
+            }
+        }
+    }
+    (0..n)
+        .map(|i| match (can_shade[i], can_blank[i]) {
+            (true, false) => Some(Cell::Shaded),
+            (false, true) => Some(Cell::Blank),
+            (true, true) => Some(line[i]),
+            (false, false) => None,
+        })
+        .collect()
+}
+
+/// Where the next run may start after a run of `length` starting at `i`,
+/// if it fits there: past the blank cell that ends it, or at the end of
+/// the line.
+fn place(length: usize, line: &[Cell], i: usize) -> Option<usize> {
+    let end = i + length;
+    if end > line.len() || line[i..end].contains(&Cell::Blank) {
+        return None;
+    }
+    match line.get(end) {
+        None => Some(end),
+        Some(Cell::Shaded) => None,
+        Some(_) => Some(end + 1),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNonogramError(pub String);
+
+impl fmt::Display for ParseNonogramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseNonogramError {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Rows,
+    Columns,
+}
+
+impl FromStr for Clues {
+    type Err = ParseNonogramError;
+
+    fn from_str(s: &str) -> Result<Clues, ParseNonogramError> {
+        let err = |message: String| Err(ParseNonogramError(message));
+        let (mut width, mut height) = (None, None);
+        let (mut rows, mut columns) = (Vec::new(), Vec::new());
+        let mut section = None;
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            let key = line.split_whitespace().next().unwrap_or("");
+            match key {
+                "width" | "height" => {
+                    let Ok(n) = line[key.len()..].trim().parse::<usize>() else {
+                        return err(format!("line {}: expected a number after {}", i + 1, key));
+                    };
+                    if key == "width" {
+                        width = Some(n);
+                    } else {
+                        height = Some(n);
+                    }
+                    section = None;
+                    continue;
+                }
+                "rows" => section = Some(Section::Rows),
+                "columns" => section = Some(Section::Columns),
+                _ => {}
+            }
+            let Some(current) = section.filter(|_| key != "rows" && key != "columns") else {
+                continue;
+            };
+            let clue: Result<Vec<usize>, _> = line
+                .split([',', ' '])
+                .filter(|r| !r.is_empty())
+                .map(str::parse)
+                .collect();
+            let Ok(mut clue) = clue else {
+                section = None;
+                continue;
+            };
+            clue.retain(|&r| r != 0);
+            let (lines, expected) = match current {
+                Section::Rows => (&mut rows, height),
+                Section::Columns => (&mut columns, width),
+            };
+            // A blank line ends the section once it has all its lines; some
+            // files write a line with no runs as a blank line, others as 0.
+            if line.is_empty() && expected.is_none_or(|e| lines.len() >= e) {
+                section = None;
+                continue;
+            }
+            lines.push(clue);
+        }
+        if rows.is_empty() || columns.is_empty() {
+            return err("expected rows and columns sections".to_string());
+        }
+        if width.is_some_and(|w| w != columns.len()) || height.is_some_and(|h| h != rows.len()) {
+            return err(format!(
+                "width {} and height {} do not match {} columns and {} rows",
+                width.unwrap_or(columns.len()),
+                height.unwrap_or(rows.len()),
+                columns.len(),
+                rows.len()
+            ));
+        }
+        let clues = Clues { rows, columns };
+        for (name, lines, length) in [
+            ("row", &clues.rows, clues.width()),
+            ("column", &clues.columns, clues.height()),
+        ] {
+            for (i, clue) in lines.iter().enumerate() {
+                if clue.iter().sum::<usize>() + clue.len().saturating_sub(1) > length {
+                    return err(format!(
+                        "{} {} does not fit in {} cells",
+                        name,
+                        i + 1,
+                        length
+                    ));
+                }
+            }
+        }
+        let shaded = |lines: &[Vec<usize>]| lines.iter().flatten().sum::<usize>();
+        if shaded(&clues.rows) != shaded(&clues.columns) {
+            return err(format!(
+                "the rows shade {} cells but the columns {}",
+                shaded(&clues.rows),
+                shaded(&clues.columns)
+            ));
+        }
+        Ok(clues)
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::multimove::MultiMove;
+use crate::{Action, Direction};
+
+/// The conventional name of a cell: columns `a` to `g` from left to right
+/// and rows `1` to `7` from top to bottom, so the centre is `d4`.
+pub fn cell_name(x: usize, y: usize) -> String {
+    format!("{}{}", (b'a' + x as u8) as char, y + 1)
+}
+
+pub fn parse_cell(s: &str) -> Option<(usize, usize)> {
+    let mut chars = s.chars();
+    let column = chars.next()?.to_ascii_lowercase();
+    let row = chars.as_str().parse::<usize>().ok()?;
+    if !('a'..='g').contains(&column) || !(1..=7).contains(&row) {
+        return None;
+    }
+    Some((column as usize - 'a' as usize, row - 1))
+}
+
+/// A jump in standard notation, such as `d2-d4` for the peg on `d2`
+/// jumping over `d3` into the centre.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Standard(pub Action);
+
+impl fmt::Display for Standard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (x, y) = self.0.origin();
+        write!(f, "{}", cell_name(x, y))?;
+        match self.0.landing() {
+            Some((tx, ty)) => write!(f, "-{}", cell_name(tx, ty)),
+            None => write!(f, "-?"),
+        }
+    }
+}
+
+/// A chained move in standard notation, listing every cell the peg lands
+/// on: `d2-d4-f4`.
+#[cfg(feature = "std")]
+pub fn chained(jumps: &MultiMove) -> String {
+    let (x, y) = jumps.origin();
+    let mut text = cell_name(x, y);
+    for action in jumps.jumps() {
+        if let Some((tx, ty)) = action.landing() {
+            text.push('-');
+            text.push_str(&cell_name(tx, ty));
+        }
+    }
+    text
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNotationError(String);
+
+impl fmt::Display for ParseNotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid move {:?}, expected cells two apart such as `d2-d4`",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseNotationError {}
+
+/// Reads a single jump or a chain of jumps by one peg, `d2-d4` or
+/// `d2-d4-f4`, into the individual jumps.
+pub fn parse(s: &str) -> Result<Vec<Action>, ParseNotationError> {
+    let err = || ParseNotationError(s.to_string());
+    let cells = s
+        .trim()
+        .split('-')
+        .map(|c| parse_cell(c.trim()).ok_or_else(err))
+        .collect::<Result<Vec<_>, _>>()?;
+    if cells.len() < 2 {
+        return Err(err());
+    }
+    cells
+        .windows(2)
+        .map(|pair| {
+            let [(x, y), (tx, ty)] = [pair[0], pair[1]];
+            let dir = match (tx as isize - x as isize, ty as isize - y as isize) {
+                (0, -2) => Direction::Up,
+                (0, 2) => Direction::Down,
+                (-2, 0) => Direction::Left,
+                (2, 0) => Direction::Right,
+                _ => return Err(err()),
+            };
+            Ok(Action { x, y, dir })
+        })
+        .collect()
+}
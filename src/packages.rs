@@ -0,0 +1,200 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{Action, Grid, ReplayError, Tile};
+
+/// Classic block-removal manoeuvres. Each removes as many pegs as it has
+/// jumps, with any catalyst pegs and holes restored by the end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Package {
+    /// Three pegs in a line.
+    ThreePurge,
+    /// Three pegs forming an L.
+    LPurge,
+    /// A 2x3 block of pegs.
+    SixPurge,
+}
+
+impl Package {
+    pub const ALL: [Package; 3] = [Package::SixPurge, Package::ThreePurge, Package::LPurge];
+
+    fn shape(self) -> &'static [(i32, i32)] {
+        match self {
+            Package::ThreePurge => &[(0, 0), (1, 0), (2, 0)],
+            Package::LPurge => &[(0, 0), (1, 0), (0, 1)],
+            Package::SixPurge => &[(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)],
+        }
+    }
+
+    /// Whether `cells` is this package's shape in some position and
+    /// orientation.
+    pub fn matches(self, cells: &[(usize, usize)]) -> bool {
+        let shape = self.shape();
+        if cells.len() != shape.len() {
+            return false;
+        }
+        let target = normalize(cells.iter().map(|&(x, y)| (x as i32, y as i32)).collect());
+        for swap in [false, true] {
+            for (sx, sy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let oriented = shape
+                    .iter()
+                    .map(|&(x, y)| {
+                        if swap {
+                            (y * sx, x * sy)
+                        } else {
+                            (x * sx, y * sy)
+                        }
+                    })
+                    .collect();
+                if normalize(oriented) == target {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl fmt::Display for Package {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Package::ThreePurge => "3-purge",
+            Package::LPurge => "L-purge",
+            Package::SixPurge => "6-purge",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn normalize(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    for c in &mut cells {
+        *c = (c.0 - min_x, c.1 - min_y);
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// A recognised package within a move list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub package: Package,
+    /// Indices into the move list.
+    pub moves: Range<usize>,
+    /// The cells whose pegs the package removed.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} over moves {}-{} removes",
+            self.package,
+            self.moves.start + 1,
+            self.moves.end
+        )?;
+        for (x, y) in &self.cells {
+            write!(f, " ({}, {})", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+/// Scans `moves` played from `start` for runs of consecutive jumps that
+/// amount to a package, preferring longer packages and never overlapping.
+/// Fails with the first move that is not legal where it is played.
+pub fn annotate(start: &Grid, moves: &[Action]) -> Result<Vec<Annotation>, ReplayError> {
+    start.play_moves(moves)?;
+    let mut positions = vec![*start];
+    for &action in moves {
+        positions.push(positions.last().unwrap().perform_action(action));
+    }
+    let mut found = Vec::new();
+    let mut i = 0;
+    'scan: while i < moves.len() {
+        for package in Package::ALL {
+            let len = package.shape().len();
+            if i + len > moves.len() {
+                continue;
+            }
+            if let Some(cells) = removed_only(&positions[i], &positions[i + len]) {
+                if package.matches(&cells) {
+                    found.push(Annotation {
+                        package,
+                        moves: i..i + len,
+                        cells,
+                    });
+                    i += len;
+                    continue 'scan;
+                }
+            }
+        }
+        i += 1;
+    }
+    Ok(found)
+}
+
+/// The cells that lost their peg between `before` and `after`, provided
+/// nothing else changed.
+fn removed_only(before: &Grid, after: &Grid) -> Option<Vec<(usize, usize)>> {
+    let mut cells = Vec::new();
+    for x in 0..7 {
+        for y in 0..7 {
+            match (before.grid[x][y], after.grid[x][y]) {
+                (Tile::Filled, Tile::Empty) => cells.push((x, y)),
+                (a, b) if a != b => return None,
+                _ => {}
+            }
+        }
+    }
+    Some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, IllegalMove};
+
+    /// The three pegs of a row cleared with one catalyst peg, which ends
+    /// where it started.
+    const THREE_PURGE: &str = "..---../..---../--O----/--OOO--/-------/..---../..---..";
+
+    fn three_purge() -> Vec<Action> {
+        vec![
+            Action::new(2, 2, Direction::Down),
+            Action::new(4, 3, Direction::Left),
+            Action::new(2, 4, Direction::Up),
+        ]
+    }
+
+    #[test]
+    fn finds_a_three_purge() {
+        let start: Grid = THREE_PURGE.parse().unwrap();
+        assert_eq!(
+            annotate(&start, &three_purge()),
+            Ok(vec![Annotation {
+                package: Package::ThreePurge,
+                moves: 0..3,
+                cells: vec![(2, 3), (3, 3), (4, 3)],
+            }])
+        );
+    }
+
+    #[test]
+    fn refuses_an_illegal_move() {
+        let start: Grid = THREE_PURGE.parse().unwrap();
+        let mut moves = three_purge();
+        let illegal = Action::new(0, 3, Direction::Right);
+        moves.insert(1, illegal);
+        assert_eq!(
+            annotate(&start, &moves),
+            Err(ReplayError {
+                index: 1,
+                action: illegal,
+                reason: IllegalMove::NoPeg,
+            })
+        );
+    }
+}
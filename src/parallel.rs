@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::stats::SearchStats;
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, GameTree, Grid, SearchStrategy};
+
+/// How the search for one board ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Solved(Vec<Action>, SearchStats),
+    Unsolvable(SearchStats),
+    Timeout,
+}
+
+/// Searches for a line from `board` to `goal`, giving up after `timeout`.
+pub fn solve_one(
+    board: Grid,
+    goal: Grid,
+    strategy: SearchStrategy,
+    timeout: Option<Duration>,
+) -> Outcome {
+    solve_tree(&GameTree::new(board, Vec::new()), &goal, strategy, timeout)
+}
+
+/// [`solve_one`] from `tree`, which may carry a seed.
+pub fn solve_tree(
+    tree: &GameTree,
+    goal: &Grid,
+    strategy: SearchStrategy,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let stop = AtomicBool::new(false);
+    let (found, stats) = thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel::<()>();
+        if let Some(timeout) = timeout {
+            let stop = &stop;
+            scope.spawn(move || {
+                if rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+        let result = tree.search_cancellable(goal, strategy, &stop);
+        drop(tx);
+        result
+    });
+    match found {
+        Some(g) => Outcome::Solved(g.history().to_vec(), stats),
+        // A search that was stopped found nothing, which proves nothing.
+        None if stop.load(Ordering::Relaxed) => Outcome::Timeout,
+        None => Outcome::Unsolvable(stats),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// The goal for every board; `None` means the centre peg of each.
+    pub goal: Option<Grid>,
+    pub strategy: SearchStrategy,
+    /// Worker threads; 0 uses one per available core.
+    pub threads: usize,
+    /// Per board.
+    pub timeout: Option<Duration>,
+    /// Remember boards found unsolvable, up to the symmetries they share
+    /// with their goal, so that a later board equal to one of them under
+    /// such a symmetry is answered without searching.
+    pub share_unsolvable: bool,
+}
+
+/// Solves `boards` on a pool of threads, returning an outcome per board in
+/// the same order.
+pub fn solve_many(boards: &[Grid], options: &BatchOptions) -> Vec<Outcome> {
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(boards.len())
+    .max(1);
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(vec![None; boards.len()]);
+    let unsolvable: Mutex<HashSet<(u64, u64, u64)>> = Mutex::new(HashSet::new());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&board) = boards.get(index) else {
+                    break;
+                };
+                let goal = options.goal.unwrap_or_else(|| board.single_peg(3, 3));
+                let key = options.share_unsolvable.then(|| cache_key(&board, &goal));
+                let known = key.is_some_and(|k| unsolvable.lock().unwrap().contains(&k));
+                let outcome = if known {
+                    Outcome::Unsolvable(SearchStats::default())
+                } else {
+                    solve_one(board, goal, options.strategy, options.timeout)
+                };
+                if let (Some(key), Outcome::Unsolvable(_)) = (key, &outcome) {
+                    unsolvable.lock().unwrap().insert(key);
+                }
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect()
+}
+
+/// The layout, the goal and the board canonicalised under the symmetries
+/// that the goal shares with the layout, which map lines to the goal onto
+/// lines to the goal.
+fn cache_key(board: &Grid, goal: &Grid) -> (u64, u64, u64) {
+    let layout = symmetry::layout_symmetries(board);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    (
+        board.with_pegs(u64::MAX).encode(),
+        goal.encode(),
+        symmetry::canonical(board.encode(), &group),
+    )
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::rng::Rng;
+use crate::Grid;
+
+/// Plays uniformly random legal moves from `grid` until none remain and
+/// returns the stranded position.
+pub fn random_playout(grid: &Grid, rng: &mut Rng) -> Grid {
+    let mut cur = *grid;
+    loop {
+        let actions = cur.valid_actions();
+        if actions.is_empty() {
+            return cur;
+        }
+        cur = cur.perform_action(actions[rng.below(actions.len())]);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayoutReport {
+    pub playouts: u32,
+    /// Number of playouts that ended with the given number of pegs.
+    pub final_pegs: BTreeMap<u32, u32>,
+}
+
+impl PlayoutReport {
+    pub fn count_with(&self, pegs: u32) -> u32 {
+        self.final_pegs.get(&pegs).copied().unwrap_or(0)
+    }
+
+    /// Empirical probability that random play finishes with a single peg.
+    pub fn success_rate(&self) -> f64 {
+        if self.playouts == 0 {
+            return 0.0;
+        }
+        self.count_with(1) as f64 / self.playouts as f64
+    }
+
+    pub fn mean_pegs(&self) -> f64 {
+        if self.playouts == 0 {
+            return 0.0;
+        }
+        let total: u64 = self
+            .final_pegs
+            .iter()
+            .map(|(&pegs, &n)| pegs as u64 * n as u64)
+            .sum();
+        total as f64 / self.playouts as f64
+    }
+}
+
+impl fmt::Display for PlayoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} random playouts", self.playouts)?;
+        writeln!(f, "pegs  count  share")?;
+        for (pegs, n) in &self.final_pegs {
+            let share = *n as f64 / self.playouts as f64 * 100.0;
+            writeln!(f, "{:>4}  {:>5}  {:>5.1}%", pegs, n, share)?;
+        }
+        writeln!(f, "mean pegs left: {:.2}", self.mean_pegs())?;
+        write!(f, "one-peg rate: {:.4}", self.success_rate())
+    }
+}
+
+/// Runs `n` random playouts from `grid` using a generator seeded with `seed`.
+pub fn estimate(grid: &Grid, n: u32, seed: u64) -> PlayoutReport {
+    let mut rng = Rng::new(seed);
+    let mut final_pegs = BTreeMap::new();
+    for _ in 0..n {
+        let end = random_playout(grid, &mut rng);
+        *final_pegs.entry(end.filled_count).or_insert(0) += 1;
+    }
+    PlayoutReport {
+        playouts: n,
+        final_pegs,
+    }
+}
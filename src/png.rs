@@ -0,0 +1,205 @@
+use crate::raster::{Image, Palette};
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier occurrences of a three byte prefix are tried.
+const CHAIN: usize = 64;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Packs bits least significant first, as DEFLATE expects.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, width: u32) {
+        self.buffer |= (value as u64) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which DEFLATE stores most significant bit
+    /// first.
+    fn code(&mut self, code: u32, width: u32) {
+        self.write(code.reverse_bits() >> (32 - width), width);
+    }
+
+    /// A symbol of the fixed literal and length code.
+    fn literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn hash(data: &[u8]) -> usize {
+    ((data[0] as usize) << 10 ^ (data[1] as usize) << 5 ^ data[2] as usize) & 0x7fff
+}
+
+/// Records `pos` as the latest occurrence of the three bytes starting
+/// there.
+fn insert(data: &[u8], pos: usize, head: &mut [usize], prev: &mut [usize]) {
+    if pos + MIN_MATCH <= data.len() {
+        let h = hash(&data[pos..]);
+        prev[pos] = head[h];
+        head[h] = pos;
+    }
+}
+
+/// Compresses `data` into a single DEFLATE block with the fixed Huffman
+/// codes and greedy LZ77 matching.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    out.write(1, 1);
+    out.write(1, 2);
+    let mut head = vec![usize::MAX; 1 << 15];
+    let mut prev = vec![usize::MAX; data.len()];
+    let mut pos = 0;
+    while pos < data.len() {
+        let (mut best_len, mut best_dist) = (0, 0);
+        if pos + MIN_MATCH <= data.len() {
+            let mut candidate = head[hash(&data[pos..])];
+            let limit = (data.len() - pos).min(MAX_MATCH);
+            for _ in 0..CHAIN {
+                if candidate == usize::MAX || pos - candidate > WINDOW {
+                    break;
+                }
+                let len = (0..limit)
+                    .take_while(|&i| data[candidate + i] == data[pos + i])
+                    .count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, pos - candidate);
+                }
+                candidate = prev[candidate];
+            }
+        }
+        if best_len >= MIN_MATCH {
+            let code = LENGTH_BASE
+                .iter()
+                .rposition(|&b| b as usize <= best_len)
+                .unwrap();
+            out.literal(257 + code as u16);
+            out.write(
+                (best_len - LENGTH_BASE[code] as usize) as u32,
+                LENGTH_EXTRA[code] as u32,
+            );
+            let code = DISTANCE_BASE
+                .iter()
+                .rposition(|&b| b as usize <= best_dist)
+                .unwrap();
+            out.code(code as u32, 5);
+            out.write(
+                (best_dist - DISTANCE_BASE[code] as usize) as u32,
+                DISTANCE_EXTRA[code] as u32,
+            );
+            for p in pos..pos + best_len {
+                insert(data, p, &mut head, &mut prev);
+            }
+            pos += best_len;
+        } else {
+            out.literal(data[pos] as u16);
+            insert(data, pos, &mut head, &mut prev);
+            pos += 1;
+        }
+    }
+    out.literal(256);
+    out.finish()
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// Encodes `image` as an indexed-colour PNG.
+pub fn encode(image: &Image, palette: &Palette) -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    header.extend([8, 3, 0, 0, 0]);
+    chunk(&mut out, b"IHDR", &header);
+    chunk(&mut out, b"PLTE", &palette.colors().concat());
+
+    // Every row starts with filter type 0, no filtering.
+    let mut raw = Vec::with_capacity((image.width + 1) * image.height);
+    for row in image.pixels.chunks(image.width.max(1)) {
+        raw.push(0);
+        raw.extend(row);
+    }
+    let mut compressed = vec![0x78, 0x01];
+    compressed.extend(deflate(&raw));
+    compressed.extend(adler32(&raw).to_be_bytes());
+    chunk(&mut out, b"IDAT", &compressed);
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
@@ -0,0 +1,107 @@
+//! Several solvers raced against the same board on threads of their own,
+//! for when it is not clear which will suit it: the first to find a line
+//! wins and the rest are told to stop.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::registry::{Builtin, Solver, DEFAULT_BEAM_WIDTH};
+use crate::stats::SearchStats;
+use crate::{Action, Grid, SearchStrategy};
+
+/// Raced when no solvers are named: a few orderings of the depth-first
+/// search, the bidirectional search, IDA* and a beam.
+pub const DEFAULT: [Builtin; 6] = [
+    Builtin::Search {
+        strategy: SearchStrategy::DepthFirst,
+        seed: None,
+    },
+    Builtin::Search {
+        strategy: SearchStrategy::DepthFirst,
+        seed: Some(1),
+    },
+    Builtin::Search {
+        strategy: SearchStrategy::DepthFirst,
+        seed: Some(2),
+    },
+    Builtin::Search {
+        strategy: SearchStrategy::Bidirectional,
+        seed: None,
+    },
+    Builtin::IdaStar,
+    Builtin::Beam {
+        width: DEFAULT_BEAM_WIDTH,
+    },
+];
+
+/// How a race ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Finish {
+    /// `solvers[solver]` found `moves`.
+    Solved {
+        solver: usize,
+        moves: Vec<Action>,
+        stats: SearchStats,
+    },
+    /// `solvers[solver]` finished without finding a line, which proves
+    /// there is none.
+    Unsolvable {
+        solver: usize,
+        stats: SearchStats,
+    },
+    /// Every solver gave up without proving anything.
+    GaveUp,
+    Timeout,
+}
+
+/// Runs every one of `solvers` on its own thread and returns as soon as one
+/// of them settles whether `goal` can be reached from `board`, or when
+/// `timeout` has passed. The others are stopped before it returns.
+pub fn race(
+    board: &Grid,
+    goal: &Grid,
+    solvers: &[Box<dyn Solver>],
+    timeout: Option<Duration>,
+) -> Finish {
+    let stop = AtomicBool::new(false);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        for (index, solver) in solvers.iter().enumerate() {
+            let (tx, stop) = (tx.clone(), &stop);
+            scope.spawn(move || {
+                let (line, stats) = solver.solve(board, goal, stop);
+                // Nobody is listening once the race has been decided.
+                let _ = tx.send((index, line, stats));
+            });
+        }
+        drop(tx);
+        let finish = loop {
+            let received = match deadline {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok((solver, Some(moves), stats)) => {
+                    break Finish::Solved {
+                        solver,
+                        moves,
+                        stats,
+                    }
+                }
+                Ok((solver, None, stats)) if solvers[solver].is_complete() => {
+                    break Finish::Unsolvable { solver, stats }
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break Finish::Timeout,
+                Err(RecvTimeoutError::Disconnected) => break Finish::GaveUp,
+            }
+        };
+        stop.store(true, Ordering::Relaxed);
+        finish
+    })
+}
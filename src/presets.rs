@@ -0,0 +1,121 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diamond::Diamond;
+use crate::Grid;
+
+/// Classic teaching positions on the English board, each solvable down to
+/// a single peg in the centre, and the two diamond boards, too big for a
+/// [`Grid`] and played as [`Diamond`]s. [`Preset::ALL`] lists the English
+/// positions from fewest to most pegs, then the diamonds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Preset {
+    Cross,
+    Plus,
+    Fireplace,
+    Lamp,
+    Pyramid,
+    Arrow,
+    Diamond,
+    Full,
+    Diamond32,
+    Diamond41,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 10] = [
+        Preset::Cross,
+        Preset::Plus,
+        Preset::Fireplace,
+        Preset::Lamp,
+        Preset::Pyramid,
+        Preset::Arrow,
+        Preset::Diamond,
+        Preset::Full,
+        Preset::Diamond32,
+        Preset::Diamond41,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Cross => "cross",
+            Preset::Plus => "plus",
+            Preset::Fireplace => "fireplace",
+            Preset::Lamp => "lamp",
+            Preset::Pyramid => "pyramid",
+            Preset::Arrow => "arrow",
+            Preset::Diamond => "diamond",
+            Preset::Full => "full",
+            Preset::Diamond32 => Diamond::ThirtyTwo.name(),
+            Preset::Diamond41 => Diamond::FortyOne.name(),
+        }
+    }
+
+    fn notation(&self) -> Option<&'static str> {
+        Some(match self {
+            Preset::Cross => "..---../..-O-../--OOO--/---O---/---O---/..---../..---..",
+            Preset::Plus => "..---../..-O-../---O---/-OOOOO-/---O---/..-O-../..---..",
+            Preset::Fireplace => "..OOO../..OOO../--OOO--/--O-O--/-------/..---../..---..",
+            Preset::Lamp => "..-O-../..OOO../--OOO--/--OOO--/--OOO--/..---../..---..",
+            Preset::Pyramid => "..---../..-O-../--OOO--/-OOOOO-/OOOOOOO/..---../..---..",
+            Preset::Arrow => "..-O-../..OOO../-OOOOO-/---O---/---O---/..OOO../..OOO..",
+            Preset::Diamond => "..-O-../..OOO../-OOOOO-/OOO-OOO/-OOOOO-/..OOO../..-O-..",
+            Preset::Full => "..OOO../..OOO../OOOOOOO/OOO-OOO/OOOOOOO/..OOO../..OOO..",
+            Preset::Diamond32 | Preset::Diamond41 => return None,
+        })
+    }
+
+    /// The position on the English board, or `None` for a diamond board.
+    pub fn position(&self) -> Option<Grid> {
+        self.notation().map(|notation| notation.parse().unwrap())
+    }
+
+    /// The single centre peg every English preset is played down to.
+    pub fn goal(&self) -> Option<Grid> {
+        self.position().map(|_| Grid::new().single_peg(3, 3))
+    }
+
+    /// The diamond board this preset names, if it is one.
+    pub fn diamond(&self) -> Option<Diamond> {
+        match self {
+            Preset::Diamond32 => Some(Diamond::ThirtyTwo),
+            Preset::Diamond41 => Some(Diamond::FortyOne),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPreset(pub String);
+
+impl fmt::Display for UnknownPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Preset::ALL.iter().map(Preset::name).collect();
+        write!(
+            f,
+            "unknown preset {:?}, expected one of {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl Error for UnknownPreset {}
+
+impl FromStr for Preset {
+    type Err = UnknownPreset;
+
+    fn from_str(s: &str) -> Result<Preset, UnknownPreset> {
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownPreset(s.to_string()))
+    }
+}
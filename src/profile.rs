@@ -0,0 +1,275 @@
+//! A player's statistics across games, kept in a small local file so that
+//! the play mode has something to improve on.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::daily::Date;
+use crate::Grid;
+
+/// Solves kept on the leaderboard.
+pub const LEADERBOARD_LEN: usize = 10;
+
+/// A solved game on the leaderboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Solve {
+    pub seconds: u64,
+    pub start: Grid,
+}
+
+/// The outcome of a timed challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChallengeResult {
+    pub score: u64,
+    pub pegs: u32,
+    pub seconds_left: u64,
+    pub start: Grid,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// Games played until no jump was left.
+    pub games_finished: u64,
+    pub games_solved: u64,
+    /// Fewest pegs left at the end of a finished game.
+    pub best_pegs: Option<u32>,
+    /// The days in a row on which the daily puzzle was solved, ending on
+    /// `last_daily`.
+    pub daily_streak: u32,
+    pub best_daily_streak: u32,
+    pub last_daily: Option<Date>,
+    /// The fastest solves, fastest first.
+    pub leaderboard: Vec<Solve>,
+    /// Every timed challenge, in the order played.
+    pub challenges: Vec<ChallengeResult>,
+}
+
+impl Profile {
+    /// Where the statistics live: `$PUZZLETHING_STATS`, or `puzzlething/stats`
+    /// under `$XDG_DATA_HOME` or `~/.local/share`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("PUZZLETHING_STATS") {
+            return Some(path.into());
+        }
+        let data = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => Path::new(&env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(data.join("puzzlething").join("stats"))
+    }
+
+    /// Reads the statistics at `path`, or empty ones if there is no file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Profile> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse().map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Profile::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the statistics to `path`, creating its directory if needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    /// The fastest solve, if any.
+    pub fn fastest_solve(&self) -> Option<u64> {
+        self.leaderboard.first().map(|solve| solve.seconds)
+    }
+
+    /// Counts a game that ended with `pegs` left after `seconds`, solved
+    /// when it reached its goal. Returns the solve's place on the
+    /// leaderboard, from 1, if it made it.
+    pub fn record_game(
+        &mut self,
+        start: &Grid,
+        pegs: u32,
+        solved: bool,
+        seconds: u64,
+    ) -> Option<usize> {
+        self.games_finished += 1;
+        self.best_pegs = Some(self.best_pegs.map_or(pegs, |best| best.min(pegs)));
+        if !solved {
+            return None;
+        }
+        self.games_solved += 1;
+        let place = self
+            .leaderboard
+            .partition_point(|solve| solve.seconds <= seconds);
+        if place >= LEADERBOARD_LEN {
+            return None;
+        }
+        let start = *start;
+        self.leaderboard.insert(place, Solve { seconds, start });
+        self.leaderboard.truncate(LEADERBOARD_LEN);
+        Some(place + 1)
+    }
+
+    /// The highest scoring challenge, the earliest of any tied.
+    pub fn best_challenge(&self) -> Option<&ChallengeResult> {
+        self.challenges
+            .iter()
+            .rev()
+            .max_by_key(|result| result.score)
+    }
+
+    /// Counts the daily puzzle for `date` as solved. The streak carries on
+    /// from the day before, and solving the same day twice changes nothing.
+    pub fn record_daily(&mut self, date: Date) {
+        let yesterday = Date::from_days(date.days() - 1);
+        match self.last_daily {
+            Some(last) if last == date => return,
+            Some(last) if last == yesterday => self.daily_streak += 1,
+            _ => self.daily_streak = 1,
+        }
+        self.last_daily = Some(date);
+        self.best_daily_streak = self.best_daily_streak.max(self.daily_streak);
+    }
+
+    /// The streak as of `today`: zero once a day has been missed.
+    pub fn current_daily_streak(&self, today: Date) -> u32 {
+        match self.last_daily {
+            Some(last) if today.days() - last.days() <= 1 => self.daily_streak,
+            _ => 0,
+        }
+    }
+}
+
+/// The file format: `key = value` lines, with one `solve = SECONDS BOARD`
+/// line per leaderboard entry and one `challenge = SCORE PEGS SECONDS
+/// BOARD` line per timed challenge.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# puzzlething statistics")?;
+        writeln!(f, "games_finished = {}", self.games_finished)?;
+        writeln!(f, "games_solved = {}", self.games_solved)?;
+        if let Some(pegs) = self.best_pegs {
+            writeln!(f, "best_pegs = {}", pegs)?;
+        }
+        writeln!(f, "daily_streak = {}", self.daily_streak)?;
+        writeln!(f, "best_daily_streak = {}", self.best_daily_streak)?;
+        if let Some(date) = self.last_daily {
+            writeln!(f, "last_daily = {}", date)?;
+        }
+        for solve in &self.leaderboard {
+            writeln!(f, "solve = {} {}", solve.seconds, solve.start.notation())?;
+        }
+        for result in &self.challenges {
+            writeln!(
+                f,
+                "challenge = {} {} {} {}",
+                result.score,
+                result.pegs,
+                result.seconds_left,
+                result.start.notation()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseProfileError {
+    /// One-based line of the problem.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseProfileError {}
+
+impl FromStr for Profile {
+    type Err = ParseProfileError;
+
+    fn from_str(text: &str) -> Result<Profile, ParseProfileError> {
+        let mut profile = Profile::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |message: String| ParseProfileError {
+                line: i + 1,
+                message,
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`".to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| err(format!("invalid number {:?}", value)))
+            };
+            match key {
+                "games_finished" => profile.games_finished = number()?,
+                "games_solved" => profile.games_solved = number()?,
+                "best_pegs" => profile.best_pegs = Some(number()? as u32),
+                "daily_streak" => profile.daily_streak = number()? as u32,
+                "best_daily_streak" => profile.best_daily_streak = number()? as u32,
+                "last_daily" => {
+                    profile.last_daily =
+                        Some(value.parse::<Date>().map_err(|e| err(e.to_string()))?)
+                }
+                "solve" => {
+                    let (seconds, start) = value
+                        .split_once(' ')
+                        .ok_or_else(|| err("expected `solve = SECONDS BOARD`".to_string()))?;
+                    profile.leaderboard.push(Solve {
+                        seconds: seconds
+                            .parse()
+                            .map_err(|_| err(format!("invalid number {:?}", seconds)))?,
+                        start: start
+                            .trim()
+                            .parse::<Grid>()
+                            .map_err(|e| err(e.to_string()))?,
+                    });
+                }
+                "challenge" => {
+                    let [score, pegs, seconds_left, start] =
+                        value.split_whitespace().collect::<Vec<_>>()[..]
+                    else {
+                        return Err(err(
+                            "expected `challenge = SCORE PEGS SECONDS BOARD`".to_string()
+                        ));
+                    };
+                    let number = |field: &str| {
+                        field
+                            .parse::<u64>()
+                            .map_err(|_| err(format!("invalid number {:?}", field)))
+                    };
+                    profile.challenges.push(ChallengeResult {
+                        score: number(score)?,
+                        pegs: number(pegs)? as u32,
+                        seconds_left: number(seconds_left)?,
+                        start: start.parse::<Grid>().map_err(|e| err(e.to_string()))?,
+                    });
+                }
+                _ => return Err(err(format!("unknown key {:?}", key))),
+            }
+        }
+        profile.leaderboard.sort_by_key(|solve| solve.seconds);
+        profile.leaderboard.truncate(LEADERBOARD_LEN);
+        Ok(profile)
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::bitboard::JumpTable;
+use crate::stats::SearchStats;
+use crate::symmetry::{self, Symmetry};
+use crate::trace::{self, Level};
+use crate::{Action, Grid};
+
+const INFINITY: u32 = u32::MAX;
+const NO_PARENT: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct Node {
+    bits: u64,
+    parent: usize,
+    action: Option<Action>,
+    children: Vec<usize>,
+    expanded: bool,
+    proof: u32,
+    disproof: u32,
+}
+
+/// Proof-number search for a line from `start` to `goal`.
+///
+/// Peg solitaire only has one player, so every node is an OR node: its
+/// proof number is the smallest proof number among its children and its
+/// disproof number is their sum. The search repeatedly expands the most
+/// proving leaf and stops once the root is proved or disproved. Positions
+/// that have been disproved once are remembered up to symmetry so they are
+/// never expanded again anywhere in the tree.
+pub fn search(start: &Grid, goal: &Grid) -> Option<Vec<Action>> {
+    search_counted(
+        start,
+        goal,
+        &mut SearchStats::default(),
+        &AtomicBool::new(false),
+        None,
+    )
+}
+
+/// The search behind [`search`], counting its work in `stats` and giving up
+/// once `stop` is set. A `seed` shuffles the order moves are tried in.
+pub(crate) fn search_counted(
+    start: &Grid,
+    goal: &Grid,
+    stats: &mut SearchStats,
+    stop: &AtomicBool,
+    seed: Option<u64>,
+) -> Option<Vec<Action>> {
+    let table = JumpTable::ordered(start, seed);
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let goal_bits = goal.encode();
+    let mut dead = HashSet::new();
+    let mut nodes = vec![Node {
+        bits: start.encode(),
+        parent: NO_PARENT,
+        action: None,
+        children: Vec::new(),
+        expanded: false,
+        proof: 1,
+        disproof: 1,
+    }];
+    evaluate(&mut nodes[0], &table, goal_bits, &dead, &group, stats);
+
+    while nodes[0].proof != 0 && nodes[0].disproof != 0 {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut cur = 0;
+        while nodes[cur].expanded {
+            cur = *nodes[cur]
+                .children
+                .iter()
+                .min_by_key(|&&c| nodes[c].proof)
+                .unwrap();
+        }
+
+        nodes[cur].expanded = true;
+        stats.nodes_expanded += 1;
+        if stats
+            .nodes_expanded
+            .is_multiple_of(trace::PROGRESS_INTERVAL)
+        {
+            trace::event(
+                Level::Debug,
+                "proof_number",
+                "progress",
+                &[
+                    ("expanded", &stats.nodes_expanded),
+                    ("tree", &nodes.len()),
+                    ("root_proof", &nodes[0].proof),
+                    ("root_disproof", &nodes[0].disproof),
+                ],
+            );
+        }
+        for (action, bits) in table.forward(nodes[cur].bits) {
+            let mut child = Node {
+                bits,
+                parent: cur,
+                action: Some(action),
+                children: Vec::new(),
+                expanded: false,
+                proof: 1,
+                disproof: 1,
+            };
+            evaluate(&mut child, &table, goal_bits, &dead, &group, stats);
+            nodes.push(child);
+            let index = nodes.len() - 1;
+            nodes[cur].children.push(index);
+        }
+        stats.observe_frontier(nodes.len());
+
+        while cur != NO_PARENT {
+            let (proof, disproof) = if nodes[cur].children.is_empty() {
+                (INFINITY, 0)
+            } else {
+                let children = &nodes[cur].children;
+                let proof = children.iter().map(|&c| nodes[c].proof).min().unwrap();
+                let disproof = children
+                    .iter()
+                    .fold(0u32, |acc, &c| acc.saturating_add(nodes[c].disproof));
+                (proof, disproof)
+            };
+            let node = &mut nodes[cur];
+            if node.proof == proof && node.disproof == disproof {
+                break;
+            }
+            node.proof = proof;
+            node.disproof = disproof;
+            if disproof == 0 {
+                dead.insert(symmetry::canonical(node.bits, &group));
+                node.children.clear();
+                stats.observe_memo(&dead);
+            }
+            cur = node.parent;
+        }
+    }
+
+    if nodes[0].proof != 0 {
+        return None;
+    }
+    let mut moves = Vec::new();
+    let mut cur = 0;
+    while nodes[cur].bits != goal_bits {
+        cur = *nodes[cur]
+            .children
+            .iter()
+            .find(|&&c| nodes[c].proof == 0)
+            .unwrap();
+        moves.push(nodes[cur].action.unwrap());
+    }
+    Some(moves)
+}
+
+/// Sets the numbers of a fresh leaf: proved if it is the goal, disproved if
+/// it is stuck or already known to be dead.
+fn evaluate(
+    node: &mut Node,
+    table: &JumpTable,
+    goal: u64,
+    dead: &HashSet<u64>,
+    group: &[Symmetry],
+    stats: &mut SearchStats,
+) {
+    if node.bits == goal {
+        node.proof = 0;
+        node.disproof = INFINITY;
+        node.expanded = true;
+        return;
+    }
+    if table.forward(node.bits).next().is_none() {
+        stats.prune("stuck");
+    } else if dead.contains(&symmetry::canonical(node.bits, group)) {
+        stats.duplicates += 1;
+        stats.prune("disproved");
+    } else {
+        return;
+    }
+    node.proof = INFINITY;
+    node.disproof = 0;
+    node.expanded = true;
+}
@@ -0,0 +1,280 @@
+//! Search that works on any puzzle described by a [`Puzzle`]: its states,
+//! the moves out of each and which states are solved. Peg solitaire is
+//! [`PegSolitaire`]; other puzzles implement the trait the same way and get
+//! [`breadth_first`], [`depth_first`] and [`a_star`] for free.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::stats::SearchStats;
+use crate::{Action, Grid};
+
+/// A position of a puzzle. The searches remember states they have seen,
+/// so states are compared and hashed whole.
+pub trait State: Clone + Eq + Hash {}
+
+impl<T: Clone + Eq + Hash> State for T {}
+
+pub trait Move: Clone {}
+
+impl<T: Clone> Move for T {}
+
+pub trait Puzzle {
+    type State: State;
+    type Move: Move;
+
+    /// Every move from `state` with the state it leads to.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Move, Self::State)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// A lower bound on the moves left to a goal, which guides
+    /// [`a_star`]. Never overestimating keeps its lines shortest.
+    fn estimate(&self, _state: &Self::State) -> usize {
+        0
+    }
+}
+
+/// Which of the generic searches to run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Search {
+    BreadthFirst,
+    DepthFirst,
+    #[default]
+    AStar,
+}
+
+impl Search {
+    pub const ALL: [Search; 3] = [Search::BreadthFirst, Search::DepthFirst, Search::AStar];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Search::BreadthFirst => "bfs",
+            Search::DepthFirst => "dfs",
+            Search::AStar => "astar",
+        }
+    }
+
+    pub fn run<P: Puzzle>(
+        &self,
+        puzzle: &P,
+        start: &P::State,
+    ) -> (Option<Vec<P::Move>>, SearchStats) {
+        match self {
+            Search::BreadthFirst => breadth_first(puzzle, start),
+            Search::DepthFirst => depth_first(puzzle, start),
+            Search::AStar => a_star(puzzle, start),
+        }
+    }
+}
+
+impl fmt::Display for Search {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownSearch(pub String);
+
+impl fmt::Display for UnknownSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown search {:?}, expected bfs, dfs or astar", self.0)
+    }
+}
+
+impl Error for UnknownSearch {}
+
+impl FromStr for Search {
+    type Err = UnknownSearch;
+
+    fn from_str(s: &str) -> Result<Search, UnknownSearch> {
+        match s.to_ascii_lowercase().as_str() {
+            "bfs" | "breadth-first" => Ok(Search::BreadthFirst),
+            "dfs" | "depth-first" => Ok(Search::DepthFirst),
+            "astar" | "a*" => Ok(Search::AStar),
+            _ => Err(UnknownSearch(s.to_string())),
+        }
+    }
+}
+
+/// The state a state was first reached from, and the move between them.
+type Parent<P> = Option<(<P as Puzzle>::State, <P as Puzzle>::Move)>;
+
+/// A state reached by [`a_star`] with the index of its parent's node and
+/// the move from there.
+type Node<P> = (<P as Puzzle>::State, Option<(usize, <P as Puzzle>::Move)>);
+
+/// A line from `start` to a goal with as few moves as possible.
+pub fn breadth_first<P: Puzzle>(
+    puzzle: &P,
+    start: &P::State,
+) -> (Option<Vec<P::Move>>, SearchStats) {
+    let started = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut parents: HashMap<P::State, Parent<P>> = HashMap::new();
+    parents.insert(start.clone(), None);
+    let mut frontier = VecDeque::from([start.clone()]);
+    let mut found = puzzle.is_goal(start).then(|| start.clone());
+    while let (None, Some(state)) = (&found, frontier.pop_front()) {
+        stats.nodes_expanded += 1;
+        for (action, next) in puzzle.successors(&state) {
+            match parents.entry(next.clone()) {
+                Entry::Occupied(_) => stats.duplicates += 1,
+                Entry::Vacant(slot) => {
+                    slot.insert(Some((state.clone(), action)));
+                    if puzzle.is_goal(&next) {
+                        found = Some(next);
+                        break;
+                    }
+                    frontier.push_back(next);
+                }
+            }
+        }
+        stats.observe_frontier(frontier.len());
+    }
+    stats.observe_memo_size(
+        parents.len(),
+        parents.capacity() * std::mem::size_of::<P::State>(),
+    );
+    let line = found.map(|mut state| {
+        let mut line = Vec::new();
+        while let Some(Some((previous, action))) = parents.get(&state) {
+            line.push(action.clone());
+            state = previous.clone();
+        }
+        line.reverse();
+        line
+    });
+    stats.elapsed = started.elapsed();
+    (line, stats)
+}
+
+/// The first line to a goal that a depth-first search comes across,
+/// visiting each state at most once.
+pub fn depth_first<P: Puzzle>(puzzle: &P, start: &P::State) -> (Option<Vec<P::Move>>, SearchStats) {
+    let started = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut line = Vec::new();
+    let found = if puzzle.is_goal(start) {
+        true
+    } else {
+        let mut seen = HashSet::from([start.clone()]);
+        // The moves not yet tried from each state along the current line.
+        let mut stack = vec![puzzle.successors(start).into_iter()];
+        stats.nodes_expanded += 1;
+        let mut found = false;
+        while let Some(moves) = stack.last_mut() {
+            let Some((action, next)) = moves.next() else {
+                stack.pop();
+                line.pop();
+                continue;
+            };
+            if !seen.insert(next.clone()) {
+                stats.duplicates += 1;
+                continue;
+            }
+            line.push(action);
+            if puzzle.is_goal(&next) {
+                found = true;
+                break;
+            }
+            stats.nodes_expanded += 1;
+            stack.push(puzzle.successors(&next).into_iter());
+            stats.observe_frontier(stack.len());
+        }
+        stats.observe_memo(&seen);
+        found
+    };
+    stats.elapsed = started.elapsed();
+    (found.then_some(line), stats)
+}
+
+/// A shortest line to a goal, searching first where the moves made plus
+/// [`Puzzle::estimate`] is least.
+pub fn a_star<P: Puzzle>(puzzle: &P, start: &P::State) -> (Option<Vec<P::Move>>, SearchStats) {
+    let started = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut nodes: Vec<Node<P>> = vec![(start.clone(), None)];
+    let mut best: HashMap<P::State, usize> = HashMap::from([(start.clone(), 0)]);
+    // Ties go to the deeper state, which is nearer a goal.
+    let mut open = BinaryHeap::from([(Reverse(puzzle.estimate(start)), 0, 0)]);
+    let mut found = None;
+    while let Some((_, depth, index)) = open.pop() {
+        let state = nodes[index].0.clone();
+        if best[&state] < depth {
+            // Reached again by a shorter line since it was queued.
+            stats.duplicates += 1;
+            continue;
+        }
+        if puzzle.is_goal(&state) {
+            found = Some(index);
+            break;
+        }
+        stats.nodes_expanded += 1;
+        for (action, next) in puzzle.successors(&state) {
+            let depth = depth + 1;
+            if best.get(&next).is_some_and(|&known| known <= depth) {
+                stats.duplicates += 1;
+                continue;
+            }
+            best.insert(next.clone(), depth);
+            let estimate = depth + puzzle.estimate(&next);
+            nodes.push((next, Some((index, action))));
+            open.push((Reverse(estimate), depth, nodes.len() - 1));
+        }
+        stats.observe_frontier(open.len());
+    }
+    stats.observe_memo_size(
+        best.len(),
+        best.capacity() * std::mem::size_of::<P::State>(),
+    );
+    let line = found.map(|mut index| {
+        let mut line = Vec::new();
+        while let Some((parent, action)) = &nodes[index].1 {
+            line.push(action.clone());
+            index = *parent;
+        }
+        line.reverse();
+        line
+    });
+    stats.elapsed = started.elapsed();
+    (line, stats)
+}
+
+/// Peg solitaire from a board down to `goal`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PegSolitaire {
+    pub goal: Grid,
+}
+
+impl Puzzle for PegSolitaire {
+    type State = Grid;
+    type Move = Action;
+
+    fn successors(&self, state: &Grid) -> Vec<(Action, Grid)> {
+        state
+            .valid_actions()
+            .into_iter()
+            .map(|a| (a, state.perform_action(a)))
+            .collect()
+    }
+
+    fn is_goal(&self, state: &Grid) -> bool {
+        *state == self.goal
+    }
+
+    /// Exact when the goal can be reached at all, as every jump takes one
+    /// peg.
+    fn estimate(&self, state: &Grid) -> usize {
+        state
+            .filled_count()
+            .saturating_sub(self.goal.filled_count()) as usize
+    }
+}
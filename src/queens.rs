@@ -0,0 +1,185 @@
+//! The N-queens puzzle: place N queens on an N by N board so that no two
+//! share a row, column or diagonal. The search goes row by row, keeping
+//! the columns and both diagonals already attacked as bitmasks, so each
+//! row's free squares are a few machine operations away.
+
+use std::fmt::{self, Write};
+
+use crate::notation::cell_name;
+use crate::render::{display_width, RenderOptions};
+
+/// The largest board the masks hold.
+pub const MAX_SIZE: usize = 32;
+
+/// A solution: `columns[y]` is the column of the queen on row `y`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    columns: Vec<usize>,
+}
+
+impl Placement {
+    pub fn size(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    /// The board with the theme's peg symbol for a queen and its hole
+    /// symbol for an empty square, labelled like [`fmt::Display`] names
+    /// squares when `options.coords` is set.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let theme = &options.theme;
+        let width = theme.cell_width();
+        let size = self.size();
+        let margin = size.to_string().len();
+        let mut out = String::new();
+        if options.coords {
+            let _ = write!(out, "{:margin$} ", "");
+            for x in 0..size {
+                let _ = write!(out, "{:<width$}", (b'a' + x as u8) as char);
+            }
+            out.push('\n');
+        }
+        for (y, &column) in self.columns.iter().enumerate() {
+            if options.coords {
+                let _ = write!(out, "{:>margin$} ", y + 1);
+            }
+            for x in 0..size {
+                let (symbol, color) = if x == column {
+                    (&theme.peg, &theme.peg_color)
+                } else {
+                    (&theme.hole, &theme.hole_color)
+                };
+                let padding = " ".repeat(width - display_width(symbol));
+                if options.color {
+                    let _ = write!(out, "\x1b[{}m{}\x1b[0m{}", color, symbol, padding);
+                } else {
+                    let _ = write!(out, "{}{}", symbol, padding);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The queens' squares from the top row down, such as `b1 d2 a3 c4`.
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, &x) in self.columns.iter().enumerate() {
+            if y > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", cell_name(x, y))?;
+        }
+        Ok(())
+    }
+}
+
+/// How many ways `size` queens can be placed, counting rotations and
+/// reflections separately. Solutions with the first queen on the right
+/// half mirror those on the left, so only the left half is searched.
+pub fn count(size: usize) -> u64 {
+    assert!(size <= MAX_SIZE, "at most {} queens", MAX_SIZE);
+    if size == 0 {
+        return 1;
+    }
+    let full = mask(size);
+    let mut total = 0;
+    for x in 0..size.div_ceil(2) {
+        let bit = 1u64 << x;
+        let n = count_from(full, bit, bit << 1, bit >> 1);
+        let mirrored = 2 * x + 1 < size;
+        total += if mirrored { 2 * n } else { n };
+    }
+    total
+}
+
+fn mask(size: usize) -> u64 {
+    (1u64 << size) - 1
+}
+
+/// Solutions for the rows left, given the columns and the two diagonals
+/// attacked on the next one.
+fn count_from(full: u64, columns: u64, left: u64, right: u64) -> u64 {
+    if columns == full {
+        return 1;
+    }
+    let mut free = full & !(columns | left | right);
+    let mut total = 0;
+    while free != 0 {
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+        total += count_from(full, columns | bit, (left | bit) << 1, (right | bit) >> 1);
+    }
+    total
+}
+
+/// Every placement of `size` queens, in order of their columns from the
+/// top row, stopping once `max` have been found.
+pub fn solutions(size: usize, max: Option<usize>) -> Vec<Placement> {
+    let mut found = Vec::new();
+    walk(size, max, &mut |placement| {
+        found.push(placement);
+        true
+    });
+    found
+}
+
+/// The first placement of `size` queens, if there is one.
+pub fn first(size: usize) -> Option<Placement> {
+    solutions(size, Some(1)).pop()
+}
+
+/// Runs the search, passing every placement to `emit` until it returns
+/// false. Returns how many were found.
+pub fn walk(size: usize, max: Option<usize>, emit: &mut dyn FnMut(Placement) -> bool) -> usize {
+    assert!(size <= MAX_SIZE, "at most {} queens", MAX_SIZE);
+    let mut walk = Walk {
+        full: mask(size),
+        max: max.unwrap_or(usize::MAX),
+        columns: Vec::new(),
+        found: 0,
+        emit,
+        stopped: false,
+    };
+    if walk.max > 0 {
+        walk.visit(0, 0, 0);
+    }
+    walk.found
+}
+
+struct Walk<'a> {
+    full: u64,
+    max: usize,
+    columns: Vec<usize>,
+    found: usize,
+    emit: &'a mut dyn FnMut(Placement) -> bool,
+    /// Set once `emit` asks to stop or `max` is reached.
+    stopped: bool,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, columns: u64, left: u64, right: u64) {
+        if columns == self.full {
+            self.found += 1;
+            let placement = Placement {
+                columns: self.columns.clone(),
+            };
+            if !(self.emit)(placement) || self.found >= self.max {
+                self.stopped = true;
+            }
+            return;
+        }
+        let mut free = self.full & !(columns | left | right);
+        while free != 0 && !self.stopped {
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            self.columns.push(bit.trailing_zeros() as usize);
+            self.visit(columns | bit, (left | bit) << 1, (right | bit) >> 1);
+            self.columns.pop();
+        }
+    }
+}
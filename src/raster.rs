@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Action, Grid, Tile};
+
+/// Colours of a rasterised board, in the order of the palette indices used
+/// by [`Image::pixels`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub background: [u8; 3],
+    pub cell: [u8; 3],
+    pub peg: [u8; 3],
+    pub hole: [u8; 3],
+    pub jump: [u8; 3],
+}
+
+pub const BACKGROUND: u8 = 0;
+pub const CELL: u8 = 1;
+pub const PEG: u8 = 2;
+pub const HOLE: u8 = 3;
+pub const JUMP: u8 = 4;
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette {
+            background: [0xff, 0xff, 0xff],
+            cell: [0xe8, 0xd6, 0xb3],
+            peg: [0x8b, 0x2e, 0x16],
+            hole: [0x6b, 0x55, 0x35],
+            jump: [0x1f, 0x5f, 0xa8],
+        }
+    }
+}
+
+impl Palette {
+    pub const NAMES: [&'static str; 5] = ["background", "cell", "peg", "hole", "jump"];
+
+    pub fn colors(&self) -> [[u8; 3]; 5] {
+        [self.background, self.cell, self.peg, self.hole, self.jump]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePaletteError(String);
+
+impl fmt::Display for ParsePaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+fn parse_color(s: &str) -> Option<[u8; 3]> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Overrides of the default colours, such as `peg=#224488,hole=#000000`.
+impl FromStr for Palette {
+    type Err = ParsePaletteError;
+
+    fn from_str(s: &str) -> Result<Palette, ParsePaletteError> {
+        let mut palette = Palette::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((name, value)) = part.split_once('=') else {
+                return Err(ParsePaletteError(format!(
+                    "expected `name=#rrggbb`, found {:?}",
+                    part
+                )));
+            };
+            let slot = match name.trim() {
+                "background" => &mut palette.background,
+                "cell" => &mut palette.cell,
+                "peg" => &mut palette.peg,
+                "hole" => &mut palette.hole,
+                "jump" => &mut palette.jump,
+                other => {
+                    return Err(ParsePaletteError(format!(
+                        "unknown colour {:?}, expected one of {}",
+                        other,
+                        Palette::NAMES.join(", ")
+                    )))
+                }
+            };
+            *slot = parse_color(value.trim()).ok_or_else(|| {
+                ParsePaletteError(format!("invalid colour {:?}, expected #rrggbb", value))
+            })?;
+        }
+        Ok(palette)
+    }
+}
+
+/// A picture made of palette indices, row by row from the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    /// Paints every pixel within `reach` of `around` whose centre
+    /// satisfies `inside`.
+    fn fill(
+        &mut self,
+        color: u8,
+        around: (f64, f64),
+        reach: f64,
+        inside: impl Fn(f64, f64) -> bool,
+    ) {
+        let span = |centre: f64, limit: usize| {
+            let low = (centre - reach).floor().max(0.0) as usize;
+            let high = ((centre + reach).ceil().max(0.0) as usize).min(limit);
+            low..high
+        };
+        for py in span(around.1, self.height) {
+            for px in span(around.0, self.width) {
+                if inside(px as f64 + 0.5, py as f64 + 0.5) {
+                    self.pixels[py * self.width + px] = color;
+                }
+            }
+        }
+    }
+}
+
+/// Distance from `(px, py)` to the segment between `a` and `b`.
+fn segment_distance((px, py): (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((px - a.0) * dx + (py - a.1) * dy) / length).clamp(0.0, 1.0)
+    };
+    let (cx, cy) = (a.0 + t * dx, a.1 + t * dy);
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+/// Draws `grid` with square cells `cell` pixels wide, with an arrow for
+/// `jump` if there is one.
+pub fn draw(grid: &Grid, cell: usize, jump: Option<Action>) -> Image {
+    let margin = cell / 2;
+    let size = 7 * cell + 2 * margin;
+    let mut image = Image::new(size, size);
+    let c = cell as f64;
+    let centre = |(x, y): (usize, usize)| {
+        (
+            (margin + x * cell) as f64 + c / 2.0,
+            (margin + y * cell) as f64 + c / 2.0,
+        )
+    };
+    for y in 0..7 {
+        for x in 0..7 {
+            let tile = grid.tile(x, y);
+            if tile == Tile::Blocked {
+                continue;
+            }
+            let (cx, cy) = centre((x, y));
+            // Leave a one pixel gap between cells.
+            let half = c / 2.0 - 1.0;
+            image.fill(CELL, (cx, cy), c, |px, py| {
+                (px - cx).abs() <= half && (py - cy).abs() <= half
+            });
+            let (color, radius) = match tile {
+                Tile::Filled => (PEG, c * 3.0 / 8.0),
+                _ => (HOLE, c / 8.0),
+            };
+            image.fill(color, (cx, cy), c, |px, py| {
+                (px - cx).powi(2) + (py - cy).powi(2) <= radius * radius
+            });
+        }
+    }
+    if let Some((action, to)) = jump.and_then(|a| a.landing().map(|to| (a, to))) {
+        let (from, to) = (centre(action.origin()), centre(to));
+        let (dx, dy) = ((to.0 - from.0) / (2.0 * c), (to.1 - from.1) / (2.0 * c));
+        let head = c / 2.0;
+        // The shaft stops where the head begins.
+        let tip = (to.0 - dx * head, to.1 - dy * head);
+        let middle = ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+        image.fill(JUMP, middle, 2.0 * c, |px, py| {
+            segment_distance((px, py), from, tip) <= c / 12.0
+        });
+        image.fill(JUMP, to, c, |px, py| {
+            let along = (px - tip.0) * dx + (py - tip.1) * dy;
+            let across = ((px - tip.0) * -dy + (py - tip.1) * dx).abs();
+            (0.0..=head).contains(&along) && across <= (head - along) * 0.6
+        });
+    }
+    image
+}
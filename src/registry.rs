@@ -0,0 +1,379 @@
+//! Solvers picked by name: the built-in ones, written like `dfs:7` or
+//! `beam:500`, and any that a program built on this crate registers before
+//! handing its arguments to [`crate::cli::run`]. `solve --engine` runs any
+//! of them and `solve --solvers` races them.
+//!
+//! ```text
+//! fn main() {
+//!     registry::register("mysolver", |param| Some(Box::new(MySolver::new(param?))));
+//!     let args: Vec<String> = std::env::args().skip(1).collect();
+//!     puzzlething::cli::run(&args).unwrap();
+//! }
+//! ```
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::bitboard::JumpTable;
+use crate::stats::SearchStats;
+use crate::{feasibility, Action, GameTree, Grid, SearchStrategy};
+
+pub trait Solver: Send + Sync {
+    /// Searches for a line from `board` to `goal`, giving up soon after
+    /// `stop` is set.
+    fn solve(
+        &self,
+        board: &Grid,
+        goal: &Grid,
+        stop: &AtomicBool,
+    ) -> (Option<Vec<Action>>, SearchStats);
+
+    /// Whether finding nothing proves there is nothing to find.
+    fn is_complete(&self) -> bool {
+        true
+    }
+}
+
+/// Makes a solver from what follows the `:` in its name, if anything, or
+/// returns `None` when that makes no sense to it.
+pub type Factory = Arc<dyn Fn(Option<&str>) -> Option<Box<dyn Solver>> + Send + Sync>;
+
+static REGISTERED: Mutex<Vec<(String, Factory)>> = Mutex::new(Vec::new());
+
+/// Lets `name` pick the solvers `make` builds, wherever a solver is chosen
+/// by name. Panics if `name` contains `:` or `,`, or is already taken by a
+/// built-in solver or an earlier registration.
+pub fn register(
+    name: &str,
+    make: impl Fn(Option<&str>) -> Option<Box<dyn Solver>> + Send + Sync + 'static,
+) {
+    assert!(
+        !name.is_empty() && !name.contains([':', ',']),
+        "solver name {:?} must be non-empty, without `:` or `,`",
+        name
+    );
+    let mut registered = REGISTERED.lock().unwrap();
+    let taken = name.parse::<Builtin>().is_ok()
+        || registered
+            .iter()
+            .any(|(other, _)| other.eq_ignore_ascii_case(name));
+    assert!(!taken, "solver name {:?} is already taken", name);
+    registered.push((name.to_string(), Arc::new(make)));
+}
+
+/// The names given to [`register`], in the order they were registered.
+pub fn registered() -> Vec<String> {
+    let registered = REGISTERED.lock().unwrap();
+    registered.iter().map(|(name, _)| name.clone()).collect()
+}
+
+/// The solver `spec` names: a registered or built-in name, then perhaps
+/// `:` and a parameter for it.
+pub fn solver(spec: &str) -> Result<Box<dyn Solver>, UnknownSolver> {
+    let (name, param) = match spec.split_once(':') {
+        Some((name, param)) => (name, Some(param)),
+        None => (spec, None),
+    };
+    // Cloned out so that a factory may itself look solvers up.
+    let make = REGISTERED
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(other, _)| other.eq_ignore_ascii_case(name))
+        .map(|(_, make)| Arc::clone(make));
+    if let Some(make) = make {
+        return make(param).ok_or_else(|| UnknownSolver(spec.to_string()));
+    }
+    Ok(Box::new(spec.parse::<Builtin>()?))
+}
+
+/// Beam width used when `beam` is given without one.
+pub const DEFAULT_BEAM_WIDTH: usize = 1000;
+
+/// The solvers that come with the crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    /// A search of [`GameTree`], trying moves in an order shuffled by
+    /// `seed` if there is one.
+    Search {
+        strategy: SearchStrategy,
+        seed: Option<u64>,
+    },
+    /// Iterative deepening A* with the pegs left over the goal as its
+    /// estimate, which the feasibility checks raise to infinity where they
+    /// rule a position out. As every jump takes one peg the first bound is
+    /// already exact, so it amounts to a depth-first search that keeps no
+    /// memo and needs next to no memory.
+    IdaStar,
+    /// Plays forward a layer of positions at a time, keeping the `width`
+    /// with their pegs nearest the goal's. It can miss a line that exists,
+    /// so it never proves a board unsolvable.
+    Beam { width: usize },
+}
+
+impl Solver for Builtin {
+    fn solve(
+        &self,
+        board: &Grid,
+        goal: &Grid,
+        stop: &AtomicBool,
+    ) -> (Option<Vec<Action>>, SearchStats) {
+        let started = Instant::now();
+        let mut stats = SearchStats::default();
+        let line = match *self {
+            Builtin::Search { strategy, seed } => {
+                let mut tree = GameTree::new(*board, Vec::new());
+                if let Some(seed) = seed {
+                    tree = tree.with_seed(seed);
+                }
+                let (found, searched) = tree.search_cancellable(goal, strategy, stop);
+                stats = searched;
+                found.map(|g| g.history().to_vec())
+            }
+            Builtin::IdaStar => ida_star(board, goal, &mut stats, stop),
+            Builtin::Beam { width } => beam(board, goal, width, &mut stats, stop),
+        };
+        stats.elapsed = started.elapsed();
+        (line, stats)
+    }
+
+    fn is_complete(&self) -> bool {
+        !matches!(self, Builtin::Beam { .. })
+    }
+}
+
+/// `dfs`, `dfs:7` for the seed 7, `ida` or `beam:500`.
+impl fmt::Display for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Builtin::Search {
+                strategy,
+                seed: None,
+            } => write!(f, "{}", strategy),
+            Builtin::Search {
+                strategy,
+                seed: Some(seed),
+            } => write!(f, "{}:{}", strategy, seed),
+            Builtin::IdaStar => write!(f, "ida"),
+            Builtin::Beam { width } => write!(f, "beam:{}", width),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownSolver(pub String);
+
+impl fmt::Display for UnknownSolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown solver {:?}, expected a strategy with an optional :SEED, ida, beam:WIDTH \
+             or a registered name",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownSolver {}
+
+impl FromStr for Builtin {
+    type Err = UnknownSolver;
+
+    fn from_str(s: &str) -> Result<Builtin, UnknownSolver> {
+        let err = || UnknownSolver(s.to_string());
+        let (name, param) = match s.split_once(':') {
+            Some((name, param)) => (name, Some(param.parse::<u64>().map_err(|_| err())?)),
+            None => (s, None),
+        };
+        match (name.to_ascii_lowercase().as_str(), param) {
+            ("ida" | "ida*", None) => Ok(Builtin::IdaStar),
+            ("beam", width) => match width.unwrap_or(DEFAULT_BEAM_WIDTH as u64) {
+                0 => Err(err()),
+                width => Ok(Builtin::Beam {
+                    width: width as usize,
+                }),
+            },
+            (_, seed) => Ok(Builtin::Search {
+                strategy: name.parse().map_err(|_| err())?,
+                seed,
+            }),
+        }
+    }
+}
+
+/// The pegs left over the goal's, or `None` where the feasibility checks
+/// show the goal out of reach.
+fn estimate(board: &Grid, goal: &Grid, pegs: u64) -> Option<usize> {
+    let position = board.with_pegs(pegs);
+    feasibility::check(&position, goal).ok()?;
+    Some((position.filled_count() - goal.filled_count()) as usize)
+}
+
+fn ida_star(
+    board: &Grid,
+    goal: &Grid,
+    stats: &mut SearchStats,
+    stop: &AtomicBool,
+) -> Option<Vec<Action>> {
+    struct Probe<'a> {
+        board: &'a Grid,
+        goal: &'a Grid,
+        table: &'a JumpTable,
+        target: u64,
+        stats: &'a mut SearchStats,
+        stop: &'a AtomicBool,
+        line: Vec<Action>,
+    }
+
+    impl Probe<'_> {
+        /// Looks for the goal within `bound` moves of the start, `depth`
+        /// of them made to reach `pegs`. Fails with the least bound over
+        /// `bound` that could do better, if any.
+        fn probe(&mut self, pegs: u64, depth: usize, bound: usize) -> Result<(), Option<usize>> {
+            self.stats.nodes_expanded += 1;
+            self.stats.observe_frontier(depth);
+            let mut over = None::<usize>;
+            let table = self.table;
+            for (action, next) in table.forward(pegs) {
+                if self.stop.load(Ordering::Relaxed) {
+                    return Err(None);
+                }
+                let Some(left) = estimate(self.board, self.goal, next) else {
+                    self.stats.prune("feasibility");
+                    continue;
+                };
+                let f = depth + 1 + left;
+                if f > bound {
+                    over = Some(over.map_or(f, |over| over.min(f)));
+                    continue;
+                }
+                self.line.push(action);
+                if next == self.target {
+                    return Ok(());
+                }
+                match self.probe(next, depth + 1, bound) {
+                    Ok(()) => return Ok(()),
+                    Err(Some(f)) => over = Some(over.map_or(f, |over| over.min(f))),
+                    Err(None) => {}
+                }
+                self.line.pop();
+            }
+            Err(over)
+        }
+    }
+
+    let start = board.encode();
+    let mut bound = estimate(board, goal, start)?;
+    let table = JumpTable::new(board);
+    let mut probe = Probe {
+        board,
+        goal,
+        table: &table,
+        target: goal.encode(),
+        stats,
+        stop,
+        line: Vec::new(),
+    };
+    if start == probe.target {
+        return Some(Vec::new());
+    }
+    loop {
+        match probe.probe(start, 0, bound) {
+            Ok(()) => return Some(probe.line),
+            Err(Some(next)) => bound = next,
+            Err(None) => return None,
+        }
+    }
+}
+
+fn beam(
+    board: &Grid,
+    goal: &Grid,
+    width: usize,
+    stats: &mut SearchStats,
+    stop: &AtomicBool,
+) -> Option<Vec<Action>> {
+    let table = JumpTable::new(board);
+    let target = goal.encode();
+    let cells: Vec<(usize, usize)> = (0..49).map(|bit| (bit / 7, bit % 7)).collect();
+    let goal_cells: Vec<(usize, usize)> = cells
+        .iter()
+        .copied()
+        .filter(|&(x, y)| target & (1 << (x * 7 + y)) != 0)
+        .collect();
+    // Lower is better: how far each peg is from the nearest of the goal's,
+    // with pegs that have no neighbour to jump with counting extra.
+    let score = |pegs: u64| -> usize {
+        let peg = |x: usize, y: usize| x < 7 && y < 7 && pegs & (1 << (x * 7 + y)) != 0;
+        let mut total = 0;
+        for &(x, y) in &cells {
+            if !peg(x, y) {
+                continue;
+            }
+            total += goal_cells
+                .iter()
+                .map(|&(gx, gy)| x.abs_diff(gx) + y.abs_diff(gy))
+                .min()
+                .unwrap_or(0);
+            let alone = !peg(x + 1, y)
+                && !peg(x, y + 1)
+                && (x == 0 || !peg(x - 1, y))
+                && (y == 0 || !peg(x, y - 1));
+            if alone {
+                total += 6;
+            }
+        }
+        total
+    };
+    let mut layer = vec![board.encode()];
+    // Per layer, the position each kept one was reached from and how.
+    let mut parents: Vec<HashMap<u64, (u64, Action)>> = Vec::new();
+    while !layer.contains(&target) {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut reached = HashMap::new();
+        for &pegs in &layer {
+            stats.nodes_expanded += 1;
+            for (action, next) in table.forward(pegs) {
+                match reached.entry(next) {
+                    Entry::Occupied(_) => stats.duplicates += 1,
+                    Entry::Vacant(slot) => {
+                        slot.insert((pegs, action));
+                    }
+                }
+            }
+        }
+        let mut kept: Vec<u64> = Vec::with_capacity(reached.len());
+        for &pegs in reached.keys() {
+            if estimate(board, goal, pegs).is_some() {
+                kept.push(pegs);
+            } else {
+                stats.prune("feasibility");
+            }
+        }
+        if kept.is_empty() {
+            return None;
+        }
+        kept.sort_unstable_by_key(|&pegs| (score(pegs), pegs));
+        kept.truncate(width);
+        stats.observe_frontier(kept.len());
+        parents.push(kept.iter().map(|pegs| (*pegs, reached[pegs])).collect());
+        layer = kept;
+    }
+    let mut line = Vec::with_capacity(parents.len());
+    let mut pegs = target;
+    for reached in parents.iter().rev() {
+        let (previous, action) = reached[&pegs];
+        line.push(action);
+        pegs = previous;
+    }
+    line.reverse();
+    Some(line)
+}
@@ -0,0 +1,285 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use crate::speech;
+use crate::{Action, Grid, Tile};
+
+/// The symbols and colours used to draw cells. Colours are ANSI SGR
+/// parameters such as `1;33` for bold yellow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub peg: String,
+    pub hole: String,
+    pub blocked: String,
+    pub peg_color: String,
+    pub hole_color: String,
+    pub last_color: String,
+    pub goal_color: String,
+    pub jump_color: String,
+    pub capture_color: String,
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 5] = ["classic", "dots", "rings", "blocks", "emoji"];
+
+    /// The symbols of `Grid`'s `Display`.
+    pub fn classic() -> Theme {
+        Theme {
+            peg: "O".to_string(),
+            hole: "-".to_string(),
+            blocked: " ".to_string(),
+            peg_color: "1;33".to_string(),
+            hole_color: "90".to_string(),
+            last_color: "1;35".to_string(),
+            goal_color: "44".to_string(),
+            jump_color: "1;32".to_string(),
+            capture_color: "1;31".to_string(),
+        }
+    }
+
+    pub fn named(name: &str) -> Option<Theme> {
+        let (peg, hole, blocked) = match name {
+            "classic" => return Some(Theme::classic()),
+            "dots" => ("●", "·", " "),
+            "rings" => ("◉", "○", " "),
+            "blocks" => ("█", "░", " "),
+            // Every cell is an emoji so the rows line up when pasted into
+            // chat apps with proportional fonts.
+            "emoji" => ("🔵", "⚫", "⬜"),
+            _ => return None,
+        };
+        Some(Theme {
+            peg: peg.to_string(),
+            hole: hole.to_string(),
+            blocked: blocked.to_string(),
+            ..Theme::classic()
+        })
+    }
+
+    /// Reads `key = value` lines on top of the classic theme. `theme =
+    /// NAME` switches the base to a built-in theme, the other keys are the
+    /// field names. Values may be quoted to keep spaces, and `#` starts a
+    /// comment line.
+    pub fn parse(text: &str) -> Result<Theme, ParseThemeError> {
+        let mut theme = Theme::classic();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |message: &str| ParseThemeError {
+                line: i + 1,
+                message: message.to_string(),
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`"))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value)
+                .to_string();
+            let field = match key.trim() {
+                "theme" => {
+                    theme = Theme::named(&value).ok_or_else(|| err("unknown theme"))?;
+                    continue;
+                }
+                "peg" => &mut theme.peg,
+                "hole" => &mut theme.hole,
+                "blocked" => &mut theme.blocked,
+                "peg_color" => &mut theme.peg_color,
+                "hole_color" => &mut theme.hole_color,
+                "last_color" => &mut theme.last_color,
+                "goal_color" => &mut theme.goal_color,
+                "jump_color" => &mut theme.jump_color,
+                "capture_color" => &mut theme.capture_color,
+                _ => return Err(err("unknown key")),
+            };
+            *field = value;
+        }
+        Ok(theme)
+    }
+
+    pub fn symbol(&self, tile: Tile) -> &str {
+        match tile {
+            Tile::Filled => &self.peg,
+            Tile::Empty => &self.hole,
+            Tile::Blocked => &self.blocked,
+        }
+    }
+
+    /// Terminal columns per cell: the widest of the three symbols.
+    pub fn cell_width(&self) -> usize {
+        [&self.peg, &self.hole, &self.blocked]
+            .iter()
+            .map(|s| display_width(s))
+            .max()
+            .unwrap_or(1)
+            .max(1)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseThemeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseThemeError {}
+
+/// How [`render`] draws a board.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Colour the cells with ANSI escapes. Without it the goal and the last
+    /// move are not shown.
+    pub color: bool,
+    /// Cells where this position has a peg get the goal colour as well.
+    pub goal: Option<Grid>,
+    /// The peg that landed with this move is highlighted.
+    pub last_move: Option<Action>,
+    /// The peg about to make this move and the peg it captures are
+    /// highlighted.
+    pub next_move: Option<Action>,
+    /// Label columns with `x` and rows with `y` so the coordinates in
+    /// printed moves can be found on the board.
+    pub coords: bool,
+    pub theme: Theme,
+    /// Describe the board in sentences with [`speech::describe_board`]
+    /// instead of drawing it.
+    pub spoken: bool,
+}
+
+/// Columns `s` takes up in a terminal. Emoji and East Asian wide
+/// characters count twice, combining marks and variation selectors not at
+/// all, and a character followed by the emoji variation selector U+FE0F
+/// counts as wide.
+pub fn display_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let c = c as u32;
+        width += match c {
+            0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+            _ if chars.peek() == Some(&'\u{FE0F}') => 2,
+            0x1100..=0x115F
+            | 0x231A..=0x231B
+            | 0x23E9..=0x23EC
+            | 0x23F0
+            | 0x23F3
+            | 0x25FD..=0x25FE
+            | 0x2614..=0x2615
+            | 0x2648..=0x2653
+            | 0x267F
+            | 0x2693
+            | 0x26A1
+            | 0x26AA..=0x26AB
+            | 0x26BD..=0x26BE
+            | 0x26C4..=0x26C5
+            | 0x26CE
+            | 0x26D4
+            | 0x26EA
+            | 0x26F2..=0x26F5
+            | 0x26FA
+            | 0x26FD
+            | 0x2705
+            | 0x270A..=0x270B
+            | 0x2728
+            | 0x274C
+            | 0x274E
+            | 0x2753..=0x2755
+            | 0x2757
+            | 0x2795..=0x2797
+            | 0x27B0
+            | 0x27BF
+            | 0x2B1B..=0x2B1C
+            | 0x2B50
+            | 0x2B55
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F680..=0x1F6FF
+            | 0x1F7E0..=0x1F7EB
+            | 0x1F900..=0x1F9FF
+            | 0x1FA70..=0x1FAFF
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        };
+    }
+    width
+}
+
+pub fn render(grid: &Grid, options: &RenderOptions) -> String {
+    if options.spoken {
+        return speech::describe_board(grid, options);
+    }
+    let theme = &options.theme;
+    let width = theme.cell_width();
+    let last = options.last_move.and_then(|a| a.landing());
+    let jumping = options.next_move.map(|a| a.origin());
+    let captured = options.next_move.and_then(|a| a.captured());
+    let mut out = String::new();
+    if options.coords {
+        out.push_str("  ");
+        for x in 0..7 {
+            let _ = write!(out, "{:<width$}", x, width = width);
+        }
+        out.push('\n');
+    }
+    for y in 0..7 {
+        if options.coords {
+            let _ = write!(out, "{} ", y);
+        }
+        for x in 0..7 {
+            let tile = grid.grid[x][y];
+            let symbol = theme.symbol(tile);
+            let padding = " ".repeat(width - display_width(symbol));
+            if !options.color || tile == Tile::Blocked {
+                out.push_str(symbol);
+                out.push_str(&padding);
+                continue;
+            }
+            let color = match tile {
+                Tile::Filled if jumping == Some((x, y)) => &theme.jump_color,
+                Tile::Filled if captured == Some((x, y)) => &theme.capture_color,
+                Tile::Filled if last == Some((x, y)) => &theme.last_color,
+                Tile::Filled => &theme.peg_color,
+                _ => &theme.hole_color,
+            };
+            let _ = write!(out, "\x1b[{}", color);
+            if options.goal.is_some_and(|g| g.grid[x][y] == Tile::Filled) {
+                let _ = write!(out, ";{}", theme.goal_color);
+            }
+            let _ = write!(out, "m{}\x1b[0m{}", symbol, padding);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+impl Grid {
+    /// The board with column and row numbers around it.
+    pub fn display_with_coords(&self) -> String {
+        let options = RenderOptions {
+            coords: true,
+            ..RenderOptions::default()
+        };
+        render(self, &options)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::bitboard::JumpTable;
+use crate::endgame::read_u64;
+use crate::symmetry::{self, Symmetry};
+use crate::Grid;
+
+const MAGIC: &[u8; 8] = b"PEGRET01";
+const WINNABLE: u64 = 1 << 63;
+
+/// Win/loss labels for every position reachable from a start position,
+/// stored up to the symmetries of the board that fix the goal.
+#[derive(Clone, Debug)]
+pub struct RetrogradeDb {
+    start: Grid,
+    goal: Grid,
+    group: Vec<Symmetry>,
+    winnable: HashMap<u64, bool>,
+}
+
+impl RetrogradeDb {
+    /// Enumerates everything reachable from `start` layer by layer, then
+    /// labels the layers from the fewest pegs upwards: a position is
+    /// winnable if it is the goal or one of its successors is winnable.
+    pub fn analyze(start: &Grid, goal: &Grid) -> RetrogradeDb {
+        let group = symmetry::grid_symmetries(goal);
+        let table = JumpTable::new(start);
+        let canon = |bits| symmetry::canonical(bits, &group);
+
+        let mut layers = vec![vec![canon(start.encode())]];
+        loop {
+            let mut seen = HashSet::new();
+            for &bits in layers.last().unwrap() {
+                seen.extend(table.forward(bits).map(|(_, n)| canon(n)));
+            }
+            if seen.is_empty() {
+                break;
+            }
+            layers.push(seen.into_iter().collect());
+        }
+
+        let goal_key = canon(goal.encode());
+        let mut winnable = HashMap::new();
+        for layer in layers.iter().rev() {
+            for &bits in layer {
+                let win = bits == goal_key
+                    || table
+                        .forward(bits)
+                        .any(|(_, n)| winnable.get(&canon(n)) == Some(&true));
+                winnable.insert(bits, win);
+            }
+        }
+        RetrogradeDb {
+            start: *start,
+            goal: *goal,
+            group,
+            winnable,
+        }
+    }
+
+    pub fn start(&self) -> &Grid {
+        &self.start
+    }
+
+    pub fn goal(&self) -> &Grid {
+        &self.goal
+    }
+
+    /// `Some(winnable)` for positions reachable from the start (or a
+    /// symmetric image of one), `None` for anything else.
+    pub fn lookup(&self, grid: &Grid) -> Option<bool> {
+        let key = symmetry::canonical(grid.encode(), &self.group);
+        self.winnable.get(&key).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.winnable.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.winnable.is_empty()
+    }
+
+    pub fn winnable_count(&self) -> usize {
+        self.winnable.values().filter(|&&w| w).count()
+    }
+
+    pub fn dead_count(&self) -> usize {
+        self.len() - self.winnable_count()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&self.start.with_pegs(u64::MAX).encode().to_le_bytes())?;
+        out.write_all(&self.start.encode().to_le_bytes())?;
+        out.write_all(&self.goal.encode().to_le_bytes())?;
+        out.write_all(&(self.winnable.len() as u64).to_le_bytes())?;
+        let mut entries: Vec<u64> = self
+            .winnable
+            .iter()
+            .map(|(&bits, &win)| if win { bits | WINNABLE } else { bits })
+            .collect();
+        entries.sort_unstable();
+        for entry in entries {
+            out.write_all(&entry.to_le_bytes())?;
+        }
+        out.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<RetrogradeDb> {
+        let mut input = BufReader::new(File::open(path)?);
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a retrograde database file",
+            ));
+        }
+        let layout = read_u64(&mut input)?;
+        let start = Grid::from_masks(layout, read_u64(&mut input)?);
+        let goal = Grid::from_masks(layout, read_u64(&mut input)?);
+        let len = read_u64(&mut input)?;
+        let mut winnable = HashMap::with_capacity(len as usize);
+        for _ in 0..len {
+            let entry = read_u64(&mut input)?;
+            winnable.insert(entry & !WINNABLE, entry & WINNABLE != 0);
+        }
+        Ok(RetrogradeDb {
+            start,
+            goal,
+            group: symmetry::grid_symmetries(&goal),
+            winnable,
+        })
+    }
+}
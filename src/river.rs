@@ -0,0 +1,280 @@
+//! River crossings, the oldest state-space puzzles: everyone starts on the
+//! left bank and must reach the right in a small boat, without leaving a
+//! bank in a state the rules forbid. Their states are a few counts rather
+//! than a grid, which makes them the smallest users of [`Puzzle`].
+
+use std::fmt;
+
+use crate::puzzle::Puzzle;
+
+/// What the farmer may take across besides himself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Item {
+    Wolf,
+    Goat,
+    Cabbage,
+}
+
+impl Item {
+    pub const ALL: [Item; 3] = [Item::Wolf, Item::Goat, Item::Cabbage];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Item::Wolf => "wolf",
+            Item::Goat => "goat",
+            Item::Cabbage => "cabbage",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            Item::Wolf => 1,
+            Item::Goat => 2,
+            Item::Cabbage => 4,
+        }
+    }
+}
+
+/// Who is on the right bank: bit 8 for the farmer and [`Item::bit`] for
+/// the rest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Banks(u8);
+
+const FARMER: u8 = 8;
+
+impl Banks {
+    pub fn start() -> Banks {
+        Banks(0)
+    }
+
+    fn across(&self, item: Item) -> bool {
+        self.0 & item.bit() != 0
+    }
+}
+
+/// The banks as `farmer ... | ~~~ | ...`, left to right.
+impl fmt::Display for Banks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bank = |right: bool| {
+            let mut names = Vec::new();
+            if (self.0 & FARMER != 0) == right {
+                names.push("farmer");
+            }
+            names.extend(
+                Item::ALL
+                    .iter()
+                    .filter(|&&i| self.across(i) == right)
+                    .map(Item::name),
+            );
+            names.join(" ")
+        };
+        write!(f, "{} | ~~~ | {}", bank(false), bank(true))
+    }
+}
+
+/// The farmer rows across, alone or with one item from his bank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Crossing(pub Option<Item>);
+
+impl fmt::Display for Crossing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(item) => write!(f, "farmer takes the {}", item.name()),
+            None => write!(f, "farmer crosses alone"),
+        }
+    }
+}
+
+/// The wolf, the goat and the cabbage: the wolf eats the goat and the goat
+/// the cabbage on any bank the farmer is not on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WolfGoatCabbage;
+
+impl Puzzle for WolfGoatCabbage {
+    type State = Banks;
+    type Move = Crossing;
+
+    fn successors(&self, state: &Banks) -> Vec<(Crossing, Banks)> {
+        let farmer_across = state.0 & FARMER != 0;
+        let mut cargo = vec![None];
+        cargo.extend(
+            Item::ALL
+                .into_iter()
+                .filter(|&i| state.across(i) == farmer_across)
+                .map(Some),
+        );
+        cargo
+            .into_iter()
+            .filter_map(|item| {
+                let moved = FARMER | item.map_or(0, Item::bit);
+                let next = Banks(state.0 ^ moved);
+                // The bank the farmer leaves must be safe.
+                let left_behind = |i: Item| next.across(i) == farmer_across;
+                let goat = left_behind(Item::Goat);
+                let safe = !(goat && (left_behind(Item::Wolf) || left_behind(Item::Cabbage)));
+                safe.then_some((Crossing(item), next))
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, state: &Banks) -> bool {
+        state.0 == FARMER | 7
+    }
+
+    /// Every item still on the left needs a crossing there and back, but
+    /// the last goes one way only.
+    fn estimate(&self, state: &Banks) -> usize {
+        let left = Item::ALL.iter().filter(|&&i| !state.across(i)).count();
+        (2 * left).saturating_sub(1)
+    }
+}
+
+/// The missionaries and cannibals still on the left bank, and which bank
+/// the boat is at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shore {
+    pub missionaries: usize,
+    pub cannibals: usize,
+    pub boat_left: bool,
+}
+
+/// Who rows the boat over this time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Boatload {
+    pub missionaries: usize,
+    pub cannibals: usize,
+}
+
+/// An `M` per missionary and a `C` per cannibal, such as `MC`.
+impl fmt::Display for Boatload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            "M".repeat(self.missionaries),
+            "C".repeat(self.cannibals)
+        )
+    }
+}
+
+/// Missionaries and cannibals: `people` of each cross in a boat for at
+/// most `boat`, and cannibals may never outnumber the missionaries on a
+/// bank, or in the boat as it lands, where there are any missionaries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MissionariesAndCannibals {
+    pub people: usize,
+    pub boat: usize,
+}
+
+impl Default for MissionariesAndCannibals {
+    fn default() -> Self {
+        MissionariesAndCannibals { people: 3, boat: 2 }
+    }
+}
+
+impl MissionariesAndCannibals {
+    pub fn start(&self) -> Shore {
+        Shore {
+            missionaries: self.people,
+            cannibals: self.people,
+            boat_left: true,
+        }
+    }
+
+    fn safe(missionaries: usize, cannibals: usize) -> bool {
+        missionaries == 0 || missionaries >= cannibals
+    }
+
+    /// The banks as `MMM CCC B| ~~~ |`, left to right, with `B` for the
+    /// boat.
+    pub fn render(&self, shore: &Shore) -> String {
+        let (m, c) = (shore.missionaries, shore.cannibals);
+        let (rm, rc) = (self.people - m, self.people - c);
+        let boat = |here: bool| if here { "B" } else { " " };
+        format!(
+            "{:>w$} {:>w$} {}| ~~~ |{} {:<w$} {:<w$}",
+            "M".repeat(m),
+            "C".repeat(c),
+            boat(shore.boat_left),
+            boat(!shore.boat_left),
+            "M".repeat(rm),
+            "C".repeat(rc),
+            w = self.people
+        )
+    }
+}
+
+impl Puzzle for MissionariesAndCannibals {
+    type State = Shore;
+    type Move = Boatload;
+
+    fn successors(&self, state: &Shore) -> Vec<(Boatload, Shore)> {
+        // Who is on the boat's bank.
+        let (here_m, here_c) = if state.boat_left {
+            (state.missionaries, state.cannibals)
+        } else {
+            (
+                self.people - state.missionaries,
+                self.people - state.cannibals,
+            )
+        };
+        let mut out = Vec::new();
+        for m in 0..=here_m.min(self.boat) {
+            for c in 0..=here_c.min(self.boat - m) {
+                if m + c == 0 || !Self::safe(m, c) {
+                    continue;
+                }
+                let next = if state.boat_left {
+                    Shore {
+                        missionaries: state.missionaries - m,
+                        cannibals: state.cannibals - c,
+                        boat_left: false,
+                    }
+                } else {
+                    Shore {
+                        missionaries: state.missionaries + m,
+                        cannibals: state.cannibals + c,
+                        boat_left: true,
+                    }
+                };
+                let (lm, lc) = (next.missionaries, next.cannibals);
+                if Self::safe(lm, lc) && Self::safe(self.people - lm, self.people - lc) {
+                    let load = Boatload {
+                        missionaries: m,
+                        cannibals: c,
+                    };
+                    out.push((load, next));
+                }
+            }
+        }
+        out
+    }
+
+    fn is_goal(&self, state: &Shore) -> bool {
+        state.missionaries == 0 && state.cannibals == 0
+    }
+
+    /// Every round trip brings at most `boat - 1` more people across, since
+    /// someone must row back.
+    fn estimate(&self, state: &Shore) -> usize {
+        let left = state.missionaries + state.cannibals;
+        if left == 0 {
+            0
+        } else if state.boat_left {
+            trips(left, self.boat)
+        } else {
+            1 + trips(left + 1, self.boat)
+        }
+    }
+}
+
+/// The fewest crossings to take `left` people over from the boat's bank.
+fn trips(left: usize, boat: usize) -> usize {
+    if boat < 2 {
+        left
+    } else if left <= boat {
+        1
+    } else {
+        2 * (left - boat).div_ceil(boat - 1) + 1
+    }
+}
@@ -0,0 +1,245 @@
+//! Rush Hour: cars and trucks slide back and forth along their own rows
+//! and columns until the red car can drive out of the right side.
+//!
+//! A level is given as in the usual puzzle databases: the cells row by
+//! row, as 36 characters on one line for the 6 by 6 board or one line per
+//! row, each vehicle as a run of its letter, `.` or `o` for an empty cell
+//! and `x` for a wall. The red car is `X` if there is one and `A`
+//! otherwise: `BBoKMxDDDKMoIAALooIoJLEEooJFFNoGGoxN`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+use crate::Direction;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Vehicle {
+    label: char,
+    horizontal: bool,
+    length: usize,
+    /// The row of a horizontal vehicle or the column of a vertical one.
+    lane: usize,
+}
+
+/// How far along its lane each vehicle is, the red car first.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Traffic {
+    offsets: Vec<u8>,
+}
+
+/// The vehicle with `label` drives `distance` cells towards `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Drive {
+    pub label: char,
+    pub direction: Direction,
+    pub distance: usize,
+}
+
+impl fmt::Display for Drive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = match self.direction {
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+            Direction::Left => "←",
+            Direction::Right => "→",
+        };
+        write!(f, "{} {} {}", self.label, dir, self.distance)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RushHour {
+    size: usize,
+    walls: Vec<bool>,
+    vehicles: Vec<Vehicle>,
+    start: Traffic,
+}
+
+impl RushHour {
+    pub fn start(&self) -> &Traffic {
+        &self.start
+    }
+
+    /// The cells `vehicle` covers at `offset` along its lane.
+    fn cells(&self, vehicle: &Vehicle, offset: usize) -> impl Iterator<Item = usize> + '_ {
+        let (size, v) = (self.size, *vehicle);
+        (offset..offset + v.length).map(move |along| {
+            if v.horizontal {
+                v.lane * size + along
+            } else {
+                along * size + v.lane
+            }
+        })
+    }
+
+    fn occupancy(&self, traffic: &Traffic) -> Vec<Option<usize>> {
+        let mut cells = vec![None; self.size * self.size];
+        for (i, (vehicle, &offset)) in self.vehicles.iter().zip(&traffic.offsets).enumerate() {
+            for cell in self.cells(vehicle, offset as usize) {
+                cells[cell] = Some(i);
+            }
+        }
+        cells
+    }
+
+    pub fn render(&self, traffic: &Traffic) -> String {
+        let cells = self.occupancy(traffic);
+        let mut out = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 && i % self.size == 0 {
+                out.push('\n');
+            }
+            out.push(match cell {
+                Some(v) => self.vehicles[*v].label,
+                None if self.walls[i] => 'x',
+                None => '.',
+            });
+        }
+        out
+    }
+}
+
+impl Puzzle for RushHour {
+    type State = Traffic;
+    type Move = Drive;
+
+    fn successors(&self, state: &Traffic) -> Vec<(Drive, Traffic)> {
+        let cells = self.occupancy(state);
+        let free = |cell: usize| cells[cell].is_none() && !self.walls[cell];
+        let mut out = Vec::new();
+        for (i, vehicle) in self.vehicles.iter().enumerate() {
+            let offset = state.offsets[i] as usize;
+            let (back, forward) = if vehicle.horizontal {
+                (Direction::Left, Direction::Right)
+            } else {
+                (Direction::Up, Direction::Down)
+            };
+            let free_at = |along: usize| free(self.cells(vehicle, along).next().unwrap());
+            // Every offset the vehicle can reach, nearest first each way.
+            let behind = (0..offset).rev().take_while(|&to| free_at(to));
+            let ahead = (offset + 1..=self.size - vehicle.length)
+                .take_while(|&to| free_at(to + vehicle.length - 1));
+            let reach = behind
+                .map(|to| (back, to))
+                .chain(ahead.map(|to| (forward, to)));
+            for (direction, to) in reach {
+                let mut offsets = state.offsets.clone();
+                offsets[i] = to as u8;
+                let drive = Drive {
+                    label: vehicle.label,
+                    direction,
+                    distance: to.abs_diff(offset),
+                };
+                out.push((drive, Traffic { offsets }));
+            }
+        }
+        out
+    }
+
+    /// The red car reaches the right edge.
+    fn is_goal(&self, state: &Traffic) -> bool {
+        state.offsets[0] as usize + self.vehicles[0].length == self.size
+    }
+
+    /// One drive for the red car if it is not out, and one more if a
+    /// vehicle stands in its way.
+    fn estimate(&self, state: &Traffic) -> usize {
+        if self.is_goal(state) {
+            return 0;
+        }
+        let cells = self.occupancy(state);
+        let red = &self.vehicles[0];
+        let ahead = state.offsets[0] as usize + red.length..self.size;
+        let blocked = ahead
+            .map(|x| red.lane * self.size + x)
+            .any(|c| cells[c].is_some());
+        1 + usize::from(blocked)
+    }
+}
+
+impl fmt::Display for RushHour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&self.start))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRushHourError(pub String);
+
+impl fmt::Display for ParseRushHourError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseRushHourError {}
+
+impl FromStr for RushHour {
+    type Err = ParseRushHourError;
+
+    fn from_str(s: &str) -> Result<RushHour, ParseRushHourError> {
+        let err = |message: String| Err(ParseRushHourError(message));
+        let cells: Vec<char> = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '/')
+            .collect();
+        let size = (1..=16).find(|n| n * n == cells.len());
+        let Some(size) = size else {
+            return err(format!("{} cells do not make a square board", cells.len()));
+        };
+        let mut walls = vec![false; size * size];
+        let mut labels: Vec<char> = Vec::new();
+        for (i, &c) in cells.iter().enumerate() {
+            match c {
+                '.' | 'o' => {}
+                'x' => walls[i] = true,
+                c if c.is_ascii_uppercase() => {
+                    if !labels.contains(&c) {
+                        labels.push(c);
+                    }
+                }
+                _ => return err(format!("unknown cell {:?}", c)),
+            }
+        }
+        let red = if labels.contains(&'X') { 'X' } else { 'A' };
+        let Some(index) = labels.iter().position(|&l| l == red) else {
+            return err("no red car, X or A".to_string());
+        };
+        labels.swap(0, index);
+        let mut vehicles = Vec::new();
+        let mut offsets = Vec::new();
+        for &label in &labels {
+            let at: Vec<usize> = (0..cells.len()).filter(|&i| cells[i] == label).collect();
+            let (first, last) = (at[0], at[at.len() - 1]);
+            let horizontal = first / size == last / size;
+            let step = if horizontal { 1 } else { size };
+            let straight = at.windows(2).all(|w| w[1] - w[0] == step);
+            if at.len() < 2 || !straight {
+                return err(format!("vehicle {} is not a straight run of cells", label));
+            }
+            let (lane, offset) = if horizontal {
+                (first / size, first % size)
+            } else {
+                (first % size, first / size)
+            };
+            vehicles.push(Vehicle {
+                label,
+                horizontal,
+                length: at.len(),
+                lane,
+            });
+            offsets.push(offset as u8);
+        }
+        if !vehicles[0].horizontal {
+            return err(format!("the red car {} must lie across its row", red));
+        }
+        Ok(RushHour {
+            size,
+            walls,
+            vehicles,
+            start: Traffic { offsets },
+        })
+    }
+}
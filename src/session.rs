@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Action, Grid, ReplayError};
+
+/// A game in progress, as written to a session file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub start: Grid,
+    pub goal: Grid,
+    pub moves: Vec<Action>,
+    /// Seconds since the Unix epoch when the game began.
+    pub started: u64,
+    /// Seconds since the Unix epoch when the session was last written.
+    pub saved: u64,
+    /// Other `key = value` lines, such as the theme, in file order.
+    pub settings: Vec<(String, String)>,
+}
+
+impl Session {
+    /// The position the moves lead to.
+    pub fn position(&self) -> Result<Grid, ReplayError> {
+        self.start.play_moves(&self.moves)
+    }
+
+    pub fn setting(&self, key: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The file format: `key = value` lines with one `move` line per move
+/// played.
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# puzzlething session")?;
+        writeln!(f, "start = {}", self.start.notation())?;
+        writeln!(f, "goal = {}", self.goal.notation())?;
+        writeln!(f, "started = {}", self.started)?;
+        writeln!(f, "saved = {}", self.saved)?;
+        for (key, value) in &self.settings {
+            writeln!(f, "{} = {}", key, value)?;
+        }
+        for action in &self.moves {
+            writeln!(f, "move = {}", action)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSessionError {
+    /// One-based line of the problem, or 0 when the file as a whole is at
+    /// fault.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+impl Error for ParseSessionError {}
+
+impl FromStr for Session {
+    type Err = ParseSessionError;
+
+    fn from_str(text: &str) -> Result<Session, ParseSessionError> {
+        let (mut start, mut goal) = (None, None);
+        let (mut started, mut saved) = (0, 0);
+        let mut moves = Vec::new();
+        let mut move_lines = Vec::new();
+        let mut settings = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |message: String| ParseSessionError {
+                line: i + 1,
+                message,
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`".to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "start" => start = Some(value.parse::<Grid>().map_err(|e| err(e.to_string()))?),
+                "goal" => goal = Some(value.parse::<Grid>().map_err(|e| err(e.to_string()))?),
+                "started" | "saved" => {
+                    let time = value
+                        .parse()
+                        .map_err(|_| err(format!("invalid timestamp {:?}", value)))?;
+                    if key == "started" {
+                        started = time;
+                    } else {
+                        saved = time;
+                    }
+                }
+                "move" => {
+                    moves.push(value.parse::<Action>().map_err(|e| err(e.to_string()))?);
+                    move_lines.push(i + 1);
+                }
+                _ => settings.push((key.to_string(), value.to_string())),
+            }
+        }
+        let missing = |key: &str| ParseSessionError {
+            line: 0,
+            message: format!("missing `{}`", key),
+        };
+        let start = start.ok_or_else(|| missing("start"))?;
+        let session = Session {
+            goal: goal.unwrap_or_else(|| start.single_peg(3, 3)),
+            start,
+            moves,
+            started,
+            saved,
+            settings,
+        };
+        if let Err(e) = session.position() {
+            return Err(ParseSessionError {
+                line: move_lines[e.index],
+                message: format!("{} is not legal: {}", e.action, e.reason),
+            });
+        }
+        Ok(session)
+    }
+}
@@ -0,0 +1,452 @@
+//! Sokoban: a warehouse keeper pushes boxes, one at a time and never
+//! pulling, until every box is on a goal. Levels are read and written in
+//! the usual XSB text:
+//!
+//! ```text
+//! #  wall            $  box             @  keeper
+//! .  goal            *  box on a goal   +  keeper on a goal
+//! ```
+//!
+//! with a space, `-` or `_` for floor. The search counts pushes; the
+//! keeper's walks between them are filled in by [`Level::lurd`].
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::puzzle::Puzzle;
+use crate::Direction;
+
+/// Where the boxes and the keeper are. Positions differing only in where
+/// the keeper stands within the floor it can reach are equal.
+#[derive(Clone, Debug)]
+pub struct Position {
+    /// Cell indices, sorted.
+    boxes: Vec<usize>,
+    /// The first cell of the keeper's floor.
+    region: usize,
+    keeper: usize,
+}
+
+impl PartialEq for Position {
+    fn eq(&self, other: &Position) -> bool {
+        (&self.boxes, self.region) == (&other.boxes, other.region)
+    }
+}
+
+impl Eq for Position {}
+
+impl Hash for Position {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.boxes.hash(state);
+        self.region.hash(state);
+    }
+}
+
+/// A push of the box at `(x, y)` one cell towards `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Push {
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+}
+
+impl fmt::Display for Push {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = match self.direction {
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+            Direction::Left => "←",
+            Direction::Right => "→",
+        };
+        write!(f, "({}, {}) {}", self.x, self.y, dir)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    goals: Vec<bool>,
+    /// Floor from which no box can ever be pushed onto a goal.
+    dead: Vec<bool>,
+    keeper: usize,
+    boxes: Vec<usize>,
+}
+
+impl Level {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn start(&self) -> Position {
+        self.position(self.boxes.clone(), self.keeper)
+    }
+
+    /// The cell one step from `cell` towards `direction`, if it is on the
+    /// map and not a wall.
+    fn step(&self, cell: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let next = match direction {
+            Direction::Up if y > 0 => cell - self.width,
+            Direction::Down if y + 1 < self.height => cell + self.width,
+            Direction::Left if x > 0 => cell - 1,
+            Direction::Right if x + 1 < self.width => cell + 1,
+            _ => return None,
+        };
+        (!self.walls[next]).then_some(next)
+    }
+
+    /// The floor the keeper can walk to from `keeper` around `boxes`.
+    fn reach(&self, boxes: &[usize], keeper: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.walls.len()];
+        seen[keeper] = true;
+        let mut queue = VecDeque::from([keeper]);
+        while let Some(cell) = queue.pop_front() {
+            for direction in Direction::ALL {
+                if let Some(next) = self.step(cell, direction) {
+                    if !seen[next] && boxes.binary_search(&next).is_err() {
+                        seen[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        seen
+    }
+
+    fn position(&self, boxes: Vec<usize>, keeper: usize) -> Position {
+        let region = self.reach(&boxes, keeper).iter().position(|&r| r).unwrap();
+        Position {
+            boxes,
+            region,
+            keeper,
+        }
+    }
+
+    /// Whether the box just pushed to `cell` is frozen in a two by two
+    /// square of walls and boxes that is not all on goals.
+    fn frozen(&self, boxes: &[usize], cell: usize) -> bool {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let blocked = |x: usize, y: usize| {
+            let i = y * self.width + x;
+            self.walls[i] || boxes.binary_search(&i).is_ok()
+        };
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let (Some(left), Some(top)) = (x.checked_sub(dx), y.checked_sub(dy)) else {
+                continue;
+            };
+            if left + 1 >= self.width || top + 1 >= self.height {
+                continue;
+            }
+            let square = [
+                (left, top),
+                (left + 1, top),
+                (left, top + 1),
+                (left + 1, top + 1),
+            ];
+            if square.iter().all(|&(x, y)| blocked(x, y))
+                && square.iter().any(|&(x, y)| {
+                    let i = y * self.width + x;
+                    boxes.binary_search(&i).is_ok() && !self.goals[i]
+                })
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether a box of `position` can never reach a goal, being on dead
+    /// floor or frozen in place. The search never pushes into either.
+    pub fn is_deadlocked(&self, position: &Position) -> bool {
+        position
+            .boxes
+            .iter()
+            .any(|&b| self.dead[b] || self.frozen(&position.boxes, b))
+    }
+
+    /// The keeper's walk and pushes as a LURD string, lower case for a
+    /// step and upper case for a push, or `None` if a push cannot be made.
+    pub fn lurd(&self, pushes: &[Push]) -> Option<String> {
+        let mut boxes = self.boxes.clone();
+        let mut keeper = self.keeper;
+        let mut out = String::new();
+        for push in pushes {
+            let cell = push.y * self.width + push.x;
+            let behind = self.step(cell, opposite(push.direction))?;
+            out.push_str(&self.walk(&boxes, keeper, behind)?);
+            let to = self.step(cell, push.direction)?;
+            let index = boxes.binary_search(&cell).ok()?;
+            if boxes.binary_search(&to).is_ok() {
+                return None;
+            }
+            boxes.remove(index);
+            boxes.insert(boxes.binary_search(&to).unwrap_err(), to);
+            keeper = cell;
+            out.push(letter(push.direction).to_ascii_uppercase());
+        }
+        Some(out)
+    }
+
+    /// The shortest walk from `from` to `to` around `boxes`, in LURD.
+    fn walk(&self, boxes: &[usize], from: usize, to: usize) -> Option<String> {
+        let mut parent: Vec<Option<(usize, Direction)>> = vec![None; self.walls.len()];
+        let mut queue = VecDeque::from([from]);
+        let mut seen = vec![false; self.walls.len()];
+        seen[from] = true;
+        while let Some(cell) = queue.pop_front() {
+            if cell == to {
+                let mut steps = Vec::new();
+                let mut at = to;
+                while let Some((previous, direction)) = parent[at] {
+                    steps.push(letter(direction));
+                    at = previous;
+                }
+                return Some(steps.into_iter().rev().collect());
+            }
+            for direction in Direction::ALL {
+                if let Some(next) = self.step(cell, direction) {
+                    if !seen[next] && boxes.binary_search(&next).is_err() {
+                        seen[next] = true;
+                        parent[next] = Some((cell, direction));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// `position` in XSB.
+    pub fn render(&self, position: &Position) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            if y > 0 {
+                out.push('\n');
+            }
+            let mut row = String::new();
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                let is_box = position.boxes.binary_search(&i).is_ok();
+                row.push(
+                    match (self.walls[i], is_box, i == position.keeper, self.goals[i]) {
+                        (true, ..) => '#',
+                        (_, true, _, true) => '*',
+                        (_, true, _, false) => '$',
+                        (_, _, true, true) => '+',
+                        (_, _, true, false) => '@',
+                        (_, _, _, true) => '.',
+                        _ => ' ',
+                    },
+                );
+            }
+            out.push_str(row.trim_end());
+        }
+        out
+    }
+
+    /// Marks the floor a box can never leave for a goal, by pulling boxes
+    /// back from every goal.
+    fn find_dead(&mut self) {
+        let mut live = vec![false; self.walls.len()];
+        let mut queue: VecDeque<usize> = (0..self.walls.len()).filter(|&i| self.goals[i]).collect();
+        for &goal in &queue {
+            live[goal] = true;
+        }
+        while let Some(cell) = queue.pop_front() {
+            for direction in Direction::ALL {
+                // A box reaches `cell` from `from` with the keeper behind.
+                let Some(from) = self.step(cell, opposite(direction)) else {
+                    continue;
+                };
+                if !live[from] && self.step(from, opposite(direction)).is_some() {
+                    live[from] = true;
+                    queue.push_back(from);
+                }
+            }
+        }
+        self.dead = (0..self.walls.len())
+            .map(|i| !self.walls[i] && !live[i])
+            .collect();
+    }
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+fn letter(direction: Direction) -> char {
+    match direction {
+        Direction::Up => 'u',
+        Direction::Down => 'd',
+        Direction::Left => 'l',
+        Direction::Right => 'r',
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&self.start()))
+    }
+}
+
+impl Puzzle for Level {
+    type State = Position;
+    type Move = Push;
+
+    fn successors(&self, state: &Position) -> Vec<(Push, Position)> {
+        let reach = self.reach(&state.boxes, state.keeper);
+        let mut out = Vec::new();
+        for (index, &cell) in state.boxes.iter().enumerate() {
+            for direction in Direction::ALL {
+                let (Some(behind), Some(to)) = (
+                    self.step(cell, opposite(direction)),
+                    self.step(cell, direction),
+                ) else {
+                    continue;
+                };
+                if !reach[behind] || self.dead[to] || state.boxes.binary_search(&to).is_ok() {
+                    continue;
+                }
+                let mut boxes = state.boxes.clone();
+                boxes.remove(index);
+                boxes.insert(boxes.binary_search(&to).unwrap_err(), to);
+                if self.frozen(&boxes, to) {
+                    continue;
+                }
+                let push = Push {
+                    x: cell % self.width,
+                    y: cell / self.width,
+                    direction,
+                };
+                out.push((push, self.position(boxes, cell)));
+            }
+        }
+        out
+    }
+
+    fn is_goal(&self, state: &Position) -> bool {
+        state.boxes.iter().all(|&b| self.goals[b])
+    }
+
+    /// Each box's distance to the nearest goal, counting rows and columns.
+    fn estimate(&self, state: &Position) -> usize {
+        let goals: Vec<usize> = (0..self.goals.len()).filter(|&i| self.goals[i]).collect();
+        let w = self.width;
+        state
+            .boxes
+            .iter()
+            .map(|&b| {
+                goals
+                    .iter()
+                    .map(|&g| (b % w).abs_diff(g % w) + (b / w).abs_diff(g / w))
+                    .min()
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLevelError(pub String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseLevelError {}
+
+fn is_map_line(line: &str) -> bool {
+    !line.trim().is_empty() && line.chars().all(|c| "#@+$*. -_".contains(c))
+}
+
+/// Every level in `text`, as in a collection where levels are separated by
+/// blank lines and titles or comments starting with `;`.
+pub fn parse_levels(text: &str) -> Result<Vec<Level>, ParseLevelError> {
+    let mut levels = Vec::new();
+    let mut rows: Vec<&str> = Vec::new();
+    for line in text.lines().chain([""]) {
+        let line = line.trim_end();
+        if is_map_line(line) {
+            rows.push(line);
+        } else if !rows.is_empty() {
+            let level = parse_rows(&rows)
+                .map_err(|e| ParseLevelError(format!("level {}: {}", levels.len() + 1, e.0)))?;
+            levels.push(level);
+            rows.clear();
+        }
+    }
+    Ok(levels)
+}
+
+fn parse_rows(rows: &[&str]) -> Result<Level, ParseLevelError> {
+    let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+    let height = rows.len();
+    let mut level = Level {
+        width,
+        height,
+        walls: vec![false; width * height],
+        goals: vec![false; width * height],
+        dead: Vec::new(),
+        keeper: usize::MAX,
+        boxes: Vec::new(),
+    };
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let i = y * width + x;
+            level.walls[i] = c == '#';
+            level.goals[i] = matches!(c, '.' | '*' | '+');
+            if matches!(c, '$' | '*') {
+                level.boxes.push(i);
+            }
+            if matches!(c, '@' | '+') {
+                if level.keeper != usize::MAX {
+                    return Err(ParseLevelError("more than one keeper".to_string()));
+                }
+                level.keeper = i;
+            }
+        }
+    }
+    if level.keeper == usize::MAX {
+        return Err(ParseLevelError("no keeper".to_string()));
+    }
+    let goals = level.goals.iter().filter(|&&g| g).count();
+    if level.boxes.is_empty() || level.boxes.len() != goals {
+        return Err(ParseLevelError(format!(
+            "{} boxes for {} goals",
+            level.boxes.len(),
+            goals
+        )));
+    }
+    level.find_dead();
+    Ok(level)
+}
+
+/// A single level; see [`parse_levels`] for a collection.
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Level, ParseLevelError> {
+        let mut levels = parse_levels(s)?;
+        match levels.len() {
+            1 => Ok(levels.remove(0)),
+            0 => Err(ParseLevelError("no level found".to_string())),
+            n => Err(ParseLevelError(format!("expected one level, found {}", n))),
+        }
+    }
+}
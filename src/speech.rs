@@ -0,0 +1,95 @@
+//! Boards and moves in plain sentences, for screen readers that make
+//! nothing of the drawn board. Columns count from 1 on the left and rows
+//! from 1 at the top, as in the standard notation's `d2`.
+
+use crate::render::RenderOptions;
+use crate::{Action, Direction, Grid, Tile};
+
+/// `column 4 row 2`.
+pub fn cell((x, y): (usize, usize)) -> String {
+    format!("column {} row {}", x + 1, y + 1)
+}
+
+/// `peg at column 4 row 2 jumps down, capturing column 4 row 3`.
+pub fn describe_action(action: Action) -> String {
+    describe_jump(action.origin(), action.dir, action.captured())
+}
+
+/// [`describe_action`] for the jump of the peg on `from` over `captured`,
+/// on any board.
+pub fn describe_jump(
+    from: (usize, usize),
+    direction: Direction,
+    captured: Option<(usize, usize)>,
+) -> String {
+    let mut out = format!("peg at {} jumps {}", cell(from), direction.name());
+    if let Some(captured) = captured {
+        out.push_str(", capturing ");
+        out.push_str(&cell(captured));
+    }
+    out
+}
+
+/// `3`, `3 and 5` or `3, 4 and 5`.
+fn list(items: &[usize]) -> String {
+    let words: Vec<String> = items.iter().map(usize::to_string).collect();
+    match words.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+fn plural(count: u32, one: &str, many: &str) -> String {
+    match count {
+        1 => format!("1 {}", one),
+        _ => format!("{} {}", count, many),
+    }
+}
+
+/// One sentence for the peg and hole counts, one per row saying which
+/// columns hold pegs, then the move about to be made or just played and
+/// where the goal wants pegs, from `options`.
+pub fn describe_board(grid: &Grid, options: &RenderOptions) -> String {
+    let pegs = grid.filled_count();
+    let holes = (0..7)
+        .flat_map(|x| (0..7).map(move |y| (x, y)))
+        .filter(|&(x, y)| grid.tile(x, y) == Tile::Empty)
+        .count() as u32;
+    let mut out = format!(
+        "{} and {}.\n",
+        plural(pegs, "peg", "pegs"),
+        plural(holes, "hole", "holes")
+    );
+    for y in 0..7 {
+        let columns: Vec<usize> = (0..7)
+            .filter(|&x| grid.tile(x, y) == Tile::Filled)
+            .map(|x| x + 1)
+            .collect();
+        let row = match columns.len() {
+            0 => "no pegs".to_string(),
+            1 => format!("peg in column {}", list(&columns)),
+            _ => format!("pegs in columns {}", list(&columns)),
+        };
+        out.push_str(&format!("Row {}: {}.\n", y + 1, row));
+    }
+    if let Some(action) = options.last_move {
+        out.push_str(&format!("Last move: {}.\n", describe_action(action)));
+    }
+    if let Some(action) = options.next_move {
+        out.push_str(&format!("Next move: {}.\n", describe_action(action)));
+    }
+    if let Some(goal) = options.goal {
+        let cells: Vec<String> = (0..7)
+            .flat_map(|y| (0..7).map(move |x| (x, y)))
+            .filter(|&(x, y)| goal.tile(x, y) == Tile::Filled)
+            .map(cell)
+            .collect();
+        if let [only] = &cells[..] {
+            out.push_str(&format!("Goal: one peg on {}.\n", only));
+        } else if !cells.is_empty() {
+            out.push_str(&format!("Goal: pegs on {}.\n", cells.join("; ")));
+        }
+    }
+    out
+}
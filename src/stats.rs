@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
+use std::time::Duration;
+
+/// Counters gathered while a search runs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// Positions whose moves were generated.
+    pub nodes_expanded: u64,
+    /// Generated positions that were already in the memo.
+    pub duplicates: u64,
+    /// Positions dropped without expanding them, by the rule that dropped
+    /// them, in the order the rules first fired.
+    pub pruned: Vec<(&'static str, u64)>,
+    /// Most positions held in the memo at once.
+    pub memo_peak: usize,
+    /// Estimated bytes the memo's table occupied at its largest.
+    pub memo_bytes: usize,
+    /// Most positions waiting to be expanded at once.
+    pub frontier_peak: usize,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    pub fn prune(&mut self, rule: &'static str) {
+        match self.pruned.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, count)) => *count += 1,
+            None => self.pruned.push((rule, 1)),
+        }
+    }
+
+    pub fn pruned_total(&self) -> u64 {
+        self.pruned.iter().map(|(_, count)| count).sum()
+    }
+
+    pub(crate) fn observe_memo<T>(&mut self, memo: &HashSet<T>) {
+        self.observe_memo_size(memo.len(), memo.capacity() * (mem::size_of::<T>() + 1));
+    }
+
+    /// Records a memo of `entries` positions taking about `bytes`.
+    pub(crate) fn observe_memo_size(&mut self, entries: usize, bytes: usize) {
+        self.memo_peak = self.memo_peak.max(entries);
+        self.memo_bytes = self.memo_bytes.max(bytes);
+    }
+
+    pub(crate) fn observe_frontier(&mut self, len: usize) {
+        self.frontier_peak = self.frontier_peak.max(len);
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "nodes expanded: {}", self.nodes_expanded)?;
+        writeln!(f, "duplicate hits: {}", self.duplicates)?;
+        writeln!(f, "pruned: {}", self.pruned_total())?;
+        for (rule, count) in &self.pruned {
+            writeln!(f, "  {}: {}", rule, count)?;
+        }
+        writeln!(
+            f,
+            "memo peak: {} positions, {:.1} KiB",
+            self.memo_peak,
+            self.memo_bytes as f64 / 1024.0
+        )?;
+        writeln!(f, "frontier peak: {}", self.frontier_peak)?;
+        write!(f, "time: {:.3} s", self.elapsed.as_secs_f64())
+    }
+}
@@ -0,0 +1,440 @@
+//! Sudoku: fill a 9 by 9 grid so that every row, column and 3 by 3 box
+//! holds the digits 1 to 9. The solver keeps each empty cell's candidates
+//! as a bitmask, fills in what the logical techniques of [`Grade`] force
+//! and guesses on the cell with the fewest candidates when they stall.
+//! The same techniques grade a puzzle by the hardest one it needs, and
+//! [`generate`] empties a random solved grid clue by clue for as long as
+//! the puzzle keeps a unique solution and stays within the grade asked for.
+//!
+//! A grid is read as 81 cells in reading order, digits for clues and `.`
+//! or `0` for empty cells; white space and the `|`, `-` and `+` of drawn
+//! grids are skipped.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::rng::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Sudoku {
+    /// 0 for an empty cell.
+    cells: [u8; 81],
+}
+
+impl Sudoku {
+    pub fn empty() -> Sudoku {
+        Sudoku { cells: [0; 81] }
+    }
+
+    /// The digit at column `x` and row `y`, both from 0.
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        Some(self.cells[y * 9 + x]).filter(|&d| d != 0)
+    }
+
+    pub fn clues(&self) -> usize {
+        self.cells.iter().filter(|&&d| d != 0).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.clues() == 81
+    }
+
+    /// The 81 cells on one line, `.` for the empty ones.
+    pub fn line(&self) -> String {
+        self.cells
+            .iter()
+            .map(|&d| if d == 0 { '.' } else { (b'0' + d) as char })
+            .collect()
+    }
+
+    /// The solution, if the clues allow one. With several, the first the
+    /// search comes to.
+    pub fn solve(&self) -> Option<Sudoku> {
+        let mut found = None;
+        Candidates::new(self)?.search(&mut |s| {
+            found = Some(s);
+            false
+        });
+        found
+    }
+
+    /// How many solutions there are, counting no further than `limit`;
+    /// a proper puzzle has exactly one.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let Some(candidates) = Candidates::new(self) else {
+            return 0;
+        };
+        let mut count = 0;
+        if limit > 0 {
+            candidates.search(&mut |_| {
+                count += 1;
+                count < limit
+            });
+        }
+        count
+    }
+
+    /// The hardest technique needed to solve the puzzle without guessing,
+    /// or [`Grade::Expert`] when they are not enough. Meant for puzzles
+    /// with a unique solution.
+    pub fn grade(&self) -> Grade {
+        let Some(mut candidates) = Candidates::new(self) else {
+            return Grade::Expert;
+        };
+        let mut hardest = Grade::Easy;
+        loop {
+            if candidates.is_complete() {
+                return hardest;
+            }
+            match candidates.step(Grade::Hard) {
+                Some(grade) => hardest = hardest.max(grade),
+                None => return Grade::Expert,
+            }
+        }
+    }
+}
+
+/// Bands of three rows with `|` between the boxes of a row.
+impl fmt::Display for Sudoku {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..9 {
+            if y > 0 {
+                writeln!(f)?;
+                if y % 3 == 0 {
+                    writeln!(f, "------+-------+------")?;
+                }
+            }
+            for x in 0..9 {
+                if x > 0 {
+                    write!(f, "{}", if x % 3 == 0 { " | " } else { " " })?;
+                }
+                match self.get(x, y) {
+                    Some(d) => write!(f, "{}", d)?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSudokuError(pub String);
+
+impl fmt::Display for ParseSudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ParseSudokuError {}
+
+impl FromStr for Sudoku {
+    type Err = ParseSudokuError;
+
+    fn from_str(s: &str) -> Result<Sudoku, ParseSudokuError> {
+        let mut cells = [0; 81];
+        let mut count = 0;
+        for c in s.chars() {
+            let digit = match c {
+                '1'..='9' => c as u8 - b'0',
+                '.' | '0' => 0,
+                '|' | '-' | '+' => continue,
+                c if c.is_whitespace() => continue,
+                c => return Err(ParseSudokuError(format!("unknown cell {:?}", c))),
+            };
+            if count < 81 {
+                cells[count] = digit;
+            }
+            count += 1;
+        }
+        if count != 81 {
+            return Err(ParseSudokuError(format!(
+                "expected 81 cells, found {}",
+                count
+            )));
+        }
+        Ok(Sudoku { cells })
+    }
+}
+
+/// How hard a puzzle is by the techniques it needs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Grade {
+    /// Hidden singles: a digit with one place left in a row, column or
+    /// box.
+    #[default]
+    Easy,
+    /// Naked singles as well: a cell with one digit left.
+    Medium,
+    /// Locked candidates as well: a digit confined to where a box meets a
+    /// row or column is ruled out of the rest of the other.
+    Hard,
+    /// Guessing and backing out of contradictions.
+    Expert,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 4] = [Grade::Easy, Grade::Medium, Grade::Hard, Grade::Expert];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Grade::Easy => "easy",
+            Grade::Medium => "medium",
+            Grade::Hard => "hard",
+            Grade::Expert => "expert",
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownGrade(pub String);
+
+impl fmt::Display for UnknownGrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown difficulty {:?}, expected easy, medium, hard or expert",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownGrade {}
+
+impl FromStr for Grade {
+    type Err = UnknownGrade;
+
+    fn from_str(s: &str) -> Result<Grade, UnknownGrade> {
+        Grade::ALL
+            .into_iter()
+            .find(|g| g.name() == s)
+            .ok_or_else(|| UnknownGrade(s.to_string()))
+    }
+}
+
+/// The 27 rows, columns and boxes, as cell indices.
+fn units() -> Vec<[usize; 9]> {
+    let mut units = Vec::with_capacity(27);
+    for i in 0..9 {
+        units.push(std::array::from_fn(|j| i * 9 + j));
+        units.push(std::array::from_fn(|j| j * 9 + i));
+        let (bx, by) = (i % 3 * 3, i / 3 * 3);
+        units.push(std::array::from_fn(|j| (by + j / 3) * 9 + bx + j % 3));
+    }
+    units
+}
+
+fn peers(cell: usize) -> impl Iterator<Item = usize> {
+    let (x, y) = (cell % 9, cell / 9);
+    (0..81).filter(move |&other| {
+        let (ox, oy) = (other % 9, other / 9);
+        other != cell && (ox == x || oy == y || (ox / 3 == x / 3 && oy / 3 == y / 3))
+    })
+}
+
+/// A grid being solved: the digits placed and the candidates left for
+/// each empty cell, bit `d` for digit `d`.
+#[derive(Clone)]
+struct Candidates {
+    sudoku: Sudoku,
+    left: [u16; 81],
+}
+
+impl Candidates {
+    /// The grid with every clue placed, or `None` when two clues clash.
+    fn new(sudoku: &Sudoku) -> Option<Candidates> {
+        let mut candidates = Candidates {
+            sudoku: Sudoku::empty(),
+            left: [0b11_1111_1110; 81],
+        };
+        for (cell, &d) in sudoku.cells.iter().enumerate() {
+            if d != 0 && !candidates.place(cell, d) {
+                return None;
+            }
+        }
+        Some(candidates)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.sudoku.is_complete()
+    }
+
+    /// Puts `d` in `cell`, returning false if that leaves some cell with
+    /// nothing to hold.
+    fn place(&mut self, cell: usize, d: u8) -> bool {
+        if self.left[cell] & 1 << d == 0 {
+            return false;
+        }
+        self.sudoku.cells[cell] = d;
+        self.left[cell] = 0;
+        for peer in peers(cell) {
+            self.left[peer] &= !(1 << d);
+            if self.sudoku.cells[peer] == 0 && self.left[peer] == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Applies the easiest technique, no harder than `limit`, that makes
+    /// progress, and returns its grade; `None` when none does. A
+    /// contradiction counts as progress, found by [`Candidates::dead`].
+    fn step(&mut self, limit: Grade) -> Option<Grade> {
+        let units = units();
+        for unit in &units {
+            for d in 1..=9u8 {
+                let mut places = unit.iter().filter(|&&c| self.left[c] & 1 << d != 0);
+                if let (Some(&cell), None) = (places.next(), places.next()) {
+                    self.place(cell, d);
+                    return Some(Grade::Easy);
+                }
+            }
+        }
+        if limit < Grade::Medium {
+            return None;
+        }
+        for cell in 0..81 {
+            if self.left[cell].count_ones() == 1 {
+                self.place(cell, self.left[cell].trailing_zeros() as u8);
+                return Some(Grade::Medium);
+            }
+        }
+        if limit < Grade::Hard {
+            return None;
+        }
+        // A box against each row or column it crosses, both ways round.
+        let boxes: Vec<&[usize; 9]> = units.iter().skip(2).step_by(3).collect();
+        let lines: Vec<&[usize; 9]> = (0..27).filter(|i| i % 3 != 2).map(|i| &units[i]).collect();
+        let mut progress = false;
+        for b in &boxes {
+            for line in &lines {
+                let shared: Vec<usize> = b.iter().copied().filter(|c| line.contains(c)).collect();
+                if shared.is_empty() {
+                    continue;
+                }
+                for (within, outside) in [(b, line), (line, b)] {
+                    for d in 1..=9u8 {
+                        let bit = 1 << d;
+                        let here = shared.iter().any(|&c| self.left[c] & bit != 0);
+                        let elsewhere = within
+                            .iter()
+                            .any(|c| !shared.contains(c) && self.left[*c] & bit != 0);
+                        if !here || elsewhere {
+                            continue;
+                        }
+                        for &c in outside.iter().filter(|c| !shared.contains(c)) {
+                            if self.left[c] & bit != 0 {
+                                self.left[c] &= !bit;
+                                progress = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        progress.then_some(Grade::Hard)
+    }
+
+    /// Whether some empty cell has no digit left, or some unit no place
+    /// left for a digit it lacks.
+    fn dead(&self) -> bool {
+        let empty_cell = (0..81).any(|c| self.sudoku.cells[c] == 0 && self.left[c] == 0);
+        empty_cell
+            || units().iter().any(|unit| {
+                let placed = unit
+                    .iter()
+                    .fold(0u16, |m, &c| m | 1 << self.sudoku.cells[c]);
+                let open = unit.iter().fold(0u16, |m, &c| m | self.left[c]);
+                (placed | open) & 0b11_1111_1110 != 0b11_1111_1110
+            })
+    }
+
+    /// Passes each solution to `emit` until it returns false, which it
+    /// passes back up.
+    fn search(mut self, emit: &mut dyn FnMut(Sudoku) -> bool) -> bool {
+        while !self.dead() && !self.is_complete() {
+            if self.step(Grade::Medium).is_none() {
+                break;
+            }
+        }
+        if self.dead() {
+            return true;
+        }
+        if self.is_complete() {
+            return emit(self.sudoku);
+        }
+        let cell = (0..81)
+            .filter(|&c| self.sudoku.cells[c] == 0)
+            .min_by_key(|&c| self.left[c].count_ones())
+            .unwrap();
+        for d in 1..=9u8 {
+            if self.left[cell] & 1 << d == 0 {
+                continue;
+            }
+            let mut guess = self.clone();
+            if guess.place(cell, d) && !guess.search(emit) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A puzzle of grade `grade` with a unique solution, the same for the same
+/// seed. Random solved grids are emptied one clue at a time in random
+/// order, keeping each removal that neither admits a second solution nor
+/// asks more than `grade`, until one lands on the grade exactly.
+pub fn generate(grade: Grade, seed: u64) -> Sudoku {
+    let mut rng = Rng::new(seed);
+    loop {
+        let solved = random_solution(&mut rng);
+        let mut puzzle = solved;
+        let mut order: Vec<usize> = (0..81).collect();
+        rng.shuffle(&mut order);
+        for cell in order {
+            let digit = puzzle.cells[cell];
+            puzzle.cells[cell] = 0;
+            if puzzle.count_solutions(2) != 1 || puzzle.grade() > grade {
+                puzzle.cells[cell] = digit;
+            }
+        }
+        if puzzle.grade() == grade {
+            return puzzle;
+        }
+    }
+}
+
+/// A solved grid, filled cell by cell with the digits tried in a random
+/// order.
+fn random_solution(rng: &mut Rng) -> Sudoku {
+    fill(Candidates::new(&Sudoku::empty()).unwrap(), rng).unwrap()
+}
+
+fn fill(candidates: Candidates, rng: &mut Rng) -> Option<Sudoku> {
+    if candidates.is_complete() {
+        return Some(candidates.sudoku);
+    }
+    let cell = (0..81)
+        .filter(|&c| candidates.sudoku.cells[c] == 0)
+        .min_by_key(|&c| candidates.left[c].count_ones())?;
+    let mut digits: Vec<u8> = (1..=9)
+        .filter(|d| candidates.left[cell] & 1 << d != 0)
+        .collect();
+    rng.shuffle(&mut digits);
+    digits.into_iter().find_map(|d| {
+        let mut next = candidates.clone();
+        if next.place(cell, d) {
+            fill(next, rng)
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use crate::notation::Standard;
+use crate::{Action, Grid, Tile};
+
+const CELL: usize = 40;
+const MARGIN: usize = 20;
+const CAPTION: usize = 28;
+const COLUMNS: usize = 4;
+const BOARD: usize = 7 * CELL;
+
+const STYLE: &str = "  <style>
+    .cell { fill: #e8d6b3; stroke: #b59a6a; stroke-width: 1; }
+    .peg { fill: #8b2e16; stroke: #4a1608; stroke-width: 1.5; }
+    .hole { fill: #6b5535; }
+    .jump { stroke: #1f5fa8; stroke-width: 4; stroke-linecap: round; marker-end: url(#head); }
+    text { font-family: sans-serif; font-size: 16px; fill: #222; text-anchor: middle; }
+  </style>
+  <defs>
+    <marker id=\"head\" viewBox=\"0 0 10 10\" refX=\"6\" refY=\"5\" markerWidth=\"4\" markerHeight=\"4\" orient=\"auto\">
+      <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#1f5fa8\"/>
+    </marker>
+  </defs>
+";
+
+fn centre(origin: (usize, usize), (x, y): (usize, usize)) -> (usize, usize) {
+    (
+        origin.0 + x * CELL + CELL / 2,
+        origin.1 + y * CELL + CELL / 2,
+    )
+}
+
+/// Draws `grid` with its top left corner at `origin`, with an arrow for
+/// `jump` if there is one.
+fn board(out: &mut String, grid: &Grid, origin: (usize, usize), jump: Option<Action>) {
+    for y in 0..7 {
+        for x in 0..7 {
+            let tile = grid.tile(x, y);
+            if tile == Tile::Blocked {
+                continue;
+            }
+            let (cx, cy) = centre(origin, (x, y));
+            let _ = writeln!(
+                out,
+                "  <rect class=\"cell\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                cx - CELL / 2,
+                cy - CELL / 2,
+                CELL,
+                CELL
+            );
+            let (class, radius) = match tile {
+                Tile::Filled => ("peg", CELL * 3 / 8),
+                _ => ("hole", CELL / 8),
+            };
+            let _ = writeln!(
+                out,
+                "  <circle class=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>",
+                class, cx, cy, radius
+            );
+        }
+    }
+    if let Some((action, to)) = jump.and_then(|a| a.landing().map(|to| (a, to))) {
+        let (x1, y1) = centre(origin, action.origin());
+        let (x2, y2) = centre(origin, to);
+        let _ = writeln!(
+            out,
+            "  <line class=\"jump\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+            x1, y1, x2, y2
+        );
+    }
+}
+
+fn document(width: usize, height: usize, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}{}</svg>\n",
+        STYLE,
+        body,
+        w = width,
+        h = height
+    )
+}
+
+/// A single position as a standalone SVG image.
+pub fn position(grid: &Grid) -> String {
+    let mut body = String::new();
+    board(&mut body, grid, (MARGIN, MARGIN), None);
+    let size = BOARD + 2 * MARGIN;
+    document(size, size, &body)
+}
+
+/// One panel per position along `moves` from `start`, left to right and
+/// then down in rows of four. Every panel but the last shows the next jump
+/// as an arrow and names it underneath.
+pub fn solution(start: &Grid, moves: &[Action]) -> String {
+    let panels = moves.len() + 1;
+    let columns = panels.min(COLUMNS);
+    let rows = panels.div_ceil(COLUMNS);
+    let (panel_w, panel_h) = (BOARD + MARGIN, BOARD + CAPTION + MARGIN);
+    let mut body = String::new();
+    let mut state = *start;
+    for i in 0..panels {
+        let next = moves.get(i).copied();
+        let origin = (
+            MARGIN + (i % COLUMNS) * panel_w,
+            MARGIN + (i / COLUMNS) * panel_h,
+        );
+        board(&mut body, &state, origin, next);
+        let caption = match next {
+            Some(action) => format!("{}. {}", i + 1, Standard(action)),
+            None => format!("{} pegs left", state.filled_count()),
+        };
+        let _ = writeln!(
+            body,
+            "  <text x=\"{}\" y=\"{}\">{}</text>",
+            origin.0 + BOARD / 2,
+            origin.1 + BOARD + CAPTION - 8,
+            caption
+        );
+        if let Some(action) = next {
+            state = state.perform_action(action);
+        }
+    }
+    document(columns * panel_w + MARGIN, rows * panel_h + MARGIN, &body)
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::bitboard::JumpTable;
+use crate::symmetry::{self, Symmetry};
+use crate::{Action, GameTree, Grid};
+
+/// A solution chosen to pass through as many symmetric positions as
+/// possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymmetricSolution {
+    pub moves: Vec<Action>,
+    /// Move counts after which the board was symmetric under some rotation
+    /// or reflection, in increasing order.
+    pub symmetric_after: Vec<usize>,
+}
+
+impl SymmetricSolution {
+    /// Length of the opening during which every other position at most was
+    /// asymmetric, i.e. how long the line "stayed" symmetric.
+    pub fn symmetric_opening(&self) -> usize {
+        let mut last = 0;
+        for &after in &self.symmetric_after {
+            if after > last + 2 {
+                break;
+            }
+            last = after;
+        }
+        last
+    }
+}
+
+const DEAD: u8 = u8::MAX;
+
+/// Finds a line from `start` to `goal` maximising the number of positions
+/// along it that are fixed by some non-trivial symmetry of the board.
+///
+/// Every position reachable from `start` is enumerated and labelled, from
+/// the fewest pegs upwards, with the largest number of symmetric positions
+/// still to come on a winning line (or as dead). Whether a position is
+/// symmetric does not change under the board's symmetries, so positions
+/// are stored up to symmetry.
+pub fn search(start: &Grid, goal: &Grid) -> Option<SymmetricSolution> {
+    let table = JumpTable::new(start);
+    let layout = symmetry::layout_symmetries(start);
+    let group: Vec<Symmetry> = symmetry::grid_symmetries(goal)
+        .into_iter()
+        .filter(|s| layout.contains(s))
+        .collect();
+    let canon = |bits| symmetry::canonical(bits, &group);
+    let symmetric = |bits: u64| {
+        group
+            .iter()
+            .any(|&s| s != Symmetry::Identity && s.apply_bits(bits) == bits)
+    };
+
+    let mut layers = vec![vec![canon(start.encode())]];
+    loop {
+        let mut seen = HashSet::new();
+        for &bits in layers.last().unwrap() {
+            seen.extend(table.forward(bits).map(|(_, n)| canon(n)));
+        }
+        if seen.is_empty() {
+            break;
+        }
+        layers.push(seen.into_iter().collect());
+    }
+
+    let goal_bits = goal.encode();
+    let goal_key = canon(goal_bits);
+    let mut value: HashMap<u64, u8> = HashMap::new();
+    let gain = |value: &HashMap<u64, u8>, next: u64| match value[&canon(next)] {
+        DEAD => None,
+        v => Some(v + u8::from(symmetric(next))),
+    };
+    while let Some(layer) = layers.pop() {
+        for bits in layer {
+            let best = if bits == goal_key {
+                0
+            } else {
+                table
+                    .forward(bits)
+                    .filter_map(|(_, next)| gain(&value, next))
+                    .max()
+                    .unwrap_or(DEAD)
+            };
+            value.insert(bits, best);
+        }
+    }
+
+    let mut cur = start.encode();
+    let mut remaining = value[&canon(cur)];
+    if remaining == DEAD {
+        return None;
+    }
+    let mut moves = Vec::new();
+    let mut symmetric_after = Vec::new();
+    while cur != goal_bits {
+        let (action, next) = table
+            .forward(cur)
+            .find(|&(_, n)| gain(&value, n) == Some(remaining))
+            .unwrap();
+        moves.push(action);
+        if symmetric(next) {
+            symmetric_after.push(moves.len());
+            remaining -= 1;
+        }
+        cur = next;
+    }
+    Some(SymmetricSolution {
+        moves,
+        symmetric_after,
+    })
+}
+
+impl GameTree {
+    /// Solves for the centre peg through as many symmetric positions as
+    /// possible.
+    pub fn search_symmetric(&self) -> Option<(GameTree, SymmetricSolution)> {
+        let goal = self.state.single_peg(3, 3);
+        let found = search(&self.state, &goal)?;
+        Some((self.extend(&found.moves), found))
+    }
+}
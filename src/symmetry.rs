@@ -0,0 +1,162 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Action, Direction, Grid, Tile};
+
+/// The eight rotations and reflections of the 7x7 square about its centre.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::Identity => "identity",
+            Symmetry::Rotate90 => "rotate-90",
+            Symmetry::Rotate180 => "rotate-180",
+            Symmetry::Rotate270 => "rotate-270",
+            Symmetry::FlipHorizontal => "flip-horizontal",
+            Symmetry::FlipVertical => "flip-vertical",
+            Symmetry::Transpose => "transpose",
+            Symmetry::AntiTranspose => "anti-transpose",
+        }
+    }
+
+    pub fn apply_point(self, x: usize, y: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (6 - y, x),
+            Symmetry::Rotate180 => (6 - x, 6 - y),
+            Symmetry::Rotate270 => (y, 6 - x),
+            Symmetry::FlipHorizontal => (6 - x, y),
+            Symmetry::FlipVertical => (x, 6 - y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (6 - y, 6 - x),
+        }
+    }
+
+    pub fn apply_direction(self, dir: Direction) -> Direction {
+        let (dx, dy): (i32, i32) = match dir {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+        // Map the vector through the point transform around the centre cell.
+        let (x, y) = self.apply_point((3 + dx) as usize, (3 + dy) as usize);
+        match (x as i32 - 3, y as i32 - 3) {
+            (0, -1) => Direction::Up,
+            (0, 1) => Direction::Down,
+            (-1, 0) => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+
+    pub fn apply_action(self, action: Action) -> Action {
+        let (x, y) = self.apply_point(action.x, action.y);
+        Action {
+            x,
+            y,
+            dir: self.apply_direction(action.dir),
+        }
+    }
+
+    pub fn apply_grid(self, grid: &Grid) -> Grid {
+        let mut tiles = [[Tile::Blocked; 7]; 7];
+        for x in 0..7 {
+            for y in 0..7 {
+                let (nx, ny) = self.apply_point(x, y);
+                tiles[nx][ny] = grid.grid[x][y];
+            }
+        }
+        Grid {
+            grid: tiles,
+            filled_count: grid.filled_count,
+        }
+    }
+
+    /// Transforms a peg bitmask as produced by [`Grid::encode`].
+    pub fn apply_bits(self, bits: u64) -> u64 {
+        let mut out = 0;
+        let mut rest = bits;
+        while rest != 0 {
+            let i = rest.trailing_zeros() as usize;
+            rest &= rest - 1;
+            let (x, y) = self.apply_point(i / 7, i % 7);
+            out |= 1 << (x * 7 + y);
+        }
+        out
+    }
+
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The line `moves` from `start` as seen on the board turned or mirrored by
+/// each of the eight symmetries, with the start position it is played from.
+pub fn variants(start: &Grid, moves: &[Action]) -> Vec<(Symmetry, Grid, Vec<Action>)> {
+    Symmetry::ALL
+        .into_iter()
+        .map(|s| {
+            let line = moves.iter().map(|&a| s.apply_action(a)).collect();
+            (s, s.apply_grid(start), line)
+        })
+        .collect()
+}
+
+/// Symmetries that map the playable cells of `grid` onto themselves.
+pub fn layout_symmetries(grid: &Grid) -> Vec<Symmetry> {
+    let layout = grid.with_pegs(u64::MAX).encode();
+    Symmetry::ALL
+        .into_iter()
+        .filter(|s| s.apply_bits(layout) == layout)
+        .collect()
+}
+
+/// Symmetries of the layout that additionally leave `grid`'s pegs in place.
+pub fn grid_symmetries(grid: &Grid) -> Vec<Symmetry> {
+    let bits = grid.encode();
+    layout_symmetries(grid)
+        .into_iter()
+        .filter(|s| s.apply_bits(bits) == bits)
+        .collect()
+}
+
+/// Smallest encoding of `bits` over the given symmetry group.
+pub fn canonical(bits: u64, group: &[Symmetry]) -> u64 {
+    group
+        .iter()
+        .map(|s| s.apply_bits(bits))
+        .min()
+        .unwrap_or(bits)
+}
@@ -0,0 +1,211 @@
+//! Where jumps can run at the edges of the 7x7 grid: nowhere, as in the
+//! standard game, or round to the far side as if the grid were drawn on a
+//! torus. Either way only cells of the layout can be jumped from, over or
+//! onto, so on the English board jumps wrap along the middle three rows
+//! and columns, whose arms reach the edges.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+#[cfg(feature = "std")]
+use crate::bitboard::JumpTable;
+use crate::{Action, Direction, Grid, IllegalMove, ReplayError, Tile};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    #[default]
+    Flat,
+    /// The right edge joins the left and the bottom the top, so the peg at
+    /// `(5, 3)` can jump right over `(6, 3)` onto `(0, 3)`.
+    Torus,
+}
+
+impl Topology {
+    pub const ALL: [Topology; 2] = [Topology::Flat, Topology::Torus];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Flat => "flat",
+            Topology::Torus => "torus",
+        }
+    }
+
+    /// The cell `action` jumps over and the one it lands on, if both are
+    /// in the 7x7 grid under this topology.
+    pub fn span(self, action: Action) -> Option<((usize, usize), (usize, usize))> {
+        let (x, y) = action.origin();
+        match self {
+            Topology::Flat => action.span(),
+            Topology::Torus if x < 7 && y < 7 => {
+                // One step in the direction, as a step forward modulo 7.
+                let (dx, dy) = match action.direction() {
+                    Direction::Up => (0, 6),
+                    Direction::Down => (0, 1),
+                    Direction::Left => (6, 0),
+                    Direction::Right => (1, 0),
+                };
+                let step = |(x, y): (usize, usize)| ((x + dx) % 7, (y + dy) % 7);
+                let over = step((x, y));
+                Some((over, step(over)))
+            }
+            Topology::Torus => None,
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownTopology(pub String);
+
+impl fmt::Display for UnknownTopology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown topology {:?}, expected flat or torus", self.0)
+    }
+}
+
+impl Error for UnknownTopology {}
+
+impl FromStr for Topology {
+    type Err = UnknownTopology;
+
+    fn from_str(s: &str) -> Result<Topology, UnknownTopology> {
+        match s.to_ascii_lowercase().as_str() {
+            "flat" => Ok(Topology::Flat),
+            "torus" | "toroidal" => Ok(Topology::Torus),
+            _ => Err(UnknownTopology(s.to_string())),
+        }
+    }
+}
+
+impl Grid {
+    /// [`Grid::check_action`] with the edges joined as `topology` says.
+    pub fn check_action_on(&self, action: Action, topology: Topology) -> Result<(), IllegalMove> {
+        let (x, y) = action.origin();
+        if x >= 7 || y >= 7 {
+            return Err(IllegalMove::OffBoard);
+        }
+        match self.grid[x][y] {
+            Tile::Blocked => return Err(IllegalMove::OffBoard),
+            Tile::Empty => return Err(IllegalMove::NoPeg),
+            Tile::Filled => {}
+        }
+        let Some(((mx, my), (tx, ty))) = topology.span(action) else {
+            return Err(IllegalMove::OffBoard);
+        };
+        if self.grid[mx][my] == Tile::Blocked || self.grid[tx][ty] == Tile::Blocked {
+            return Err(IllegalMove::OffBoard);
+        }
+        if self.grid[mx][my] != Tile::Filled {
+            return Err(IllegalMove::NothingToJump);
+        }
+        if self.grid[tx][ty] == Tile::Filled {
+            return Err(IllegalMove::Occupied);
+        }
+        Ok(())
+    }
+
+    /// [`Grid::valid_actions`] under `topology`, in the same order.
+    pub fn valid_actions_on(&self, topology: Topology) -> Vec<Action> {
+        if topology == Topology::Flat {
+            return self.valid_actions();
+        }
+        let mut actions = Vec::new();
+        for x in 0..7 {
+            for y in 0..7 {
+                for dir in [
+                    Direction::Left,
+                    Direction::Right,
+                    Direction::Up,
+                    Direction::Down,
+                ] {
+                    let action = Action::new(x, y, dir);
+                    if self.check_action_on(action, topology).is_ok() {
+                        actions.push(action);
+                    }
+                }
+            }
+        }
+        actions
+    }
+
+    /// [`Grid::perform_action`] under `topology`.
+    pub fn perform_action_on(&self, action: Action, topology: Topology) -> Grid {
+        assert!(self.check_action_on(action, topology).is_ok());
+        let ((mx, my), (tx, ty)) = topology.span(action).unwrap();
+        let (x, y) = action.origin();
+        let mut grid = *self;
+        grid.grid[x][y] = Tile::Empty;
+        grid.grid[mx][my] = Tile::Empty;
+        grid.grid[tx][ty] = Tile::Filled;
+        grid.filled_count -= 1;
+        grid
+    }
+
+    /// [`Grid::play_moves`] under `topology`.
+    pub fn play_moves_on(&self, moves: &[Action], topology: Topology) -> Result<Grid, ReplayError> {
+        let mut state = *self;
+        for (index, &action) in moves.iter().enumerate() {
+            state
+                .check_action_on(action, topology)
+                .map_err(|reason| ReplayError {
+                    index,
+                    action,
+                    reason,
+                })?;
+            state = state.perform_action_on(action, topology);
+        }
+        Ok(state)
+    }
+}
+
+/// A depth-first search from `start` to `goal` under `topology`,
+/// remembering the positions that led nowhere. Unlike the searches of
+/// [`crate::GameTree`] it does no feasibility pruning, whose peg-class
+/// arguments hold only on a flat board.
+#[cfg(feature = "std")]
+pub fn search(start: &Grid, goal: &Grid, topology: Topology) -> Option<Vec<Action>> {
+    fn extend(
+        table: &JumpTable,
+        pegs: u64,
+        goal: u64,
+        dead: &mut HashSet<u64>,
+        line: &mut Vec<Action>,
+    ) -> bool {
+        if pegs == goal {
+            return true;
+        }
+        if pegs.count_ones() <= goal.count_ones() || dead.contains(&pegs) {
+            return false;
+        }
+        for (action, next) in table.forward(pegs) {
+            line.push(action);
+            if extend(table, next, goal, dead, line) {
+                return true;
+            }
+            line.pop();
+        }
+        dead.insert(pegs);
+        false
+    }
+
+    let table = JumpTable::with_topology(start, topology);
+    let mut line = Vec::new();
+    extend(
+        &table,
+        start.encode(),
+        goal.encode(),
+        &mut HashSet::new(),
+        &mut line,
+    )
+    .then_some(line)
+}
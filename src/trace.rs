@@ -0,0 +1,79 @@
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// How much the searches report on stderr while they run. Nothing is
+/// written unless [`set_verbosity`] turned it on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Searches starting and finishing, and positions pruned before any
+    /// search.
+    Info = 1,
+    /// Progress through long searches: nodes expanded, frontier and memo
+    /// sizes.
+    Debug = 2,
+}
+
+/// Expansions between two progress events at [`Level::Debug`].
+pub const PROGRESS_INTERVAL: u64 = 100_000;
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// 0 is silent, 1 enables [`Level::Info`] and 2 or more [`Level::Debug`].
+pub fn set_verbosity(verbosity: u8) {
+    EPOCH.get_or_init(Instant::now);
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= level as u8
+}
+
+/// Writes one `key=value` line for `target`, stamped with the time since
+/// logging was turned on.
+pub fn event(level: Level, target: &str, message: &str, fields: &[(&str, &dyn fmt::Display)]) {
+    if !enabled(level) {
+        return;
+    }
+    let elapsed = EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64();
+    let name = match level {
+        Level::Info => "INFO",
+        Level::Debug => "DEBUG",
+    };
+    let mut line = format!("[{:>9.3}s {:<5} {}] {}", elapsed, name, target, message);
+    for (key, value) in fields {
+        let _ = write!(line, " {}={}", key, value);
+    }
+    eprintln!("{}", line);
+}
+
+/// A phase of work that logs when it starts and, when dropped, how long it
+/// took.
+pub struct Span {
+    level: Level,
+    target: &'static str,
+    started: Option<Instant>,
+}
+
+impl Span {
+    pub fn enter(level: Level, target: &'static str, fields: &[(&str, &dyn fmt::Display)]) -> Span {
+        let started = enabled(level).then(Instant::now);
+        event(level, target, "start", fields);
+        Span {
+            level,
+            target,
+            started,
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(started) = self.started {
+            let seconds = format!("{:.6}", started.elapsed().as_secs_f64());
+            event(self.level, self.target, "done", &[("seconds", &seconds)]);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Exercises for training: positions where exactly one of several legal
+//! jumps keeps the centre-peg finish in reach, graded by the difficulty
+//! estimator so a trainer can make them harder as the player improves.
+
+use crate::difficulty::{self, Rating};
+use crate::generate;
+use crate::{Action, GameTree, Grid, SearchStrategy};
+
+/// Seeds tried for one exercise before giving up.
+const ATTEMPTS: u64 = 200;
+
+/// A position with one winning jump, and how hard the position is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exercise {
+    pub position: Grid,
+    pub goal: Grid,
+    pub answer: Action,
+    /// How many legal jumps the player has to choose from.
+    pub choices: usize,
+    pub rating: Rating,
+    pub seed: u64,
+}
+
+/// The jumps from `position` after which `goal` can still be reached.
+pub fn winning_moves(position: &Grid, goal: &Grid) -> Vec<Action> {
+    position
+        .valid_actions()
+        .into_iter()
+        .filter(|&action| {
+            let next = position.perform_action(action);
+            next == *goal
+                || GameTree::new(next, Vec::new())
+                    .search_for(goal, SearchStrategy::DepthFirst)
+                    .is_some()
+        })
+        .collect()
+}
+
+/// A random exercise with `pegs` pegs rated at least `rating`, trying
+/// seeds from `seed` on; failing that, the hardest one found. `None` only
+/// when no position with `pegs` pegs and a single winning jump turned up.
+pub fn exercise(pegs: u32, rating: Rating, seed: u64) -> Option<Exercise> {
+    let mut best: Option<Exercise> = None;
+    for attempt in 0..ATTEMPTS {
+        let seed = seed.wrapping_add(attempt);
+        let Some(puzzle) = generate::random_solvable(pegs, seed) else {
+            continue;
+        };
+        let choices = puzzle.position.valid_actions().len();
+        let winning = winning_moves(&puzzle.position, &puzzle.goal);
+        if choices < 2 || winning.len() != 1 {
+            continue;
+        }
+        let found = Exercise {
+            position: puzzle.position,
+            goal: puzzle.goal,
+            answer: winning[0],
+            choices,
+            rating: difficulty::estimate_difficulty(&puzzle.position).rating,
+            seed,
+        };
+        if found.rating >= rating {
+            return Some(found);
+        }
+        if best.as_ref().is_none_or(|b| found.rating > b.rating) {
+            best = Some(found);
+        }
+    }
+    best
+}
@@ -0,0 +1,110 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::bitboard::JumpTable;
+use crate::{Action, GameTree, Grid};
+
+/// What a travel-minimising search counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Travel {
+    /// Cells covered by all jumps. Each jump covers two cells, so every
+    /// solution of a position has the same total; the search degenerates
+    /// to a fast depth-first dive.
+    Total,
+    /// Cells covered by the peg starting on `(x, y)` until it is captured.
+    Peg(usize, usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bits: u64,
+    tracked: Option<(usize, usize)>,
+    parent: usize,
+    action: Option<Action>,
+    cost: u32,
+}
+
+/// A* over move costs from `start` to `goal`, returning the cheapest line
+/// and its cost. Ties are broken towards deeper positions so that the
+/// search dives instead of sweeping whole layers.
+pub fn min_travel(start: &Grid, goal: &Grid, travel: Travel) -> Option<(Vec<Action>, u32)> {
+    let table = JumpTable::new(start);
+    let goal_bits = goal.encode();
+    let goal_pegs = goal_bits.count_ones();
+    let tracked = match travel {
+        Travel::Total => None,
+        Travel::Peg(x, y) => {
+            if start.encode() & (1 << (x * 7 + y)) == 0 {
+                return None;
+            }
+            Some((x, y))
+        }
+    };
+    // Exact for `Travel::Total`, trivially admissible otherwise.
+    let estimate = |bits: u64| match travel {
+        Travel::Total => 2 * bits.count_ones().saturating_sub(goal_pegs),
+        Travel::Peg(..) => 0,
+    };
+
+    let mut nodes = vec![Node {
+        bits: start.encode(),
+        tracked,
+        parent: usize::MAX,
+        action: None,
+        cost: 0,
+    }];
+    let mut best: HashMap<(u64, Option<(usize, usize)>), u32> = HashMap::new();
+    best.insert((nodes[0].bits, tracked), 0);
+    let mut open = BinaryHeap::new();
+    open.push((std::cmp::Reverse(estimate(nodes[0].bits)), 0u32, 0usize));
+
+    while let Some((_, depth, index)) = open.pop() {
+        let node = nodes[index];
+        if best[&(node.bits, node.tracked)] < node.cost {
+            continue;
+        }
+        if node.bits == goal_bits {
+            let mut moves = Vec::new();
+            let mut at = index;
+            while let Some(action) = nodes[at].action {
+                moves.push(action);
+                at = nodes[at].parent;
+            }
+            moves.reverse();
+            return Some((moves, node.cost));
+        }
+        for (action, bits) in table.forward(node.bits) {
+            let ((mx, my), to) = action.span().unwrap();
+            let (step, tracked) = match (travel, node.tracked) {
+                (Travel::Total, _) => (2, None),
+                (_, Some(at)) if at == (action.x, action.y) => (2, Some(to)),
+                (_, Some(at)) if at == (mx, my) => (0, None),
+                (_, other) => (0, other),
+            };
+            let cost = node.cost + step;
+            let key = (bits, tracked);
+            if best.get(&key).is_some_and(|&c| c <= cost) {
+                continue;
+            }
+            best.insert(key, cost);
+            nodes.push(Node {
+                bits,
+                tracked,
+                parent: index,
+                action: Some(action),
+                cost,
+            });
+            let f = cost + estimate(bits);
+            open.push((std::cmp::Reverse(f), depth + 1, nodes.len() - 1));
+        }
+    }
+    None
+}
+
+impl GameTree {
+    /// Solves for the centre peg minimising `travel`.
+    pub fn search_min_travel(&self, travel: Travel) -> Option<GameTree> {
+        let goal = self.state.single_peg(3, 3);
+        let (moves, _) = min_travel(&self.state, &goal, travel)?;
+        Some(self.extend(&moves))
+    }
+}
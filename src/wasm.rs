@@ -0,0 +1,212 @@
+//! Functions exported for JavaScript when the crate is built for the
+//! browser with the `wasm` feature:
+//!
+//! ```text
+//! cargo rustc --release --lib --crate-type cdylib \
+//!     --target wasm32-unknown-unknown --features wasm
+//! ```
+//!
+//! Only integers cross the boundary, so the module needs no generated glue
+//! and can be loaded with `WebAssembly.instantiateStreaming`. A board is a
+//! pair of bitmasks in the [`Grid::encode`] layout: `layout` marks the
+//! playable cells and `pegs` the occupied ones; both are `BigInt`s on the
+//! JavaScript side. A move is packed as `x | y << 3 | direction << 6` with
+//! directions numbered up, down, left, right.
+//!
+//! The same library loads into Python through `ctypes`, see
+//! `python/puzzlething.py`.
+//!
+//! The solver is driven in chunks so it never blocks the page:
+//!
+//! ```text
+//! const h = pt_solver_new(layout, pegs, goal);
+//! while (pt_solver_step(h, 10000) === 0) await nextFrame();
+//! ```
+
+use std::sync::Mutex;
+
+use crate::chunked::{ChunkedSearch, Progress};
+use crate::enumerate;
+use crate::generate;
+use crate::{Action, Direction, Grid, Tile};
+
+/// Returned by the move functions when there is no such move.
+pub const NO_MOVE: u32 = u32::MAX;
+
+static SOLVERS: Mutex<Vec<Option<ChunkedSearch>>> = Mutex::new(Vec::new());
+
+fn grid(layout: u64, pegs: u64) -> Grid {
+    let mut grid = Grid::new();
+    for x in 0..7 {
+        for y in 0..7 {
+            let bit = 1 << (x * 7 + y);
+            let tile = match (layout & bit != 0, pegs & bit != 0) {
+                (false, _) => Tile::Blocked,
+                (true, true) => Tile::Filled,
+                (true, false) => Tile::Empty,
+            };
+            grid = grid.with_tile(x, y, tile);
+        }
+    }
+    grid
+}
+
+fn pack(action: Action) -> u32 {
+    let dir = Direction::ALL
+        .iter()
+        .position(|&d| d == action.dir)
+        .unwrap();
+    (action.x | action.y << 3 | dir << 6) as u32
+}
+
+fn unpack(packed: u32) -> Option<Action> {
+    let (x, y, dir) = (packed & 7, packed >> 3 & 7, packed >> 6);
+    if x > 6 || y > 6 {
+        return None;
+    }
+    Some(Action {
+        x: x as usize,
+        y: y as usize,
+        dir: *Direction::ALL.get(dir as usize)?,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn pt_english_layout() -> u64 {
+    Grid::new().with_pegs(u64::MAX).encode()
+}
+
+/// The English board with every peg but the centre one.
+#[no_mangle]
+pub extern "C" fn pt_english_start() -> u64 {
+    Grid::new().encode()
+}
+
+/// The centre peg goal.
+#[no_mangle]
+pub extern "C" fn pt_centre_goal() -> u64 {
+    Grid::new().single_peg(3, 3).encode()
+}
+
+#[no_mangle]
+pub extern "C" fn pt_legal_count(layout: u64, pegs: u64) -> u32 {
+    grid(layout, pegs).valid_actions().len() as u32
+}
+
+/// The `index`th legal move, or [`NO_MOVE`].
+#[no_mangle]
+pub extern "C" fn pt_legal_move(layout: u64, pegs: u64, index: u32) -> u32 {
+    grid(layout, pegs)
+        .valid_actions()
+        .get(index as usize)
+        .map_or(NO_MOVE, |&a| pack(a))
+}
+
+/// The pegs after playing `mv`, or `u64::MAX` when it is not legal.
+#[no_mangle]
+pub extern "C" fn pt_apply(layout: u64, pegs: u64, mv: u32) -> u64 {
+    let board = grid(layout, pegs);
+    match unpack(mv) {
+        Some(action) if board.check_action(action).is_ok() => board.perform_action(action).encode(),
+        _ => u64::MAX,
+    }
+}
+
+/// Starts a solver and returns its handle.
+#[no_mangle]
+pub extern "C" fn pt_solver_new(layout: u64, pegs: u64, goal: u64) -> u32 {
+    let search = ChunkedSearch::new(&grid(layout, pegs), &grid(layout, goal));
+    let mut solvers = SOLVERS.lock().unwrap();
+    let slot = solvers.iter().position(Option::is_none).unwrap_or_else(|| {
+        solvers.push(None);
+        solvers.len() - 1
+    });
+    solvers[slot] = Some(search);
+    slot as u32
+}
+
+/// Runs up to `budget` expansions: 0 means still searching, 1 solved, -1
+/// unsolvable and -2 an unknown handle.
+#[no_mangle]
+pub extern "C" fn pt_solver_step(handle: u32, budget: u32) -> i32 {
+    let mut solvers = SOLVERS.lock().unwrap();
+    let Some(Some(search)) = solvers.get_mut(handle as usize) else {
+        return -2;
+    };
+    match search.step(budget as u64) {
+        Progress::Searching => 0,
+        Progress::Solved(_) => 1,
+        Progress::Unsolvable => -1,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pt_solver_nodes(handle: u32) -> u64 {
+    let solvers = SOLVERS.lock().unwrap();
+    match solvers.get(handle as usize) {
+        Some(Some(search)) => search.stats().nodes_expanded,
+        _ => 0,
+    }
+}
+
+/// Moves in the solution, 0 until one is found.
+#[no_mangle]
+pub extern "C" fn pt_solver_len(handle: u32) -> u32 {
+    let solvers = SOLVERS.lock().unwrap();
+    match solvers.get(handle as usize) {
+        Some(Some(search)) => match search.progress() {
+            Progress::Solved(moves) => moves.len() as u32,
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pt_solver_move(handle: u32, index: u32) -> u32 {
+    let solvers = SOLVERS.lock().unwrap();
+    match solvers.get(handle as usize) {
+        Some(Some(search)) => match search.progress() {
+            Progress::Solved(moves) => moves.get(index as usize).map_or(NO_MOVE, |&a| pack(a)),
+            _ => NO_MOVE,
+        },
+        _ => NO_MOVE,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pt_solver_free(handle: u32) {
+    if let Some(slot) = SOLVERS.lock().unwrap().get_mut(handle as usize) {
+        *slot = None;
+    }
+}
+
+/// A random position with `pegs` pegs that can be played down to `goal`,
+/// or `u64::MAX` when the generator gives up.
+#[no_mangle]
+pub extern "C" fn pt_generate(layout: u64, goal: u64, pegs: u32, seed: u64) -> u64 {
+    generate::random_solvable_from(&grid(layout, goal), pegs, seed)
+        .map_or(u64::MAX, |p| p.position.encode())
+}
+
+/// Counts the positions reachable in exactly 0, 1, 2, ... moves, writing up
+/// to `capacity` of the counts to `out`, and returns how many depths there
+/// are.
+///
+/// # Safety
+///
+/// `out` must be valid for writing `capacity` `u64`s.
+#[no_mangle]
+pub unsafe extern "C" fn pt_state_space(
+    layout: u64,
+    pegs: u64,
+    up_to_symmetry: bool,
+    out: *mut u64,
+    capacity: u32,
+) -> u32 {
+    let space = enumerate::enumerate(&grid(layout, pegs), up_to_symmetry);
+    for (i, &count) in space.by_depth.iter().take(capacity as usize).enumerate() {
+        *out.add(i) = count;
+    }
+    space.by_depth.len() as u32
+}